@@ -0,0 +1,98 @@
+//! Lookup throughput with every core reading the same list.
+//!
+//! Each iteration runs one lookup per host on every thread, so a read path
+//! that takes a lock or bumps a shared refcount per lookup shows up as time
+//! growing with the thread count.
+use criterion::{criterion_group, criterion_main, Criterion};
+use publicsuffix2::{List, MatchOpts, SharedList, SharedListCache};
+use std::hint::black_box;
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const HOSTS: &[&str] = &[
+    "www.example.com",
+    "a.b.example.co.uk",
+    "foo.city.kawasaki.jp",
+    "user.github.io",
+    "www.example.com.au",
+    "unlisted.example",
+];
+
+fn threads() -> usize {
+    thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+/// Runs `lookup` for `iters` rounds on `n` threads and returns the wall time
+/// of the slowest thread.
+fn run<F>(n: usize, iters: u64, lookup: F) -> Duration
+where
+    F: Fn(u64) + Sync,
+{
+    let barrier = Barrier::new(n);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..n)
+            .map(|_| {
+                s.spawn(|| {
+                    barrier.wait();
+                    let start = Instant::now();
+                    lookup(iters);
+                    start.elapsed()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .max()
+            .unwrap_or_default()
+    })
+}
+
+fn concurrent(c: &mut Criterion) {
+    let n = threads();
+    let opts = MatchOpts::default();
+    List::global_ref().warm(&[]);
+    let shared = SharedList::new(List::global_ref().clone());
+
+    let mut group = c.benchmark_group(format!("concurrent_sld_{n}_threads"));
+    group.bench_function("global_ref", |b| {
+        b.iter_custom(|iters| {
+            run(n, iters, |iters| {
+                for _ in 0..iters {
+                    for host in HOSTS {
+                        black_box(List::global_ref().sld(black_box(host), opts));
+                    }
+                }
+            })
+        })
+    });
+    group.bench_function("shared_load_full_cached", |b| {
+        b.iter_custom(|iters| {
+            run(n, iters, |iters| {
+                let mut cache = SharedListCache::default();
+                for _ in 0..iters {
+                    for host in HOSTS {
+                        let list = shared.load_full_cached(&mut cache);
+                        black_box(list.sld(black_box(host), opts));
+                    }
+                }
+            })
+        })
+    });
+    group.bench_function("shared_load_full", |b| {
+        b.iter_custom(|iters| {
+            run(n, iters, |iters| {
+                for _ in 0..iters {
+                    for host in HOSTS {
+                        black_box(shared.load_full().sld(black_box(host), opts));
+                    }
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, concurrent);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+//! Lookup and load times on the embedded list, for every engine.
+//!
+//! Each corpus exercises one path through the matcher: short and deep
+//! hosts, Unicode hosts that go through IDNA, hosts no rule matches, and
+//! hosts matched through `*` and `!` rules. Running with `--features fst`
+//! adds `Engine::Compact` next to `Trie` and `Flat`.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use publicsuffix2::{Engine, List, MatchOpts};
+use std::hint::black_box;
+
+const PSL: &str = include_str!("../tests/fixtures/public_suffix_list.dat");
+
+const CORPORA: &[(&str, &[&str])] = &[
+    (
+        "short",
+        &[
+            "example.com",
+            "www.example.org",
+            "google.de",
+            "bbc.co.uk",
+            "user.github.io",
+            "example.com.au",
+        ],
+    ),
+    (
+        "deep",
+        &[
+            "a.b.c.d.e.f.www.example.co.uk",
+            "x1.x2.x3.x4.x5.x6.x7.x8.example.com",
+            "static.cdn.eu-west-1.prod.service.example.co.jp",
+            "one.two.three.four.five.six.user.github.io",
+        ],
+    ),
+    (
+        "idn",
+        &[
+            "пример.рф",
+            "bücher.example.de",
+            "例え.テスト.jp",
+            "MÜNCHEN.Example.DE",
+            "食狮.公司.cn",
+        ],
+    ),
+    (
+        "miss",
+        &[
+            "example.notatld",
+            "www.example.local",
+            "a.b.c.internal",
+            "host.invalid-tld-name",
+        ],
+    ),
+    (
+        "wildcard",
+        &[
+            "www.foo.ck",
+            "a.b.kawasaki.jp",
+            "shop.example.bd",
+            "i-0abc.eu-west-1.compute.amazonaws.com",
+        ],
+    ),
+    (
+        "exception",
+        &[
+            "www.ck",
+            "a.city.kawasaki.jp",
+            "www.city.kobe.jp",
+            "a.city.sapporo.jp",
+        ],
+    ),
+];
+
+fn engines() -> Vec<Engine> {
+    let mut engines = vec![Engine::Trie, Engine::Flat];
+    if cfg!(feature = "fst") {
+        engines.push(Engine::Compact);
+    }
+    engines
+}
+
+fn lookup(c: &mut Criterion) {
+    let opts = MatchOpts::default();
+    let lists: Vec<_> = engines()
+        .into_iter()
+        .map(|e| (e, List::default().with_engine(e)))
+        .collect();
+    for (corpus, hosts) in CORPORA {
+        let mut group = c.benchmark_group(format!("sld_{corpus}"));
+        group.throughput(Throughput::Elements(hosts.len() as u64));
+        for (engine, list) in &lists {
+            group.bench_function(BenchmarkId::from_parameter(format!("{engine:?}")), |b| {
+                b.iter(|| {
+                    for host in *hosts {
+                        black_box(list.sld(black_box(host), opts));
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+fn load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.sample_size(20);
+    group.throughput(Throughput::Bytes(PSL.len() as u64));
+    for engine in engines() {
+        group.bench_function(BenchmarkId::new("parse", format!("{engine:?}")), |b| {
+            b.iter(|| List::parse(black_box(PSL)).unwrap().with_engine(engine))
+        });
+        let bytes = List::parse(PSL).unwrap().with_engine(engine).to_bytes();
+        group.bench_function(BenchmarkId::new("from_bytes", format!("{engine:?}")), |b| {
+            b.iter(|| List::from_bytes(black_box(&bytes)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookup, load);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Prepares the list embedded by the `bundled-list` feature.
+//!
+//! The list is taken from `PUBLICSUFFIX2_LIST` (a file path or an
+//! `http(s)://` URL) if set, and otherwise downloaded from publicsuffix.org.
+//! Set `PUBLICSUFFIX2_OFFLINE` (or build on docs.rs) to skip the download;
+//! the test fixture is then embedded, as it is when the download fails.
+//! The build script only reruns when it or these variables change, so a
+//! build fetches the list once.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "bundled-list")]
+    bundled::embed();
+}
+
+#[cfg(feature = "bundled-list")]
+mod bundled {
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    const OFFICIAL_URL: &str = "https://publicsuffix.org/list/public_suffix_list.dat";
+    const FIXTURE: &str = "tests/fixtures/public_suffix_list.dat";
+
+    pub fn embed() {
+        println!("cargo:rerun-if-env-changed=PUBLICSUFFIX2_LIST");
+        println!("cargo:rerun-if-env-changed=PUBLICSUFFIX2_OFFLINE");
+        let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("public_suffix_list.dat");
+        let (text, url) = match env::var("PUBLICSUFFIX2_LIST") {
+            // An explicit source must work: falling back would hide the mistake.
+            Ok(source) => match load(&source) {
+                Ok(text) => (text, is_url(&source).then_some(source)),
+                Err(e) => panic!("PUBLICSUFFIX2_LIST={source}: {e}"),
+            },
+            Err(_)
+                if env::var_os("PUBLICSUFFIX2_OFFLINE").is_some()
+                    || env::var_os("DOCS_RS").is_some() =>
+            {
+                (fixture(), None)
+            }
+            Err(_) => match load(OFFICIAL_URL) {
+                Ok(text) => (text, Some(OFFICIAL_URL.to_string())),
+                Err(e) => {
+                    println!("cargo:warning=bundled-list: {e}; embedding the test fixture instead");
+                    (fixture(), None)
+                }
+            },
+        };
+        fs::write(&out, text).unwrap();
+        println!(
+            "cargo:rustc-env=PUBLICSUFFIX2_BUNDLED_URL={}",
+            url.unwrap_or_default()
+        );
+    }
+
+    fn is_url(source: &str) -> bool {
+        source.starts_with("http://") || source.starts_with("https://")
+    }
+
+    /// Reads `source` and checks that it looks like the PSL.
+    fn load(source: &str) -> Result<String, String> {
+        let text = if is_url(source) {
+            ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .get(source)
+                .call()
+                .map_err(|e| e.to_string())?
+                .into_string()
+                .map_err(|e| e.to_string())?
+        } else {
+            println!("cargo:rerun-if-changed={source}");
+            fs::read_to_string(source).map_err(|e| e.to_string())?
+        };
+        if !text.contains("===BEGIN ICANN DOMAINS===") {
+            return Err("not a Public Suffix List (no ICANN section)".into());
+        }
+        Ok(text)
+    }
+
+    fn fixture() -> String {
+        let path = Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap()).join(FIXTURE);
+        fs::read_to_string(path).unwrap()
+    }
+}
@@ -0,0 +1,16 @@
+//! Loading arbitrary list text must not panic, and a loaded list must
+//! survive the binary format unchanged.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use publicsuffix2::List;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(list) = List::parse(text) else {
+        return;
+    };
+    let back = List::from_bytes(&list.to_bytes()).expect("written lists load");
+    assert!(list.rules().eq(back.rules()));
+});
@@ -0,0 +1,42 @@
+//! Every engine must split a host as `List::reference_split` does.
+//!
+//! Input: list text, a NUL byte, then the host.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use publicsuffix2::{Engine, FallbackPolicy, List, MatchOpts};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Some((rules, host)) = text.split_once('\0') else {
+        return;
+    };
+    // The reference scans every rule per label; keep lists small.
+    if rules.len() > 512 {
+        return;
+    }
+    let Ok(list) = List::parse(rules) else {
+        return;
+    };
+    let lists = [
+        list.clone(),
+        list.clone().with_engine(Engine::Flat),
+        list.clone().with_engine(Engine::Compact),
+    ];
+    for opts in [
+        MatchOpts::default(),
+        MatchOpts::raw(),
+        MatchOpts {
+            wildcard: false,
+            fallback: FallbackPolicy::EntireHost,
+            ..MatchOpts::default()
+        },
+    ] {
+        let want = list.reference_split(host, opts);
+        for engine in &lists {
+            let got = engine.split(host, opts).map(|p| p.into_owned());
+            assert_eq!(got, want, "{:?} engine, host {host:?}", engine.engine());
+        }
+    }
+});
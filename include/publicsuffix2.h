@@ -0,0 +1,58 @@
+/*
+ * C interface to the publicsuffix2 matcher.
+ *
+ * Build the library with the `ffi` feature, e.g.
+ *   cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * Everything returned by this library is owned by the caller and released
+ * with the matching free function. Lookups use the crate's default match
+ * options. NULL pointers and hosts that are not UTF-8 yield NULL (or -1).
+ */
+#ifndef PUBLICSUFFIX2_H
+#define PUBLICSUFFIX2_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A parsed Public Suffix List. */
+typedef struct PslList PslList;
+
+/* The parts of a host. Absent parts are NULL. */
+typedef struct PslParts {
+    char *prefix; /* labels left of the registrable domain */
+    char *sll;    /* the label immediately left of the public suffix */
+    char *sld;    /* the registrable domain (eTLD+1) */
+    char *tld;    /* the public suffix */
+} PslParts;
+
+/* Parses `len` bytes of PSL text (need not be NUL-terminated).
+ * Returns NULL if the text is not UTF-8 or not a valid list. */
+PslList *psl_list_new_from_text(const char *text, size_t len);
+
+/* Frees a list. NULL is ignored. */
+void psl_list_free(PslList *list);
+
+/* Registrable domain of `host`, or NULL. Free with psl_free. */
+char *psl_sld(const PslList *list, const char *host);
+
+/* Public suffix of `host`, or NULL. Free with psl_free. */
+char *psl_tld(const PslList *list, const char *host);
+
+/* Splits `host` into `out`. Returns 0 on success, -1 otherwise (all fields
+ * of `out` are then NULL). Free a filled `out` with psl_parts_free. */
+int psl_split(const PslList *list, const char *host, PslParts *out);
+
+/* Frees the strings in `parts` and sets them to NULL. NULL is ignored. */
+void psl_parts_free(PslParts *parts);
+
+/* Frees a string returned by psl_sld or psl_tld. NULL is ignored. */
+void psl_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PUBLICSUFFIX2_H */
@@ -0,0 +1,296 @@
+//! Lookups over many hosts at once.
+//!
+//! The batch methods return one result per input host, in input order. With
+//! the `parallel` feature, batches of a few thousand hosts or more are
+//! spread over the rayon thread pool; results are identical either way.
+//!
+//! [`List::group_by_registrable`] and [`List::count_by_registrable`] bucket
+//! hosts by registrable domain instead, for deduplicating logs, and
+//! [`List::map_slds`] streams registrable domains for lines of input.
+use crate::engine::{Parts, PartsIdx};
+use crate::options::MatchOpts;
+use crate::List;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use hashbrown::HashMap;
+
+/// Smallest batch that is split across threads with the `parallel` feature;
+/// below it, scheduling costs more than the lookups.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN: usize = 4096;
+
+impl List {
+    /// [`List::sld`] for each host in `hosts`.
+    pub fn sld_batch<'a>(
+        &self,
+        hosts: &[&'a str],
+        opts: MatchOpts<'_>,
+    ) -> Vec<Option<Cow<'a, str>>> {
+        map(hosts, |h| self.sld(h, opts))
+    }
+
+    /// [`List::tld`] for each host in `hosts`.
+    pub fn tld_batch<'a>(
+        &self,
+        hosts: &[&'a str],
+        opts: MatchOpts<'_>,
+    ) -> Vec<Option<Cow<'a, str>>> {
+        map(hosts, |h| self.tld(h, opts))
+    }
+
+    /// [`List::split`] for each host in `hosts`.
+    pub fn split_batch<'a>(
+        &self,
+        hosts: &[&'a str],
+        opts: MatchOpts<'_>,
+    ) -> Vec<Option<Parts<'a>>> {
+        map(hosts, |h| self.split(h, opts))
+    }
+
+    /// Buckets `hosts` by registrable domain ([`List::sld`]).
+    ///
+    /// Each bucket keeps its hosts as given, in input order. Hosts with no
+    /// registrable domain (malformed, or unlisted under `strict`) are left
+    /// out.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list = List::parse("com\nuk\nco.uk\n")?;
+    /// let hosts = ["www.example.com", "API.EXAMPLE.COM", "example.co.uk", ""];
+    /// let groups = list.group_by_registrable(hosts, MatchOpts::default());
+    /// assert_eq!(groups.len(), 2);
+    /// assert_eq!(groups["example.com"], ["www.example.com", "API.EXAMPLE.COM"]);
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn group_by_registrable<'a>(
+        &self,
+        hosts: impl IntoIterator<Item = &'a str>,
+        opts: MatchOpts<'_>,
+    ) -> HashMap<String, Vec<&'a str>> {
+        let mut groups: HashMap<String, Vec<&'a str>> = HashMap::new();
+        self.each_registrable(hosts, opts, |sld, host| {
+            groups.entry_ref(sld).or_default().push(host)
+        });
+        groups
+    }
+
+    /// Like [`List::group_by_registrable`], but only counts the hosts in
+    /// each bucket.
+    pub fn count_by_registrable<'a>(
+        &self,
+        hosts: impl IntoIterator<Item = &'a str>,
+        opts: MatchOpts<'_>,
+    ) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        self.each_registrable(hosts, opts, |sld, _| {
+            *counts.entry_ref(sld).or_default() += 1
+        });
+        counts
+    }
+
+    /// [`List::sld`] for each line of `lines`, as an iterator.
+    ///
+    /// Made for streams such as `stdin().lines()`: surrounding whitespace
+    /// is trimmed, and the registrable domain is cut out of each line's own
+    /// buffer where it can be, so most lines cost no allocation beyond
+    /// reading them.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list = List::parse("com\nuk\nco.uk\n")?;
+    /// let lines = ["www.example.co.uk", "  API.Example.COM\r", "a..com"].map(String::from);
+    /// let slds: Vec<_> = list.map_slds(lines.into_iter(), MatchOpts::default()).collect();
+    /// assert_eq!(
+    ///     slds,
+    ///     [Some("example.co.uk".to_string()), Some("example.com".to_string()), None]
+    /// );
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn map_slds<'l, 'o, I>(&'l self, lines: I, opts: MatchOpts<'o>) -> MapSlds<'l, 'o, I>
+    where
+        I: Iterator<Item = String>,
+    {
+        MapSlds {
+            list: self,
+            lines,
+            opts,
+        }
+    }
+
+    /// [`List::sld`] of `line`, reusing its buffer for the result.
+    fn sld_in_place(&self, mut line: String, opts: MatchOpts<'_>) -> Option<String> {
+        let start = line.len() - line.trim_start().len();
+        line.truncate(line.trim_end().len());
+        line.drain(..start);
+        if opts.normalizer.is_some_and(|n| n.lowercase) && line.is_ascii() {
+            line.make_ascii_lowercase();
+        }
+        match self.split_indices(&line, opts) {
+            Some(PartsIdx { sld: Some(sld), .. }) => {
+                line.truncate(sld.end);
+                line.drain(..sld.start);
+                Some(line)
+            }
+            // Normalizing rewrote the line, or there is no match.
+            _ => self.sld(&line, opts).map(Cow::into_owned),
+        }
+    }
+
+    /// Calls `f` with the registrable domain of each host that has one.
+    ///
+    /// ASCII hosts that need lowercasing are lowercased into one reused
+    /// buffer first, so [`List::sld`] can borrow the result instead of
+    /// allocating a copy per host.
+    fn each_registrable<'a>(
+        &self,
+        hosts: impl IntoIterator<Item = &'a str>,
+        opts: MatchOpts<'_>,
+        mut f: impl FnMut(&str, &'a str),
+    ) {
+        let lowercase = opts.normalizer.is_some_and(|n| n.lowercase);
+        let mut buf = String::new();
+        for host in hosts {
+            let view = match lowercase && host.bytes().any(|b| b.is_ascii_uppercase()) {
+                true if host.is_ascii() => {
+                    buf.clear();
+                    buf.push_str(host);
+                    buf.make_ascii_lowercase();
+                    &*buf
+                }
+                _ => host,
+            };
+            if let Some(sld) = self.sld(view, opts) {
+                f(&sld, host);
+            }
+        }
+    }
+}
+
+/// Iterator returned by [`List::map_slds`].
+pub struct MapSlds<'l, 'o, I> {
+    list: &'l List,
+    lines: I,
+    opts: MatchOpts<'o>,
+}
+
+impl<I: Iterator<Item = String>> Iterator for MapSlds<'_, '_, I> {
+    type Item = Option<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        Some(self.list.sld_in_place(line, self.opts))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn map<'a, T, F>(hosts: &[&'a str], f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&'a str) -> T + Sync,
+{
+    use rayon::prelude::*;
+    if hosts.len() < PARALLEL_MIN {
+        return hosts.iter().map(|h| f(h)).collect();
+    }
+    hosts.par_iter().map(|h| f(h)).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map<'a, T, F>(hosts: &[&'a str], f: F) -> Vec<T>
+where
+    F: Fn(&'a str) -> T,
+{
+    hosts.iter().map(|h| f(h)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_matches_single_lookups() {
+        let list: List = "com\nuk\nco.uk\n*.ck\n!www.ck".parse().unwrap();
+        let m = MatchOpts::default();
+        let base = ["www.example.co.uk", "A.B.CK", "www.ck", "", "example.local"];
+        // Large enough to take the parallel path when it is enabled.
+        let hosts: Vec<&str> = base.iter().cycle().take(5000).copied().collect();
+
+        let slds = list.sld_batch(&hosts, m);
+        let tlds = list.tld_batch(&hosts, m);
+        let splits = list.split_batch(&hosts, m);
+        assert_eq!(slds.len(), hosts.len());
+        for (i, h) in hosts.iter().enumerate() {
+            assert_eq!(slds[i], list.sld(h, m), "{h}");
+            assert_eq!(tlds[i], list.tld(h, m), "{h}");
+            assert_eq!(splits[i], list.split(h, m), "{h}");
+        }
+        assert!(list.sld_batch(&[], m).is_empty());
+    }
+
+    #[test]
+    fn groups_by_registrable_domain() {
+        let list: List = "com\nuk\nco.uk\n*.ck\n!www.ck".parse().unwrap();
+        let m = MatchOpts::default();
+        let hosts = [
+            "www.example.co.uk",
+            "a.b.ck",
+            "EXAMPLE.co.uk.",
+            "www.ck",
+            "x.a.b.ck",
+            "a..com",
+        ];
+        let groups = list.group_by_registrable(hosts, m);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups["example.co.uk"],
+            ["www.example.co.uk", "EXAMPLE.co.uk."]
+        );
+        assert_eq!(groups["a.b.ck"], ["a.b.ck", "x.a.b.ck"]);
+        assert_eq!(groups["www.ck"], ["www.ck"]);
+
+        let counts = list.count_by_registrable(hosts.iter().copied(), m);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["example.co.uk"], 2);
+        for (sld, group) in &groups {
+            assert_eq!(counts[sld], group.len());
+        }
+    }
+
+    #[test]
+    fn map_slds_matches_single_lookups() {
+        let list: List = "com\nuk\nco.uk\n*.ck\n!www.ck".parse().unwrap();
+        let lines = [
+            "www.example.co.uk",
+            " A.B.CK\r",
+            "www.ck.",
+            "",
+            "example.local",
+            "ÉXAMPLE.com",
+            "a..com",
+        ];
+        for m in [MatchOpts::default(), MatchOpts::raw()] {
+            let got: Vec<_> = list
+                .map_slds(lines.iter().map(|l| String::from(*l)), m)
+                .collect();
+            let want: Vec<_> = lines
+                .iter()
+                .map(|l| list.sld(l.trim(), m).map(Cow::into_owned))
+                .collect();
+            assert_eq!(got, want);
+        }
+    }
+
+    #[test]
+    fn grouping_keeps_case_without_lowercasing() {
+        let list: List = "com".parse().unwrap();
+        let groups =
+            list.group_by_registrable(["a.Example.com", "b.example.com"], MatchOpts::raw());
+        assert_eq!(groups["Example.com"], ["a.Example.com"]);
+        assert_eq!(groups["example.com"], ["b.example.com"]);
+    }
+}
@@ -0,0 +1,200 @@
+//! `psl`: query the Public Suffix List from the command line.
+//!
+//! Built with the `cli` feature. Run `psl help` for usage.
+use publicsuffix2::source::Source;
+use publicsuffix2::{List, MatchOpts, Parts, TypeFilter};
+use serde_json::json;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: psl [options] <command> [args]
+
+commands:
+  tld [host...]           print the public suffix of each host
+  sld [host...]           print the registrable domain of each host
+  split [--json] [host...]
+                          print prefix, sll, sld and tld of each host
+  fetch [--url URL] [--out FILE]
+                          download the list to FILE (default: stdout)
+  help                    show this message
+
+Without hosts, or with `-`, hosts are read from stdin, one per line.
+Without an answer, `tld` and `sld` print an empty line; `split` prints `-`
+for each missing field (null in JSON) and always prints the host.
+
+options:
+  --list FILE             match against FILE instead of the built-in list
+  --strict                no answer for hosts no rule matches
+  --icann                 ignore the private section of the list
+";
+
+/// A command-line error, printed to stderr.
+enum CliError {
+    /// Bad arguments: the usage is printed too.
+    Usage(String),
+    Failed(String),
+}
+
+impl From<publicsuffix2::Error> for CliError {
+    fn from(e: publicsuffix2::Error) -> Self {
+        CliError::Failed(e.to_string())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Failed(e.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    list: Option<String>,
+    strict: bool,
+    icann: bool,
+    json: bool,
+    url: Option<String>,
+    out: Option<String>,
+    command: Option<String>,
+    rest: Vec<String>,
+}
+
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Args, CliError> {
+    let mut args = Args::default();
+    let value = |name: &str, argv: &mut dyn Iterator<Item = String>| {
+        argv.next()
+            .ok_or_else(|| CliError::Usage(format!("{name} needs a value")))
+    };
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--list" => args.list = Some(value("--list", &mut argv)?),
+            "--url" => args.url = Some(value("--url", &mut argv)?),
+            "--out" => args.out = Some(value("--out", &mut argv)?),
+            "--strict" => args.strict = true,
+            "--icann" => args.icann = true,
+            "--json" => args.json = true,
+            "-h" | "--help" => args.command = Some("help".into()),
+            "-" => args.rest.push(arg),
+            s if s.starts_with('-') => return Err(CliError::Usage(format!("unknown option {s}"))),
+            _ if args.command.is_none() => args.command = Some(arg),
+            _ => args.rest.push(arg),
+        }
+    }
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    match run(parse_args(std::env::args().skip(1))) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(msg)) => {
+            eprintln!("psl: {msg}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(msg)) => {
+            eprintln!("psl: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Result<Args, CliError>) -> Result<(), CliError> {
+    let args = args?;
+    let command = args.command.as_deref().unwrap_or("help");
+    match command {
+        "help" => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        "fetch" => fetch(&args),
+        "tld" | "sld" | "split" => query(command, &args),
+        other => Err(CliError::Usage(format!("unknown command {other}"))),
+    }
+}
+
+fn fetch(args: &Args) -> Result<(), CliError> {
+    let source = args
+        .url
+        .as_deref()
+        .map_or(Source::official(), Source::custom);
+    let text = source.fetch()?;
+    // Refuse to save something the matcher cannot load.
+    List::parse(&text)?;
+    match &args.out {
+        Some(path) => {
+            let tmp = format!("{path}.tmp");
+            std::fs::write(&tmp, &text)?;
+            std::fs::rename(&tmp, path)?;
+        }
+        None => io::stdout().write_all(text.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn query(command: &str, args: &Args) -> Result<(), CliError> {
+    let list = match &args.list {
+        Some(path) => List::from_file(path)?,
+        None => List::global().clone(),
+    };
+    let opts = MatchOpts {
+        strict: args.strict,
+        types: if args.icann {
+            TypeFilter::Icann
+        } else {
+            TypeFilter::Any
+        },
+        ..MatchOpts::default()
+    };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut answer = |host: &str| -> io::Result<()> {
+        let host = host.trim();
+        match command {
+            "tld" => writeln!(out, "{}", list.tld(host, opts).unwrap_or_default()),
+            "sld" => writeln!(out, "{}", list.sld(host, opts).unwrap_or_default()),
+            _ => write_split(&mut out, host, list.split(host, opts), args.json),
+        }
+    };
+
+    let stdin_only = ["-".to_string()];
+    let hosts = if args.rest.is_empty() {
+        &stdin_only[..]
+    } else {
+        &args.rest
+    };
+    for host in hosts {
+        if host == "-" {
+            for line in io::stdin().lock().lines() {
+                answer(&line?)?;
+            }
+        } else {
+            answer(host)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_split(
+    out: &mut impl Write,
+    host: &str,
+    parts: Option<Parts<'_>>,
+    json: bool,
+) -> io::Result<()> {
+    let (prefix, sll, sld, tld) = match &parts {
+        Some(p) => (
+            p.prefix.as_deref(),
+            p.sll.as_deref(),
+            p.sld.as_deref(),
+            Some(&*p.tld),
+        ),
+        None => (None, None, None, None),
+    };
+    if json {
+        let obj = json!({ "host": host, "prefix": prefix, "sll": sll, "sld": sld, "tld": tld });
+        writeln!(out, "{obj}")
+    } else {
+        let [prefix, sll, sld, tld] = [prefix, sll, sld, tld].map(|s| s.unwrap_or("-"));
+        writeln!(out, "{host}\t{prefix}\t{sll}\t{sld}\t{tld}")
+    }
+}
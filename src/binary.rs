@@ -0,0 +1,405 @@
+//! Compiled lists in a compact binary form.
+//!
+//! [`List::to_bytes`] writes the rule trie so that [`List::from_bytes`] can
+//! rebuild it without parsing PSL text. After the [`Format::CompiledList`]
+//! header, version 2 holds:
+//!
+//! - the engine: one byte, `0` for `Engine::Trie`, `1` for `Engine::Flat`,
+//!   `2` for `Engine::Compact`;
+//! - the root node. A node is its leaf kind (`0` none, `1` rule,
+//!   `2` exception), its section (`0` none, `1` ICANN, `2` private), its
+//!   child count, then each child as a label followed by the child node,
+//!   in label order;
+//! - the number of annotated rules, then each rule, its `@expires` and its
+//!   `@owner`;
+//! - the snapshot the list was loaded from: its version, date, commit and
+//!   URL, each an optional string. Version 1 ends before this.
+//!
+//! Counts and string lengths are LEB128 varints; strings are UTF-8. An
+//! optional string is a `0` byte for `None` or `1` followed by the string.
+//! Load warnings are not stored.
+use crate::engine::Trie;
+use crate::errors::{Error, Result};
+use crate::flat::FlatTrie;
+use crate::format::{Format, FormatVersion};
+use crate::rules::{Engine, Labels, Leaf, ListVersion, Node, RuleMeta, RuleSet, Type};
+use crate::{List, Store};
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Deepest node accepted when decoding; a DNS name has at most 127 labels.
+pub(crate) const MAX_DEPTH: usize = 127;
+
+impl List {
+    /// Serializes the compiled rules, keeping the storage engine.
+    ///
+    /// The output is deterministic: equal lists produce equal bytes. See
+    /// the [`Format::CompiledList`] version for compatibility.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Format::CompiledList.binary_header();
+        match &*self.store {
+            Store::Trie(rs) => {
+                out.push(0);
+                write_node(&mut out, &rs.root, &rs.labels);
+            }
+            Store::Flat(f) => {
+                out.push(1);
+                write_flat(&mut out, f, f.root());
+            }
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => {
+                out.push(2);
+                let rs = c.to_ruleset();
+                write_node(&mut out, &rs.root, &rs.labels);
+            }
+        }
+        self.write_annotations(&mut out);
+        out
+    }
+
+    /// Writes the annotated rules and the snapshot the list was loaded from.
+    pub(crate) fn write_annotations(&self, out: &mut Vec<u8>) {
+        write_len(out, self.meta.len());
+        for m in &self.meta {
+            write_str(out, &m.rule);
+            write_opt(out, m.expires.as_deref());
+            write_opt(out, m.owner.as_deref());
+        }
+        let version = self.version.clone().unwrap_or_default();
+        for field in [
+            &version.version,
+            &version.date,
+            &version.commit,
+            &version.url,
+        ] {
+            write_opt(out, field.as_deref());
+        }
+    }
+
+    /// Rebuilds a list written by [`List::to_bytes`].
+    ///
+    /// Returns `Error::UnsupportedFormatVersion` for data from an
+    /// incompatible crate version, and `Error::InvalidBinary` for truncated
+    /// or corrupted data.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (format, body) = Format::CompiledList.check_binary_header(bytes)?;
+        let mut r = Reader { buf: body, pos: 0 };
+        let engine = match r.byte()? {
+            0 => Engine::Trie,
+            1 => Engine::Flat,
+            2 => Engine::Compact,
+            _ => return Err(r.error()),
+        };
+        let mut labels = Labels::default();
+        let root = r.node(&mut labels, 0)?;
+        let (meta, version) = r.annotations(format >= FormatVersion(2))?;
+        if r.pos != body.len() {
+            return Err(r.error());
+        }
+        let rules = RuleSet {
+            root,
+            labels,
+            meta,
+            version,
+            ..RuleSet::default()
+        };
+        Ok(List::from_ruleset(rules).with_engine(engine))
+    }
+}
+
+fn write_len(out: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let b = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(b);
+            return;
+        }
+        out.push(b | 0x80);
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_len(out, s.len());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_opt(out: &mut Vec<u8>, s: Option<&str>) {
+    match s {
+        Some(s) => {
+            out.push(1);
+            write_str(out, s);
+        }
+        None => out.push(0),
+    }
+}
+
+fn write_kind(out: &mut Vec<u8>, leaf: Leaf, typ: Option<Type>) {
+    out.push(match leaf {
+        Leaf::None => 0,
+        Leaf::Positive => 1,
+        Leaf::Negative => 2,
+    });
+    out.push(match typ {
+        None => 0,
+        Some(Type::Icann) => 1,
+        Some(Type::Private) => 2,
+    });
+}
+
+fn write_node(out: &mut Vec<u8>, node: &Node, labels: &Labels) {
+    write_kind(out, node.leaf, node.typ);
+    let kids = node.sorted_kids(labels);
+    write_len(out, kids.len());
+    for (label, kid) in kids {
+        write_str(out, label);
+        write_node(out, kid, labels);
+    }
+}
+
+fn write_flat(out: &mut Vec<u8>, f: &FlatTrie, node: u32) {
+    write_kind(out, f.leaf(node), f.typ(node));
+    write_len(out, f.children(node).count());
+    for (label, kid) in f.children(node) {
+        write_str(out, label);
+        write_flat(out, f, kid);
+    }
+}
+
+/// Reads what [`List::write_annotations`] wrote, which must fill `buf`.
+pub(crate) fn read_annotations(buf: &[u8]) -> Result<(Vec<RuleMeta>, Option<Box<ListVersion>>)> {
+    let mut r = Reader { buf, pos: 0 };
+    let annotations = r.annotations(true)?;
+    if r.pos != buf.len() {
+        return Err(r.error());
+    }
+    Ok(annotations)
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self) -> Error {
+        Error::InvalidBinary { offset: self.pos }
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let b = *self.buf.get(self.pos).ok_or_else(|| self.error())?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let mut n: usize = 0;
+        for shift in (0..usize::BITS).step_by(7) {
+            let b = self.byte()?;
+            n |= usize::from(b & 0x7f)
+                .checked_shl(shift)
+                .ok_or_else(|| self.error())?;
+            if b & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(self.error())
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.str().map(Into::into)
+    }
+
+    fn str(&mut self) -> Result<&'a str> {
+        let len = self.len()?;
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.buf.get(self.pos..end))
+            .ok_or_else(|| self.error())?;
+        let s = core::str::from_utf8(bytes).map_err(|_| self.error())?;
+        self.pos += len;
+        Ok(s)
+    }
+
+    fn opt(&mut self) -> Result<Option<String>> {
+        match self.byte()? {
+            0 => Ok(None),
+            1 => self.string().map(Some),
+            _ => Err(self.error()),
+        }
+    }
+
+    /// The annotated rules, then the snapshot fields if `with_version`.
+    fn annotations(
+        &mut self,
+        with_version: bool,
+    ) -> Result<(Vec<RuleMeta>, Option<Box<ListVersion>>)> {
+        let mut meta = Vec::new();
+        for _ in 0..self.len()? {
+            meta.push(RuleMeta {
+                rule: self.string()?,
+                expires: self.opt()?,
+                owner: self.opt()?,
+            });
+        }
+        let mut version = None;
+        if with_version {
+            let v = ListVersion {
+                version: self.opt()?,
+                date: self.opt()?,
+                commit: self.opt()?,
+                url: self.opt()?,
+            };
+            version = Some(v)
+                .filter(|v| *v != ListVersion::default())
+                .map(Box::new);
+        }
+        Ok((meta, version))
+    }
+
+    fn node(&mut self, labels: &mut Labels, depth: usize) -> Result<Node> {
+        if depth > MAX_DEPTH {
+            return Err(self.error());
+        }
+        let leaf = match self.byte()? {
+            0 => Leaf::None,
+            1 => Leaf::Positive,
+            2 => Leaf::Negative,
+            _ => return Err(self.error()),
+        };
+        let typ = match self.byte()? {
+            0 => None,
+            1 => Some(Type::Icann),
+            2 => Some(Type::Private),
+            _ => return Err(self.error()),
+        };
+        let mut node = Node {
+            leaf,
+            typ,
+            ..Node::default()
+        };
+        for _ in 0..self.len()? {
+            let label = labels.intern(self.str()?);
+            let kid = self.node(labels, depth + 1)?;
+            if node.kids.insert(label, kid).is_some() {
+                return Err(self.error());
+            }
+        }
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::MatchOpts;
+
+    const TEXT: &str = "\
+// ===BEGIN ICANN DOMAINS===
+uk
+co.uk
+*.ck
+!www.ck
+// @owner team-x
+// @expires 2030-01-01
+example
+// ===BEGIN PRIVATE DOMAINS===
+github.io
+";
+
+    #[test]
+    fn roundtrip_keeps_rules_engine_and_meta() {
+        for list in [
+            TEXT.parse::<List>().unwrap(),
+            TEXT.parse::<List>().unwrap().with_engine(Engine::Flat),
+        ] {
+            let bytes = list.to_bytes();
+            let back = List::from_bytes(&bytes).unwrap();
+            assert_eq!(back.engine(), list.engine());
+            assert_eq!(back.to_bytes(), bytes);
+            assert_eq!(back.rule_meta(), list.rule_meta());
+            let m = MatchOpts::default();
+            for host in ["a.b.co.uk", "a.b.ck", "www.ck", "x.github.io", "a.example"] {
+                assert_eq!(back.split(host, m), list.split(host, m), "{host}");
+            }
+        }
+        // Both engines encode the same trie the same way.
+        let trie = TEXT.parse::<List>().unwrap().to_bytes();
+        let mut flat = TEXT
+            .parse::<List>()
+            .unwrap()
+            .with_engine(Engine::Flat)
+            .to_bytes();
+        let engine_at = Format::CompiledList.binary_header().len();
+        flat[engine_at] = 0;
+        assert_eq!(trie, flat);
+    }
+
+    #[test]
+    fn version_info_roundtrips_and_v1_still_reads() {
+        let text = alloc::format!("// VERSION: 2025-09-23_13-07-02_UTC\n// COMMIT: abc123\n{TEXT}");
+        let list: List = text.parse().unwrap();
+        let back = List::from_bytes(&list.to_bytes()).unwrap();
+        assert_eq!(back.version_info(), list.version_info());
+        assert_eq!(
+            back.version_info().unwrap().commit.as_deref(),
+            Some("abc123")
+        );
+
+        // Version 1 data: the same body without the snapshot fields.
+        let plain = TEXT.parse::<List>().unwrap();
+        let mut v1 = plain.to_bytes();
+        assert_eq!(v1.split_off(v1.len() - 4), [0; 4]);
+        let header = Format::CompiledList.binary_header().len();
+        v1[header - 2..header].copy_from_slice(&1u16.to_le_bytes());
+        let old = List::from_bytes(&v1).unwrap();
+        assert_eq!(old.version_info(), None);
+        assert_eq!(old.to_bytes(), plain.to_bytes());
+    }
+
+    #[test]
+    fn corrupt_bytes_are_errors() {
+        let bytes = TEXT.parse::<List>().unwrap().to_bytes();
+        let body = Format::CompiledList.binary_header().len();
+        for cut in body..bytes.len() {
+            assert!(
+                matches!(
+                    List::from_bytes(&bytes[..cut]),
+                    Err(Error::InvalidBinary { .. })
+                ),
+                "{cut}"
+            );
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            List::from_bytes(&trailing),
+            Err(Error::InvalidBinary { .. })
+        ));
+
+        let mut bad_leaf = bytes.clone();
+        bad_leaf[body + 1] = 9;
+        assert!(matches!(
+            List::from_bytes(&bad_leaf),
+            Err(Error::InvalidBinary { offset: 2 })
+        ));
+
+        assert!(matches!(
+            List::from_bytes(b"com\nuk\n"),
+            Err(Error::UnsupportedFormatVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let mut bytes = Format::CompiledList.binary_header();
+        bytes.push(0);
+        for _ in 0..=MAX_DEPTH + 1 {
+            bytes.extend_from_slice(&[0, 0, 1, 1, b'a']);
+        }
+        assert!(matches!(
+            List::from_bytes(&bytes),
+            Err(Error::InvalidBinary { .. })
+        ));
+    }
+}
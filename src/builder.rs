@@ -0,0 +1,463 @@
+//! Programmatic construction of a [`List`].
+//!
+//! [`ListBuilder`] accumulates rules from method calls and PSL fragments and
+//! validates them as they arrive. Problems are reported once, by
+//! [`ListBuilder::finish`], so a chain of calls needs no intermediate error
+//! handling.
+use crate::errors::{Error, Result, RuleSyntax, Warning};
+use crate::loader;
+use crate::options::LoadOpts;
+use crate::rules::{Labels, Leaf, Node, RuleSet, Type};
+use crate::List;
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Longest label allowed in a rule, in bytes (the DNS limit).
+const MAX_LABEL_LEN: usize = 63;
+
+/// Builds a `List` from individual rules and PSL text fragments.
+///
+/// ```rust
+/// use publicsuffix2::{ListBuilder, MatchOpts, Type};
+///
+/// let (list, warnings) = ListBuilder::new()
+///     .set_type(Some(Type::Icann))
+///     .add_rule("uk")
+///     .add_rule("co.uk")
+///     .add_wildcard("ck")
+///     .add_exception("www.ck")
+///     .add_rule_as("blogspot.co.uk", Type::Private)
+///     .finish()
+///     .unwrap();
+/// assert!(warnings.is_empty());
+/// assert_eq!(list.tld("a.b.co.uk", MatchOpts::default()).as_deref(), Some("co.uk"));
+/// assert_eq!(list.tld("a.blogspot.co.uk", MatchOpts::default()).as_deref(), Some("blogspot.co.uk"));
+/// ```
+#[derive(Debug, Default)]
+pub struct ListBuilder {
+    rules: RuleSet,
+    typ: Option<Type>,
+    warnings: Vec<Warning>,
+    error: Option<Error>,
+}
+
+impl ListBuilder {
+    /// An empty builder. Rules are unclassified until [`set_type`](Self::set_type).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the section of the rules added after this call.
+    pub fn set_type(mut self, typ: Option<Type>) -> Self {
+        self.typ = typ;
+        self
+    }
+
+    /// Adds a positive rule such as `co.uk` or `*.ck`.
+    pub fn add_rule(self, rule: &str) -> Self {
+        self.add(rule, false)
+    }
+
+    /// Adds a positive rule in section `typ`, leaving the section set by
+    /// [`set_type`](Self::set_type) unchanged for later rules.
+    pub fn add_rule_as(mut self, rule: &str, typ: Type) -> Self {
+        let cur = self.typ.replace(typ);
+        self = self.add(rule, false);
+        self.typ = cur;
+        self
+    }
+
+    /// Adds an exception rule; `city.kawasaki.jp` is the PSL `!city.kawasaki.jp`.
+    pub fn add_exception(self, rule: &str) -> Self {
+        self.add(rule, true)
+    }
+
+    /// Adds a wildcard below `parent`; `ck` is the PSL `*.ck`.
+    pub fn add_wildcard(self, parent: &str) -> Self {
+        self.add(&format!("*.{parent}"), false)
+    }
+
+    /// Parses a PSL fragment with `LoadOpts::default()` and adds its rules,
+    /// keeping the sections given by its markers.
+    pub fn merge_text(self, text: &str) -> Self {
+        self.merge_text_with(text, LoadOpts::default())
+    }
+
+    /// Parses a PSL fragment with explicit `LoadOpts` and adds its rules.
+    ///
+    /// Rules also present in the builder are replaced and reported as
+    /// `Warning::DuplicateRule`.
+    pub fn merge_text_with(mut self, text: &str, opts: LoadOpts) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        match loader::load(text, opts) {
+            Ok(mut other) => {
+                self.warnings.append(&mut other.warnings);
+                self.rules.meta.append(&mut other.meta);
+                *self.rules.sections += *other.sections;
+                let mut path = Vec::new();
+                merge(
+                    &mut self.rules.root,
+                    &mut self.rules.labels,
+                    &other.root,
+                    &other.labels,
+                    &mut path,
+                    &mut self.warnings,
+                );
+            }
+            Err(e) => self.error = Some(e),
+        }
+        self
+    }
+
+    /// Reads a PSL file and adds its rules as [`merge_text`](Self::merge_text)
+    /// does. A read failure is reported by [`finish`](Self::finish) as
+    /// `Error::Io`.
+    #[cfg(feature = "std")]
+    pub fn merge_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        match std::fs::read_to_string(path) {
+            Ok(text) => self.merge_text(&text),
+            Err(e) => {
+                self.error = Some(Error::Io(e));
+                self
+            }
+        }
+    }
+
+    /// Validates the accumulated rules and compiles them into a `List`.
+    ///
+    /// Returns the first invalid rule as `Error::InvalidRule` (or
+    /// `Error::LabelTooLong`), the first error from a merged fragment, or
+    /// `Error::EmptyList` if no rule was added. Non-fatal issues (duplicate
+    /// rules, lowercased rules) are returned alongside the list.
+    pub fn finish(self) -> Result<(List, Vec<Warning>)> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if self.rules.root.kids.is_empty() {
+            return Err(Error::EmptyList);
+        }
+        Ok((List::from_ruleset(self.rules), self.warnings))
+    }
+
+    fn add(mut self, rule: &str, neg: bool) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        if let Err(e) = validate(rule, neg) {
+            self.error = Some(e);
+            return self;
+        }
+        let written = if neg { format!("!{rule}") } else { rule.into() };
+        let lower = rule.to_lowercase();
+        if lower != rule {
+            self.warnings.push(Warning::RuleCaseNormalized {
+                rule: written.clone(),
+            });
+        }
+        if leaf_of(&self.rules, &lower) != Leaf::None {
+            self.warnings.push(Warning::DuplicateRule { rule: written });
+        }
+        loader::insert_rule(&mut self.rules, &lower, self.typ, neg);
+        self
+    }
+}
+
+/// Checks rule syntax: non-empty labels of letters, digits, `-`, `_` or
+/// non-ASCII characters, and `*` only as the whole leftmost label of a
+/// positive rule.
+pub(crate) fn validate(rule: &str, neg: bool) -> Result<()> {
+    let invalid = |reason| {
+        Err(Error::InvalidRule {
+            rule: rule.into(),
+            reason,
+        })
+    };
+    if rule.is_empty() {
+        return invalid(RuleSyntax::Empty);
+    }
+    if rule.starts_with('.') || rule.ends_with('.') {
+        return invalid(RuleSyntax::StartsOrEndsWithDot);
+    }
+    for (i, label) in rule.split('.').enumerate() {
+        if label.is_empty() {
+            return invalid(RuleSyntax::HasEmptyLabel);
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(Error::LabelTooLong {
+                label: label.into(),
+            });
+        }
+        if label == "*" && i == 0 && !neg {
+            continue;
+        }
+        for c in label.chars() {
+            if c.is_whitespace() {
+                return invalid(RuleSyntax::ContainsWhitespace);
+            }
+            if !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()) {
+                return invalid(RuleSyntax::ContainsIllegalChar);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Leaf kind of `rule` in `rules`, or `Leaf::None` if it is not a rule.
+fn leaf_of(rules: &RuleSet, rule: &str) -> Leaf {
+    rules.node_at(rule).map_or(Leaf::None, |n| n.leaf)
+}
+
+/// Merges `src` into `dst`, with their labels in `dst_labels` and
+/// `src_labels`; `path` holds the labels leading to both, from the right.
+fn merge(
+    dst: &mut Node,
+    dst_labels: &mut Labels,
+    src: &Node,
+    src_labels: &Labels,
+    path: &mut Vec<String>,
+    warnings: &mut Vec<Warning>,
+) {
+    if src.leaf != Leaf::None {
+        if dst.leaf != Leaf::None {
+            let rule: Vec<&str> = path.iter().rev().map(String::as_str).collect();
+            let rule = rule.join(".");
+            warnings.push(Warning::DuplicateRule {
+                rule: if src.leaf == Leaf::Negative {
+                    format!("!{rule}")
+                } else {
+                    rule
+                },
+            });
+        }
+        dst.leaf = src.leaf;
+        dst.typ = src.typ;
+    }
+    for (label, child) in src.labeled_kids(src_labels) {
+        path.push(label.into());
+        let kid = dst.kid_or_insert(dst_labels, label);
+        merge(kid, dst_labels, child, src_labels, path, warnings);
+        path.pop();
+    }
+}
+
+/// Builds a list of unclassified rules, one per item, written as in the
+/// PSL (`co.uk`, `*.ck`, `!www.ck`). No items give an empty list.
+///
+/// For small lists in tests and embedded tables; see also [`psl!`].
+///
+/// # Panics
+///
+/// If an item is not a valid rule, as [`ListBuilder::finish`] would report.
+///
+/// ```rust
+/// use publicsuffix2::{List, MatchOpts};
+///
+/// let list: List = ["com", "*.ck", "!www.ck"].into_iter().collect();
+/// assert_eq!(list.tld("www.ck", MatchOpts::default()).as_deref(), Some("ck"));
+/// ```
+impl<'a> FromIterator<&'a str> for List {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(rules: I) -> Self {
+        let builder =
+            rules
+                .into_iter()
+                .fold(ListBuilder::new(), |b, rule| match rule.strip_prefix('!') {
+                    Some(rule) => b.add_exception(rule),
+                    None => b.add_rule(rule),
+                });
+        match builder.finish() {
+            Ok((list, _)) => list,
+            Err(Error::EmptyList) => List::from_ruleset(RuleSet::default()),
+            Err(e) => panic!("invalid rule: {e}"),
+        }
+    }
+}
+
+/// Builds a [`List`] from rules written as in the PSL, collecting them
+/// with its `FromIterator` impl.
+///
+/// ```rust
+/// use publicsuffix2::{psl, MatchOpts};
+///
+/// let list = psl!["com", "uk", "co.uk", "*.ck", "!www.ck"];
+/// assert_eq!(list.sld("www.example.co.uk", MatchOpts::default()).as_deref(), Some("example.co.uk"));
+/// assert!(psl![].is_empty());
+/// ```
+#[macro_export]
+macro_rules! psl {
+    ($($rule:expr),* $(,)?) => {
+        <$crate::List as ::core::iter::FromIterator<&str>>::from_iter([$($rule),*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::MatchOpts;
+    use crate::TypeFilter;
+
+    #[test]
+    fn builds_rules_with_sections() {
+        let (list, warnings) = ListBuilder::new()
+            .set_type(Some(Type::Icann))
+            .add_rule("uk")
+            .add_rule("co.uk")
+            .set_type(Some(Type::Private))
+            .add_rule("blogspot.co.uk")
+            .finish()
+            .unwrap();
+        assert!(warnings.is_empty());
+        let m = MatchOpts::default();
+        assert_eq!(
+            list.tld("a.blogspot.co.uk", m).as_deref(),
+            Some("blogspot.co.uk")
+        );
+        let icann = MatchOpts {
+            types: TypeFilter::Icann,
+            ..m
+        };
+        assert_eq!(
+            list.tld("a.blogspot.co.uk", icann).as_deref(),
+            Some("co.uk")
+        );
+    }
+
+    #[test]
+    fn wildcard_and_exception() {
+        let (list, _) = ListBuilder::new()
+            .add_wildcard("ck")
+            .add_exception("www.ck")
+            .finish()
+            .unwrap();
+        let m = MatchOpts::default();
+        assert_eq!(list.tld("a.b.ck", m).as_deref(), Some("b.ck"));
+        assert_eq!(list.tld("www.ck", m).as_deref(), Some("ck"));
+    }
+
+    #[test]
+    fn merge_text_reports_duplicates() {
+        let (list, warnings) = ListBuilder::new()
+            .add_rule("com")
+            .merge_text("// ===BEGIN PRIVATE DOMAINS===\ncom\ngithub.io\n")
+            .add_rule("GitHub.io")
+            .finish()
+            .unwrap();
+        assert_eq!(
+            list.tld("x.github.io", MatchOpts::default()).as_deref(),
+            Some("github.io")
+        );
+        let dups: Vec<_> = warnings
+            .iter()
+            .filter_map(|w| match w {
+                Warning::DuplicateRule { rule } => Some(rule.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dups, ["com", "GitHub.io"]);
+        assert!(warnings
+            .iter()
+            .any(|w| matches!(w, Warning::RuleCaseNormalized { .. })));
+    }
+
+    #[test]
+    fn add_rule_as_keeps_current_section() {
+        let (list, _) = ListBuilder::new()
+            .set_type(Some(Type::Icann))
+            .add_rule("com")
+            .add_rule_as("blogspot.com", Type::Private)
+            .add_rule("net")
+            .finish()
+            .unwrap();
+        let icann = MatchOpts {
+            types: TypeFilter::Icann,
+            ..MatchOpts::default()
+        };
+        assert_eq!(list.tld("a.blogspot.com", icann).as_deref(), Some("com"));
+        assert_eq!(list.tld("a.example.net", icann).as_deref(), Some("net"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn merge_file_reads_fragment_or_defers_error() {
+        let path = std::env::temp_dir().join(format!("psl-builder-{}.dat", std::process::id()));
+        std::fs::write(&path, "com\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n").unwrap();
+        let (list, _) = ListBuilder::new()
+            .add_rule("uk")
+            .merge_file(&path)
+            .finish()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let m = MatchOpts::default();
+        assert_eq!(list.tld("a.github.io", m).as_deref(), Some("github.io"));
+        assert_eq!(list.tld("a.example.uk", m).as_deref(), Some("uk"));
+
+        let missing = ListBuilder::new()
+            .merge_file(&path)
+            .add_rule("com")
+            .finish();
+        assert!(matches!(missing, Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn finish_reports_first_invalid_rule() {
+        let cases: [(ListBuilder, RuleSyntax); 5] = [
+            (ListBuilder::new().add_rule(""), RuleSyntax::Empty),
+            (
+                ListBuilder::new().add_rule(".com"),
+                RuleSyntax::StartsOrEndsWithDot,
+            ),
+            (
+                ListBuilder::new().add_rule("a..b"),
+                RuleSyntax::HasEmptyLabel,
+            ),
+            (
+                ListBuilder::new().add_rule("a b"),
+                RuleSyntax::ContainsWhitespace,
+            ),
+            (
+                ListBuilder::new().add_exception("*.ck"),
+                RuleSyntax::ContainsIllegalChar,
+            ),
+        ];
+        for (b, want) in cases {
+            match b.add_rule("com").finish() {
+                Err(Error::InvalidRule { reason, .. }) => {
+                    assert_eq!(format!("{reason:?}"), format!("{want:?}"))
+                }
+                other => panic!("expected {want:?}, got {other:?}"),
+            }
+        }
+        assert!(matches!(
+            ListBuilder::new().add_rule(&"a".repeat(64)).finish(),
+            Err(Error::LabelTooLong { .. })
+        ));
+        assert!(matches!(ListBuilder::new().finish(), Err(Error::EmptyList)));
+    }
+
+    #[test]
+    fn collects_rules_from_an_iterator() {
+        let list = crate::psl!["com", "CO.UK", "uk", "*.ck", "!www.ck",];
+        assert_eq!(list.len(), 5);
+        assert!(list.rules().all(|r| r.typ.is_none()));
+        let m = MatchOpts::default();
+        assert_eq!(list.tld("a.b.ck", m).as_deref(), Some("b.ck"));
+        assert_eq!(list.tld("www.ck", m).as_deref(), Some("ck"));
+        assert_eq!(list.tld("x.co.uk", m).as_deref(), Some("co.uk"));
+
+        let text: List = "com\nco.uk\nuk\n*.ck\n!www.ck".parse().unwrap();
+        assert_eq!(list, text);
+        let empty: List = core::iter::empty().collect();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid rule")]
+    fn collecting_an_invalid_rule_panics() {
+        let _ = crate::psl!["com", "a..com"];
+    }
+}
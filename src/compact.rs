@@ -0,0 +1,282 @@
+//! Finite-state-transducer storage engine for the rule trie.
+//!
+//! Every rule is stored as one key, its labels reversed and joined by
+//! dots (`*.kobe.jp` becomes `jp.kobe.*`), in an [`fst`] map whose value
+//! encodes the rule's leaf kind and section. Shared prefixes and suffixes of
+//! keys are stored once, so the whole list fits in one small contiguous
+//! buffer. A trie node is a state of the transducer reached at a label
+//! boundary; interior nodes are the states followed by a `.` transition.
+use crate::engine::Trie;
+use crate::rules::{Labels, Leaf, Node, RuleFn, RuleSet, Type};
+use core::fmt;
+use core::mem::size_of;
+use fst::raw::{CompiledAddr, Fst, Output};
+use fst::{MapBuilder, Streamer};
+
+/// A compiled rule trie stored as a minimal finite-state transducer.
+#[derive(Clone)]
+pub(crate) struct CompactTrie {
+    fst: Fst<Vec<u8>>,
+    /// Top-level labels, sorted; kept aside because the transducer does
+    /// not store whole labels anywhere.
+    tlds: Vec<Box<str>>,
+}
+
+/// A node of a [`CompactTrie`]: the transducer state at a label boundary
+/// and the output accumulated on the way there.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CompactNode {
+    addr: CompiledAddr,
+    out: u64,
+    root: bool,
+}
+
+impl CompactTrie {
+    /// Compiles a `RuleSet` into its transducer form.
+    pub(crate) fn from_ruleset(rules: &RuleSet) -> Self {
+        let mut keys = Vec::new();
+        let mut path = Vec::new();
+        collect(&rules.root, &rules.labels, &mut path, &mut keys);
+        keys.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let mut builder = MapBuilder::memory();
+        for (key, value) in &keys {
+            // Keys are sorted and unique, and building in memory cannot fail.
+            builder.insert(key, *value).expect("sorted unique keys");
+        }
+        let bytes = builder.into_inner().expect("in-memory builder");
+        let fst = Fst::new(bytes).expect("freshly built transducer");
+
+        let mut tlds: Vec<Box<str>> = rules
+            .root
+            .labeled_kids(&rules.labels)
+            .map(|(label, _)| label.into())
+            .collect();
+        tlds.sort_unstable();
+        Self { fst, tlds }
+    }
+
+    /// Rebuilds the `RuleSet` this trie was compiled from.
+    pub(crate) fn to_ruleset(&self) -> RuleSet {
+        let mut rules = RuleSet::default();
+        let mut stream = self.fst.stream();
+        while let Some((key, out)) = stream.next() {
+            let (leaf, typ) = decode(out.value());
+            let mut cur = &mut rules.root;
+            for label in String::from_utf8_lossy(key).split('.') {
+                cur = cur.kid_or_insert(&mut rules.labels, label);
+            }
+            cur.leaf = leaf;
+            cur.typ = typ;
+        }
+        rules
+    }
+
+    /// Calls `f` with every rule: its labels from the right, its kind and
+    /// its section.
+    pub(crate) fn for_each_rule(&self, f: &mut RuleFn<'_>) {
+        let mut stream = self.fst.stream();
+        while let Some((key, out)) = stream.next() {
+            let (leaf, typ) = decode(out.value());
+            let key = String::from_utf8_lossy(key);
+            let labels: Vec<&str> = key.split('.').collect();
+            f(&labels, leaf, typ);
+        }
+    }
+
+    /// Approximate heap and inline size of this trie in bytes.
+    pub(crate) fn approx_bytes(&self) -> usize {
+        size_of::<Self>()
+            + self.fst.as_bytes().len()
+            + self.tlds.capacity() * size_of::<Box<str>>()
+            + self.tlds.iter().map(|t| t.len()).sum::<usize>()
+    }
+
+    /// Top-level labels, sorted.
+    pub(crate) fn tlds(&self) -> impl Iterator<Item = &str> + '_ {
+        self.tlds.iter().map(|t| &**t)
+    }
+}
+
+impl fmt::Debug for CompactTrie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompactTrie")
+            .field("rules", &self.fst.len())
+            .field("bytes", &self.fst.size())
+            .finish()
+    }
+}
+
+/// Gathers the key and encoded value of every rule below `node`. `path`
+/// holds the labels leading to `node`, from the right.
+fn collect<'a>(
+    node: &'a Node,
+    labels: &'a Labels,
+    path: &mut Vec<&'a str>,
+    keys: &mut Vec<(String, u64)>,
+) {
+    if node.leaf != Leaf::None {
+        keys.push((path.join("."), encode(node.leaf, node.typ)));
+    }
+    for (label, kid) in node.labeled_kids(labels) {
+        path.push(label);
+        collect(kid, labels, path, keys);
+        path.pop();
+    }
+}
+
+fn encode(leaf: Leaf, typ: Option<Type>) -> u64 {
+    let leaf = match leaf {
+        Leaf::None => 0,
+        Leaf::Positive => 1,
+        Leaf::Negative => 2,
+    };
+    let typ = match typ {
+        None => 0,
+        Some(Type::Icann) => 1,
+        Some(Type::Private) => 2,
+    };
+    leaf | typ << 2
+}
+
+fn decode(value: u64) -> (Leaf, Option<Type>) {
+    let leaf = match value & 3 {
+        1 => Leaf::Positive,
+        2 => Leaf::Negative,
+        _ => Leaf::None,
+    };
+    let typ = match value >> 2 {
+        1 => Some(Type::Icann),
+        2 => Some(Type::Private),
+        _ => None,
+    };
+    (leaf, typ)
+}
+
+impl CompactTrie {
+    /// The rule value of `node`, if it ends a rule.
+    fn value(&self, node: CompactNode) -> Option<u64> {
+        let state = self.fst.node(node.addr);
+        state
+            .is_final()
+            .then(|| Output::new(node.out).cat(state.final_output()).value())
+    }
+}
+
+impl Trie for CompactTrie {
+    type Node<'a> = CompactNode;
+
+    fn root(&self) -> CompactNode {
+        CompactNode {
+            addr: self.fst.root().addr(),
+            out: 0,
+            root: true,
+        }
+    }
+
+    fn child(&self, node: CompactNode, label: &str) -> Option<CompactNode> {
+        if label.is_empty() {
+            return None;
+        }
+        let sep = (!node.root).then_some(b'.');
+        let mut state = self.fst.node(node.addr);
+        let mut out = Output::new(node.out);
+        for b in sep.into_iter().chain(label.bytes()) {
+            let t = state.transition(state.find_input(b)?);
+            out = out.cat(t.out);
+            state = self.fst.node(t.addr);
+        }
+        // Stopping inside a longer label (`co` on the way to `com`) is not
+        // a node: a node ends a rule or continues with another label.
+        (state.is_final() || state.find_input(b'.').is_some()).then_some(CompactNode {
+            addr: state.addr(),
+            out: out.value(),
+            root: false,
+        })
+    }
+
+    fn leaf(&self, node: CompactNode) -> Leaf {
+        self.value(node).map_or(Leaf::None, |v| decode(v).0)
+    }
+
+    fn typ(&self, node: CompactNode) -> Option<Type> {
+        self.value(node).and_then(|v| decode(v).1)
+    }
+
+    fn is_childless(&self, node: CompactNode) -> bool {
+        let state = self.fst.node(node.addr);
+        if node.root {
+            state.is_empty()
+        } else {
+            state.find_input(b'.').is_none()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine;
+    use crate::options::{LoadOpts, MatchOpts};
+
+    fn rules() -> RuleSet {
+        crate::loader::load(
+            "com\nco\nuk\nco.uk\n*.ck\n!www.ck\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io",
+            LoadOpts::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn compact_matches_hashmap_trie() {
+        let rs = rules();
+        let compact = CompactTrie::from_ruleset(&rs);
+        let m = MatchOpts::default();
+        for host in [
+            "www.example.co.uk",
+            "a.b.ck",
+            "www.ck",
+            "x.github.io",
+            "example.io",
+            "example.local",
+            "c.com",
+            "c.co",
+            "com",
+            "",
+            "a..b",
+        ] {
+            assert_eq!(
+                engine::split(&compact, host, m),
+                engine::split(&rs, host, m),
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn nodes_stop_at_label_boundaries() {
+        let compact = CompactTrie::from_ruleset(&rules());
+        let root = compact.root();
+        assert!(compact.child(root, "c").is_none());
+        let io = compact.child(root, "io").unwrap();
+        assert_eq!(compact.leaf(io), Leaf::None);
+        assert!(!compact.is_childless(io));
+        assert!(compact.child(io, "git").is_none());
+        let gh = compact.child(io, "github").unwrap();
+        assert_eq!(compact.leaf(gh), Leaf::Positive);
+        assert_eq!(compact.typ(gh), Some(Type::Private));
+        assert!(compact.is_childless(gh));
+        assert_eq!(
+            compact.tlds().collect::<Vec<_>>(),
+            ["ck", "co", "com", "io", "uk"]
+        );
+    }
+
+    #[test]
+    fn round_trips_to_ruleset() {
+        let compact = CompactTrie::from_ruleset(&rules());
+        let again = CompactTrie::from_ruleset(&compact.to_ruleset());
+        assert_eq!(again.fst.as_bytes(), compact.fst.as_bytes());
+        assert_eq!(again.tlds, compact.tlds);
+    }
+}
@@ -0,0 +1,223 @@
+//! Running the upstream Public Suffix List test vectors.
+//!
+//! The list's maintainers publish `tests.txt` next to the list: lines of
+//! `checkPublicSuffix('host', 'expected');`, where `expected` is the
+//! registrable domain of `host` or `null` when it has none.
+//! [`List::run_conformance`](crate::List::run_conformance) runs such a file
+//! against a list, so a custom build of the list can be held to the
+//! upstream expectations; [`check_with`] runs it against any function with
+//! the same shape.
+//!
+//! The vectors follow the reference algorithm rather than PS2 defaults:
+//! hosts are lowercased but not converted to or from punycode, a host with
+//! an empty label (such as a leading dot) has no registrable domain, and
+//! an unlisted TLD is matched by the implicit `*` rule.
+use crate::options::{MatchOpts, Normalizer};
+use crate::List;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// One `checkPublicSuffix` line of a test file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceCase<'a> {
+    /// 1-based line of the case in the test file.
+    pub line: usize,
+    /// Host to look up; `None` for a `null` input.
+    pub host: Option<&'a str>,
+    /// Expected registrable domain.
+    pub expected: Option<&'a str>,
+}
+
+/// A case whose actual result differs from the expected one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceFailure {
+    /// 1-based line of the case in the test file.
+    pub line: usize,
+    /// Host looked up; `None` for a `null` input.
+    pub host: Option<String>,
+    /// Expected registrable domain.
+    pub expected: Option<String>,
+    /// What the implementation under test returned.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for ConformanceFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: checkPublicSuffix({:?}): expected {:?}, got {:?}",
+            self.line, self.host, self.expected, self.actual
+        )
+    }
+}
+
+/// Outcome of running a test file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Number of cases evaluated.
+    pub total: usize,
+    /// Cases that did not produce the expected result.
+    pub failures: Vec<ConformanceFailure>,
+    /// 1-based lines that are neither blank, comments nor valid cases.
+    pub malformed: Vec<usize>,
+}
+
+impl ConformanceReport {
+    /// Returns true if every line parsed and every case matched.
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty() && self.malformed.is_empty()
+    }
+}
+
+/// Parses a test file into its cases, and the lines that could not be
+/// parsed.
+///
+/// Blank lines and `//` comments, including commented-out cases, are
+/// skipped.
+pub fn cases(text: &str) -> (Vec<ConformanceCase<'_>>, Vec<usize>) {
+    let mut cases = Vec::new();
+    let mut malformed = Vec::new();
+    for (i, l) in text.lines().enumerate() {
+        let l = l.trim();
+        if l.is_empty() || l.starts_with("//") {
+            continue;
+        }
+        match parse_line(i + 1, l) {
+            Some(case) => cases.push(case),
+            None => malformed.push(i + 1),
+        }
+    }
+    (cases, malformed)
+}
+
+/// Runs the test file `text` against `registrable`, which returns the
+/// registrable domain of a host.
+pub fn check_with<F>(text: &str, mut registrable: F) -> ConformanceReport
+where
+    F: FnMut(Option<&str>) -> Option<String>,
+{
+    let (cases, malformed) = cases(text);
+    let mut report = ConformanceReport {
+        malformed,
+        ..ConformanceReport::default()
+    };
+    for case in cases {
+        let actual = registrable(case.host);
+        report.total += 1;
+        if actual.as_deref() != case.expected {
+            report.failures.push(ConformanceFailure {
+                line: case.line,
+                host: case.host.map(Into::into),
+                expected: case.expected.map(Into::into),
+                actual,
+            });
+        }
+    }
+    report
+}
+
+fn parse_line(line: usize, l: &str) -> Option<ConformanceCase<'_>> {
+    let args = l
+        .strip_prefix("checkPublicSuffix(")?
+        .strip_suffix(';')?
+        .trim_end()
+        .strip_suffix(')')?;
+    let (host, expected) = args.split_once(',')?;
+    Some(ConformanceCase {
+        line,
+        host: parse_arg(host)?,
+        expected: parse_arg(expected)?,
+    })
+}
+
+/// A quoted string or `null`; `None` if it is neither.
+fn parse_arg(arg: &str) -> Option<Option<&str>> {
+    let arg = arg.trim();
+    if arg == "null" {
+        return Some(None);
+    }
+    let s = arg
+        .strip_prefix('\'')
+        .and_then(|a| a.strip_suffix('\''))
+        .or_else(|| arg.strip_prefix('"').and_then(|a| a.strip_suffix('"')))?;
+    Some(Some(s))
+}
+
+impl List {
+    /// Runs an upstream `tests.txt` file against this list.
+    ///
+    /// Each host is looked up with [`List::registrable_domain`], lowercased
+    /// only and rejected if it has an empty label, as described in the
+    /// [`conformance`](crate::conformance) module. A list built from the
+    /// `public_suffix_list.dat` the file was published with yields a clean
+    /// report.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let list = List::parse("com\n*.ck\n!www.ck\n")?;
+    /// let report = list.run_conformance(
+    ///     "// Wildcard and exception.\n\
+    ///      checkPublicSuffix('a.b.test.ck', 'b.test.ck');\n\
+    ///      checkPublicSuffix('www.www.ck', 'www.ck');\n\
+    ///      checkPublicSuffix('.example.com', null);\n",
+    /// );
+    /// assert_eq!(report.total, 3);
+    /// assert!(report.is_clean());
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn run_conformance(&self, tests_text: &str) -> ConformanceReport {
+        let normalizer = Normalizer::lowercase_only();
+        let opts = MatchOpts::with_normalizer(&normalizer);
+        check_with(tests_text, |host| {
+            let host = host?;
+            if host.split('.').any(str::is_empty) {
+                return None;
+            }
+            self.registrable_domain(host, opts)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_parse() {
+        let (cases, malformed) = cases(
+            "// comment\n\n\
+             checkPublicSuffix(null, null);\n\
+             checkPublicSuffix('COM', null);\n\
+             //checkPublicSuffix('local', null);\n\
+             checkPublicSuffix(\"a.b.com\" , \"b.com\") ;\n\
+             checkPublicSuffix('a.com');\n\
+             checkPublicSuffix(a.com, 'a.com');\n",
+        );
+        let got: Vec<_> = cases.iter().map(|c| (c.line, c.host, c.expected)).collect();
+        assert_eq!(
+            got,
+            [
+                (3, None, None),
+                (4, Some("COM"), None),
+                (6, Some("a.b.com"), Some("b.com")),
+            ]
+        );
+        assert_eq!(malformed, [7, 8]);
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let report = check_with("checkPublicSuffix('a.b.com', 'b.com');\nbogus\n", |h| {
+            h.map(Into::into)
+        });
+        assert_eq!(report.total, 1);
+        assert_eq!(report.malformed, [2]);
+        let msg = report.failures[0].to_string();
+        assert_eq!(
+            msg,
+            "line 1: checkPublicSuffix(Some(\"a.b.com\")): expected Some(\"b.com\"), got Some(\"a.b.com\")"
+        );
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,223 @@
+//! Cookie `Domain` attribute checks (RFC 6265, sections 5.1.3 and 5.3).
+//!
+//! A server may only set a cookie for a domain that its own host
+//! domain-matches, and never for a public suffix: otherwise `evil.co.uk`
+//! could set a "supercookie" on `co.uk` that every site under it receives.
+//! [`List::cookie_domain`] applies both rules and tells whether the cookie
+//! is host-only; [`List::cookie_domain_ok`] is the yes/no form.
+use crate::engine::normalize_view;
+use crate::options::is_ip_literal;
+use crate::options::MatchOpts;
+use crate::List;
+use alloc::{borrow::Cow, string::String};
+use core::fmt;
+
+/// Where a cookie accepted by [`List::cookie_domain`] will be sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CookieScope {
+    /// Only to the request host, which is given (canonicalized). Used when
+    /// the cookie has no `Domain` attribute, or names the request host
+    /// while that host is a public suffix or an IP address.
+    HostOnly(String),
+    /// To the given (canonicalized) domain and every host under it.
+    Domain(String),
+}
+
+/// Why a cookie's `Domain` attribute was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CookieError {
+    /// The request host or the `Domain` attribute is empty or malformed.
+    InvalidHost,
+    /// The request host does not domain-match the `Domain` attribute.
+    NotDomainMatch,
+    /// The `Domain` attribute is a public suffix other than the request
+    /// host.
+    PublicSuffix,
+}
+
+impl fmt::Display for CookieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CookieError::InvalidHost => "invalid host or cookie domain",
+            CookieError::NotDomainMatch => "request host does not domain-match the cookie domain",
+            CookieError::PublicSuffix => "cookie domain is a public suffix",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CookieError {}
+
+/// Returns true if `host` domain-matches `domain` (RFC 6265, section
+/// 5.1.3): they are equal, or `domain` is a suffix of `host` preceded by a
+/// dot and `host` is not an IP address. Both must already be canonicalized.
+pub fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host
+            .strip_suffix(domain)
+            .is_some_and(|head| head.ends_with('.'))
+            && !is_ip_literal(host)
+            && !host.contains(':'))
+}
+
+impl List {
+    /// Checks the `Domain` attribute of a cookie set by `request_host`.
+    ///
+    /// `cookie_domain` is the attribute value as sent, or an empty string if
+    /// the cookie has none; a leading dot is ignored. Both names are
+    /// canonicalized with `opts.normalizer` before comparison. Follows the
+    /// storage model of RFC 6265, section 5.3, steps 5 and 6, with
+    /// `opts` deciding which rules count as public suffixes.
+    ///
+    /// ```rust
+    /// use publicsuffix2::cookies::{CookieError, CookieScope};
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list: List = "uk\nco.uk".parse().unwrap();
+    /// let m = MatchOpts::default();
+    /// assert_eq!(
+    ///     list.cookie_domain("www.example.co.uk", ".Example.co.uk", m),
+    ///     Ok(CookieScope::Domain("example.co.uk".into()))
+    /// );
+    /// assert_eq!(
+    ///     list.cookie_domain("www.example.co.uk", "co.uk", m),
+    ///     Err(CookieError::PublicSuffix)
+    /// );
+    /// ```
+    pub fn cookie_domain(
+        &self,
+        request_host: &str,
+        cookie_domain: &str,
+        opts: MatchOpts<'_>,
+    ) -> Result<CookieScope, CookieError> {
+        let host = canonical(request_host, opts).ok_or(CookieError::InvalidHost)?;
+        if cookie_domain.is_empty() {
+            return Ok(CookieScope::HostOnly(host.into_owned()));
+        }
+        let attr = cookie_domain.strip_prefix('.').unwrap_or(cookie_domain);
+        let domain = canonical(attr, opts).ok_or(CookieError::InvalidHost)?;
+        if is_ip_literal(&host) || host.contains(':') {
+            return if host == domain {
+                Ok(CookieScope::HostOnly(host.into_owned()))
+            } else {
+                Err(CookieError::NotDomainMatch)
+            };
+        }
+        if self.is_public_suffix(&domain, opts) {
+            // A public suffix may only set a host-only cookie on itself.
+            return if host == domain {
+                Ok(CookieScope::HostOnly(host.into_owned()))
+            } else {
+                Err(CookieError::PublicSuffix)
+            };
+        }
+        if !domain_match(&host, &domain) {
+            return Err(CookieError::NotDomainMatch);
+        }
+        Ok(CookieScope::Domain(domain.into_owned()))
+    }
+
+    /// Returns true if a cookie from `request_host` may carry the `Domain`
+    /// attribute `cookie_domain`; see [`List::cookie_domain`].
+    pub fn cookie_domain_ok(
+        &self,
+        request_host: &str,
+        cookie_domain: &str,
+        opts: MatchOpts<'_>,
+    ) -> bool {
+        self.cookie_domain(request_host, cookie_domain, opts)
+            .is_ok()
+    }
+}
+
+/// `name` normalized for comparison, or `None` if it is empty or rejected.
+fn canonical<'a>(name: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+    normalize_view(name, opts).filter(|n| !n.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list() -> List {
+        "com\nuk\nco.uk\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn domain_match_follows_rfc() {
+        assert!(domain_match("example.com", "example.com"));
+        assert!(domain_match("www.example.com", "example.com"));
+        assert!(!domain_match("wwwexample.com", "example.com"));
+        assert!(!domain_match("example.com", "www.example.com"));
+        assert!(!domain_match("1.2.3.4", "2.3.4"));
+        assert!(!domain_match("[::1]", ":1]"));
+    }
+
+    #[test]
+    fn accepts_parent_domains() {
+        let l = list();
+        let m = MatchOpts::default();
+        for (host, attr, scope) in [
+            (
+                "www.example.com",
+                "",
+                CookieScope::HostOnly("www.example.com".into()),
+            ),
+            (
+                "www.example.com",
+                "example.com",
+                CookieScope::Domain("example.com".into()),
+            ),
+            (
+                "WWW.Example.com",
+                ".EXAMPLE.COM",
+                CookieScope::Domain("example.com".into()),
+            ),
+            (
+                "a.b.example.co.uk",
+                "b.example.co.uk",
+                CookieScope::Domain("b.example.co.uk".into()),
+            ),
+            ("co.uk", "co.uk", CookieScope::HostOnly("co.uk".into())),
+            (
+                "github.io",
+                ".github.io",
+                CookieScope::HostOnly("github.io".into()),
+            ),
+            (
+                "10.0.0.1",
+                "10.0.0.1",
+                CookieScope::HostOnly("10.0.0.1".into()),
+            ),
+        ] {
+            assert_eq!(l.cookie_domain(host, attr, m), Ok(scope), "{host} {attr}");
+        }
+    }
+
+    #[test]
+    fn rejects_supercookies_and_mismatches() {
+        let l = list();
+        let m = MatchOpts::default();
+        for (host, attr, err) in [
+            ("www.example.co.uk", "co.uk", CookieError::PublicSuffix),
+            ("www.example.com", ".com", CookieError::PublicSuffix),
+            ("user.github.io", "github.io", CookieError::PublicSuffix),
+            ("www.example.com", "other.com", CookieError::NotDomainMatch),
+            (
+                "example.com",
+                "www.example.com",
+                CookieError::NotDomainMatch,
+            ),
+            ("badexample.com", "example.com", CookieError::NotDomainMatch),
+            ("10.0.0.1", "0.0.1", CookieError::NotDomainMatch),
+            ("", "example.com", CookieError::InvalidHost),
+            ("www.example.com", ".", CookieError::InvalidHost),
+        ] {
+            assert_eq!(l.cookie_domain(host, attr, m), Err(err), "{host} {attr}");
+            assert!(!l.cookie_domain_ok(host, attr, m));
+        }
+    }
+}
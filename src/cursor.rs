@@ -0,0 +1,225 @@
+//! Incremental, label-at-a-time suffix matching.
+//!
+//! A [`SuffixCursor`] walks the rule trie as labels arrive right-to-left
+//! (`"uk"`, then `"co"`, ...), reporting after each step whether the labels
+//! seen so far form a public suffix and whether more labels could still
+//! change the answer. This suits parsers that decode names incrementally
+//! (DNS wire format, tokenizers) and do not want to assemble a host first.
+use crate::engine::{accept_type, Trie};
+use crate::options::MatchOpts;
+use crate::rules::{Leaf, Node, TypeFilter};
+use crate::{List, Store};
+
+/// What the labels pushed so far mean for suffix matching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorState {
+    /// The labels pushed so far form a public suffix.
+    /// Longer suffixes may still exist; see [`SuffixCursor::can_extend`].
+    Suffix,
+    /// The labels are on a rule path but are not themselves a suffix.
+    /// Pushing more labels may reach one.
+    Partial,
+    /// No rule matches this path or any extension of it. The suffix found
+    /// so far (if any) is final.
+    Terminal,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Pos<'l> {
+    Trie(&'l Node),
+    Flat(u32),
+    #[cfg(feature = "fst")]
+    Compact(crate::compact::CompactNode),
+    Off,
+}
+
+/// Walks a `List`'s rules one label at a time, right to left.
+///
+/// Labels are matched as given: apply lowercasing or IDNA mapping before
+/// pushing them. Wildcards and the section filter follow the `MatchOpts`
+/// the cursor was created with; the normalizer, `ignored_private`,
+/// `controls` and `literals` are not applied. Neither are the rule options:
+/// a wildcard matches exactly one label whatever `deep_wildcards` says,
+/// exception rules always apply even with `exceptions` off, and when no
+/// rule matches, [`suffix_depth`](Self::suffix_depth) stays 0 rather than
+/// following `fallback`.
+#[derive(Clone, Debug)]
+pub struct SuffixCursor<'l> {
+    list: &'l List,
+    wildcard: bool,
+    types: TypeFilter,
+    pos: Pos<'l>,
+    depth: usize,
+    suffix_depth: usize,
+}
+
+impl<'l> SuffixCursor<'l> {
+    pub(crate) fn new(list: &'l List, opts: MatchOpts<'_>) -> Self {
+        Self {
+            list,
+            wildcard: opts.wildcard,
+            types: opts.types,
+            pos: root(list),
+            depth: 0,
+            suffix_depth: 0,
+        }
+    }
+
+    /// Consumes the next label to the left and reports the new state.
+    pub fn push_label(&mut self, label: &str) -> CursorState {
+        self.depth += 1;
+        let wildcard = self.wildcard;
+        let (next, leaf_typ) = match (&*self.list.store, self.pos) {
+            (Store::Trie(rs), Pos::Trie(n)) => {
+                let next = step(rs, n, label, wildcard);
+                (next.map(Pos::Trie), next.map(|c| (rs.leaf(c), rs.typ(c))))
+            }
+            (Store::Flat(f), Pos::Flat(n)) => {
+                let next = step(f, n, label, wildcard);
+                (next.map(Pos::Flat), next.map(|c| (f.leaf(c), f.typ(c))))
+            }
+            #[cfg(feature = "fst")]
+            (Store::Compact(t), Pos::Compact(n)) => {
+                let next = step(t, n, label, wildcard);
+                (next.map(Pos::Compact), next.map(|c| (t.leaf(c), t.typ(c))))
+            }
+            _ => (None, None),
+        };
+        self.pos = next.unwrap_or(Pos::Off);
+
+        if let Some((leaf, typ)) = leaf_typ {
+            if accept_type(typ, self.types) {
+                self.suffix_depth = match leaf {
+                    Leaf::Positive => self.depth,
+                    // An exception makes its parent the suffix.
+                    Leaf::Negative => self.depth - 1,
+                    Leaf::None => self.suffix_depth,
+                };
+            }
+        }
+        self.state()
+    }
+
+    /// The state after the labels pushed so far.
+    pub fn state(&self) -> CursorState {
+        if matches!(self.pos, Pos::Off) {
+            CursorState::Terminal
+        } else if self.depth > 0 && self.suffix_depth == self.depth {
+            CursorState::Suffix
+        } else if self.can_extend() {
+            CursorState::Partial
+        } else {
+            CursorState::Terminal
+        }
+    }
+
+    /// Returns true if pushing more labels could match a longer rule.
+    pub fn can_extend(&self) -> bool {
+        match (&*self.list.store, self.pos) {
+            (Store::Trie(rs), Pos::Trie(n)) => !rs.is_childless(n),
+            (Store::Flat(f), Pos::Flat(n)) => !f.is_childless(n),
+            #[cfg(feature = "fst")]
+            (Store::Compact(t), Pos::Compact(n)) => !t.is_childless(n),
+            _ => false,
+        }
+    }
+
+    /// Number of labels pushed so far.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Number of labels in the longest public suffix seen so far (0 if none).
+    pub fn suffix_depth(&self) -> usize {
+        self.suffix_depth
+    }
+
+    /// Resets the cursor to the root, as if no label had been pushed.
+    pub fn reset(&mut self) {
+        self.pos = root(self.list);
+        self.depth = 0;
+        self.suffix_depth = 0;
+    }
+}
+
+fn root(list: &List) -> Pos<'_> {
+    match &*list.store {
+        Store::Trie(rs) => Pos::Trie(rs.root()),
+        Store::Flat(f) => Pos::Flat(f.root()),
+        #[cfg(feature = "fst")]
+        Store::Compact(t) => Pos::Compact(t.root()),
+    }
+}
+
+fn step<'a, T: Trie>(
+    trie: &'a T,
+    node: T::Node<'a>,
+    label: &str,
+    wildcard: bool,
+) -> Option<T::Node<'a>> {
+    trie.child(node, label).or_else(|| {
+        if wildcard {
+            trie.child(node, "*")
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Engine;
+
+    fn list() -> List {
+        "com\nuk\nco.uk\n*.ck\n!www.ck\nkawasaki.jp\n*.kawasaki.jp\n!city.kawasaki.jp"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn walks_multi_label_suffix() {
+        let list = list();
+        let mut c = list.cursor(MatchOpts::default());
+        assert_eq!(c.push_label("uk"), CursorState::Suffix);
+        assert!(c.can_extend());
+        assert_eq!(c.push_label("co"), CursorState::Suffix);
+        assert!(!c.can_extend());
+        assert_eq!(c.push_label("example"), CursorState::Terminal);
+        assert_eq!(c.suffix_depth(), 2);
+        assert_eq!(c.depth(), 3);
+    }
+
+    #[test]
+    fn wildcard_and_exception() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let mut c = list.cursor(MatchOpts::default());
+            assert_eq!(c.push_label("ck"), CursorState::Partial);
+            assert_eq!(c.push_label("anything"), CursorState::Suffix);
+
+            c.reset();
+            c.push_label("ck");
+            assert_eq!(c.push_label("www"), CursorState::Terminal);
+            assert_eq!(c.suffix_depth(), 1);
+
+            c.reset();
+            c.push_label("jp");
+            assert_eq!(c.state(), CursorState::Partial);
+            assert_eq!(c.push_label("kawasaki"), CursorState::Suffix);
+            assert_eq!(c.push_label("city"), CursorState::Terminal);
+            assert_eq!(c.suffix_depth(), 2);
+        }
+    }
+
+    #[test]
+    fn wildcard_disabled() {
+        let list = list();
+        let mut c = list.cursor(MatchOpts {
+            wildcard: false,
+            ..MatchOpts::default()
+        });
+        c.push_label("ck");
+        assert_eq!(c.push_label("anything"), CursorState::Terminal);
+        assert_eq!(c.suffix_depth(), 0);
+    }
+}
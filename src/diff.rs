@@ -0,0 +1,273 @@
+//! Comparing two versions of a list.
+//!
+//! [`List::diff`] enumerates the rules an update adds, removes or moves to
+//! another section, for monitoring the list without diffing its text.
+//!
+//! A PSL update can also move the registrable domain of hosts that are
+//! already stored somewhere: cookie jars, analytics tables and rate-limit
+//! buckets keyed by eTLD+1. [`ListDiff::affected_hosts`] re-evaluates such a
+//! corpus against both lists and reports the hosts that need re-keying.
+//!
+//! Lists also compare with `==`, which holds when they have the same rules
+//! in the same sections, and [`List::is_subset_of`].
+use crate::options::MatchOpts;
+use crate::rules::{RuleRef, Type};
+use crate::List;
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::cmp::Ordering;
+
+/// Two lists to compare, typically an installed list and its update, and
+/// the rules that differ between them.
+///
+/// Rules are compared as loaded, so a rule whose kind changes (`a.ck` to
+/// `!a.ck`) is both removed and added. Each vector is sorted as
+/// [`List::rules`] is.
+#[derive(Clone, Debug)]
+pub struct ListDiff<'a> {
+    old: &'a List,
+    new: &'a List,
+    /// Rules only in the new list.
+    pub added: Vec<RuleRef>,
+    /// Rules only in the old list.
+    pub removed: Vec<RuleRef>,
+    /// Rules in both lists whose section changed.
+    pub changed_type: Vec<TypeChange>,
+}
+
+/// A rule that moved to another section between two lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeChange {
+    /// The rule, with its section in the new list.
+    pub rule: RuleRef,
+    /// Its section in the old list.
+    pub old_type: Option<Type>,
+}
+
+impl List {
+    /// Compares `self`, the old list, with `new`; see [`ListDiff`].
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, Type};
+    ///
+    /// let old: List = "// ===BEGIN PRIVATE DOMAINS===\nexample.io\ngone.io".parse().unwrap();
+    /// let new: List = "// ===BEGIN PRIVATE DOMAINS===\nexample.io\nbrand.io".parse().unwrap();
+    /// let diff = List::diff(&old, &new);
+    /// assert_eq!(diff.added[0].to_string(), "brand.io");
+    /// assert_eq!(diff.added[0].typ, Some(Type::Private));
+    /// assert_eq!(diff.removed[0].to_string(), "gone.io");
+    /// assert!(diff.changed_type.is_empty());
+    /// ```
+    pub fn diff<'a>(&'a self, new: &'a List) -> ListDiff<'a> {
+        ListDiff::new(self, new)
+    }
+
+    /// Returns true if every rule of `self` is in `other`, in the same
+    /// section.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let small: List = "uk\nco.uk".parse().unwrap();
+    /// let big: List = "com\nuk\nco.uk".parse().unwrap();
+    /// assert!(small.is_subset_of(&big));
+    /// assert!(!big.is_subset_of(&small));
+    /// ```
+    pub fn is_subset_of(&self, other: &List) -> bool {
+        let diff = self.diff(other);
+        diff.removed.is_empty() && diff.changed_type.is_empty()
+    }
+}
+
+/// Lists are equal when they have the same rules in the same sections,
+/// whatever the engine or the order the rules were written in. Comments,
+/// metadata and load warnings are not compared.
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.store, &other.store) || self.rules().eq(other.rules())
+    }
+}
+
+impl Eq for List {}
+
+/// A host whose registrable domain differs between two lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SldChange {
+    /// The host as given.
+    pub host: String,
+    /// Registrable domain under the old list.
+    pub old_sld: Option<String>,
+    /// Registrable domain under the new list.
+    pub new_sld: Option<String>,
+}
+
+impl<'a> ListDiff<'a> {
+    /// Compares `old` with `new`.
+    pub fn new(old: &'a List, new: &'a List) -> Self {
+        let keyed = |list: &List| -> Vec<(String, RuleRef)> {
+            list.rules().map(|r| (r.to_string(), r)).collect()
+        };
+        let (before, after) = (keyed(old), keyed(new));
+        let mut diff = Self {
+            old,
+            new,
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed_type: Vec::new(),
+        };
+        let (mut before, mut after) = (before.into_iter().peekable(), after.into_iter().peekable());
+        loop {
+            let order = match (before.peek(), after.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((a, _)), Some((b, _))) => a.cmp(b),
+            };
+            match order {
+                Ordering::Less => diff.removed.extend(before.next().map(|(_, r)| r)),
+                Ordering::Greater => diff.added.extend(after.next().map(|(_, r)| r)),
+                Ordering::Equal => {
+                    let (_, was) = before.next().unwrap();
+                    let (_, rule) = after.next().unwrap();
+                    if was.typ != rule.typ {
+                        diff.changed_type.push(TypeChange {
+                            rule,
+                            old_type: was.typ,
+                        });
+                    }
+                }
+            }
+        }
+        diff
+    }
+
+    /// Returns true if both lists have the same rules in the same sections.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed_type.is_empty()
+    }
+
+    /// The list being replaced.
+    pub fn old(&self) -> &'a List {
+        self.old
+    }
+
+    /// The replacement list.
+    pub fn new_list(&self) -> &'a List {
+        self.new
+    }
+
+    /// Hosts whose registrable domain ([`List::sld`]) changes, in input order.
+    ///
+    /// Each host is looked up once in each list with `opts`.
+    pub fn affected_hosts<'h, I>(&self, hosts: I, opts: MatchOpts<'_>) -> Vec<SldChange>
+    where
+        I: IntoIterator<Item = &'h str>,
+    {
+        hosts
+            .into_iter()
+            .filter_map(|host| {
+                let old_sld = self.old.sld(host, opts);
+                let new_sld = self.new.sld(host, opts);
+                (old_sld != new_sld).then(|| SldChange {
+                    host: host.into(),
+                    old_sld: old_sld.map(|s| s.into_owned()),
+                    new_sld: new_sld.map(|s| s.into_owned()),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_only_changed_hosts() {
+        let old: List = "com\nio\nuk".parse().unwrap();
+        let new: List = "com\nio\nuk\nco.uk\ngithub.io".parse().unwrap();
+        let diff = ListDiff::new(&old, &new);
+        let hosts = [
+            "www.example.com",
+            "shop.example.co.uk",
+            "alice.github.io",
+            "github.io",
+            "",
+        ];
+        let changes = diff.affected_hosts(hosts, MatchOpts::default());
+        assert_eq!(
+            changes,
+            [
+                SldChange {
+                    host: "shop.example.co.uk".into(),
+                    old_sld: Some("co.uk".into()),
+                    new_sld: Some("example.co.uk".into()),
+                },
+                SldChange {
+                    host: "alice.github.io".into(),
+                    old_sld: Some("github.io".into()),
+                    new_sld: Some("alice.github.io".into()),
+                },
+            ]
+        );
+        assert!(ListDiff::new(&new, &new)
+            .affected_hosts(hosts, MatchOpts::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn rule_changes_are_sorted_and_classified() {
+        let old: List = "// ===BEGIN ICANN DOMAINS===\ncom\n*.ck\na.ck\n\
+                         // ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\n\
+                         moved.com\nold.com\n"
+            .parse()
+            .unwrap();
+        let new: List = "// ===BEGIN ICANN DOMAINS===\ncom\n*.ck\n!a.ck\nmoved.com\n\
+                         // ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\n\
+                         z.com\nb.com\n"
+            .parse()
+            .unwrap();
+        let diff = old.diff(&new);
+        let names =
+            |rules: &[RuleRef]| -> Vec<String> { rules.iter().map(|r| r.to_string()).collect() };
+        assert_eq!(names(&diff.added), ["!a.ck", "b.com", "z.com"]);
+        assert_eq!(names(&diff.removed), ["a.ck", "old.com"]);
+        assert_eq!(diff.changed_type.len(), 1);
+        let change = &diff.changed_type[0];
+        assert_eq!(change.rule.to_string(), "moved.com");
+        assert_eq!(
+            (change.old_type, change.rule.typ),
+            (Some(Type::Private), Some(Type::Icann))
+        );
+        assert!(!diff.is_empty());
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn equality_compares_rules_and_sections() {
+        let text = "// ===BEGIN ICANN DOMAINS===\ncom\n*.ck\n!www.ck\n\
+                    // ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n";
+        let list: List = text.parse().unwrap();
+        assert_eq!(list, list.clone());
+        assert_eq!(list, list.clone().with_engine(crate::Engine::Flat));
+        let reordered: List = "// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n\
+                               // ===END PRIVATE DOMAINS===\n// ===BEGIN ICANN DOMAINS===\n\
+                               !www.ck\n// a comment\n*.ck\ncom\n"
+            .parse()
+            .unwrap();
+        assert_eq!(list, reordered);
+
+        // Same rules, but github.io moved to the ICANN section.
+        let moved: List = "com\n*.ck\n!www.ck\ngithub.io".parse().unwrap();
+        assert_ne!(list, moved);
+        assert!(!moved.is_subset_of(&list));
+
+        let smaller: List = "// ===BEGIN ICANN DOMAINS===\ncom\n*.ck\n".parse().unwrap();
+        assert!(smaller.is_subset_of(&list));
+        assert!(!list.is_subset_of(&smaller));
+        assert!(list.is_subset_of(&reordered));
+    }
+}
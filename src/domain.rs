@@ -0,0 +1,307 @@
+//! Domain values built from lookup results.
+use crate::rules::Type;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::fmt;
+
+/// A host split around its registration boundary.
+///
+/// Built by [`List::domain`](crate::List::domain), which runs the lookup
+/// once; the accessors only slice the stored host. Borrows the host when
+/// normalization left it unchanged; [`into_owned`](Self::into_owned) lifts
+/// that restriction. Equality and hashing cover the host and how it was
+/// split, so a `Domain` can key a map directly.
+///
+/// ```rust
+/// use publicsuffix2::{List, MatchOpts};
+///
+/// let list: List = "uk\nco.uk".parse().unwrap();
+/// let d = list.domain("www.shop.example.co.uk", MatchOpts::default()).unwrap();
+/// assert_eq!(d.suffix(), "co.uk");
+/// assert_eq!(d.registrable(), Some("example.co.uk"));
+/// assert_eq!(d.subdomain(), Some("www.shop"));
+/// assert_eq!(d.to_string(), "www.shop.example.co.uk");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Domain<'a> {
+    host: Cow<'a, str>,
+    suffix_start: usize,
+    registrable_start: Option<usize>,
+    typ: Option<Type>,
+}
+
+impl<'a> Domain<'a> {
+    pub(crate) fn new(
+        host: Cow<'a, str>,
+        suffix_start: usize,
+        registrable_start: Option<usize>,
+        typ: Option<Type>,
+    ) -> Self {
+        Self {
+            host,
+            suffix_start,
+            registrable_start,
+            typ,
+        }
+    }
+
+    /// The whole host, normalized as the lookup saw it.
+    pub fn as_str(&self) -> &str {
+        &self.host
+    }
+
+    /// The public suffix, e.g. `co.uk`.
+    pub fn suffix(&self) -> &str {
+        &self.host[self.suffix_start..]
+    }
+
+    /// The registrable domain: the suffix plus one label.
+    ///
+    /// `None` when the host is itself a public suffix. Unlike
+    /// [`List::sld`](crate::List::sld), a host under an unlisted TLD keeps its
+    /// label (`example.invalid`).
+    pub fn registrable(&self) -> Option<&str> {
+        self.registrable_start.map(|i| &self.host[i..])
+    }
+
+    /// Everything left of the registrable domain, e.g. `www.shop`.
+    pub fn subdomain(&self) -> Option<&str> {
+        let end = self.registrable_start?.checked_sub(1)?;
+        Some(&self.host[..end])
+    }
+
+    /// The labels of the host, left to right.
+    pub fn labels(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.host.split('.')
+    }
+
+    /// Section of the rule that set the suffix; `None` for unclassified
+    /// rules and unlisted TLDs.
+    pub fn typ(&self) -> Option<Type> {
+        self.typ
+    }
+
+    /// Returns true if the suffix comes from the ICANN section.
+    pub fn is_icann(&self) -> bool {
+        self.typ == Some(Type::Icann)
+    }
+
+    /// Returns true if the suffix comes from the private section.
+    pub fn is_private(&self) -> bool {
+        self.typ == Some(Type::Private)
+    }
+
+    /// Converts a `Domain<'a>` into a `Domain<'static>` by cloning the host
+    /// if it is borrowed.
+    pub fn into_owned(self) -> Domain<'static> {
+        Domain {
+            host: Cow::Owned(self.host.into_owned()),
+            suffix_start: self.suffix_start,
+            registrable_start: self.registrable_start,
+            typ: self.typ,
+        }
+    }
+}
+
+impl fmt::Display for Domain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.host)
+    }
+}
+
+/// A registrable domain (eTLD+1) together with the public suffix it was
+/// found under.
+///
+/// Built by [`List::registrable`](crate::List::registrable). Equality and
+/// hashing compare the name as returned by the lookup; use
+/// [`canonical_key`](Self::canonical_key) when names from differently
+/// normalized sources must compare equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RegistrableDomain {
+    name: String,
+    suffix_start: usize,
+}
+
+impl RegistrableDomain {
+    /// Takes the registrable domain of `domain`: its suffix plus one label,
+    /// as [`Domain::registrable`] gives it.
+    pub(crate) fn from_domain(domain: &Domain<'_>) -> Option<Self> {
+        let name = domain.registrable()?;
+        let suffix_start = name.len().checked_sub(domain.suffix().len())?;
+        Some(Self {
+            name: name.into(),
+            suffix_start,
+        })
+    }
+
+    /// The registrable domain, e.g. `example.co.uk`.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The public suffix, e.g. `co.uk`.
+    pub fn suffix(&self) -> &str {
+        &self.name[self.suffix_start..]
+    }
+
+    /// The label left of the suffix, e.g. `example`.
+    pub fn label(&self) -> &str {
+        let end = self.suffix_start.saturating_sub(1);
+        &self.name[..end]
+    }
+
+    /// A normalized byte key for storage and deduplication.
+    ///
+    /// The key is the name with any trailing dot removed, lowercased and,
+    /// with the `idna` feature, converted to IDNA ASCII (UTS #46) so that
+    /// Unicode look-alikes that map to the same A-labels (full-width forms,
+    /// different case or normalization) share a key. Names that fail IDNA
+    /// conversion, and non-ASCII names without the `idna` feature, keep
+    /// their lowercased UTF-8 bytes.
+    ///
+    /// The key format is stable: the same name yields the same bytes in
+    /// every release of this crate with the same `idna` setting, and a
+    /// change would be a breaking release. Keys are independent of the
+    /// `List` and `MatchOpts` used for the lookup.
+    pub fn canonical_key(&self) -> Vec<u8> {
+        let name = self.name.strip_suffix('.').unwrap_or(&self.name);
+        #[cfg(feature = "idna")]
+        if !name.is_ascii() && crate::engine::idna_candidate(name) {
+            if let Ok(ascii) = idna::domain_to_ascii(name) {
+                return ascii.into_bytes();
+            }
+        }
+        name.to_lowercase().into_bytes()
+    }
+
+    /// Returns the name as a `String`.
+    pub fn into_string(self) -> String {
+        self.name
+    }
+}
+
+impl fmt::Display for RegistrableDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl AsRef<str> for RegistrableDomain {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::MatchOpts;
+    use crate::List;
+
+    fn list() -> List {
+        "com\nuk\nco.uk\n中国".parse().unwrap()
+    }
+
+    #[test]
+    fn domain_accessors() {
+        let l: List = "// ===BEGIN ICANN DOMAINS===\ncom\nuk\nco.uk\n\
+                       // ===BEGIN PRIVATE DOMAINS===\ngithub.io"
+            .parse()
+            .unwrap();
+        let m = MatchOpts::default();
+        let d = l.domain("A.B.Example.CO.UK", m).unwrap();
+        assert_eq!(d.as_str(), "a.b.example.co.uk");
+        assert_eq!(d.suffix(), "co.uk");
+        assert_eq!(d.registrable(), Some("example.co.uk"));
+        assert_eq!(d.subdomain(), Some("a.b"));
+        assert_eq!(
+            d.labels().collect::<Vec<_>>(),
+            ["a", "b", "example", "co", "uk"]
+        );
+        assert!(d.is_icann() && !d.is_private());
+
+        let d = l.domain("alice.github.io", m).unwrap();
+        assert!(d.is_private());
+        assert_eq!(d.subdomain(), None);
+
+        let d = l.domain("co.uk", m).unwrap();
+        assert_eq!((d.registrable(), d.subdomain()), (None, None));
+
+        let d = l.domain("x.example.invalid", m).unwrap();
+        assert_eq!(d.registrable(), Some("example.invalid"));
+        assert_eq!(d.typ(), None);
+        assert!(l.domain("", m).is_none());
+    }
+
+    #[test]
+    fn domain_as_map_key() {
+        let l = list();
+        let m = MatchOpts::default();
+        let mut seen = std::collections::HashMap::new();
+        for host in ["www.example.com", "WWW.example.com", "api.example.com"] {
+            *seen
+                .entry(l.domain(host, m).unwrap().into_owned())
+                .or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[&l.domain("www.example.com", m).unwrap()], 2);
+    }
+
+    #[test]
+    fn parts_of_registrable_domain() {
+        let d = list()
+            .registrable("www.example.co.uk", MatchOpts::default())
+            .unwrap();
+        assert_eq!(d.as_str(), "example.co.uk");
+        assert_eq!(d.suffix(), "co.uk");
+        assert_eq!(d.label(), "example");
+        assert_eq!(d.to_string(), "example.co.uk");
+        assert!(list().registrable("co.uk", MatchOpts::default()).is_none());
+    }
+
+    #[test]
+    fn registrable_domain_under_unlisted_tld() {
+        let list = list();
+        let m = MatchOpts::default();
+        let d = list.registrable("www.example.local", m).unwrap();
+        assert_eq!(
+            (d.as_str(), d.suffix(), d.label()),
+            ("example.local", "local", "example")
+        );
+        let d = list.registrable("example.local", m).unwrap();
+        assert_eq!(d.as_str(), "example.local");
+        assert_eq!(
+            Some(d.as_str()),
+            list.domain("example.local", m).unwrap().registrable()
+        );
+        assert!(list.registrable("local", m).is_none());
+    }
+
+    #[test]
+    fn canonical_key_ignores_case_and_trailing_dot() {
+        let domain = Domain::new("Example.COM.".into(), 8, Some(0), None);
+        let a = RegistrableDomain::from_domain(&domain).unwrap();
+        let b = list()
+            .registrable("www.example.com", MatchOpts::default())
+            .unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.suffix(), "COM.");
+        assert_eq!(a.canonical_key(), b"example.com");
+        assert_eq!(a.canonical_key(), b.canonical_key());
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn canonical_key_uses_a_labels() {
+        let raw = MatchOpts {
+            normalizer: None,
+            ..MatchOpts::default()
+        };
+        // Full-width and case variants map to the same A-labels.
+        let a = list().registrable("ＥＸＡＭＰＬＥ.中国", raw).unwrap();
+        let b = list()
+            .registrable("example.中国", MatchOpts::default())
+            .unwrap();
+        assert_eq!(a.canonical_key(), b"example.xn--fiqs8s");
+        assert_eq!(a.canonical_key(), b.canonical_key());
+    }
+}
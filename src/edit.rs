@@ -0,0 +1,194 @@
+//! Adding and removing rules on a loaded [`List`].
+//!
+//! Clones of a `List` share their rules. The first change made through one
+//! clone copies the rules for it, so the others, and lookups running on
+//! them, are unaffected.
+use crate::builder;
+use crate::errors::Result;
+use crate::loader;
+use crate::rules::{Labels, Leaf, Node, RuleSet, Type};
+use crate::{List, Store};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+
+impl List {
+    /// Adds `rule`, written as in the PSL (`co.uk`, `*.ck`, `!www.ck`), to
+    /// section `typ`.
+    ///
+    /// The rule is lowercased and checked like [`ListBuilder`] rules; an
+    /// invalid rule is returned as `Error::InvalidRule` (or
+    /// `Error::LabelTooLong`) and leaves the list unchanged. A rule already
+    /// at the same labels is replaced, kind and section included, and
+    /// `Ok(true)` is returned.
+    ///
+    /// A list in another engine is converted to [`Engine::Trie`] by its
+    /// first change; call [`with_engine`](Self::with_engine) after a batch
+    /// of changes to compile it again.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts, Type};
+    ///
+    /// let base: List = "com".parse().unwrap();
+    /// let mut tenant = base.clone();
+    /// assert!(!tenant.insert_rule("customer.com", Some(Type::Private)).unwrap());
+    /// let m = MatchOpts::default();
+    /// assert_eq!(tenant.sld("a.b.customer.com", m).as_deref(), Some("b.customer.com"));
+    /// assert_eq!(base.sld("a.b.customer.com", m).as_deref(), Some("customer.com"));
+    /// ```
+    ///
+    /// [`ListBuilder`]: crate::ListBuilder
+    /// [`Engine::Trie`]: crate::Engine::Trie
+    pub fn insert_rule(&mut self, rule: &str, typ: Option<Type>) -> Result<bool> {
+        let (neg, name) = split_rule(rule);
+        builder::validate(name, neg)?;
+        let name = name.to_lowercase();
+        let rules = self.rules_mut();
+        let existed = rules.node_at(&name).is_some_and(|n| n.leaf != Leaf::None);
+        loader::insert_rule(rules, &name, typ, neg);
+        self.recount();
+        Ok(existed)
+    }
+
+    /// Removes `rule`, written as in the PSL, returning whether it was in
+    /// the list.
+    ///
+    /// Only a rule of the same kind is removed: `!www.ck` removes the
+    /// exception and leaves a positive `www.ck` in place. The rule's
+    /// annotations and comment block go with it. Engines are handled as by
+    /// [`insert_rule`](Self::insert_rule).
+    pub fn remove_rule(&mut self, rule: &str) -> bool {
+        let (neg, name) = split_rule(rule);
+        let name = name.to_lowercase();
+        let leaf = if neg { Leaf::Negative } else { Leaf::Positive };
+        if self.rule_set().node_at(&name).map(|n| n.leaf) != Some(leaf) {
+            return false;
+        }
+        let rules = self.rules_mut();
+        for form in forms(&name) {
+            let path: Vec<&str> = form.rsplit('.').collect();
+            remove(&mut rules.root, &rules.labels, &path, leaf);
+        }
+        self.recount();
+        let written = if neg { format!("!{name}") } else { name };
+        self.meta.retain(|m| m.rule != written);
+        if self.comments.by_rule.contains_key(&written) {
+            Arc::make_mut(&mut self.comments).by_rule.remove(&written);
+        }
+        true
+    }
+
+    /// The rules of this list for changing, copied first if they are shared
+    /// or held by another engine.
+    fn rules_mut(&mut self) -> &mut RuleSet {
+        if !matches!(*self.store, Store::Trie(_)) {
+            self.store = Arc::new(Store::Trie(self.rule_set().into_owned()));
+        }
+        match Arc::make_mut(&mut self.store) {
+            Store::Trie(rules) => rules,
+            _ => unreachable!("converted above"),
+        }
+    }
+}
+
+/// Splits the `!` of an exception off `rule`.
+fn split_rule(rule: &str) -> (bool, &str) {
+    match rule.strip_prefix('!') {
+        Some(name) => (true, name),
+        None => (false, rule),
+    }
+}
+
+/// `rule` and, when IDNA is enabled and it differs, its A-label form, as
+/// [`loader::insert_rule`] stores them.
+fn forms(rule: &str) -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut forms = alloc::vec![String::from(rule)];
+    #[cfg(feature = "idna")]
+    if rule.bytes().any(|b| b >= 0x80) {
+        if let Ok(ascii) = idna::domain_to_ascii(rule) {
+            if ascii != rule {
+                forms.push(ascii);
+            }
+        }
+    }
+    forms
+}
+
+/// Clears the `leaf` rule at `path` (labels from the right) below `node`,
+/// dropping nodes left without rules or children. Returns whether a rule
+/// was cleared.
+fn remove(node: &mut Node, labels: &Labels, path: &[&str], leaf: Leaf) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        if node.leaf != leaf {
+            return false;
+        }
+        node.leaf = Leaf::None;
+        node.typ = None;
+        return true;
+    };
+    let Some(id) = labels.id(first) else {
+        return false;
+    };
+    let Some(kid) = node.kids.get_mut(&id) else {
+        return false;
+    };
+    let removed = remove(kid, labels, rest, leaf);
+    if removed && kid.leaf == Leaf::None && kid.kids.is_empty() {
+        node.kids.remove(&id);
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::MatchOpts;
+    use crate::Engine;
+
+    #[test]
+    fn removal_prunes_empty_nodes() {
+        let mut list: List = "com\na.b.c.com".parse().unwrap();
+        assert!(list.remove_rule("a.b.c.com"));
+        let rules = list.rule_set();
+        assert!(rules.node_at("com").unwrap().kids.is_empty());
+        assert!(!list.remove_rule("a.b.c.com"));
+    }
+
+    #[test]
+    fn changes_are_copy_on_write() {
+        let base: List = "com\n*.ck\n!www.ck".parse().unwrap();
+        let mut copy = base.clone();
+        assert!(Arc::ptr_eq(&base.store, &copy.store));
+        assert!(!copy.remove_rule("www.ck"));
+        assert!(Arc::ptr_eq(&base.store, &copy.store));
+        assert!(copy.remove_rule("!www.ck"));
+        assert!(!Arc::ptr_eq(&base.store, &copy.store));
+        let m = MatchOpts::default();
+        assert_eq!(copy.tld("www.ck", m).as_deref(), Some("www.ck"));
+        assert_eq!(base.tld("www.ck", m).as_deref(), Some("ck"));
+    }
+
+    #[test]
+    fn changes_keep_the_rule_count() {
+        let mut list: List = "com\n*.ck".parse().unwrap();
+        assert_eq!(list.len(), 2);
+        list.insert_rule("!www.ck", None).unwrap();
+        list.insert_rule("co.uk", Some(Type::Icann)).unwrap();
+        assert_eq!(list.len(), 4);
+        assert!(list.remove_rule("*.ck"));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.len(), list.rules().count());
+    }
+
+    #[test]
+    fn other_engines_become_tries() {
+        let mut list = "com".parse::<List>().unwrap().with_engine(Engine::Flat);
+        assert!(list.insert_rule("Example.COM", None).is_ok());
+        assert_eq!(list.engine(), Engine::Trie);
+        assert_eq!(
+            list.tld("a.example.com", MatchOpts::default()).as_deref(),
+            Some("example.com")
+        );
+        assert!(list.insert_rule("a..com", None).is_err());
+        assert!(list.insert_rule("!*.com", None).is_err());
+    }
+}
@@ -1,8 +1,19 @@
-use crate::options::MatchOpts;
-use crate::rules::{Leaf, Node, RuleSet, TypeFilter};
-use std::borrow::Cow;
-
-#[derive(Debug, PartialEq, Eq)]
+use crate::domain::Domain;
+use crate::errors::{HostError, MatchError};
+use crate::options::{CompatMode, FallbackPolicy, LiteralPolicy, MatchOpts, Normalizer};
+use crate::rules::{Leaf, Node, RuleSet, Type, TypeFilter};
+use crate::scratch::recycle;
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use core::ops::Range;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Represents the constituent parts of a domain name, separated according to the Public Suffix List rules.
 pub struct Parts<'a> {
     /// The part of the host that is not part of the registrable domain, if any.
@@ -20,6 +31,71 @@ pub struct Parts<'a> {
 }
 
 impl<'a> Parts<'a> {
+    /// The label immediately left of the public suffix (`sll`).
+    ///
+    /// Some ecosystems call this the "second-level domain"; this crate's
+    /// `sld` is the registrable domain instead.
+    pub fn second_level_label(&self) -> Option<&str> {
+        self.sll.as_deref()
+    }
+
+    /// The registrable domain, eTLD+1 (`sld`).
+    pub fn registrable_domain(&self) -> Option<&str> {
+        self.sld.as_deref()
+    }
+
+    /// The public suffix, eTLD (`tld`).
+    pub fn public_suffix(&self) -> &str {
+        &self.tld
+    }
+
+    /// The whole host the parts were split from, e.g. `www.example.com`.
+    ///
+    /// Borrowed from the parts unless there is a prefix to join back on.
+    pub fn fqdn(&self) -> Cow<'_, str> {
+        let sld = self.sld.as_deref().unwrap_or(&self.tld);
+        match &self.prefix {
+            Some(prefix) => Cow::Owned(format!("{prefix}.{sld}")),
+            None => Cow::Borrowed(sld),
+        }
+    }
+
+    /// The labels of the host, left to right.
+    pub fn labels(&self) -> impl Iterator<Item = &str> + '_ {
+        let sld = self.sld.as_deref().unwrap_or(&self.tld);
+        self.prefix
+            .as_deref()
+            .into_iter()
+            .chain([sld])
+            .flat_map(|part| part.split('.'))
+    }
+
+    /// Returns true if there is anything left of the registrable domain
+    /// (`www` in `www.example.com`).
+    pub fn has_subdomain(&self) -> bool {
+        self.prefix.is_some()
+    }
+
+    /// The labels left of the registrable domain, left to right: `a`, `b`
+    /// for `a.b.example.com`.
+    pub fn subdomain_labels(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.prefix
+            .as_deref()
+            .into_iter()
+            .flat_map(|prefix| prefix.split('.'))
+    }
+
+    /// Number of labels left of the registrable domain: 0 for
+    /// `example.com`, 2 for `a.b.example.com`.
+    pub fn depth_below_registrable(&self) -> usize {
+        self.subdomain_labels().count()
+    }
+
+    /// Number of labels in the public suffix: 1 for `com`, 2 for `co.uk`.
+    pub fn suffix_label_count(&self) -> usize {
+        self.tld.split('.').count()
+    }
+
     /// Converts a `Parts<'a>` into a `Parts<'static>` by cloning the internal data.
     pub fn into_owned(self) -> Parts<'static> {
         Parts {
@@ -31,119 +107,288 @@ impl<'a> Parts<'a> {
     }
 }
 
-impl RuleSet {
-    /// Splits a domain name into its constituent parts: prefix, second-level label,
-    /// registrable domain, and public suffix.
-    ///
-    /// This is the most comprehensive parsing function, returning all parts of a domain.
-    /// Behavior is controlled by `MatchOpts` (wildcards, strict mode, type filter,
-    /// normalization).
-    pub fn split<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Parts<'a>> {
-        let s = normalize_view(host, opts);
-
-        match s {
-            Cow::Borrowed(b) => {
-                let (_, tld) = self.match_tld(b, opts)?;
-                let sld_end = b.len().saturating_sub(tld.len()).saturating_sub(1);
-
-                // If public suffix covers the whole host, registrable domain equals the host.
-                if tld.len() == b.len() {
-                    return Some(Parts {
-                        prefix: None,
-                        sll: None,
-                        sld: Some(Cow::Borrowed(b)),
-                        tld: Cow::Borrowed(tld),
-                    });
-                }
+/// Writes the whole host, as [`Parts::fqdn`].
+impl fmt::Display for Parts<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = &self.prefix {
+            write!(f, "{prefix}.")?;
+        }
+        f.write_str(self.sld.as_deref().unwrap_or(&self.tld))
+    }
+}
 
-                // Unlisted-TLD fallback: when suffix is a single label *not* in the rules,
-                // collapse SLD to the TLD (e.g., "example.example" → "example", "example.local" → "local").
-                if !tld.contains('.') && !self.root.kids.contains_key(tld) {
-                    return Some(Parts {
-                        prefix: None,
-                        sll: None,
-                        sld: Some(Cow::Borrowed(tld)),
-                        tld: Cow::Borrowed(tld),
-                    });
-                }
+/// The parts of a host as byte ranges into the string given to
+/// [`List::split_indices`](crate::List::split_indices).
+///
+/// Each range covers the same text as the matching field of [`Parts`], as
+/// it appears in the input: `&host[idx.tld.clone()]` is the public suffix,
+/// in whatever case the host was written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartsIdx {
+    /// Where [`Parts::prefix`] is.
+    pub prefix: Option<Range<usize>>,
+    /// Where [`Parts::sll`] is.
+    pub sll: Option<Range<usize>>,
+    /// Where [`Parts::sld`] is.
+    pub sld: Option<Range<usize>>,
+    /// Where [`Parts::tld`] is.
+    pub tld: Range<usize>,
+}
 
-                debug_assert_eq!(b.as_bytes()[sld_end], b'.');
+/// IDNA forms of the parts, whichever form the host was given in.
+///
+/// The `_ascii` accessors return A-labels (`xn--fiqs8s`), for storage and
+/// comparison; they are `None` when the part is absent or cannot be
+/// converted. The `_unicode` accessors return U-labels (`中国`), for
+/// display; labels that do not decode are kept as they are. Parts already
+/// in the requested form are borrowed.
+#[cfg(feature = "idna")]
+impl Parts<'_> {
+    /// The prefix as A-labels.
+    pub fn prefix_ascii(&self) -> Option<Cow<'_, str>> {
+        self.prefix.as_deref().and_then(to_ascii)
+    }
 
-                let idx = b[..sld_end].rfind('.');
-                let mut start = idx.map(|i| i + 1).unwrap_or(0);
-                if start == 0 && b.as_bytes().first() == Some(&b'.') {
-                    start = 1;
-                }
+    /// The prefix as U-labels.
+    pub fn prefix_unicode(&self) -> Option<Cow<'_, str>> {
+        self.prefix.as_deref().map(to_unicode)
+    }
 
-                let prefix = idx.filter(|&i| i > 0).map(|i| Cow::Borrowed(&b[..i]));
-                let sll_slice = &b[start..sld_end];
-                let sll = if !sll_slice.is_empty() {
-                    Some(Cow::Borrowed(sll_slice))
-                } else {
-                    None
-                };
-                let sld = Some(Cow::Borrowed(&b[start..]));
-
-                Some(Parts {
-                    prefix,
-                    sll,
-                    sld,
-                    tld: Cow::Borrowed(tld),
-                })
-            }
+    /// The second-level label as an A-label.
+    pub fn sll_ascii(&self) -> Option<Cow<'_, str>> {
+        self.sll.as_deref().and_then(to_ascii)
+    }
 
-            Cow::Owned(o) => {
-                let (_, tld) = self.match_tld(&o, opts)?;
-                let sld_end = o.len().saturating_sub(tld.len()).saturating_sub(1);
-
-                // If public suffix covers the whole host, registrable domain equals the host.
-                if tld.len() == o.len() {
-                    return Some(Parts {
-                        prefix: None,
-                        sll: None,
-                        sld: Some(Cow::<str>::Owned(o.clone())),
-                        tld: Cow::<str>::Owned(tld.to_string()),
-                    });
-                }
-                if !tld.contains('.') && !self.root.kids.contains_key(tld) {
-                    return Some(Parts {
-                        prefix: None,
-                        sll: None,
-                        sld: Some(Cow::Owned(tld.to_string())),
-                        tld: Cow::Owned(tld.to_string()),
-                    });
-                }
+    /// The second-level label as a U-label.
+    pub fn sll_unicode(&self) -> Option<Cow<'_, str>> {
+        self.sll.as_deref().map(to_unicode)
+    }
 
-                debug_assert_eq!(o.as_bytes()[sld_end], b'.');
+    /// The registrable domain as A-labels.
+    pub fn sld_ascii(&self) -> Option<Cow<'_, str>> {
+        self.sld.as_deref().and_then(to_ascii)
+    }
 
-                let idx = o[..sld_end].rfind('.');
-                let mut start = idx.map(|i| i + 1).unwrap_or(0);
-                if start == 0 && o.as_bytes().first() == Some(&b'.') {
-                    start = 1;
-                }
+    /// The registrable domain as U-labels.
+    pub fn sld_unicode(&self) -> Option<Cow<'_, str>> {
+        self.sld.as_deref().map(to_unicode)
+    }
 
-                let prefix = idx
-                    .filter(|&i| i > 0)
-                    .map(|i| Cow::<str>::Owned(o[..i].to_string()));
-                let sll = {
-                    let lbl = &o[start..sld_end];
-                    if !lbl.is_empty() {
-                        Some(Cow::<str>::Owned(lbl.to_string()))
-                    } else {
-                        None
-                    }
-                };
-                let sld = Some(Cow::<str>::Owned(o[start..].to_string()));
-
-                Some(Parts {
-                    prefix,
-                    sll,
-                    sld,
-                    tld: Cow::<str>::Owned(tld.to_string()),
-                })
+    /// The public suffix as A-labels.
+    pub fn tld_ascii(&self) -> Option<Cow<'_, str>> {
+        to_ascii(&self.tld)
+    }
+
+    /// The public suffix as U-labels.
+    pub fn tld_unicode(&self) -> Cow<'_, str> {
+        to_unicode(&self.tld)
+    }
+}
+
+/// `s` as A-labels, or `None` if IDNA conversion fails.
+#[cfg(feature = "idna")]
+fn to_ascii(s: &str) -> Option<Cow<'_, str>> {
+    if s.is_ascii() {
+        return Some(Cow::Borrowed(s));
+    }
+    if !idna_candidate(s) {
+        return None;
+    }
+    idna::domain_to_ascii(s).ok().map(Cow::Owned)
+}
+
+/// `s` with its A-labels decoded, or `s` itself if it has none or they do
+/// not decode.
+#[cfg(feature = "idna")]
+fn to_unicode(s: &str) -> Cow<'_, str> {
+    let has_a_label = s
+        .split('.')
+        .any(|l| l.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("xn--")));
+    if !has_a_label {
+        return Cow::Borrowed(s);
+    }
+    match idna::domain_to_unicode(s) {
+        (unicode, Ok(())) => Cow::Owned(unicode),
+        (_, Err(_)) => Cow::Borrowed(s),
+    }
+}
+
+/// What kind of rule puts the registration boundary of a host where it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// An explicit ICANN rule: the registrable domain is bought from a registrar.
+    Icann,
+    /// An explicit private rule: the registrable domain is handed out by the
+    /// operator of the suffix (e.g. a `github.io` subdomain).
+    Private,
+    /// A wildcard rule (e.g. `*.ck`): the suffix is synthesized from the
+    /// host, and the registry behind it may not sell names at that level.
+    Wildcard,
+    /// A rule without a section (lists without markers), or an intermediate
+    /// label implied by deeper rules.
+    Unclassified,
+    /// No rule matched; the last label was taken as the suffix.
+    Unlisted,
+}
+
+/// Where a host's registration boundary lies and what defines it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundaryInfo<'a> {
+    /// The public suffix.
+    pub suffix: Cow<'a, str>,
+    /// The public suffix plus one label, or `None` if the host is itself
+    /// the suffix.
+    pub registrable: Option<Cow<'a, str>>,
+    /// The kind of rule that produced `suffix`.
+    pub boundary: Boundary,
+    /// Section of the matched rule, if any.
+    pub typ: Option<Type>,
+    /// The matched rule as written in a list (`co.uk`, `*.ck`, `!www.ck`),
+    /// or `None` if no rule matched.
+    pub rule: Option<String>,
+    /// The rule is an exception overriding the wildcard `*.<suffix>`.
+    pub overrides_wildcard: bool,
+}
+
+/// How the rule behind a match is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RuleKind {
+    /// A plain rule such as `co.uk`.
+    Normal,
+    /// A wildcard rule such as `*.ck`.
+    Wildcard,
+    /// An exception rule such as `!www.ck`.
+    Exception,
+    /// No rule names the suffix; it is an intermediate label of deeper rules
+    /// (`kobe.jp` under `*.kobe.jp`).
+    Implied,
+    /// No rule matched; the last label was taken as the suffix.
+    Unlisted,
+}
+
+/// The PSL rule that decided a lookup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchInfo<'a> {
+    /// The public suffix the rule produced.
+    pub suffix: Cow<'a, str>,
+    /// The rule as written in a list (`co.uk`, `*.ck`, `!www.ck`), or `None`
+    /// for implied and unlisted matches.
+    pub rule: Option<String>,
+    /// How the rule is written.
+    pub kind: RuleKind,
+    /// Section of the rule, if any.
+    pub typ: Option<Type>,
+    /// Number of labels in `rule`, counting `*`; for implied and unlisted
+    /// matches, the number of labels in `suffix`.
+    pub depth: usize,
+}
+
+/// A rule that matches a host, as listed by [`List::candidates`](crate::List::candidates).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// The rule as written in a list (`co.uk`, `*.ck`, `!www.ck`).
+    pub rule: String,
+    /// The public suffix the rule would produce on its own.
+    pub suffix: String,
+    /// How the rule is written: `Normal`, `Wildcard` or `Exception`.
+    pub kind: RuleKind,
+    /// Section of the rule, if any.
+    pub typ: Option<Type>,
+    /// Number of labels in `rule`, counting `*`.
+    pub depth: usize,
+}
+
+/// Which section of the list decided a host's public suffix, as returned by
+/// [`List::classify`](crate::List::classify).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Classification<'a> {
+    /// An ICANN rule: the host is registered under a registry's suffix.
+    Icann,
+    /// A private rule: the host is a tenant of the provider that owns
+    /// `suffix` (`github.io`, `s3.amazonaws.com`).
+    PrivateProvider {
+        /// The public suffix the private rule produced.
+        suffix: Cow<'a, str>,
+    },
+    /// No sectioned rule decided it: an unlisted TLD, a literal, a rule
+    /// outside both sections, or a host lookups reject.
+    Unknown,
+}
+
+impl Boundary {
+    /// Short human-readable description, e.g. `"wildcard rule"`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Boundary::Icann => "ICANN rule",
+            Boundary::Private => "private rule",
+            Boundary::Wildcard => "wildcard rule",
+            Boundary::Unclassified => "unclassified rule",
+            Boundary::Unlisted => "no matching rule",
+        }
+    }
+}
+
+impl BoundaryInfo<'_> {
+    /// One-line diagnostic describing how the suffix was found, e.g.
+    /// `"ICANN section, wildcard rule *.ck overridden by exception !www.ck"`.
+    pub fn summary(&self) -> String {
+        let section = self.typ.map_or("no section", Type::description);
+        match (&self.rule, self.boundary) {
+            (None, Boundary::Unlisted) => {
+                format!("not listed, last label {} used as suffix", self.suffix)
             }
+            (None, _) => format!("{section}, {} implied by deeper rules", self.suffix),
+            (Some(rule), _) if self.overrides_wildcard => format!(
+                "{section}, wildcard rule *.{} overridden by exception {rule}",
+                self.suffix
+            ),
+            (Some(rule), Boundary::Wildcard) => format!("{section}, wildcard rule {rule}"),
+            (Some(rule), _) if rule.starts_with('!') => format!("{section}, exception {rule}"),
+            (Some(rule), _) => format!("{section}, rule {rule}"),
         }
     }
+}
+
+/// Byte ranges of each part of a host, relative to the matched (normalized) string.
+struct Spans {
+    prefix: Option<Range<usize>>,
+    sll: Option<Range<usize>>,
+    sld: Range<usize>,
+    tld: Range<usize>,
+}
+
+/// Read-only view of a reverse-label rule trie.
+///
+/// The matcher is written against this trait so that every storage engine
+/// (the `HashMap` trie built by the loader, the compact flat trie) shares one
+/// lookup implementation.
+pub(crate) trait Trie {
+    /// Handle to a node; cheap to copy.
+    type Node<'a>: Copy
+    where
+        Self: 'a;
+
+    /// The root node (has no label itself).
+    fn root(&self) -> Self::Node<'_>;
+    /// The child of `node` reached through `label`, if any.
+    fn child<'a>(&'a self, node: Self::Node<'a>, label: &str) -> Option<Self::Node<'a>>;
+    /// Whether `node` is a rule and of what kind.
+    fn leaf<'a>(&'a self, node: Self::Node<'a>) -> Leaf;
+    /// Section classification of `node`'s rule.
+    fn typ<'a>(&'a self, node: Self::Node<'a>) -> Option<Type>;
+    /// Returns true if `node` has no children.
+    fn is_childless<'a>(&'a self, node: Self::Node<'a>) -> bool;
+
+    /// Splits a domain name into its constituent parts: prefix, second-level label,
+    /// registrable domain, and public suffix.
+    ///
+    /// This is the most comprehensive parsing function, returning all parts of a domain.
+    /// Behavior is controlled by `MatchOpts` (wildcards, strict mode, type filter,
+    /// normalization).
+    fn split<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Parts<'a>> {
+        split(self, host, opts)
+    }
 
     /// Extracts the registrable domain (eTLD+1) from a host name.
     ///
@@ -151,7 +396,7 @@ impl RuleSet {
     /// For example, for `www.example.com`, the registrable domain is `example.com`.
     ///
     /// This is a convenience method that calls `split` and returns only the `sld` part.
-    pub fn sld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+    fn sld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
         self.split(host, opts).and_then(|p| p.sld)
     }
 
@@ -162,147 +407,964 @@ impl RuleSet {
     ///
     /// This is an optimized method that directly finds the public suffix without calculating
     /// the other parts of the domain. If you need other parts, use `split`.
-    pub fn tld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
-        let s = normalize_view(host, opts); // Cow<'a, str>
+    fn tld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+        tld(self, host, opts)
+    }
+}
 
-        match s {
-            Cow::Borrowed(b) => {
-                let (_, tld) = self.match_tld(b, opts)?; // tld: &str inside `host`
-                Some(Cow::Borrowed(tld))
-            }
-            Cow::Owned(o) => {
-                let (_, tld) = self.match_tld(&o, opts)?; // tld: &str inside local `o`
-                Some(Cow::Owned(tld.to_string())) // copy so it outlives this fn
-            }
+impl Trie for RuleSet {
+    type Node<'a> = &'a Node;
+
+    fn root(&self) -> &Node {
+        &self.root
+    }
+    fn child<'a>(&'a self, node: &'a Node, label: &str) -> Option<&'a Node> {
+        RuleSet::child(self, node, label)
+    }
+    fn leaf(&self, node: &Node) -> Leaf {
+        node.leaf
+    }
+    fn typ(&self, node: &Node) -> Option<Type> {
+        node.typ
+    }
+    fn is_childless(&self, node: &Node) -> bool {
+        node.kids.is_empty()
+    }
+}
+
+/// Splits `host` into its parts using the rules in `trie`.
+pub(crate) fn split<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<Parts<'a>> {
+    let view = match_view(host, opts)?;
+    let sp = spans(trie, &view, opts)?;
+    let part = |r: Range<usize>| part(&view, r, opts);
+    let parts = Parts {
+        prefix: sp.prefix.and_then(part),
+        sll: sp.sll.and_then(part),
+        sld: part(sp.sld),
+        tld: part(sp.tld)?,
+    };
+    recycle(view);
+    Some(parts)
+}
+
+/// The parts of `host` as ranges into it, using the rules in `trie`.
+/// `None` if normalizing `host` rewrites it rather than slicing it.
+pub(crate) fn split_indices<T: Trie + ?Sized>(
+    trie: &T,
+    host: &str,
+    opts: MatchOpts<'_>,
+) -> Option<PartsIdx> {
+    let Cow::Borrowed(view) = match_view(host, opts)? else {
+        return None;
+    };
+    // A borrowed view is `host` less a leading or trailing dot.
+    let base = (view.as_ptr() as usize).checked_sub(host.as_ptr() as usize)?;
+    let sp = spans(trie, view, opts)?;
+    let shift = |r: Range<usize>| r.start + base..r.end + base;
+    Some(PartsIdx {
+        prefix: sp.prefix.map(shift),
+        sll: sp.sll.map(shift),
+        sld: Some(shift(sp.sld)),
+        tld: shift(sp.tld),
+    })
+}
+
+/// Why a lookup of `host` with `opts` found nothing, once it has.
+pub(crate) fn match_error(host: &str, opts: MatchOpts<'_>) -> MatchError {
+    if let Err(HostError::ControlChar { index, ch }) = opts.controls.apply(host) {
+        return MatchError::ControlChar { index, ch };
+    }
+    if !opts.within_limits(host) {
+        return MatchError::TooLong;
+    }
+    if let Ok(clean) = opts.controls.apply(host) {
+        match opts.compat {
+            CompatMode::Ps2 => {}
+            _ if clean.starts_with('.') => return MatchError::EmptyLabel,
+            CompatMode::Rfc => {}
+            _ if clean.ends_with('.') => return MatchError::TrailingDot,
+            _ => {}
         }
     }
+    let Some(view) = match_view(host, opts) else {
+        // Control characters are handled, so the normalizer rejected it.
+        #[cfg(feature = "idna")]
+        return MatchError::InvalidIdna;
+        #[cfg(not(feature = "idna"))]
+        return MatchError::NoRuleMatched;
+    };
+    let err = if view.is_empty() {
+        MatchError::EmptyInput
+    } else if view.contains("..") {
+        MatchError::EmptyLabel
+    } else if view.ends_with('.') {
+        MatchError::TrailingDot
+    } else if opts.compat.literals(opts.literals) == LiteralPolicy::Reject
+        && LiteralPolicy::is_literal(&view)
+    {
+        MatchError::Literal
+    } else {
+        MatchError::NoRuleMatched
+    };
+    recycle(view);
+    err
+}
 
-    fn match_tld<'s>(&self, s: &'s str, opts: MatchOpts<'_>) -> Option<(usize, &'s str)> {
-        // invalid: empty label, leading dot, trailing dot (when not stripped), or ".."
-        if s.is_empty() || s.ends_with('.') || s.contains("..") {
-            return None;
+/// Public suffix of `host` using the rules in `trie`.
+pub(crate) fn tld<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<Cow<'a, str>> {
+    suffix_at(host, opts, |s| match_tld(trie, s, opts))
+}
+
+/// Deepest suffix of `host` that is itself listed, using the rules in `trie`.
+pub(crate) fn listed_suffix<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<Cow<'a, str>> {
+    suffix_at(host, opts, |s| match_listed(trie, s, opts))
+}
+
+/// Registration boundary of `host` using the rules in `trie`.
+pub(crate) fn boundary<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<BoundaryInfo<'a>> {
+    let view = normalize_view(host, opts)?;
+    let m = find_match(trie, &view, opts)?;
+    let from = |start: usize| match &view {
+        Cow::Borrowed(b) => b.get(start..).map(Cow::Borrowed),
+        Cow::Owned(o) => o.get(start..).map(|v| Cow::Owned(v.to_string())),
+    };
+    let reg_start = registrable_start(&view, m.start);
+    let rule = rule_text(&view, &m);
+    let boundary = match (m.fallback, m.wildcard, m.typ) {
+        (true, _, _) => Boundary::Unlisted,
+        (_, true, _) => Boundary::Wildcard,
+        (_, _, Some(Type::Icann)) => Boundary::Icann,
+        (_, _, Some(Type::Private)) => Boundary::Private,
+        (_, _, None) => Boundary::Unclassified,
+    };
+    let info = BoundaryInfo {
+        suffix: from(m.start)?,
+        registrable: reg_start.and_then(from),
+        boundary,
+        typ: m.typ,
+        rule,
+        overrides_wildcard: m.overrides_wildcard,
+    };
+    recycle(view);
+    Some(info)
+}
+
+/// Offset of the public suffix in `host` as given, using the rules in
+/// `trie`. Never allocates.
+pub(crate) fn suffix_offset<T: Trie + ?Sized>(
+    trie: &T,
+    host: &str,
+    opts: MatchOpts<'_>,
+) -> Option<usize> {
+    if host.chars().any(char::is_control) {
+        return None;
+    }
+    let raw = MatchOpts {
+        normalizer: None,
+        match_both_forms: false,
+        ..opts
+    };
+    find_match(trie, host, raw).map(|m| m.start)
+}
+
+/// Returns true if `host` is itself a public suffix, using the rules in
+/// `trie`.
+pub(crate) fn is_public_suffix<T: Trie + ?Sized>(
+    trie: &T,
+    host: &str,
+    opts: MatchOpts<'_>,
+) -> bool {
+    match_view(host, opts).is_some_and(|view| {
+        let found = find_match(trie, &view, opts).is_some_and(|m| m.start == 0);
+        recycle(view);
+        found
+    })
+}
+
+/// Returns true if `host` is a public suffix plus exactly one label, using
+/// the rules in `trie`.
+pub(crate) fn is_registrable_domain<T: Trie + ?Sized>(
+    trie: &T,
+    host: &str,
+    opts: MatchOpts<'_>,
+) -> bool {
+    match_view(host, opts).is_some_and(|view| {
+        let found = find_match(trie, &view, opts)
+            .is_some_and(|m| m.start > 0 && registrable_start(&view, m.start) == Some(0));
+        recycle(view);
+        found
+    })
+}
+
+/// The rule behind the public suffix of `host`, using the rules in `trie`.
+pub(crate) fn match_info<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<MatchInfo<'a>> {
+    let view = normalize_view(host, opts)?;
+    let m = find_match(trie, &view, opts)?;
+    let rule = rule_text(&view, &m);
+    let kind = match m.leaf {
+        _ if m.fallback => RuleKind::Unlisted,
+        Leaf::None => RuleKind::Implied,
+        Leaf::Negative => RuleKind::Exception,
+        Leaf::Positive if m.wildcard => RuleKind::Wildcard,
+        Leaf::Positive => RuleKind::Normal,
+    };
+    let labels = |s: &str| s.split('.').count();
+    let depth = labels(rule.as_deref().unwrap_or(view.get(m.start..)?));
+    let suffix = match &view {
+        Cow::Borrowed(b) => Cow::Borrowed(b.get(m.start..)?),
+        Cow::Owned(o) => Cow::Owned(o.get(m.start..)?.to_string()),
+    };
+    recycle(view);
+    Some(MatchInfo {
+        suffix,
+        rule,
+        kind,
+        typ: m.typ,
+        depth,
+    })
+}
+
+/// Every rule in `trie` that matches `host`, in precedence order:
+/// exceptions first, then longer rules before shorter ones, and a named
+/// label before a `*` at the same depth. Unlike [`find_match`], both the
+/// named and the wildcard branch are followed at every label.
+pub(crate) fn candidates<T: Trie + ?Sized>(
+    trie: &T,
+    host: &str,
+    opts: MatchOpts<'_>,
+) -> Vec<RuleMatch> {
+    let mut out = Vec::new();
+    let Some(view) = normalize_view(host, opts) else {
+        return out;
+    };
+    let s = &*view;
+    if s.is_empty() || s.ends_with('.') || s.contains("..") {
+        return out;
+    }
+    if opts.compat.literals(opts.literals) != LiteralPolicy::Match && LiteralPolicy::is_literal(s) {
+        return out;
+    }
+    collect_candidates(
+        trie,
+        trie.root(),
+        s,
+        s.len(),
+        opts,
+        &mut Vec::new(),
+        &mut out,
+    );
+    recycle(view);
+    out.sort_by_key(|c| (c.kind != RuleKind::Exception, core::cmp::Reverse(c.depth)));
+    out
+}
+
+/// Depth-first step of [`candidates`]: matches the label of `s` ending at
+/// `lbl_end` under `node`. `rule` holds the labels matched so far, rightmost
+/// first.
+fn collect_candidates<'t, 's, T: Trie + ?Sized>(
+    trie: &'t T,
+    node: T::Node<'t>,
+    s: &'s str,
+    lbl_end: usize,
+    opts: MatchOpts<'_>,
+    rule: &mut Vec<&'s str>,
+    out: &mut Vec<RuleMatch>,
+) {
+    let lbl_start = label_start(s, lbl_end);
+    let Some(lbl) = s.get(lbl_start..lbl_end) else {
+        return;
+    };
+    let named = child_any_form(trie, node, lbl, opts);
+    let wild = if opts.wildcard && lbl != "*" {
+        trie.child(node, "*")
+    } else {
+        None
+    };
+    for (next, text) in [(named, lbl), (wild, "*")] {
+        let Some(n) = next else {
+            continue;
+        };
+        rule.push(text);
+        let (leaf, typ) = (trie.leaf(n), trie.typ(n));
+        if leaf != Leaf::None
+            && (leaf != Leaf::Negative || opts.exceptions)
+            && accept_type(typ, opts.types)
+            && !is_ignored(typ, s.get(lbl_start..), opts)
+        {
+            let mut written: String = rule.iter().rev().copied().collect::<Vec<_>>().join(".");
+            let mut start = lbl_start;
+            let kind = match leaf {
+                Leaf::Negative => {
+                    written.insert(0, '!');
+                    // An exception's public suffix is its parent.
+                    start = s[start..].find('.').map_or(0, |i| start + i + 1);
+                    RuleKind::Exception
+                }
+                _ if text == "*" => RuleKind::Wildcard,
+                _ => RuleKind::Normal,
+            };
+            out.push(RuleMatch {
+                rule: written,
+                suffix: s[start..].to_string(),
+                kind,
+                typ,
+                depth: rule.len(),
+            });
         }
-        if self.root.kids.is_empty() {
-            if opts.strict {
-                return None;
-            }
-            let last = s.rfind('.').map(|i| &s[i + 1..]).unwrap_or(s);
-            if last.is_empty() {
-                return None;
-            }
-            let start = s.len() - last.len();
-            return Some((start.saturating_sub(1), last));
+        if let Some(dot) = lbl_start.checked_sub(1) {
+            collect_candidates(trie, n, s, dot, opts, rule, out);
+        }
+        rule.pop();
+    }
+}
+
+/// The rule behind `m` as written in a list, or `None` if no rule names it.
+fn rule_text(view: &str, m: &Match) -> Option<String> {
+    let rest = view.get(m.rule_start..)?;
+    match m.leaf {
+        _ if m.fallback => None,
+        Leaf::None => None,
+        Leaf::Negative => Some(format!("!{rest}")),
+        Leaf::Positive if m.wildcard => Some(
+            rest.find('.')
+                .map_or("*".into(), |i| format!("*{}", &rest[i..])),
+        ),
+        Leaf::Positive => Some(rest.into()),
+    }
+}
+
+/// `host` split around its registration boundary, using the rules in `trie`.
+pub(crate) fn domain<'a, T: Trie + ?Sized>(
+    trie: &T,
+    host: &'a str,
+    opts: MatchOpts<'_>,
+) -> Option<Domain<'a>> {
+    let view = normalize_view(host, opts)?;
+    let m = find_match(trie, &view, opts)?;
+    let reg_start = registrable_start(&view, m.start);
+    Some(Domain::new(view, m.start, reg_start, m.typ))
+}
+
+/// Start of the label left of the suffix starting at `suffix_start`, if
+/// there is one. The label must be non-empty (raw ".com" has none).
+fn registrable_start(s: &str, suffix_start: usize) -> Option<usize> {
+    suffix_start
+        .checked_sub(1)
+        .map(|dot| (label_start(s, dot), dot))
+        .filter(|(start, dot)| start < dot)
+        .map(|(start, _)| start)
+}
+
+/// Normalizes `host` and returns the suffix starting at the offset `find`
+/// reports for the normalized string.
+fn suffix_at<'a>(
+    host: &'a str,
+    opts: MatchOpts<'_>,
+    find: impl Fn(&str) -> Option<usize>,
+) -> Option<Cow<'a, str>> {
+    let view = match_view(host, opts)?;
+    let suffix = find(&view).and_then(|start| part(&view, start..view.len(), opts));
+    recycle(view);
+    suffix
+}
+
+/// The text of `view` at `r`, lowercased if [`match_view`] left that to
+/// the lookup. Borrows from the host when `view` does and nothing needs
+/// lowercasing; otherwise copies so it outlives `view`.
+fn part<'a>(view: &Cow<'a, str>, r: Range<usize>, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+    let folded = |p: &str| {
+        (folds_case(opts) && p.bytes().any(|b| b.is_ascii_uppercase()))
+            .then(|| p.to_ascii_lowercase())
+    };
+    match view {
+        Cow::Borrowed(b) => {
+            let p = b.get(r)?;
+            Some(folded(p).map_or(Cow::Borrowed(p), Cow::Owned))
         }
+        Cow::Owned(o) => {
+            let p = o.get(r)?;
+            Some(Cow::Owned(folded(p).unwrap_or_else(|| p.into())))
+        }
+    }
+}
 
-        let mut longest_match: Option<(isize, &Node)> = None;
-        let mut parent: Option<&Node> = Some(&self.root);
+/// Computes the part ranges of `s`. All ranges start and end on a dot
+/// boundary (or the string ends), so slicing with them cannot split a char.
+fn spans<T: Trie + ?Sized>(trie: &T, s: &str, opts: MatchOpts<'_>) -> Option<Spans> {
+    let tld_start = match_tld(trie, s, opts)?;
+    let tld = tld_start..s.len();
+
+    // If public suffix covers the whole host, registrable domain equals the host.
+    if tld_start == 0 {
+        return Some(Spans {
+            prefix: None,
+            sll: None,
+            sld: tld.clone(),
+            tld,
+        });
+    }
 
-        let mut lbl_end = s.len() as isize;
-        let mut lbl_start = s.len() as isize;
+    // Unlisted-TLD fallback: when suffix is a single label *not* in the rules,
+    // collapse SLD to the TLD (e.g., "example.example" → "example", "example.local" → "local").
+    let suffix = s.get(tld.clone())?;
+    if opts.compat.collapses_unlisted()
+        && !suffix.contains('.')
+        && child(trie, trie.root(), suffix, opts).is_none()
+    {
+        return Some(Spans {
+            prefix: None,
+            sll: None,
+            sld: tld.clone(),
+            tld,
+        });
+    }
 
-        while lbl_end != -1 && parent.is_some() {
-            lbl_start = rfind_dot(s, lbl_start);
-            let lbl = &s[(lbl_start + 1) as usize..lbl_end as usize];
-            let node = parent.unwrap();
+    // `match_tld` only returns a non-zero start right after a dot.
+    let sld_end = tld_start - 1;
+    let idx = s.get(..sld_end)?.rfind('.');
+    let start = match idx {
+        Some(i) => i + 1,
+        // A leading dot directly before the SLL's (empty) label, e.g. raw ".com".
+        None if s.starts_with('.') => 1,
+        None => 0,
+    };
 
-            let mut next = node.kids.get(lbl);
-            if next.is_none() && opts.wildcard {
-                next = node.kids.get("*");
+    Some(Spans {
+        prefix: idx.filter(|&i| i > 0).map(|i| 0..i),
+        sll: (start < sld_end).then_some(start..sld_end),
+        sld: start..s.len(),
+        tld,
+    })
+}
+
+/// Returns the byte offset at which the public suffix of `s` starts.
+fn match_tld<T: Trie + ?Sized>(trie: &T, s: &str, opts: MatchOpts<'_>) -> Option<usize> {
+    find_match(trie, s, opts).map(|m| m.start)
+}
+
+/// The public suffix of a host and the rule that produced it.
+struct Match {
+    /// Byte offset at which the public suffix starts.
+    start: usize,
+    /// Section of the matched rule.
+    typ: Option<Type>,
+    /// Byte offset of the matched rule's leftmost label.
+    rule_start: usize,
+    /// Kind of the matched rule.
+    leaf: Leaf,
+    /// The deepest matched label came from a `*` rule.
+    wildcard: bool,
+    /// The matched rule is an exception to a sibling wildcard rule.
+    overrides_wildcard: bool,
+    /// No rule matched; the suffix is the unlisted-TLD fallback.
+    fallback: bool,
+}
+
+/// Finds the public suffix of `s`, reporting how it was found to the
+/// metrics sink.
+fn find_match<T: Trie + ?Sized>(trie: &T, s: &str, opts: MatchOpts<'_>) -> Option<Match> {
+    let m = walk_match(trie, s, opts);
+    #[cfg(feature = "metrics")]
+    {
+        use crate::metrics::{record, Metric};
+        record(Metric::Lookup);
+        if let Some(m) = &m {
+            if m.fallback {
+                record(Metric::Fallback);
+            }
+            if m.wildcard {
+                record(Metric::Wildcard);
             }
+            if m.leaf == Leaf::Negative {
+                record(Metric::Exception);
+            }
+        }
+    }
+    m
+}
 
-            match next {
-                Some(n) => {
-                    if accept_type(n, opts.types) {
-                        longest_match = Some((lbl_start, n));
-                    }
-                    parent = Some(n);
-                }
-                None => {
-                    parent = None;
+/// Walks the trie once, right to left, visiting at most one node per label
+/// plus the root, so lookups are O(labels) whatever the list contains.
+/// `tests::walks_visit_at_most_labels_plus_one_nodes` enforces this.
+fn walk_match<T: Trie + ?Sized>(trie: &T, s: &str, opts: MatchOpts<'_>) -> Option<Match> {
+    // invalid: empty label, leading dot, trailing dot (when not stripped), or ".."
+    if s.is_empty() || s.ends_with('.') || s.contains("..") {
+        return None;
+    }
+    let fallback = || {
+        // Non-strict fallback for unlisted TLDs, by default the last label.
+        let start = match opts.fallback {
+            _ if opts.strict => return None,
+            FallbackPolicy::None => return None,
+            FallbackPolicy::LastLabel => label_start(s, s.len()),
+            FallbackPolicy::EntireHost => 0,
+        };
+        Some(Match {
+            start,
+            typ: None,
+            rule_start: start,
+            leaf: Leaf::None,
+            wildcard: false,
+            overrides_wildcard: false,
+            fallback: true,
+        })
+    };
+    match opts.compat.literals(opts.literals) {
+        LiteralPolicy::Match => {}
+        _ if !LiteralPolicy::is_literal(s) => {}
+        LiteralPolicy::Reject => return None,
+        // The whole host is an unlisted suffix, whatever `strict` says.
+        LiteralPolicy::Whole => {
+            return Some(Match {
+                start: 0,
+                typ: None,
+                rule_start: 0,
+                leaf: Leaf::None,
+                wildcard: false,
+                overrides_wildcard: false,
+                fallback: true,
+            })
+        }
+    }
+    if trie.is_childless(trie.root()) {
+        return fallback();
+    }
+
+    let mut longest_match = None;
+    let mut node = trie.root();
+    count_visit();
+    let mut lbl_end = s.len();
+    // Start of the label the current `*` node matched, if it was reached
+    // through one.
+    let mut wild_start = None;
+
+    loop {
+        let lbl_start = label_start(s, lbl_end);
+        let lbl = s.get(lbl_start..lbl_end)?;
+
+        let mut next = child_any_form(trie, node, lbl, opts);
+        // An ignored exception with no rules beneath it is as if unlisted.
+        if !opts.exceptions
+            && next.is_some_and(|n| trie.leaf(n) == Leaf::Negative && trie.is_childless(n))
+        {
+            next = None;
+        }
+        let wildcard = next.is_none() && opts.wildcard;
+        let mut rule_start = lbl_start;
+        if wildcard {
+            next = trie.child(node, "*");
+            match (next, wild_start) {
+                (Some(_), _) => wild_start = Some(lbl_start),
+                // The `*` matched by the parent label matches this one too.
+                (None, Some(start)) if opts.deep_wildcards => {
+                    next = Some(node);
+                    rule_start = start;
                 }
+                _ => {}
             }
-            lbl_end = lbl_start;
+        } else {
+            wild_start = None;
+        }
+        let Some(n) = next else {
+            break;
+        };
+        let typ = trie.typ(n);
+        let leaf = trie.leaf(n);
+        if accept_type(typ, opts.types)
+            && !is_ignored(typ, s.get(lbl_start..), opts)
+            && (leaf != Leaf::Negative || opts.exceptions)
+        {
+            let overrides_wildcard = leaf == Leaf::Negative && trie.child(node, "*").is_some();
+            longest_match = Some((
+                lbl_start,
+                rule_start,
+                leaf,
+                typ,
+                wildcard,
+                overrides_wildcard,
+            ));
         }
+        node = n;
+        count_visit();
 
-        match longest_match {
-            Some((tld_start, node)) => {
-                // An exception rule means the public suffix is one level up from the exception.
-                // e.g., for !city.uk on foo.city.uk, the match is on 'city', but the TLD is 'uk'.
-                if node.leaf == Leaf::Negative {
-                    let dot = s[(tld_start + 1) as usize..]
-                        .find('.')
-                        .map(|i| i as isize + tld_start + 1)
-                        .unwrap_or(-1);
-                    let start = (dot + 1) as usize;
-                    return Some((dot as usize, &s[start..]));
-                }
+        // Step over the dot to the next label on the left, if any.
+        match lbl_start.checked_sub(1) {
+            Some(dot) => lbl_end = dot,
+            None => break,
+        }
+    }
 
-                let start = (tld_start + 1) as usize;
-                Some((tld_start as usize, &s[start..]))
-            }
-            None => {
-                if opts.strict {
-                    return None;
-                }
-                // Non-strict fallback for unlisted TLDs: last label is the public suffix.
-                let dot = s.rfind('.').map(|i| i as isize).unwrap_or(-1);
-                let start = (dot + 1) as usize;
-                Some((dot as usize, &s[start..]))
+    let Some((mut start, rule_start, leaf, typ, wildcard, overrides_wildcard)) = longest_match
+    else {
+        return fallback();
+    };
+    // An exception rule means the public suffix is one level up from the exception.
+    // e.g., for !city.uk on foo.city.uk, the match is on 'city', but the TLD is 'uk'.
+    if leaf == Leaf::Negative {
+        let rest = s.get(start..)?;
+        start = rest.find('.').map_or(0, |i| start + i + 1);
+    }
+    Some(Match {
+        start,
+        typ,
+        rule_start,
+        leaf,
+        wildcard,
+        overrides_wildcard,
+        fallback: false,
+    })
+}
+
+/// Returns the byte offset of the deepest suffix of `s` that a rule names
+/// exactly. Wildcards are not followed, but a `*.x` rule names `x`; an
+/// exception names its parent.
+fn match_listed<T: Trie + ?Sized>(trie: &T, s: &str, opts: MatchOpts<'_>) -> Option<usize> {
+    if s.is_empty() || s.ends_with('.') || s.contains("..") {
+        return None;
+    }
+    let literals = opts.compat.literals(opts.literals);
+    if literals != LiteralPolicy::Match && LiteralPolicy::is_literal(s) {
+        return (literals == LiteralPolicy::Whole).then_some(0);
+    }
+    let mut found = None;
+    let mut node = trie.root();
+    count_visit();
+    let mut lbl_end = s.len();
+
+    loop {
+        let lbl_start = label_start(s, lbl_end);
+        let Some(n) = child_any_form(trie, node, s.get(lbl_start..lbl_end)?, opts) else {
+            break;
+        };
+        let typ = trie.typ(n);
+        if accept_type(typ, opts.types) && !is_ignored(typ, s.get(lbl_start..), opts) {
+            match trie.leaf(n) {
+                Leaf::Positive => found = Some(lbl_start),
+                Leaf::Negative if opts.exceptions => return Some(lbl_end + 1),
+                Leaf::Negative | Leaf::None => {}
             }
         }
+        let wild = trie.child(n, "*");
+        if wild
+            .is_some_and(|w| trie.leaf(w) == Leaf::Positive && accept_type(trie.typ(w), opts.types))
+        {
+            found = Some(lbl_start);
+        }
+        node = n;
+        count_visit();
+
+        match lbl_start.checked_sub(1) {
+            Some(dot) => lbl_end = dot,
+            None => break,
+        }
     }
+    found
 }
 
-fn rfind_dot(s: &str, end: isize) -> isize {
-    match s[..end as usize].rfind('.') {
-        Some(i) => i as isize,
-        None => -1,
+/// The child of `node` reached through `lbl`, or through the label's other
+/// IDNA form when `opts.match_both_forms` is set.
+#[cfg_attr(not(feature = "idna"), allow(unused_variables))]
+fn child_any_form<'a, T: Trie + ?Sized>(
+    trie: &'a T,
+    node: T::Node<'a>,
+    lbl: &str,
+    opts: MatchOpts<'_>,
+) -> Option<T::Node<'a>> {
+    let direct = child(trie, node, lbl, opts);
+    #[cfg(feature = "idna")]
+    if direct.is_none() && opts.match_both_forms {
+        return other_idna_form(lbl).and_then(|alt| trie.child(node, &alt));
     }
+    direct
 }
 
-fn accept_type(n: &Node, filt: TypeFilter) -> bool {
-    matches!(
-        (filt, n.typ),
-        (TypeFilter::Any, _)
-            | (TypeFilter::Icann, Some(crate::rules::Type::Icann))
-            | (TypeFilter::Private, Some(crate::rules::Type::Private))
-    )
+/// Longest label [`child`] lowercases on the stack; longer ones (which no
+/// DNS name has) are copied to the heap.
+const LABEL_BUF: usize = 63;
+
+/// The child of `node` reached through `lbl`, ignoring ASCII case when
+/// `opts` lowercase hosts. Hosts reach the walks unlowercased from
+/// [`match_view`], so each label is folded here instead.
+fn child<'a, T: Trie + ?Sized>(
+    trie: &'a T,
+    node: T::Node<'a>,
+    lbl: &str,
+    opts: MatchOpts<'_>,
+) -> Option<T::Node<'a>> {
+    if !folds_case(opts) || !lbl.bytes().any(|b| b.is_ascii_uppercase()) {
+        return trie.child(node, lbl);
+    }
+    let mut buf = [0; LABEL_BUF];
+    match buf.get_mut(..lbl.len()) {
+        Some(lower) => {
+            lower.copy_from_slice(lbl.as_bytes());
+            lower.make_ascii_lowercase();
+            trie.child(node, core::str::from_utf8(lower).ok()?)
+        }
+        None => trie.child(node, &lbl.to_ascii_lowercase()),
+    }
 }
 
-fn normalize_view<'a>(s: &'a str, opts: MatchOpts<'_>) -> Cow<'a, str> {
-    let Some(n) = opts.normalizer else {
-        return Cow::Borrowed(s); // no normalization
-    };
+/// Returns true if lookups with `opts` ignore ASCII case.
+fn folds_case(opts: MatchOpts<'_>) -> bool {
+    opts.normalizer.is_some_and(|n| n.lowercase)
+}
 
-    // Drop a single leading dot, then handle trailing dot.
-    let base = s.strip_prefix('.').unwrap_or(s);
-    let mut out: Cow<'a, str> = if n.strip_trailing_dot && base.ends_with('.') {
-        Cow::Owned(base[..base.len() - 1].to_string())
+/// The U-label of an A-label, or the A-label of a non-ASCII label.
+#[cfg(feature = "idna")]
+fn other_idna_form(lbl: &str) -> Option<String> {
+    if lbl.is_ascii() {
+        if !lbl.get(..4)?.eq_ignore_ascii_case("xn--") {
+            return None;
+        }
+        let (unicode, res) = idna::domain_to_unicode(lbl);
+        res.ok()?;
+        (unicode != lbl).then_some(unicode)
+    } else if idna_candidate(lbl) {
+        idna::domain_to_ascii(lbl).ok()
     } else {
-        Cow::Borrowed(base)
-    };
+        None
+    }
+}
 
-    // Lowercase (allocate only if needed).
-    if n.lowercase && out.chars().any(|c| c.is_ascii_uppercase()) {
-        out = Cow::Owned(out.to_lowercase());
+#[cfg(test)]
+thread_local! {
+    /// Trie nodes visited by walks on this thread (test builds only).
+    static VISITS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// Counts a node visited by a trie walk. Compiles to nothing outside tests.
+#[inline(always)]
+fn count_visit() {
+    #[cfg(test)]
+    VISITS.with(|v| v.set(v.get() + 1));
+}
+
+/// Start of the label ending at byte `end`: one past the last dot before
+/// `end`, or 0 if there is none.
+fn label_start(s: &str, end: usize) -> usize {
+    s.get(..end)
+        .and_then(|head| head.rfind('.'))
+        .map_or(0, |i| i + 1)
+}
+
+/// Returns true if a private rule matching `suffix` is disabled by
+/// `MatchOpts::ignored_private`.
+fn is_ignored(typ: Option<Type>, suffix: Option<&str>, opts: MatchOpts<'_>) -> bool {
+    typ == Some(Type::Private)
+        && suffix.is_some_and(|sfx| {
+            opts.ignored_private
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(sfx))
+        })
+}
+
+pub(crate) fn accept_type(typ: Option<Type>, filt: TypeFilter) -> bool {
+    filt.accepts(typ)
+}
+
+/// Longest Unicode label (in chars) that can still fit a 63-octet A-label:
+/// "xn--" plus at least one output char per input char.
+#[cfg(feature = "idna")]
+const MAX_IDNA_LABEL_CHARS: usize = 59;
+/// Longest host (in chars) that can still fit the 253-octet DNS limit.
+#[cfg(feature = "idna")]
+const MAX_IDNA_HOST_CHARS: usize = 253;
+
+/// Cheap pre-check run before `idna::domain_to_ascii`.
+///
+/// Rejects input that cannot map to a valid A-label host (over-long labels
+/// or hosts, or non-ASCII labels already claiming the `xn--` prefix), so
+/// hostile Unicode input cannot make the default options spend unbounded
+/// CPU in IDNA processing. Rejected input is matched as-is.
+#[cfg(feature = "idna")]
+pub(crate) fn idna_candidate(s: &str) -> bool {
+    let mut total = 0;
+    for label in s.split('.') {
+        let mut chars = 0;
+        let mut ascii = true;
+        for c in label.chars() {
+            chars += 1;
+            ascii &= c.is_ascii();
+            if chars > MAX_IDNA_LABEL_CHARS {
+                return false;
+            }
+        }
+        if !ascii
+            && label
+                .get(..4)
+                .is_some_and(|p| p.eq_ignore_ascii_case("xn--"))
+        {
+            return false;
+        }
+        total += chars + 1;
+        if total > MAX_IDNA_HOST_CHARS + 1 {
+            return false;
+        }
     }
+    true
+}
+
+/// Applies `opts.controls` and `opts.normalizer` to `s`. Returns `None` if
+/// the host is rejected.
+pub(crate) fn normalize_view<'a>(s: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+    view(s, opts, true)
+}
+
+/// Like [`normalize_view`], but leaves ASCII uppercase in an ASCII host to
+/// be ignored label by label in [`child`], so looking up `WWW.EXAMPLE.COM`
+/// does not copy the host. Non-ASCII hosts are lowercased in full as
+/// before, since that lowercases their non-ASCII letters too.
+fn match_view<'a>(s: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+    view(s, opts, false)
+}
+
+fn view<'a>(s: &'a str, opts: MatchOpts<'_>, lowercase_ascii: bool) -> Option<Cow<'a, str>> {
+    if !opts.within_limits(s) {
+        return None;
+    }
+    let clean = compat_dots(opts.controls.apply(s).ok()?, opts.compat)?;
+    let Some(n) = opts.normalizer else {
+        return Some(counted(clean)); // no normalization
+    };
+    #[allow(unused_mut)]
+    let mut out = match n.lowercase && !lowercase_ascii && clean.is_ascii() {
+        true => Normalizer {
+            lowercase: false,
+            ..n.clone()
+        }
+        .prepare(clean),
+        false => n.prepare(clean),
+    };
 
     // IDNA -> ASCII (feature-gated; allocate only if non-ASCII)
     #[cfg(feature = "idna")]
-    if n.idna_ascii && !out.is_ascii() {
-        if let Ok(ascii) = idna::domain_to_ascii(&out) {
-            out = Cow::Owned(ascii);
-        }
+    match n.to_ascii(&out) {
+        Some(Ok(ascii)) => recycle(core::mem::replace(&mut out, ascii.into())),
+        Some(Err(_)) if n.idna_reject_errors => return None,
+        Some(Err(_)) | None => {}
     }
 
-    out
+    Some(counted(out))
+}
+
+/// Applies the leading and trailing dot handling of `compat` to `s`, ahead
+/// of the normalizer. Returns `None` if the host is rejected.
+fn compat_dots(s: Cow<'_, str>, compat: CompatMode) -> Option<Cow<'_, str>> {
+    match compat {
+        CompatMode::Ps2 => Some(s),
+        _ if s.starts_with('.') => None,
+        CompatMode::Rfc => Some(match s {
+            Cow::Borrowed(b) => Cow::Borrowed(b.strip_suffix('.').unwrap_or(b)),
+            Cow::Owned(mut o) => {
+                if o.ends_with('.') {
+                    o.pop();
+                }
+                Cow::Owned(o)
+            }
+        }),
+        CompatMode::Libpsl | CompatMode::GoPublicsuffix => (!s.ends_with('.')).then_some(s),
+    }
+}
+
+/// Reports a host copied by [`view`] to the metrics sink.
+#[inline(always)]
+fn counted(view: Cow<'_, str>) -> Cow<'_, str> {
+    #[cfg(feature = "metrics")]
+    if let Cow::Owned(_) = view {
+        crate::metrics::record(crate::metrics::Metric::NormalizationAlloc);
+    }
+    view
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::options::MatchOpts;
-    use crate::rules::{Leaf, Node, RuleSet};
+    use crate::flat::FlatTrie;
+    use crate::options::{LoadOpts, MatchOpts};
+    use crate::rules::{Leaf, RuleSet};
+
+    /// Runs `f` and returns the number of trie nodes it visited.
+    fn visits(f: impl FnOnce()) -> usize {
+        VISITS.with(|v| v.set(0));
+        f();
+        VISITS.with(|v| v.get())
+    }
+
+    #[test]
+    fn walks_visit_at_most_labels_plus_one_nodes() {
+        let deep = "a\n*.a\n*.*.a\n*.*.*.a\n!b.*.*.a\nb.b.b.b.b.b.b.b.a\nc.b.b.b.b.b.b.b.b.a";
+        let lists = [
+            crate::EMBEDDED_PSL,
+            deep,
+            "// ===BEGIN PRIVATE DOMAINS===\na\nb.a\n*.b.a",
+        ];
+        let mut hosts: Vec<String> = [
+            "www.example.co.uk",
+            "foo.city.kawasaki.jp",
+            "x.c.kobe.jp",
+            "a.b.ck",
+            "user.github.io",
+            "example.local",
+            "a",
+            "b.b.b.b.b.b.b.b.b.b.a",
+            "c.b.b.b.b.b.b.b.b.a",
+            "x.b.z.y.a",
+        ]
+        .map(String::from)
+        .into();
+        hosts.push(vec!["b"; 500].join(".") + ".a");
+
+        for text in lists {
+            let rs = crate::loader::load(text, LoadOpts::default()).unwrap();
+            let flat = FlatTrie::from_ruleset(&rs).unwrap();
+            for host in &hosts {
+                for wildcard in [true, false] {
+                    let opts = MatchOpts {
+                        wildcard,
+                        match_both_forms: true,
+                        ..MatchOpts::raw()
+                    };
+                    let labels = host.split('.').count();
+                    let checks = [
+                        visits(|| drop(split(&rs, host, opts))),
+                        visits(|| drop(split(&flat, host, opts))),
+                        visits(|| drop(tld(&rs, host, opts))),
+                        visits(|| drop(listed_suffix(&flat, host, opts))),
+                        visits(|| drop(boundary(&rs, host, opts))),
+                    ];
+                    for n in checks {
+                        assert!(n <= labels + 1, "{host}: {n} visits");
+                    }
+                }
+            }
+        }
+
+        // The bound is tight: a host that follows a rule path to its end.
+        let rs = crate::loader::load(deep, LoadOpts::default()).unwrap();
+        let host = "b.b.b.b.b.b.b.b.a";
+        assert_eq!(visits(|| drop(tld(&rs, host, MatchOpts::raw()))), 10);
+    }
 
     fn rs_empty() -> RuleSet {
         RuleSet::default()
@@ -310,11 +1372,7 @@ mod tests {
 
     fn rs_com_only() -> RuleSet {
         let mut rs = RuleSet::default();
-        let com = Node {
-            leaf: Leaf::Positive,
-            ..Default::default()
-        };
-        rs.root.kids.insert("com".into(), com);
+        rs.node_mut_or_insert("com").leaf = Leaf::Positive;
         rs
     }
 
@@ -322,28 +1380,11 @@ mod tests {
         let mut rs = RuleSet::default();
 
         // com => positive rule
-        let com = Node {
-            leaf: Leaf::Positive,
-            ..Default::default()
-        };
-        rs.root.kids.insert("com".into(), com);
+        rs.node_mut_or_insert("com").leaf = Leaf::Positive;
 
         // uk => wildcard positive (*.uk) and exception (!city.uk)
-        let mut uk = Node::default();
-
-        let star = Node {
-            leaf: Leaf::Positive,
-            ..Default::default()
-        };
-        uk.kids.insert("*".into(), star);
-
-        let city = Node {
-            leaf: Leaf::Negative,
-            ..Default::default()
-        };
-        uk.kids.insert("city".into(), city);
-
-        rs.root.kids.insert("uk".into(), uk);
+        rs.node_mut_or_insert("*.uk").leaf = Leaf::Positive;
+        rs.node_mut_or_insert("city.uk").leaf = Leaf::Negative;
 
         rs
     }
@@ -488,17 +1529,70 @@ mod tests {
     }
 
     #[test]
-    fn rfind_dot_various_positions() {
+    fn label_start_various_positions() {
         // "a.b.c"
         let s = "a.b.c";
-        assert_eq!(rfind_dot(s, s.len() as isize), 3); // before "c"
-        assert_eq!(rfind_dot(s, 3), 1); // before "b"
-        assert_eq!(rfind_dot(s, 2), 1);
-        assert_eq!(rfind_dot(s, 1), -1);
-        assert_eq!(rfind_dot(s, 0), -1);
+        assert_eq!(label_start(s, s.len()), 4); // "c"
+        assert_eq!(label_start(s, 3), 2); // "b"
+        assert_eq!(label_start(s, 2), 2);
+        assert_eq!(label_start(s, 1), 0);
+        assert_eq!(label_start(s, 0), 0);
 
         // no dots
         let s2 = "abc";
-        assert_eq!(rfind_dot(s2, s2.len() as isize), -1);
+        assert_eq!(label_start(s2, s2.len()), 0);
+
+        // out of range never panics
+        assert_eq!(label_start(s, 99), 0);
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna_candidate_limits() {
+        assert!(idna_candidate("食狮.中国"));
+        assert!(idna_candidate(&"é".repeat(MAX_IDNA_LABEL_CHARS)));
+        assert!(!idna_candidate(&"é".repeat(MAX_IDNA_LABEL_CHARS + 1)));
+        assert!(!idna_candidate("xn--é.com"));
+
+        // Many short labels still bounded by total length.
+        let many = vec!["é"; 200].join(".");
+        assert!(!idna_candidate(&many));
+        let fits = vec!["é"; 127].join(".");
+        assert!(idna_candidate(&fits));
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn oversized_unicode_skips_idna() {
+        let rs = rs_com_only();
+        let host = format!("{}.com", "é".repeat(10_000));
+        assert!(rs.split(&host, MatchOpts::default()).is_none());
+        let opts = MatchOpts {
+            max_len: usize::MAX,
+            ..MatchOpts::default()
+        };
+        let p = rs.split(&host, opts).expect("parts");
+        assert_eq!(p.tld, "com");
+        // Matched as-is, without conversion to an A-label.
+        assert!(p.sll.unwrap().starts_with('é'));
+    }
+
+    #[test]
+    fn raw_leading_dot_before_listed_tld_does_not_panic() {
+        let rs = rs_com_only();
+        let p = rs.split(".com", MatchOpts::raw()).expect("parts");
+        assert_eq!(p.prefix, None);
+        assert_eq!(p.sll, None);
+        assert_eq!(p.sld, Some("com".into()));
+        assert_eq!(p.tld, "com");
+    }
+
+    #[test]
+    fn parts_alias_accessors() {
+        let rs = rs_com_only();
+        let p = rs.split("www.example.com", MatchOpts::default()).unwrap();
+        assert_eq!(p.second_level_label(), Some("example"));
+        assert_eq!(p.registrable_domain(), Some("example.com"));
+        assert_eq!(p.public_suffix(), "com");
     }
 }
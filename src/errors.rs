@@ -27,14 +27,14 @@ pub enum Error {
     #[cfg(feature = "idna")]
     IdnaError(alloc::string::String),
     /// An error occurred when making an HTTP request
-    #[cfg(feature = "fetch")]
+    #[cfg(any(feature = "fetch", feature = "fetch-async", feature = "wasm"))]
     Fetch(Box<dyn StdError + Send + Sync + 'static>),
     /// A label in a domain name is longer than the 63-character limit.
     LabelTooLong {
         /// The label that is too long.
         label: alloc::string::String,
     },
-    /// A rule in the Public Suffix List exceeds the maximum allowed depth.
+    /// A rule has more labels than `LoadOpts::max_rule_depth` allows.
     RuleDepthExceeded {
         /// The depth of the rule.
         depth: usize,
@@ -42,6 +42,60 @@ pub enum Error {
     /// An I/O error occurred while reading the Public Suffix List.
     #[cfg(feature = "std")]
     Io(std::io::Error),
+    /// A date was not in `YYYY-MM-DD` form.
+    InvalidDate {
+        /// The rejected date string.
+        date: alloc::string::String,
+    },
+    /// No list snapshot exists on or before the requested date.
+    #[cfg(feature = "fetch")]
+    SnapshotNotFound {
+        /// The requested date.
+        date: alloc::string::String,
+    },
+    /// A fetched document does not look like a Public Suffix List
+    /// (e.g. an HTML error page served with a success status).
+    #[cfg(feature = "fetch")]
+    UnexpectedContent {
+        /// The URL that served the document.
+        url: alloc::string::String,
+    },
+    /// No storage engine can hold the list within the requested memory budget.
+    BudgetExceeded {
+        /// Approximate size of the smallest engine, in bytes.
+        needed: usize,
+        /// The requested budget, in bytes.
+        budget: usize,
+    },
+    /// The global list was already initialized when an explicit initializer ran.
+    GlobalAlreadyInitialized,
+    /// A line in a lookup record log could not be decoded.
+    InvalidRecord {
+        /// 1-based line number of the malformed record.
+        line: usize,
+    },
+    /// A compiled list is truncated or malformed.
+    InvalidBinary {
+        /// Byte offset (after the header) at which decoding failed.
+        offset: usize,
+    },
+    /// Persisted data was written in a format version this crate cannot read.
+    UnsupportedFormatVersion {
+        /// Name of the expected format.
+        format: &'static str,
+        /// The header that was found.
+        found: alloc::string::String,
+    },
+    /// The list does not fit the flat trie layout of `Engine::Flat` and
+    /// mapped lists: a label is longer than 65535 bytes, or the nodes or
+    /// label bytes number more than `u32::MAX`.
+    TooLargeForFlat,
+    /// Two lists being merged define the same rule differently (kind or
+    /// section), under `MergePolicy::ErrorOnConflict`.
+    MergeConflict {
+        /// The rule as written in the base list, `!` included.
+        rule: alloc::string::String,
+    },
 }
 
 /// Represents non-fatal issues encountered while parsing the Public Suffix List.
@@ -53,7 +107,8 @@ pub enum Warning {
         /// The duplicated rule.
         rule: alloc::string::String,
     },
-    /// A rule is shadowed by another more general rule (e.g., `b.com` is shadowed by `com`).
+    /// A rule has no effect because a wildcard sibling of the same section
+    /// already matches it (e.g., `a.ck` next to `*.ck`).
     ShadowedRule {
         /// The shadowed rule.
         rule: alloc::string::String,
@@ -68,8 +123,135 @@ pub enum Warning {
         /// The rule with the trailing dot.
         rule: alloc::string::String,
     },
+    /// A rule breaks the PSL rule syntax and was loaded as written, less
+    /// any leading or trailing dots. `LoadOpts::strict_rules` makes this an
+    /// error.
+    InvalidRule {
+        /// The rule as written in the list.
+        rule: alloc::string::String,
+        /// What is wrong with it.
+        reason: RuleSyntax,
+    },
+    /// A rule has a label longer than 63 octets and was loaded as written.
+    /// `LoadOpts::strict_rules` makes this an error.
+    RuleLabelTooLong {
+        /// The rule as written in the list.
+        rule: alloc::string::String,
+    },
+    /// A rule contained uppercase characters and was lowercased.
+    RuleCaseNormalized {
+        /// The rule as written in the list.
+        rule: alloc::string::String,
+    },
+    /// An `// @key value` annotation had an invalid value and was ignored.
+    InvalidAnnotation {
+        /// The annotation line.
+        line: alloc::string::String,
+    },
 }
 
+/// Why a host was rejected before matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HostError {
+    /// The host contains a control character (NUL, CR, LF, DEL, ...).
+    ControlChar {
+        /// Byte offset of the first control character.
+        index: usize,
+        /// The control character.
+        ch: char,
+    },
+    /// The host is empty.
+    Empty,
+    /// The host is longer than 253 octets, not counting a trailing dot.
+    TooLong {
+        /// Length of the host in octets.
+        len: usize,
+    },
+    /// A label is empty (a leading dot or `..`).
+    EmptyLabel {
+        /// Byte offset at which the empty label starts.
+        index: usize,
+    },
+    /// A label is longer than 63 octets.
+    LabelTooLong {
+        /// Byte offset at which the label starts.
+        index: usize,
+        /// Length of the label in octets.
+        len: usize,
+    },
+    /// The host contains a character other than an ASCII letter, digit,
+    /// hyphen or dot.
+    IllegalChar {
+        /// Byte offset of the first illegal character.
+        index: usize,
+        /// The illegal character.
+        ch: char,
+    },
+    /// A label starts or ends with a hyphen.
+    HyphenAtEdge {
+        /// Byte offset at which the label starts.
+        index: usize,
+    },
+    /// The host is not a valid internationalized domain name.
+    #[cfg(feature = "idna")]
+    InvalidIdna,
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+#[cfg(feature = "std")]
+impl StdError for HostError {}
+
+/// Why a lookup found no public suffix; see
+/// [`List::try_split`](crate::List::try_split).
+///
+/// Checked in the order listed, on the host as normalized by the match
+/// options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MatchError {
+    /// The host contains a control character and `opts.controls` rejects it.
+    ControlChar {
+        /// Byte offset of the first control character.
+        index: usize,
+        /// The control character.
+        ch: char,
+    },
+    /// The host is longer than `opts.max_len` or has more labels than
+    /// `opts.max_labels`.
+    TooLong,
+    /// The host failed IDNA conversion and the normalizer rejects such
+    /// hosts (`idna_reject_errors`).
+    #[cfg(feature = "idna")]
+    InvalidIdna,
+    /// The host is empty (or only a dot the normalizer removed).
+    EmptyInput,
+    /// The host has an empty label (`a..b`, or a leading dot that
+    /// `opts.compat` rejects).
+    EmptyLabel,
+    /// The host ends with a dot the normalizer does not strip, or that
+    /// `opts.compat` rejects.
+    TrailingDot,
+    /// The host is an IP literal or `localhost` name and `opts.literals`
+    /// (or `opts.compat`) rejects those.
+    Literal,
+    /// No rule matched, and `opts.strict` or `opts.fallback` rule out the
+    /// unlisted-TLD fallback.
+    NoRuleMatched,
+}
+
+impl fmt::Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+#[cfg(feature = "std")]
+impl StdError for MatchError {}
+
 /// Describes the reason for a rule syntax error.
 #[derive(Debug, Clone, Copy)]
 pub enum RuleSyntax {
@@ -79,9 +261,11 @@ pub enum RuleSyntax {
     HasEmptyLabel,
     /// The rule started or ended with a dot.
     StartsOrEndsWithDot,
-    /// The rule contained whitespace.
+    /// The rule contained whitespace, or text other than a comment
+    /// followed it on its line.
     ContainsWhitespace,
-    /// The rule contained an illegal character.
+    /// The rule contained an illegal character, including a `*` other than
+    /// a whole leftmost label or a `!` other than the exception mark.
     ContainsIllegalChar,
 }
 
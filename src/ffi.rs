@@ -0,0 +1,285 @@
+//! C interface, declared in `include/publicsuffix2.h`.
+//!
+//! Built with the `ffi` feature. The library is an `rlib` by default, so
+//! build a C-linkable artifact with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`).
+//!
+//! Ownership follows one rule: everything this module returns is owned by
+//! the caller and released with the matching free function. Strings come
+//! back NUL-terminated and are freed with [`psl_free`]; lists with
+//! [`psl_list_free`]; split results with [`psl_parts_free`]. Lookups use
+//! `MatchOpts::default()`, and any invalid input (a NULL pointer, a host
+//! that is not UTF-8) yields NULL or `-1` rather than undefined behavior on
+//! the Rust side.
+use crate::{List, MatchOpts};
+use alloc::{borrow::Cow, boxed::Box, ffi::CString};
+use core::ffi::{c_char, c_int, CStr};
+use core::ptr;
+
+/// An opaque parsed list, created by [`psl_list_new_from_text`].
+pub struct PslList(List);
+
+/// The parts of a host, filled in by [`psl_split`].
+///
+/// Absent parts are NULL; `tld` is always set after a successful split.
+#[repr(C)]
+#[derive(Debug)]
+pub struct PslParts {
+    /// Labels left of the registrable domain.
+    pub prefix: *mut c_char,
+    /// The label immediately left of the public suffix.
+    pub sll: *mut c_char,
+    /// The registrable domain (eTLD+1).
+    pub sld: *mut c_char,
+    /// The public suffix.
+    pub tld: *mut c_char,
+}
+
+/// Reads `host` as UTF-8, or `None` for NULL or invalid input.
+///
+/// # Safety
+///
+/// `host` must be NULL or point to a NUL-terminated string.
+unsafe fn host_str<'a>(host: *const c_char) -> Option<&'a str> {
+    if host.is_null() {
+        return None;
+    }
+    CStr::from_ptr(host).to_str().ok()
+}
+
+/// Hands `s` to the caller, or NULL if it has none or contains a NUL.
+fn into_c(s: Option<Cow<'_, str>>) -> *mut c_char {
+    s.and_then(|s| CString::new(s.into_owned()).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Parses `len` bytes of PSL text at `text`.
+///
+/// Returns NULL if the text is not UTF-8 or is not a valid list.
+///
+/// # Safety
+///
+/// `text` must point to `len` readable bytes. It need not be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn psl_list_new_from_text(text: *const c_char, len: usize) -> *mut PslList {
+    if text.is_null() {
+        return ptr::null_mut();
+    }
+    let bytes = core::slice::from_raw_parts(text.cast::<u8>(), len);
+    match core::str::from_utf8(bytes)
+        .ok()
+        .and_then(|t| t.parse().ok())
+    {
+        Some(list) => Box::into_raw(Box::new(PslList(list))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a list. NULL is ignored.
+///
+/// # Safety
+///
+/// `list` must be NULL or come from [`psl_list_new_from_text`], and must
+/// not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn psl_list_free(list: *mut PslList) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+/// Registrable domain (eTLD+1) of `host`, or NULL; see [`List::sld`].
+///
+/// # Safety
+///
+/// `list` must be NULL or a live list, and `host` NULL or a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn psl_sld(list: *const PslList, host: *const c_char) -> *mut c_char {
+    match (list.as_ref(), host_str(host)) {
+        (Some(list), Some(host)) => into_c(list.0.sld(host, MatchOpts::default())),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Public suffix of `host`, or NULL; see [`List::tld`].
+///
+/// # Safety
+///
+/// Same as [`psl_sld`].
+#[no_mangle]
+pub unsafe extern "C" fn psl_tld(list: *const PslList, host: *const c_char) -> *mut c_char {
+    match (list.as_ref(), host_str(host)) {
+        (Some(list), Some(host)) => into_c(list.0.tld(host, MatchOpts::default())),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Splits `host` into `out`; see [`List::split`].
+///
+/// Returns `0` on success and `-1` if `host` cannot be split, in which case
+/// every field of `out` is NULL. Release a filled `out` with
+/// [`psl_parts_free`].
+///
+/// # Safety
+///
+/// Same as [`psl_sld`]; `out` must be NULL or point to a writable
+/// `PslParts`.
+#[no_mangle]
+pub unsafe extern "C" fn psl_split(
+    list: *const PslList,
+    host: *const c_char,
+    out: *mut PslParts,
+) -> c_int {
+    let Some(out) = out.as_mut() else {
+        return -1;
+    };
+    *out = PslParts {
+        prefix: ptr::null_mut(),
+        sll: ptr::null_mut(),
+        sld: ptr::null_mut(),
+        tld: ptr::null_mut(),
+    };
+    let parts = match (list.as_ref(), host_str(host)) {
+        (Some(list), Some(host)) => list.0.split(host, MatchOpts::default()),
+        _ => None,
+    };
+    let Some(parts) = parts else {
+        return -1;
+    };
+    let tld = into_c(Some(parts.tld));
+    if tld.is_null() {
+        return -1;
+    }
+    *out = PslParts {
+        prefix: into_c(parts.prefix),
+        sll: into_c(parts.sll),
+        sld: into_c(parts.sld),
+        tld,
+    };
+    0
+}
+
+/// Frees the strings held by `parts` and sets them to NULL. NULL is
+/// ignored.
+///
+/// # Safety
+///
+/// `parts` must be NULL or point to a `PslParts` filled by [`psl_split`]
+/// (or all NULL).
+#[no_mangle]
+pub unsafe extern "C" fn psl_parts_free(parts: *mut PslParts) {
+    if let Some(parts) = parts.as_mut() {
+        for s in [
+            &mut parts.prefix,
+            &mut parts.sll,
+            &mut parts.sld,
+            &mut parts.tld,
+        ] {
+            psl_free(core::mem::replace(s, ptr::null_mut()));
+        }
+    }
+}
+
+/// Frees a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by [`psl_sld`] or [`psl_tld`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn psl_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = include_str!("../include/publicsuffix2.h");
+
+    fn take(s: *mut c_char) -> Option<String> {
+        if s.is_null() {
+            return None;
+        }
+        let out = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_owned();
+        unsafe { psl_free(s) };
+        Some(out)
+    }
+
+    #[test]
+    fn round_trip_through_c_api() {
+        let text = "com\nuk\nco.uk";
+        let list = unsafe { psl_list_new_from_text(text.as_ptr().cast(), text.len()) };
+        assert!(!list.is_null());
+
+        let host = c"www.example.co.uk";
+        assert_eq!(
+            take(unsafe { psl_sld(list, host.as_ptr()) }).as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            take(unsafe { psl_tld(list, host.as_ptr()) }).as_deref(),
+            Some("co.uk")
+        );
+
+        let mut parts = PslParts {
+            prefix: ptr::null_mut(),
+            sll: ptr::null_mut(),
+            sld: ptr::null_mut(),
+            tld: ptr::null_mut(),
+        };
+        assert_eq!(unsafe { psl_split(list, host.as_ptr(), &mut parts) }, 0);
+        let read = |s: *mut c_char| unsafe { CStr::from_ptr(s) }.to_str().unwrap();
+        assert_eq!(read(parts.prefix), "www");
+        assert_eq!(read(parts.sll), "example");
+        assert_eq!(read(parts.sld), "example.co.uk");
+        assert_eq!(read(parts.tld), "co.uk");
+        unsafe { psl_parts_free(&mut parts) };
+        assert!(parts.tld.is_null());
+
+        assert_eq!(unsafe { psl_split(list, c"".as_ptr(), &mut parts) }, -1);
+        assert!(parts.tld.is_null());
+        unsafe { psl_list_free(list) };
+    }
+
+    #[test]
+    fn invalid_input_yields_null() {
+        let bad = [0xffu8, b'\n'];
+        assert!(unsafe { psl_list_new_from_text(bad.as_ptr().cast(), bad.len()) }.is_null());
+        assert!(unsafe { psl_list_new_from_text(ptr::null(), 0) }.is_null());
+        assert!(unsafe { psl_sld(ptr::null(), c"a.com".as_ptr()) }.is_null());
+
+        let text = "com";
+        let list = unsafe { psl_list_new_from_text(text.as_ptr().cast(), text.len()) };
+        assert!(unsafe { psl_tld(list, ptr::null()) }.is_null());
+        let invalid = [b'a', 0xff, 0];
+        assert!(unsafe { psl_tld(list, invalid.as_ptr().cast()) }.is_null());
+        assert_eq!(
+            unsafe { psl_split(list, c"a.com".as_ptr(), ptr::null_mut()) },
+            -1
+        );
+        unsafe { psl_list_free(list) };
+        unsafe { psl_free(ptr::null_mut()) };
+        unsafe { psl_parts_free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn header_declares_every_export() {
+        for name in [
+            "psl_list_new_from_text",
+            "psl_list_free",
+            "psl_sld",
+            "psl_tld",
+            "psl_split",
+            "psl_parts_free",
+            "psl_free",
+        ] {
+            assert!(HEADER.contains(&format!("{name}(")), "{name}");
+        }
+    }
+}
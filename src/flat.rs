@@ -0,0 +1,452 @@
+//! Compact, read-only storage engine for the rule trie.
+//!
+//! The whole trie is one byte image, searched in place: fixed-size node
+//! records in breadth-first order, so the children of a node are contiguous
+//! and sorted by label and lookups binary-search them, followed by all
+//! labels back to back. The image lives on the heap, or (with the `mmap`
+//! feature) in a file mapped by [`List::open_mmap`](crate::List::open_mmap).
+//!
+//! Layout, with integers little-endian:
+//!
+//! - the node count and the length of the labels, each a `u32`;
+//! - each node: its label's offset in the labels (`u32`), the index of its
+//!   first child (`u32`), its child count (`u32`), its label's length
+//!   (`u16`), its leaf kind and its section (a byte each, coded as in
+//!   [`List::to_bytes`](crate::List::to_bytes)); the root comes first and
+//!   has an empty label;
+//! - the labels, UTF-8.
+use crate::binary::MAX_DEPTH;
+use crate::engine::Trie;
+use crate::errors::{Error, Result};
+use crate::rules::{Labels, Leaf, Node, RuleFn, RuleSet, Type};
+use alloc::{collections::VecDeque, string::String, vec, vec::Vec};
+use core::mem::size_of;
+use core::ops::Deref;
+
+/// Size of the image header.
+const HEADER_SIZE: usize = 8;
+/// Size of one node record.
+const NODE_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+struct FlatNode {
+    /// Byte range of this node's label in the labels.
+    label_start: u32,
+    label_len: u16,
+    /// Index of the first child; children are contiguous.
+    first_child: u32,
+    child_count: u32,
+    leaf: Leaf,
+    typ: Option<Type>,
+}
+
+impl FlatNode {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.label_start.to_le_bytes());
+        out.extend_from_slice(&self.first_child.to_le_bytes());
+        out.extend_from_slice(&self.child_count.to_le_bytes());
+        out.extend_from_slice(&self.label_len.to_le_bytes());
+        out.push(match self.leaf {
+            Leaf::None => 0,
+            Leaf::Positive => 1,
+            Leaf::Negative => 2,
+        });
+        out.push(match self.typ {
+            None => 0,
+            Some(Type::Icann) => 1,
+            Some(Type::Private) => 2,
+        });
+    }
+
+    /// Decodes a record, or `None` if its kind or section is unknown.
+    fn decode(rec: &[u8; NODE_SIZE]) -> Option<Self> {
+        let u32_at = |i: usize| u32::from_le_bytes([rec[i], rec[i + 1], rec[i + 2], rec[i + 3]]);
+        Some(Self {
+            label_start: u32_at(0),
+            first_child: u32_at(4),
+            child_count: u32_at(8),
+            label_len: u16::from_le_bytes([rec[12], rec[13]]),
+            leaf: match rec[14] {
+                0 => Leaf::None,
+                1 => Leaf::Positive,
+                2 => Leaf::Negative,
+                _ => return None,
+            },
+            typ: match rec[15] {
+                0 => None,
+                1 => Some(Type::Icann),
+                2 => Some(Type::Private),
+                _ => return None,
+            },
+        })
+    }
+}
+
+/// The bytes holding a trie image.
+#[derive(Clone, Debug)]
+pub(crate) enum Image {
+    Owned(Vec<u8>),
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(alloc::sync::Arc<crate::mapped::Mapping>),
+}
+
+impl Deref for Image {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Image::Owned(v) => v,
+            #[cfg(all(feature = "mmap", unix))]
+            Image::Mapped(m) => m,
+        }
+    }
+}
+
+/// A compiled rule trie stored in one byte image.
+#[derive(Clone, Debug)]
+pub(crate) struct FlatTrie {
+    image: Image,
+    /// Offset of the image header in `image`.
+    start: usize,
+    count: u32,
+    /// Offsets of the labels and of the end of the trie in `image`.
+    labels: usize,
+    end: usize,
+}
+
+impl FlatTrie {
+    /// Compiles a `RuleSet` into its flat form.
+    ///
+    /// Returns `Error::TooLargeForFlat` if a label or the whole trie
+    /// overflows the fixed-size fields of the layout.
+    pub(crate) fn from_ruleset(rules: &RuleSet) -> Result<Self> {
+        let fits = |n: usize| u32::try_from(n).map_err(|_| Error::TooLargeForFlat);
+        let mut nodes = vec![FlatNode {
+            label_start: 0,
+            label_len: 0,
+            first_child: 0,
+            child_count: 0,
+            leaf: rules.root.leaf,
+            typ: rules.root.typ,
+        }];
+        let mut labels = String::new();
+        let mut queue: VecDeque<(usize, &Node)> = VecDeque::from([(0, &rules.root)]);
+
+        while let Some((idx, node)) = queue.pop_front() {
+            let kids = node.sorted_kids(&rules.labels);
+            nodes[idx].first_child = fits(nodes.len())?;
+            nodes[idx].child_count = fits(kids.len())?;
+            for (label, child) in kids {
+                queue.push_back((nodes.len(), child));
+                nodes.push(FlatNode {
+                    label_start: fits(labels.len())?,
+                    label_len: u16::try_from(label.len()).map_err(|_| Error::TooLargeForFlat)?,
+                    first_child: 0,
+                    child_count: 0,
+                    leaf: child.leaf,
+                    typ: child.typ,
+                });
+                labels.push_str(label);
+            }
+        }
+
+        let count = fits(nodes.len())?;
+        let mut image = Vec::with_capacity(HEADER_SIZE + nodes.len() * NODE_SIZE + labels.len());
+        image.extend_from_slice(&count.to_le_bytes());
+        image.extend_from_slice(&fits(labels.len())?.to_le_bytes());
+        for node in &nodes {
+            node.encode(&mut image);
+        }
+        image.extend_from_slice(labels.as_bytes());
+        let end = image.len();
+        Ok(Self {
+            image: Image::Owned(image),
+            start: 0,
+            count,
+            labels: end - labels.len(),
+            end,
+        })
+    }
+
+    /// Reads the trie image at `start` in `image`, checking that it is a
+    /// well-formed tree, and returns it with the offset where it ends.
+    ///
+    /// Returns `Error::InvalidBinary` with the offset in `image` of the
+    /// first problem found.
+    pub(crate) fn from_image(image: Image, start: usize) -> Result<(Self, usize)> {
+        let err = |offset| Error::InvalidBinary { offset };
+        let u32_at = |at: usize| {
+            image
+                .get(at..at + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                .ok_or(err(at))
+        };
+        let count = u32_at(start)?;
+        let labels_len = u32_at(start + 4)?;
+        let labels = count
+            .checked_mul(NODE_SIZE)
+            .and_then(|n| n.checked_add(start + HEADER_SIZE))
+            .filter(|&at| at <= image.len())
+            .ok_or(err(start))?;
+        let end = labels
+            .checked_add(labels_len)
+            .filter(|&end| end <= image.len())
+            .ok_or(err(start + 4))?;
+        let text =
+            core::str::from_utf8(&image[labels..end]).map_err(|e| err(labels + e.valid_up_to()))?;
+        if count == 0 {
+            return Err(err(start));
+        }
+
+        // Each node's children must follow the previous node's, so every
+        // node but the root has exactly one parent and walks terminate.
+        let mut depth = vec![0u8; count];
+        let mut next = 1;
+        for i in 0..count {
+            let at = start + HEADER_SIZE + i * NODE_SIZE;
+            let rec = image[at..at + NODE_SIZE].try_into().ok();
+            let n = rec.and_then(FlatNode::decode).ok_or(err(at + 14))?;
+            let (label_start, label_end) = (
+                n.label_start as usize,
+                n.label_start as usize + usize::from(n.label_len),
+            );
+            if text.get(label_start..label_end).is_none() || (i == 0) != (label_end == 0) {
+                return Err(err(at));
+            }
+            let kids = n.first_child as usize..n.first_child as usize + n.child_count as usize;
+            if kids.start != next || kids.end > count {
+                return Err(err(at + 4));
+            }
+            next = kids.end;
+            if !kids.is_empty() && usize::from(depth[i]) >= MAX_DEPTH {
+                return Err(err(at + 8));
+            }
+            for kid in kids {
+                depth[kid] = depth[i] + 1;
+            }
+        }
+        if next != count {
+            return Err(err(start));
+        }
+
+        let trie = Self {
+            image,
+            start,
+            count: count as u32,
+            labels,
+            end,
+        };
+        Ok((trie, end))
+    }
+
+    /// The trie image, as read by [`FlatTrie::from_image`].
+    pub(crate) fn image(&self) -> &[u8] {
+        &self.image[self.start..self.end]
+    }
+
+    /// The bytes after the trie in its image.
+    pub(crate) fn rest(&self) -> &[u8] {
+        &self.image[self.end..]
+    }
+
+    /// Approximate heap and inline size of this trie in bytes. A mapped
+    /// image is not counted.
+    pub(crate) fn approx_bytes(&self) -> usize {
+        size_of::<Self>()
+            + match &self.image {
+                Image::Owned(v) => v.capacity(),
+                #[cfg(all(feature = "mmap", unix))]
+                Image::Mapped(_) => 0,
+            }
+    }
+
+    fn node(&self, idx: u32) -> Option<FlatNode> {
+        if idx >= self.count {
+            return None;
+        }
+        let at = self.start + HEADER_SIZE + idx as usize * NODE_SIZE;
+        let rec = self.image.get(at..at + NODE_SIZE)?;
+        FlatNode::decode(rec.try_into().ok()?)
+    }
+
+    fn label_bytes(&self, n: &FlatNode) -> &[u8] {
+        let start = self.labels + n.label_start as usize;
+        self.image
+            .get(start..start + usize::from(n.label_len))
+            .unwrap_or_default()
+    }
+
+    fn label(&self, n: &FlatNode) -> &str {
+        core::str::from_utf8(self.label_bytes(n)).unwrap_or_default()
+    }
+
+    /// Labels and indices of the children of `node`, sorted by label.
+    pub(crate) fn children(&self, node: u32) -> impl Iterator<Item = (&str, u32)> + '_ {
+        let (first, count) = self
+            .node(node)
+            .map_or((0, 0), |n| (n.first_child, n.child_count));
+        (first..first.saturating_add(count))
+            .filter_map(|i| self.node(i).map(|k| (self.label(&k), i)))
+    }
+
+    /// Rebuilds the `RuleSet` this trie was compiled from.
+    pub(crate) fn to_ruleset(&self) -> RuleSet {
+        fn copy(flat: &FlatTrie, idx: u32, node: &mut Node, labels: &mut Labels) {
+            node.leaf = flat.leaf(idx);
+            node.typ = flat.typ(idx);
+            for (label, kid) in flat.children(idx) {
+                copy(flat, kid, node.kid_or_insert(labels, label), labels);
+            }
+        }
+        let mut rules = RuleSet::default();
+        copy(self, self.root(), &mut rules.root, &mut rules.labels);
+        rules
+    }
+
+    /// Calls `f` with every rule: its labels from the right, its kind and
+    /// its section.
+    pub(crate) fn for_each_rule(&self, f: &mut RuleFn<'_>) {
+        fn walk<'a>(flat: &'a FlatTrie, idx: u32, path: &mut Vec<&'a str>, f: &mut RuleFn<'_>) {
+            if flat.leaf(idx) != Leaf::None {
+                f(path, flat.leaf(idx), flat.typ(idx));
+            }
+            for (label, kid) in flat.children(idx) {
+                path.push(label);
+                walk(flat, kid, path, f);
+                path.pop();
+            }
+        }
+        walk(self, self.root(), &mut Vec::new(), f);
+    }
+
+    /// Labels of the children of `node`, sorted.
+    pub(crate) fn child_labels(&self, node: u32) -> impl Iterator<Item = &str> + '_ {
+        self.children(node).map(|(label, _)| label)
+    }
+}
+
+impl Trie for FlatTrie {
+    type Node<'a> = u32;
+
+    fn root(&self) -> u32 {
+        0
+    }
+
+    fn child(&self, node: u32, label: &str) -> Option<u32> {
+        let n = self.node(node)?;
+        let (mut lo, mut hi) = (n.first_child, n.first_child.saturating_add(n.child_count));
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let kid = self.node(mid)?;
+            match self.label_bytes(&kid).cmp(label.as_bytes()) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Greater => hi = mid,
+                core::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    fn leaf(&self, node: u32) -> Leaf {
+        self.node(node).map_or(Leaf::None, |n| n.leaf)
+    }
+
+    fn typ(&self, node: u32) -> Option<Type> {
+        self.node(node).and_then(|n| n.typ)
+    }
+
+    fn is_childless(&self, node: u32) -> bool {
+        self.node(node).is_none_or(|n| n.child_count == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine;
+    use crate::options::{LoadOpts, MatchOpts};
+
+    fn rules() -> RuleSet {
+        crate::loader::load(
+            "com\nuk\nco.uk\n*.ck\n!www.ck\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io",
+            LoadOpts::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn flat_matches_hashmap_trie() {
+        let rs = rules();
+        let flat = FlatTrie::from_ruleset(&rs).unwrap();
+        let m = MatchOpts::default();
+        for host in [
+            "www.example.co.uk",
+            "a.b.ck",
+            "www.ck",
+            "x.github.io",
+            "example.local",
+            "com",
+            "",
+            "a..b",
+        ] {
+            assert_eq!(
+                engine::split(&flat, host, m),
+                engine::split(&rs, host, m),
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn children_are_sorted_and_contiguous() {
+        let flat = FlatTrie::from_ruleset(&rules()).unwrap();
+        let kids: Vec<(&str, u32)> = flat.children(0).collect();
+        assert_eq!(kids, [("ck", 1), ("com", 2), ("io", 3), ("uk", 4)]);
+        assert!(flat.child(0, "uk").is_some());
+        assert!(flat.child(0, "zz").is_none());
+    }
+
+    #[test]
+    fn image_roundtrips_at_an_offset() {
+        let flat = FlatTrie::from_ruleset(&rules()).unwrap();
+        let mut image = b"head".to_vec();
+        image.extend_from_slice(flat.image());
+        image.extend_from_slice(b"tail");
+        let (back, end) = FlatTrie::from_image(Image::Owned(image.clone()), 4).unwrap();
+        assert_eq!(&image[end..], b"tail");
+        assert_eq!(back.image(), flat.image());
+        assert_eq!(
+            FlatTrie::from_ruleset(&back.to_ruleset()).unwrap().image(),
+            flat.image()
+        );
+    }
+
+    #[test]
+    fn malformed_images_are_errors() {
+        let image = FlatTrie::from_ruleset(&rules()).unwrap().image().to_vec();
+        for cut in 0..image.len() {
+            assert!(
+                FlatTrie::from_image(Image::Owned(image[..cut].to_vec()), 0).is_err(),
+                "{cut}"
+            );
+        }
+        let record = |i: usize| HEADER_SIZE + i * NODE_SIZE;
+        let corrupt = |at: usize, byte: u8| {
+            let mut bad = image.clone();
+            bad[at] = byte;
+            FlatTrie::from_image(Image::Owned(bad), 0).map(|_| ())
+        };
+        // An unknown leaf kind, a child pointing back at the root, and a
+        // label running past the labels.
+        for (at, byte) in [
+            (record(1) + 14, 7),
+            (record(0) + 4, 0),
+            (record(1) + 12, 99),
+        ] {
+            assert!(
+                matches!(corrupt(at, byte), Err(Error::InvalidBinary { .. })),
+                "{at}"
+            );
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Versioning of the formats this crate writes.
+//!
+//! Every persisted format starts with a header naming the format and its
+//! version. Readers check the header before decoding anything else, so data
+//! written by an incompatible crate version fails with
+//! `Error::UnsupportedFormatVersion` instead of being misread.
+//!
+//! Text formats use a first line of the form:
+//!
+//! ```text
+//! #publicsuffix2 <format> v<version>
+//! ```
+//!
+//! Binary formats start with the bytes `PS2\0`, the length of the format
+//! name as one byte, the name, and the version as a little-endian `u16`.
+use crate::errors::{Error, Result};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+const TEXT_MAGIC: &str = "#publicsuffix2";
+const BINARY_MAGIC: &[u8] = b"PS2\0";
+
+/// A persisted format written by this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// Lookup record logs written by [`Recorder`](crate::replay::Recorder).
+    ReplayLog,
+    /// Compiled lists written by [`List::to_bytes`](crate::List::to_bytes).
+    CompiledList,
+    /// Lists compiled for memory mapping, written by
+    /// [`List::to_mapped_bytes`](crate::List::to_mapped_bytes).
+    MappedList,
+    /// HTTP validators stored next to a list cached by `List::from_url_cached`.
+    HttpCacheMeta,
+}
+
+/// Version number of a persisted format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatVersion(pub u16);
+
+impl fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl Format {
+    /// Stable name of the format, as written in headers.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Format::ReplayLog => "replay",
+            Format::CompiledList => "list",
+            Format::MappedList => "mapped",
+            Format::HttpCacheMeta => "http-cache",
+        }
+    }
+
+    /// Version written by this crate.
+    pub const fn current(self) -> FormatVersion {
+        match self {
+            Format::MappedList | Format::HttpCacheMeta => FormatVersion(1),
+            Format::ReplayLog | Format::CompiledList => FormatVersion(2),
+        }
+    }
+
+    /// Oldest version this crate can still read.
+    pub const fn min_supported(self) -> FormatVersion {
+        match self {
+            Format::ReplayLog
+            | Format::CompiledList
+            | Format::MappedList
+            | Format::HttpCacheMeta => FormatVersion(1),
+        }
+    }
+
+    /// Returns true if this crate can read `version` of the format.
+    pub fn supports(self, version: FormatVersion) -> bool {
+        (self.min_supported()..=self.current()).contains(&version)
+    }
+
+    /// Header line (without newline) for the current version.
+    pub fn text_header(self) -> String {
+        format!("{TEXT_MAGIC} {} {}", self.name(), self.current())
+    }
+
+    /// Parses a header line written by [`Format::text_header`] and checks
+    /// that its version is supported.
+    ///
+    /// Returns `Ok(None)` if `line` is not a header at all. Returns
+    /// `Error::UnsupportedFormatVersion` for a header of this format with an
+    /// unsupported version, or a header of another format.
+    pub fn check_text_header(self, line: &str) -> Result<Option<FormatVersion>> {
+        let Some(rest) = line.strip_prefix(TEXT_MAGIC) else {
+            return Ok(None);
+        };
+        let mut parts = rest.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let version = parts
+            .next()
+            .and_then(|v| v.strip_prefix('v'))
+            .and_then(|v| v.parse().ok())
+            .map(FormatVersion);
+        match version {
+            Some(v) if name == self.name() && parts.next().is_none() && self.supports(v) => {
+                Ok(Some(v))
+            }
+            _ => Err(Error::UnsupportedFormatVersion {
+                format: self.name(),
+                found: line.into(),
+            }),
+        }
+    }
+    /// Header bytes for the current version.
+    pub fn binary_header(self) -> Vec<u8> {
+        let name = self.name().as_bytes();
+        let mut out = Vec::with_capacity(BINARY_MAGIC.len() + 3 + name.len());
+        out.extend_from_slice(BINARY_MAGIC);
+        out.push(name.len() as u8);
+        out.extend_from_slice(name);
+        out.extend_from_slice(&self.current().0.to_le_bytes());
+        out
+    }
+
+    /// Checks the header written by [`Format::binary_header`] and returns
+    /// its version together with the bytes following it.
+    ///
+    /// Returns `Error::UnsupportedFormatVersion` if `bytes` do not start
+    /// with a supported header of this format.
+    pub fn check_binary_header(self, bytes: &[u8]) -> Result<(FormatVersion, &[u8])> {
+        let header = bytes.strip_prefix(BINARY_MAGIC).and_then(|rest| {
+            let (&len, rest) = rest.split_first()?;
+            let name = rest.get(..len as usize)?;
+            let rest = &rest[len as usize..];
+            let version = rest.get(..2)?;
+            let version = FormatVersion(u16::from_le_bytes([version[0], version[1]]));
+            Some((name, version, &rest[2..]))
+        });
+        match header {
+            Some((name, version, rest))
+                if name == self.name().as_bytes() && self.supports(version) =>
+            {
+                Ok((version, rest))
+            }
+            Some((name, version, _)) => Err(Error::UnsupportedFormatVersion {
+                format: self.name(),
+                found: format!("{} {version}", String::from_utf8_lossy(name)),
+            }),
+            None => Err(Error::UnsupportedFormatVersion {
+                format: self.name(),
+                found: "no binary header".into(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrips() {
+        let f = Format::ReplayLog;
+        assert_eq!(f.text_header(), "#publicsuffix2 replay v2");
+        assert_eq!(
+            f.check_text_header(&f.text_header()).unwrap(),
+            Some(f.current())
+        );
+        assert_eq!(
+            f.check_text_header("v2;w=1;s=0;t=A;n=-\tcom").unwrap(),
+            None
+        );
+        assert_eq!(
+            f.check_text_header("#publicsuffix2 replay v1").unwrap(),
+            Some(FormatVersion(1))
+        );
+    }
+
+    #[test]
+    fn unsupported_headers_are_errors() {
+        let f = Format::ReplayLog;
+        for line in [
+            "#publicsuffix2 replay v0",
+            "#publicsuffix2 replay v999",
+            "#publicsuffix2 replay 1",
+            "#publicsuffix2 bundle v1",
+            "#publicsuffix2 replay v1 extra",
+            "#publicsuffix2",
+        ] {
+            assert!(
+                matches!(
+                    f.check_text_header(line),
+                    Err(Error::UnsupportedFormatVersion {
+                        format: "replay",
+                        ..
+                    })
+                ),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn binary_header_roundtrips() {
+        let f = Format::CompiledList;
+        let mut bytes = f.binary_header();
+        bytes.extend_from_slice(b"body");
+        let (v, rest) = f.check_binary_header(&bytes).unwrap();
+        assert_eq!((v, rest), (f.current(), &b"body"[..]));
+
+        let mut future = f.binary_header();
+        let n = future.len();
+        future[n - 2..].copy_from_slice(&999u16.to_le_bytes());
+        for bad in [
+            &b""[..],
+            b"PS2\0",
+            b"com\nuk",
+            &future,
+            &Format::ReplayLog.binary_header(),
+        ] {
+            assert!(matches!(
+                f.check_binary_header(bad),
+                Err(Error::UnsupportedFormatVersion { format: "list", .. })
+            ));
+        }
+    }
+}
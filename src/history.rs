@@ -0,0 +1,160 @@
+//! Historical PSL snapshots.
+//!
+//! Resolves the Public Suffix List as it existed on a given calendar date,
+//! either from the GitHub history of `publicsuffix/list` or from any archive
+//! that exposes dated snapshots under a URL template.
+use crate::errors::{Error, Result};
+use crate::http;
+use crate::rules::is_valid_date;
+
+/// A location from which dated PSL snapshots can be retrieved.
+#[derive(Clone, Copy, Debug)]
+pub enum Archive<'a> {
+    /// The commit history of a GitHub repository.
+    ///
+    /// The last commit touching `path` on or before the requested date is
+    /// resolved through the REST API at `api`, then the file is downloaded
+    /// from `raw` at that commit.
+    GitHub {
+        /// Base URL of the GitHub REST API (e.g. `https://api.github.com`).
+        api: &'a str,
+        /// Base URL for raw file contents (e.g. `https://raw.githubusercontent.com`).
+        raw: &'a str,
+        /// Repository in `owner/name` form.
+        repo: &'a str,
+        /// Path of the list file inside the repository.
+        path: &'a str,
+    },
+    /// A snapshot archive addressed by a URL template.
+    ///
+    /// Every `{date}` in the template is replaced by the `YYYY-MM-DD` date.
+    Template(&'a str),
+}
+
+/// The official PSL repository on GitHub.
+pub const GITHUB: Archive<'static> = Archive::GitHub {
+    api: "https://api.github.com",
+    raw: "https://raw.githubusercontent.com",
+    repo: "publicsuffix/list",
+    path: "public_suffix_list.dat",
+};
+
+/// Fetches the raw PSL text as of `date` (`YYYY-MM-DD`, end of day UTC).
+pub fn fetch_at(archive: &Archive<'_>, date: &str) -> Result<String> {
+    if !is_valid_date(date) {
+        return Err(Error::InvalidDate { date: date.into() });
+    }
+    match *archive {
+        Archive::GitHub {
+            api,
+            raw,
+            repo,
+            path,
+        } => {
+            let url = format!(
+                "{}/repos/{repo}/commits?path={path}&until={date}T23:59:59Z&per_page=1",
+                api.trim_end_matches('/')
+            );
+            let body = http::get(&url)?;
+            let sha =
+                first_sha(&body).ok_or_else(|| Error::SnapshotNotFound { date: date.into() })?;
+            http::get(&format!(
+                "{}/{repo}/{sha}/{path}",
+                raw.trim_end_matches('/')
+            ))
+        }
+        Archive::Template(tpl) => http::get(&tpl.replace("{date}", date)),
+    }
+}
+
+/// Extracts the first commit SHA from a GitHub "list commits" response.
+///
+/// The response is a JSON array of commit objects whose first `"sha"` key
+/// is the commit id; a full JSON parser is not needed for that.
+fn first_sha(body: &str) -> Option<&str> {
+    let rest = &body[body.find("\"sha\"")? + 5..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    let sha = &rest[..rest.find('"')?];
+    (sha.len() >= 7 && sha.bytes().all(|b| b.is_ascii_hexdigit())).then_some(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[test]
+    fn first_sha_from_commit_list() {
+        let body =
+            r#"[{"sha": "0123abcd0123abcd", "node_id": "x", "commit": {"tree": {"sha": "ffff"}}}]"#;
+        assert_eq!(first_sha(body), Some("0123abcd0123abcd"));
+        assert_eq!(first_sha("[]"), None);
+        assert_eq!(first_sha(r#"[{"sha":"nothex!"}]"#), None);
+    }
+
+    #[test]
+    fn github_archive_resolves_commit_then_file() {
+        let mut server = Server::new();
+        let commits = server
+            .mock("GET", "/repos/o/r/commits")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "until".into(),
+                "2019-05-01T23:59:59Z".into(),
+            ))
+            .with_body(r#"[{"sha":"deadbeef"}]"#)
+            .create();
+        let file = server
+            .mock("GET", "/o/r/deadbeef/list.dat")
+            .with_body("com\n")
+            .create();
+
+        let url = server.url();
+        let archive = Archive::GitHub {
+            api: &url,
+            raw: &url,
+            repo: "o/r",
+            path: "list.dat",
+        };
+        assert_eq!(fetch_at(&archive, "2019-05-01").unwrap(), "com\n");
+        commits.assert();
+        file.assert();
+    }
+
+    #[test]
+    fn github_archive_without_commits_is_not_found() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_body("[]")
+            .create();
+        let url = server.url();
+        let archive = Archive::GitHub {
+            api: &url,
+            raw: &url,
+            repo: "o/r",
+            path: "list.dat",
+        };
+        let err = fetch_at(&archive, "1999-01-01").unwrap_err();
+        assert!(matches!(err, Error::SnapshotNotFound { .. }));
+    }
+
+    #[test]
+    fn template_archive_substitutes_date() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/snap/2021-02-03.dat")
+            .with_body("net\n")
+            .create();
+        let tpl = format!("{}/snap/{{date}}.dat", server.url());
+        assert_eq!(
+            fetch_at(&Archive::Template(&tpl), "2021-02-03").unwrap(),
+            "net\n"
+        );
+        m.assert();
+        assert!(matches!(
+            fetch_at(&Archive::Template(&tpl), "yesterday"),
+            Err(Error::InvalidDate { .. })
+        ));
+    }
+}
@@ -1,5 +1,15 @@
 use crate::errors::{Error, Result};
+use crate::format::Format;
+use crate::options::FetchOpts;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::thread;
 
+/// Fetches `url` as text.
+///
+/// Requests ask for a gzip-compressed body (`Accept-Encoding: gzip`), which
+/// ureq decompresses transparently.
 pub fn get(url: &str) -> Result<String> {
     let agent = ureq::agent();
     agent
@@ -10,6 +20,147 @@ pub fn get(url: &str) -> Result<String> {
         .map_err(Error::Io)
 }
 
+/// Opens `url` for reading the (decompressed) body as it arrives, retrying
+/// failed requests as described on [`FetchOpts`].
+pub fn open(url: &str, opts: &FetchOpts<'_>) -> Result<impl Read + Send> {
+    let agent = agent(opts)?;
+    let mut delay = opts.backoff;
+    let mut attempt = 0;
+    loop {
+        match agent.get(url).call() {
+            Ok(response) => return Ok(response.into_reader()),
+            Err(e) if attempt < opts.retries && retryable(&e) => {
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+            Err(e) => return Err(Error::Fetch(Box::new(e))),
+        }
+    }
+}
+
+fn agent(opts: &FetchOpts<'_>) -> Result<ureq::Agent> {
+    let mut builder = ureq::AgentBuilder::new();
+    if let Some(timeout) = opts.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(user_agent) = opts.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(proxy) = opts.proxy {
+        let proxy = ureq::Proxy::new(proxy).map_err(|e| Error::Fetch(Box::new(e)))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder.build())
+}
+
+/// Whether a failed request may succeed if sent again.
+fn retryable(e: &ureq::Error) -> bool {
+    match e {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(t) => matches!(
+            t.kind(),
+            ureq::ErrorKind::Dns
+                | ureq::ErrorKind::ConnectionFailed
+                | ureq::ErrorKind::Io
+                | ureq::ErrorKind::ProxyConnect
+        ),
+    }
+}
+
+/// HTTP validators of a cached response.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+        Format::HttpCacheMeta.check_text_header(lines.next().unwrap_or_default())?;
+        let mut v = Self::default();
+        for line in lines {
+            match line.split_once(' ') {
+                Some(("etag", value)) => v.etag = Some(value.into()),
+                Some(("last-modified", value)) => v.last_modified = Some(value.into()),
+                _ => {}
+            }
+        }
+        Ok(v)
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = Format::HttpCacheMeta.text_header();
+        out.push('\n');
+        for (key, value) in [("etag", &self.etag), ("last-modified", &self.last_modified)] {
+            if let Some(value) = value {
+                out.push_str(&format!("{key} {value}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// Path of the validators file kept next to `cache`.
+fn meta_path(cache: &Path) -> PathBuf {
+    let mut p = cache.as_os_str().to_owned();
+    p.push(".meta");
+    PathBuf::from(p)
+}
+
+/// Writes `contents` to `path` through a temporary file, so readers never
+/// see a partial file.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents).map_err(Error::Io)?;
+    fs::rename(&tmp, path).map_err(Error::Io)
+}
+
+/// Fetches `url` unless the copy cached at `cache` is still current, and
+/// passes the text to `parse`. Returns the parsed value and whether the
+/// text was downloaded.
+///
+/// The cache is only updated once `parse` accepts the downloaded text, so
+/// a bad response never replaces a good cached copy.
+pub fn get_cached<T>(
+    url: &str,
+    cache: &Path,
+    parse: impl FnOnce(&str) -> Result<T>,
+) -> Result<(T, bool)> {
+    let meta = meta_path(cache);
+    let cached = fs::read_to_string(cache).ok();
+    let validators = match (&cached, fs::read_to_string(&meta)) {
+        (Some(_), Ok(text)) => Validators::parse(&text).unwrap_or_default(),
+        _ => Validators::default(),
+    };
+
+    let mut req = ureq::agent().get(url);
+    if let Some(etag) = &validators.etag {
+        req = req.set("If-None-Match", etag);
+    }
+    if let Some(date) = &validators.last_modified {
+        req = req.set("If-Modified-Since", date);
+    }
+    let resp = req.call().map_err(|e| Error::Fetch(Box::new(e)))?;
+
+    if resp.status() == 304 {
+        if let Some(text) = cached {
+            return Ok((parse(&text)?, false));
+        }
+    }
+    let fresh = Validators {
+        etag: resp.header("ETag").map(Into::into),
+        last_modified: resp.header("Last-Modified").map(Into::into),
+    };
+    let text = resp.into_string().map_err(Error::Io)?;
+    let value = parse(&text)?;
+    write_atomic(cache, &text)?;
+    write_atomic(&meta, &fresh.to_text())?;
+    Ok((value, true))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,6 +184,16 @@ mod tests {
         assert_eq!(result.unwrap(), "test data");
     }
 
+    #[test]
+    fn validators_roundtrip() {
+        let v = Validators {
+            etag: Some("\"abc\"".into()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+        };
+        assert_eq!(Validators::parse(&v.to_text()).unwrap(), v);
+        assert!(Validators::parse("#publicsuffix2 http-cache v9\n").is_err());
+    }
+
     #[test]
     fn test_get_failure() {
         let mut server = Server::new();
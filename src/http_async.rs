@@ -0,0 +1,94 @@
+use crate::errors::{Error, Result};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use std::io::Read;
+
+/// Fetches `url` as text, asking for a gzip- or deflate-compressed body
+/// and decompressing it.
+pub async fn get(url: &str) -> Result<String> {
+    let fail = |e: reqwest::Error| Error::Fetch(Box::new(e));
+    let response = reqwest::Client::new()
+        .get(url)
+        .header(ACCEPT_ENCODING, "gzip, deflate")
+        .send()
+        .await
+        .map_err(fail)?
+        .error_for_status()
+        .map_err(fail)?;
+    let encoding = response.headers().get(CONTENT_ENCODING).cloned();
+    let body = response.bytes().await.map_err(fail)?;
+    let mut text = String::new();
+    match encoding.as_ref().map(|e| e.as_bytes()) {
+        Some(b"gzip") => GzDecoder::new(&body[..]).read_to_string(&mut text),
+        Some(b"deflate") => ZlibDecoder::new(&body[..]).read_to_string(&mut text),
+        _ => return String::from_utf8(body.into()).map_err(|_| Error::NotUtf8),
+    }
+    .map_err(Error::Io)?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_get_success() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/dat")
+            .with_status(200)
+            .with_body("test data")
+            .create_async()
+            .await;
+
+        let result = get(&format!("{}/dat", server.url())).await;
+
+        mock.assert_async().await;
+        assert_eq!(result.unwrap(), "test data");
+    }
+
+    #[tokio::test]
+    async fn test_get_failure() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/dat")
+            .with_status(500)
+            .with_body("server error")
+            .create_async()
+            .await;
+
+        let result = get(&format!("{}/dat", server.url())).await;
+
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_decompresses() {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"gzip data").unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(b"deflate data").unwrap();
+        let mut server = Server::new_async().await;
+        for (path, encoding, body, text) in [
+            ("/gz", "gzip", gz.finish().unwrap(), "gzip data"),
+            ("/zlib", "deflate", zlib.finish().unwrap(), "deflate data"),
+        ] {
+            let mock = server
+                .mock("GET", path)
+                .match_header("accept-encoding", "gzip, deflate")
+                .with_header("content-encoding", encoding)
+                .with_body(body)
+                .create_async()
+                .await;
+            let result = get(&format!("{}{path}", server.url())).await;
+            mock.assert_async().await;
+            assert_eq!(result.unwrap(), text);
+        }
+    }
+}
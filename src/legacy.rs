@@ -0,0 +1,81 @@
+//! python-publicsuffix2 method names, for code ported from Python.
+//!
+//! PS2 names the registrable domain `get_sld` and the public suffix
+//! `get_tld`, and keeps `get_public_suffix` as an alias of `get_sld`. The
+//! [`LegacyNames`] trait provides these names on [`List`] with the same
+//! `wildcard` and `strict` switches, so ported call sites can keep their
+//! shape. New code should prefer [`List::sld`] and [`List::tld`], or the
+//! [`Parts`](crate::Parts) accessors `registrable_domain` and `public_suffix`.
+use crate::options::MatchOpts;
+use crate::List;
+use alloc::borrow::Cow;
+
+/// PS2's lookup methods, implemented for [`List`].
+///
+/// ```rust
+/// use publicsuffix2::legacy::LegacyNames;
+/// use publicsuffix2::List;
+///
+/// let list: List = "com\nuk\nco.uk".parse().unwrap();
+/// assert_eq!(list.get_sld("www.example.co.uk", true, false).as_deref(), Some("example.co.uk"));
+/// assert_eq!(list.get_tld("www.example.co.uk", true, false).as_deref(), Some("co.uk"));
+/// ```
+pub trait LegacyNames {
+    /// PS2 `get_sld`: the registrable domain (eTLD+1), as [`List::sld`].
+    fn get_sld<'a>(&self, host: &'a str, wildcard: bool, strict: bool) -> Option<Cow<'a, str>>;
+
+    /// PS2 `get_tld`: the public suffix (eTLD), as [`List::tld`].
+    fn get_tld<'a>(&self, host: &'a str, wildcard: bool, strict: bool) -> Option<Cow<'a, str>>;
+
+    /// PS2 `get_public_suffix`: a deprecated alias of `get_sld` in PS2.
+    ///
+    /// Despite the name it returns the registrable domain, not the public
+    /// suffix; use [`LegacyNames::get_tld`] for the latter.
+    fn get_public_suffix<'a>(
+        &self,
+        host: &'a str,
+        wildcard: bool,
+        strict: bool,
+    ) -> Option<Cow<'a, str>> {
+        self.get_sld(host, wildcard, strict)
+    }
+}
+
+fn ps2_opts(wildcard: bool, strict: bool) -> MatchOpts<'static> {
+    MatchOpts {
+        wildcard,
+        strict,
+        ..MatchOpts::ps2()
+    }
+}
+
+impl LegacyNames for List {
+    fn get_sld<'a>(&self, host: &'a str, wildcard: bool, strict: bool) -> Option<Cow<'a, str>> {
+        self.sld(host, ps2_opts(wildcard, strict))
+    }
+
+    fn get_tld<'a>(&self, host: &'a str, wildcard: bool, strict: bool) -> Option<Cow<'a, str>> {
+        self.tld(host, ps2_opts(wildcard, strict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_names_map_to_ps2_semantics() {
+        let list: List = "com\n*.ck\n!www.ck".parse().unwrap();
+        assert_eq!(
+            list.get_sld("a.b.example.com", true, false).as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            list.get_public_suffix("a.b.example.com", true, false),
+            list.get_sld("a.b.example.com", true, false)
+        );
+        assert_eq!(list.get_tld("a.b.ck", true, false).as_deref(), Some("b.ck"));
+        assert_eq!(list.get_tld("a.b.ck", false, false).as_deref(), Some("ck"));
+        assert_eq!(list.get_tld("example.local", true, true), None);
+    }
+}
@@ -1,28 +1,126 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+
+mod batch;
+mod binary;
+mod builder;
+#[cfg(feature = "fst")]
+mod compact;
+pub mod conformance;
+pub mod cookies;
+pub mod cursor;
+pub mod diff;
+mod domain;
+mod edit;
 pub mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod format;
 pub mod options;
+#[cfg(feature = "std")]
+pub mod replay;
 
 mod engine;
+use engine::Trie;
+mod flat;
+#[cfg(feature = "fetch")]
+pub mod history;
 #[cfg(feature = "fetch")]
 mod http;
-mod loader;
+#[cfg(feature = "fetch-async")]
+mod http_async;
+pub mod legacy;
+pub mod lint;
+pub mod loader;
+mod mapped;
+mod merge;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "psl-types")]
+mod psl_compat;
 mod rules;
+mod scratch;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "std")]
+mod shared;
+mod site;
+#[cfg(feature = "fetch")]
+pub mod source;
+pub mod spec;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod url_host;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "watch")]
+mod watch;
+mod writer;
 
-pub use engine::Parts;
-pub use errors::{Error, Result, Warning};
-use once_cell::sync::Lazy;
-pub use options::{CommentPolicy, LoadOpts, MatchOpts, Normalizer, SectionPolicy};
-pub use rules::{Type, TypeFilter};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+pub use batch::MapSlds;
+pub use builder::ListBuilder;
+use core::{fmt, str::FromStr};
+pub use domain::{Domain, RegistrableDomain};
+pub use engine::{
+    Boundary, BoundaryInfo, Classification, MatchInfo, Parts, PartsIdx, RuleKind, RuleMatch,
+};
+pub use errors::{Error, HostError, MatchError, Result, Warning};
+use hashbrown::HashMap;
+pub use merge::{LayeredList, MergePolicy};
+#[cfg(feature = "std")]
+use once_cell::sync::OnceCell;
+#[cfg(feature = "fetch")]
+pub use options::FetchOpts;
+pub use options::{
+    CommentPolicy, CompatMode, ControlPolicy, FallbackPolicy, LiteralPolicy, LoadOpts, MatchOpts,
+    Normalizer, SectionPolicy, WriteOpts,
+};
+pub use rules::{
+    CommentBlock, Engine, Leaf, ListStats, ListVersion, RuleMeta, RuleRef, RuleVisitor,
+    SectionCounts, Type, TypeFilter,
+};
+#[cfg(feature = "std")]
+pub use shared::{SharedList, SharedListCache};
 #[cfg(feature = "std")]
 use std::path::Path;
-use std::{borrow::Cow, str::FromStr};
+pub use url_host::UrlHost;
+#[cfg(feature = "watch")]
+pub use watch::{FileWatcher, WatchOpts};
 
-static GLOBAL_LIST: Lazy<List> = Lazy::new(|| {
-    let text = include_str!("../tests/fixtures/public_suffix_list.dat");
-    text.parse()
-        .expect("parsing the embedded public suffix list should not fail")
-});
+#[cfg(not(feature = "bundled-list"))]
+const EMBEDDED_PSL: &str = include_str!("../tests/fixtures/public_suffix_list.dat");
+/// The list prepared by `build.rs`.
+#[cfg(feature = "bundled-list")]
+const EMBEDDED_PSL: &str = include_str!(concat!(env!("OUT_DIR"), "/public_suffix_list.dat"));
 
-#[derive(Clone, Debug)]
+#[cfg(feature = "std")]
+static GLOBAL_LIST: OnceCell<List> = OnceCell::new();
+#[cfg(feature = "bundled-list")]
+static BUNDLED_LIST: OnceCell<List> = OnceCell::new();
+
+/// Hosts used by [`List::warm`] when no samples are given: common gTLDs,
+/// multi-label ccTLD suffixes, a wildcard/exception pair, a private suffix
+/// and an IDN.
+const WARM_HOSTS: &[&str] = &[
+    "www.example.com",
+    "www.example.net",
+    "www.example.org",
+    "www.example.co.uk",
+    "www.example.com.au",
+    "www.example.co.jp",
+    "foo.city.kawasaki.jp",
+    "user.github.io",
+    "www.食狮.中国",
+];
+
+#[derive(Clone)]
 /// A compiled Public Suffix List (PSL) and matcher.
 ///
 /// This type owns the parsed rule tree and provides PS2-compatible queries:
@@ -31,8 +129,69 @@ static GLOBAL_LIST: Lazy<List> = Lazy::new(|| {
 /// - split: prefix / SLL / SLD / TLD
 ///
 /// Cloning `List` is cheap (the underlying rules are shared).
+///
+/// Its `Debug` output is a summary that takes no walk over the rules: the
+/// engine, the rule count, the rules loaded into each section (see
+/// [`section_counts`](Self::section_counts)), the `// VERSION:` and
+/// `// COMMIT:` of the snapshot and the number of warnings. Use
+/// [`dump`](Self::dump) to print the whole rule tree and
+/// [`fingerprint`](Self::fingerprint).
 pub struct List {
-    rules: rules::RuleSet,
+    store: Arc<Store>,
+    warnings: Vec<Warning>,
+    meta: Vec<RuleMeta>,
+    comments: Arc<rules::CommentIndex>,
+    version: Option<ListVersion>,
+    sections: SectionCounts,
+    /// Number of rules in `store`, counted when it is built or changed.
+    len: usize,
+}
+
+/// The storage engine holding a `List`'s rules.
+#[derive(Clone, Debug)]
+enum Store {
+    Trie(rules::RuleSet),
+    Flat(flat::FlatTrie),
+    #[cfg(feature = "fst")]
+    Compact(compact::CompactTrie),
+}
+
+impl List {
+    fn from_ruleset(mut rules: rules::RuleSet) -> Self {
+        let warnings = core::mem::take(&mut rules.warnings);
+        let meta = core::mem::take(&mut rules.meta);
+        let comments = Arc::new(core::mem::take(&mut rules.comments));
+        let version = rules.version.take().map(|v| *v);
+        let sections = *core::mem::take(&mut rules.sections);
+        let mut list = Self {
+            store: Arc::new(Store::Trie(rules)),
+            warnings,
+            meta,
+            comments,
+            version,
+            sections,
+            len: 0,
+        };
+        list.recount();
+        list
+    }
+
+    /// Counts the rules again after `store` was built or changed.
+    fn recount(&mut self) {
+        self.len = self.count_rules(|_| true);
+    }
+
+    /// Records `url` as the address the list was downloaded from.
+    #[cfg(any(
+        feature = "fetch",
+        feature = "fetch-async",
+        feature = "wasm",
+        feature = "bundled-list"
+    ))]
+    fn fetched_from(mut self, url: &str) -> Self {
+        self.version.get_or_insert_with(ListVersion::default).url = Some(url.into());
+        self
+    }
 }
 
 impl Default for List {
@@ -40,7 +199,8 @@ impl Default for List {
     ///
     /// This is a convenient way to get a `List` ready for use without
     /// needing to parse a list source manually. It is equivalent to
-    /// calling `List::global().clone()`.
+    /// calling `List::global().clone()`. Without the `std` feature there is
+    /// no global list, and the built-in list is parsed on every call.
     ///
     /// # Example
     ///
@@ -52,7 +212,12 @@ impl Default for List {
     /// assert_eq!(tld.as_deref(), Some("com"));
     /// ```
     fn default() -> Self {
-        GLOBAL_LIST.clone()
+        #[cfg(feature = "std")]
+        return Self::global().clone();
+        #[cfg(not(feature = "std"))]
+        EMBEDDED_PSL
+            .parse()
+            .expect("parsing the embedded public suffix list should not fail")
     }
 }
 
@@ -71,11 +236,45 @@ impl FromStr for List {
     /// let psl_data = "com\nuk\nco.uk";
     /// let list: List = psl_data.parse().expect("Failed to parse list");
     /// ```
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         Self::parse(s)
     }
 }
 
+impl fmt::Debug for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = self.version.as_ref();
+        f.debug_struct("List")
+            .field("engine", &self.engine())
+            .field("rules", &self.len)
+            .field("icann", &self.sections.icann)
+            .field("private", &self.sections.private)
+            .field("unclassified", &self.sections.unclassified)
+            .field("version", &version.and_then(|v| v.version.as_deref()))
+            .field("commit", &version.and_then(|v| v.commit.as_deref()))
+            .field("warnings", &self.warnings.len())
+            .finish()
+    }
+}
+
+/// Full `Debug` view of a `List`, returned by [`List::dump`].
+struct Dump<'a>(&'a List);
+
+impl fmt::Debug for Dump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = self.0;
+        f.debug_struct("List")
+            .field("store", &list.store)
+            .field("warnings", &list.warnings)
+            .field("meta", &list.meta)
+            .field("comments", &list.comments)
+            .field("version", &list.version)
+            .field("sections", &list.sections)
+            .field("fingerprint", &format_args!("{:#018x}", list.fingerprint()))
+            .finish()
+    }
+}
+
 impl List {
     /// Parse a PSL text into a `List` using `LoadOpts::default()`.
     ///
@@ -89,7 +288,383 @@ impl List {
     /// Load options affect only parsing (e.g., handling of ICANN/PRIVATE
     /// sections and comment styles), not match-time behavior.
     pub fn parse_with(text: &str, opts: LoadOpts) -> Result<Self> {
-        loader::load(text, opts).map(|rules| Self { rules })
+        loader::load(text, opts).map(Self::from_ruleset)
+    }
+
+    /// Parse a PSL text with explicit `LoadOpts`, collecting warnings.
+    ///
+    /// `opts.collect_warnings` is forced on. The returned warnings are also
+    /// available from [`List::warnings`]: duplicate rules, rules shadowed by
+    /// a wildcard, rules with a trailing dot, unrecognized section markers,
+    /// lowercased rules and invalid annotations.
+    pub fn parse_with_warnings(text: &str, opts: LoadOpts) -> Result<(Self, Vec<Warning>)> {
+        let opts = LoadOpts {
+            collect_warnings: true,
+            ..opts
+        };
+        let list = Self::parse_with(text, opts)?;
+        let warnings = list.warnings.clone();
+        Ok((list, warnings))
+    }
+
+    /// Parse a PSL text, choosing the storage engine that fits `budget_bytes`.
+    ///
+    /// The fastest engine whose approximate size fits the budget is used:
+    /// [`Engine::Trie`] if possible, then the compact [`Engine::Flat`], then
+    /// (with the `fst` feature) [`Engine::Compact`].
+    /// The chosen engine is returned alongside the list; its size is
+    /// available from [`List::approx_bytes`].
+    ///
+    /// Each engine is built at most once, and none after the first that fits.
+    ///
+    /// The budget bounds the steady-state size of the list. Parsing itself
+    /// briefly needs the `Trie` form in memory before it is compacted.
+    /// There is no lazily loaded tier: a list parsed from text is always
+    /// held in memory. To keep the rules out of the heap, write them once
+    /// with [`List::to_mapped_bytes`] and map the file with `List::open_mmap`
+    /// (`mmap` feature).
+    ///
+    /// Returns `Error::BudgetExceeded` if no engine fits.
+    pub fn parse_with_budget(
+        text: &str,
+        opts: LoadOpts,
+        budget_bytes: usize,
+    ) -> Result<(Self, Engine)> {
+        let list = Self::parse_with(text, opts)?;
+        if list.approx_bytes() <= budget_bytes {
+            return Ok((list, Engine::Trie));
+        }
+        let mut needed = list.approx_bytes();
+        // Without `fst`, `Engine::Compact` would only build `Engine::Flat` again.
+        for engine in [
+            Engine::Flat,
+            #[cfg(feature = "fst")]
+            Engine::Compact,
+        ] {
+            if let Some(compiled) = list.compiled(engine) {
+                let size = compiled.approx_bytes();
+                if size <= budget_bytes {
+                    return Ok((compiled, engine));
+                }
+                needed = needed.min(size);
+            }
+        }
+        Err(Error::BudgetExceeded {
+            needed,
+            budget: budget_bytes,
+        })
+    }
+
+    /// The storage engine backing this list.
+    pub fn engine(&self) -> Engine {
+        match *self.store {
+            Store::Trie(_) => Engine::Trie,
+            Store::Flat(_) => Engine::Flat,
+            #[cfg(feature = "fst")]
+            Store::Compact(_) => Engine::Compact,
+        }
+    }
+
+    /// Converts the list to the given storage engine.
+    ///
+    /// Only an `Engine::Trie` list can be converted, as the other forms are
+    /// read-only; the list is returned unchanged otherwise, and so is a list
+    /// too large for `Engine::Flat` (see `Error::TooLargeForFlat`). Without
+    /// the `fst` feature, `Engine::Compact` builds `Engine::Flat` instead.
+    pub fn with_engine(self, engine: Engine) -> Self {
+        self.compiled(engine).unwrap_or(self)
+    }
+
+    /// A copy of this `Trie` list in `engine`, or `None` if there is
+    /// nothing to convert or the list does not fit `engine`.
+    fn compiled(&self, engine: Engine) -> Option<Self> {
+        let Store::Trie(rs) = &*self.store else {
+            return None;
+        };
+        let store = match engine {
+            Engine::Trie => return None,
+            #[cfg(feature = "fst")]
+            Engine::Compact => Store::Compact(compact::CompactTrie::from_ruleset(rs)),
+            _ => Store::Flat(flat::FlatTrie::from_ruleset(rs).ok()?),
+        };
+        Some(Self {
+            store: Arc::new(store),
+            warnings: self.warnings.clone(),
+            meta: self.meta.clone(),
+            comments: Arc::clone(&self.comments),
+            version: self.version.clone(),
+            sections: self.sections,
+            len: self.len,
+        })
+    }
+
+    /// Compiles the list into its smallest storage engine.
+    ///
+    /// With the `fst` feature this is [`Engine::Compact`], which stores the
+    /// built-in list in about a tenth of the memory of [`Engine::Trie`] and
+    /// a third of [`Engine::Flat`]; without it, `Engine::Flat`. Lookups give
+    /// the same answers with every engine.
+    pub fn compile(self) -> Self {
+        self.with_engine(Engine::Compact)
+    }
+
+    /// Approximate memory used by the list's rules, in bytes.
+    pub fn approx_bytes(&self) -> usize {
+        let rules = match &*self.store {
+            Store::Trie(rs) => rs.approx_bytes(),
+            Store::Flat(f) => f.approx_bytes(),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.approx_bytes(),
+        };
+        rules + self.warnings.capacity() * core::mem::size_of::<Warning>()
+    }
+
+    /// The rules in `Trie` form, rebuilt when the list uses another engine.
+    pub(crate) fn rule_set(&self) -> Cow<'_, rules::RuleSet> {
+        match &*self.store {
+            Store::Trie(rs) => Cow::Borrowed(rs),
+            Store::Flat(f) => Cow::Owned(f.to_ruleset()),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => Cow::Owned(c.to_ruleset()),
+        }
+    }
+
+    /// Every rule of the list, sorted as written (`!www.ck` sorts before
+    /// `*.ck`, which sorts before `ck`).
+    ///
+    /// Walks the compiled rules whatever the engine, without the original
+    /// text. Rules are reported as loaded, so lowercasing and
+    /// `a_label_duplicates` show, along with their `@expires` and `@owner`
+    /// annotations (see [`RuleRef::expires`]).
+    pub fn rules(&self) -> impl Iterator<Item = RuleRef> {
+        let mut out = Vec::new();
+        self.for_each_rule(&mut |labels, leaf, typ| out.push(RuleRef::new(labels, leaf, typ)));
+        self.sort_and_annotate(&mut out);
+        out.into_iter()
+    }
+
+    /// The rules beneath `parent`, sorted like [`List::rules`].
+    ///
+    /// For `"uk"` these are `co.uk`, `*.sch.uk` and the like, exceptions
+    /// included, but not `uk` itself. `parent` is matched label by label,
+    /// ignoring ASCII case and a trailing dot; no IDNA mapping is done. An
+    /// empty `parent` yields every rule.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let list = List::parse("uk\nco.uk\n*.sch.uk\n!a.sch.uk\ncom\n")?;
+    /// let under: Vec<String> = list.suffixes_under("UK").map(|r| r.to_string()).collect();
+    /// assert_eq!(under, ["!a.sch.uk", "*.sch.uk", "co.uk"]);
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn suffixes_under(&self, parent: &str) -> impl Iterator<Item = RuleRef> {
+        let parent = parent.strip_suffix('.').unwrap_or(parent);
+        let parent: Vec<String> = match parent.is_empty() {
+            true => Vec::new(),
+            false => parent.rsplit('.').map(str::to_ascii_lowercase).collect(),
+        };
+        let mut out = Vec::new();
+        self.for_each_rule(&mut |labels, leaf, typ| {
+            if labels.len() > parent.len() && labels.iter().zip(&parent).all(|(l, p)| l == p) {
+                out.push(RuleRef::new(labels, leaf, typ));
+            }
+        });
+        self.sort_and_annotate(&mut out);
+        out.into_iter()
+    }
+
+    /// Sorts `rules` as written and attaches their [`RuleMeta`].
+    fn sort_and_annotate(&self, rules: &mut [RuleRef]) {
+        rules.sort_by_cached_key(ToString::to_string);
+        if self.meta.is_empty() {
+            return;
+        }
+        let meta: HashMap<&str, &RuleMeta> =
+            self.meta.iter().map(|m| (m.rule.as_str(), m)).collect();
+        for rule in rules {
+            if let Some(m) = meta.get(rule.to_string().as_str()) {
+                rule.expires.clone_from(&m.expires);
+                rule.owner.clone_from(&m.owner);
+            }
+        }
+    }
+
+    /// Number of rules in the list, counted once when the list is built.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the list has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of rules in the ICANN section.
+    pub fn icann_len(&self) -> usize {
+        self.count_rules(|typ| typ == Some(Type::Icann))
+    }
+
+    /// Number of rules in the private section.
+    pub fn private_len(&self) -> usize {
+        self.count_rules(|typ| typ == Some(Type::Private))
+    }
+
+    /// Rule counts, rule depth and memory use of the list, in one pass.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let list = List::parse("uk\nco.uk\n*.sch.uk\n!a.sch.uk\n")?;
+    /// let stats = list.stats();
+    /// assert_eq!((stats.rules, stats.wildcard_rules, stats.exception_rules), (4, 1, 1));
+    /// assert_eq!(stats.max_depth, 3);
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn stats(&self) -> ListStats {
+        let mut stats = ListStats {
+            approx_bytes: self.approx_bytes(),
+            ..ListStats::default()
+        };
+        self.for_each_rule(&mut |labels, leaf, typ| {
+            stats.rules += 1;
+            stats.wildcard_rules += usize::from(labels.last() == Some(&"*"));
+            stats.exception_rules += usize::from(leaf == Leaf::Negative);
+            stats.max_depth = stats.max_depth.max(labels.len());
+            stats.icann_rules += usize::from(typ == Some(Type::Icann));
+            stats.private_rules += usize::from(typ == Some(Type::Private));
+        });
+        stats
+    }
+
+    /// A 64-bit fingerprint of the list's rules and their sections.
+    ///
+    /// Lists with the same rules in the same sections have the same
+    /// fingerprint, whatever order the rules were written in, the engine
+    /// and the platform; comments and warnings do not count. Distributed
+    /// systems can compare it to check that every node loaded the same
+    /// snapshot. The hash is 64-bit FNV-1a, which catches accidents but is
+    /// not collision resistant against someone crafting lists.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let a = List::parse("com\nuk\nco.uk\n")?;
+    /// let b = List::parse("co.uk\ncom\nuk\n")?;
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// assert_ne!(a.fingerprint(), List::parse("com\nuk\n")?.fingerprint());
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = OFFSET;
+        let mut feed = |bytes: &[u8]| {
+            for &b in bytes {
+                hash = (hash ^ u64::from(b)).wrapping_mul(PRIME);
+            }
+        };
+        for rule in self.rules() {
+            feed(rule.to_string().as_bytes());
+            feed(match rule.typ {
+                Some(Type::Icann) => b" icann\n",
+                Some(Type::Private) => b" private\n",
+                None => b"\n",
+            });
+        }
+        hash
+    }
+
+    /// Everything the list holds, for debugging: the whole rule tree of its
+    /// engine, warnings, rule metadata, comments, version and
+    /// [`fingerprint`](Self::fingerprint).
+    ///
+    /// `{:?}` on a `List` only prints a summary; the dump of the full list
+    /// runs to megabytes, so this is best kept to small lists.
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let list = List::parse("com\n")?;
+    /// assert!(format!("{:?}", list).starts_with("List { engine: "));
+    /// assert!(format!("{:?}", list.dump()).contains("store: "));
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn dump(&self) -> impl fmt::Debug + '_ {
+        Dump(self)
+    }
+
+    fn count_rules(&self, keep: impl Fn(Option<Type>) -> bool) -> usize {
+        let mut n = 0;
+        self.for_each_rule(&mut |_, _, typ| n += usize::from(keep(typ)));
+        n
+    }
+
+    /// Walks the rule trie depth first, calling `visitor` on every node but
+    /// the root.
+    ///
+    /// Siblings are visited in label order, and every engine gives the same
+    /// walk, so exporters (DOT graphs, other serializations) need no access
+    /// to the engine's internals.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{Leaf, List};
+    ///
+    /// let list = List::parse("uk\nco.uk\n*.kobe.jp\n")?;
+    /// let mut nodes = Vec::new();
+    /// list.visit(&mut |path: &[&str], leaf, _| nodes.push((path.join("."), leaf)));
+    /// assert_eq!(
+    ///     nodes,
+    ///     [
+    ///         ("jp".to_string(), Leaf::None),
+    ///         ("jp.kobe".to_string(), Leaf::None),
+    ///         ("jp.kobe.*".to_string(), Leaf::Positive),
+    ///         ("uk".to_string(), Leaf::Positive),
+    ///         ("uk.co".to_string(), Leaf::Positive),
+    ///     ]
+    /// );
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn visit(&self, visitor: &mut impl RuleVisitor) {
+        let mut rules: Vec<(Vec<String>, Leaf, Option<Type>)> = Vec::new();
+        self.for_each_rule(&mut |labels, leaf, typ| {
+            rules.push((labels.iter().map(|l| String::from(*l)).collect(), leaf, typ));
+        });
+        // Label-wise order puts each rule right after its ancestors.
+        rules.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let mut path: Vec<&str> = Vec::new();
+        for (labels, leaf, typ) in &rules {
+            let common = path
+                .iter()
+                .zip(labels)
+                .take_while(|(p, l)| **p == l.as_str())
+                .count();
+            while path.len() > common {
+                visitor.leave(&path);
+                path.pop();
+            }
+            let (last, between) = labels.split_last().expect("rules have a label");
+            for label in &between[common..] {
+                path.push(label);
+                visitor.enter(&path, Leaf::None, None);
+            }
+            path.push(last);
+            visitor.enter(&path, *leaf, *typ);
+        }
+        while !path.is_empty() {
+            visitor.leave(&path);
+            path.pop();
+        }
+    }
+
+    fn for_each_rule(&self, f: &mut rules::RuleFn<'_>) {
+        match &*self.store {
+            Store::Trie(rs) => rs.for_each_rule(f),
+            Store::Flat(fl) => fl.for_each_rule(f),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.for_each_rule(f),
+        }
     }
 
     /// Parse a PSL from a file path using `LoadOpts::default()`.
@@ -109,34 +684,322 @@ impl List {
         Self::parse_with(&text, opts)
     }
 
+    /// Parse a PSL from a reader, such as a decompressor, as it is read.
+    ///
+    /// The text is fed to a [`loader::Parser`] in chunks, so it is never
+    /// held in memory as a whole. Read failures are returned as
+    /// `Error::Io`; otherwise the same as [`List::parse_with`].
+    ///
+    /// This method is only available when the `std` feature is enabled.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, LoadOpts, MatchOpts};
+    ///
+    /// let text: &[u8] = b"com\nco.uk\n";
+    /// let list = List::from_reader(text, LoadOpts::default()).unwrap();
+    /// assert_eq!(list.tld("a.co.uk", MatchOpts::default()).as_deref(), Some("co.uk"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut reader: R, opts: LoadOpts) -> Result<Self> {
+        let mut parser = loader::Parser::new(opts);
+        let mut buf = [0; 8192];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return parser.finish(),
+                Ok(n) => parser.feed(&buf[..n])?,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+    }
+
     /// Parse a PSL from a URL using `LoadOpts::default()`.
     ///
     /// This method is only available when the `fetch` feature is enabled.
     #[cfg(feature = "fetch")]
     pub fn from_url(url: &str) -> Result<Self> {
-        Self::from_url_with(url, LoadOpts::default())
+        Self::from_url_with(url, LoadOpts::default(), FetchOpts::default())
+    }
+
+    /// Parse a PSL from a URL using explicit `LoadOpts`, with `fetch`
+    /// bounding how long the download may take and how often it is retried.
+    ///
+    /// The list is requested gzip-compressed and parsed as it downloads.
+    ///
+    /// This method is only available when the `fetch` feature is enabled.
+    ///
+    /// ```rust,no_run
+    /// use core::time::Duration;
+    /// use publicsuffix2::{FetchOpts, List, LoadOpts};
+    ///
+    /// let fetch = FetchOpts {
+    ///     timeout: Some(Duration::from_secs(10)),
+    ///     retries: 2,
+    ///     user_agent: Some("my-service/1.0"),
+    ///     ..FetchOpts::default()
+    /// };
+    /// let list = List::from_url_with(
+    ///     "https://publicsuffix.org/list/public_suffix_list.dat",
+    ///     LoadOpts::default(),
+    ///     fetch,
+    /// )?;
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    #[cfg(feature = "fetch")]
+    pub fn from_url_with(url: &str, opts: LoadOpts, fetch: FetchOpts<'_>) -> Result<Self> {
+        let body = http::open(url, &fetch)?;
+        Ok(Self::from_reader(body, opts)?.fetched_from(url))
+    }
+
+    /// Parse a PSL from a URL without blocking, using `LoadOpts::default()`.
+    ///
+    /// Must be awaited within a Tokio runtime. This method is only available
+    /// when the `fetch-async` feature is enabled.
+    #[cfg(feature = "fetch-async")]
+    pub async fn from_url_async(url: &str) -> Result<Self> {
+        Self::from_url_async_with(url, LoadOpts::default()).await
+    }
+
+    /// Parse a PSL from a URL without blocking, using explicit `LoadOpts`.
+    ///
+    /// Must be awaited within a Tokio runtime. This method is only available
+    /// when the `fetch-async` feature is enabled.
+    #[cfg(feature = "fetch-async")]
+    pub async fn from_url_async_with(url: &str, opts: LoadOpts) -> Result<Self> {
+        let text = http_async::get(url).await?;
+        Ok(Self::parse_with(&text, opts)?.fetched_from(url))
+    }
+
+    /// Parse a PSL from a URL with the JavaScript `fetch` of the host
+    /// environment (browser, service worker or edge runtime).
+    ///
+    /// Only works on `wasm32` targets running under JavaScript. This method
+    /// is only available when the `wasm` feature is enabled.
+    #[cfg(feature = "wasm")]
+    pub async fn from_url_fetch(url: &str) -> Result<Self> {
+        Self::from_url_fetch_with(url, LoadOpts::default()).await
+    }
+
+    /// Parse a PSL from a URL with the JavaScript `fetch`, using explicit
+    /// `LoadOpts`.
+    ///
+    /// This method is only available when the `wasm` feature is enabled.
+    #[cfg(feature = "wasm")]
+    pub async fn from_url_fetch_with(url: &str, opts: LoadOpts) -> Result<Self> {
+        let text = wasm::get(url).await?;
+        Ok(Self::parse_with(&text, opts)?.fetched_from(url))
+    }
+
+    /// Parse a PSL from a URL, re-downloading it only when it changed.
+    ///
+    /// The list text is cached at `cache_path`, and the response's `ETag`
+    /// and `Last-Modified` validators in `<cache_path>.meta`. Later calls
+    /// send them as `If-None-Match` / `If-Modified-Since`; on
+    /// `304 Not Modified` the cached copy is parsed instead. Returns the list
+    /// and whether it was downloaded. A download that fails to parse is
+    /// returned as an error and does not replace the cache.
+    ///
+    /// This method is only available when the `fetch` feature is enabled.
+    #[cfg(feature = "fetch")]
+    pub fn from_url_cached<P: AsRef<Path>>(
+        url: &str,
+        cache_path: P,
+        opts: LoadOpts,
+    ) -> Result<(Self, bool)> {
+        http::get_cached(url, cache_path.as_ref(), |text| {
+            Ok(Self::parse_with(text, opts)?.fetched_from(url))
+        })
+    }
+
+    /// Fetch and parse the PSL from a well-known [`source::Source`] using
+    /// `LoadOpts::default()`.
+    ///
+    /// The downloaded text is validated first, so an HTML error page from a
+    /// mirror is reported as `Error::UnexpectedContent` instead of being
+    /// parsed as a (nearly empty) list.
+    ///
+    /// This method is only available when the `fetch` feature is enabled.
+    #[cfg(feature = "fetch")]
+    pub fn from_source(source: &source::Source<'_>) -> Result<Self> {
+        Self::from_source_with(source, LoadOpts::default())
+    }
+
+    /// Fetch and parse the PSL from a [`source::Source`] using explicit `LoadOpts`.
+    ///
+    /// This method is only available when the `fetch` feature is enabled.
+    #[cfg(feature = "fetch")]
+    pub fn from_source_with(source: &source::Source<'_>, opts: LoadOpts) -> Result<Self> {
+        let text = source.fetch()?;
+        Ok(Self::parse_with(&text, opts)?.fetched_from(source.url()))
+    }
+
+    /// Parse the PSL as it was published on `date` (`YYYY-MM-DD`), using the
+    /// GitHub history of `publicsuffix/list`.
+    ///
+    /// Resolves the last commit to the list on or before the end of that day
+    /// (UTC) and parses the file at that commit with `LoadOpts::default()`.
+    /// Useful for forensic questions such as "what was the registrable domain
+    /// of this host when the event happened?".
+    ///
+    /// This method is only available when the `fetch` feature is enabled.
+    #[cfg(feature = "fetch")]
+    pub fn from_github_at(date: &str) -> Result<Self> {
+        Self::from_archive_at(&history::GITHUB, date, LoadOpts::default())
     }
 
-    /// Parse a PSL from a URL using explicit `LoadOpts`.
+    /// Parse the PSL as of `date` from an explicit snapshot archive.
     ///
     /// This method is only available when the `fetch` feature is enabled.
     #[cfg(feature = "fetch")]
-    pub fn from_url_with(url: &str, opts: LoadOpts) -> Result<Self> {
-        let text = http::get(url)?;
+    pub fn from_archive_at(
+        archive: &history::Archive<'_>,
+        date: &str,
+        opts: LoadOpts,
+    ) -> Result<Self> {
+        let text = history::fetch_at(archive, date)?;
         Self::parse_with(&text, opts)
     }
 
+    /// Non-fatal issues found while parsing the list.
+    ///
+    /// Empty unless the list was loaded with `LoadOpts::collect_warnings`.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// How many rule lines the loader put in each section, and how many
+    /// `SectionPolicy::Require` dropped.
+    ///
+    /// Counted while parsing text, so all zero for lists read from binary;
+    /// a [`ListBuilder`] adds up the text it merges, and rules added or
+    /// removed one by one are not counted.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, LoadOpts, SectionPolicy};
+    ///
+    /// let text = "com\n// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n// ===END ICANN DOMAINS===\n";
+    /// let require = LoadOpts { sections: SectionPolicy::Require, ..LoadOpts::default() };
+    /// let counts = List::parse_with(text, require)?.section_counts();
+    /// assert_eq!((counts.icann, counts.skipped), (2, 1));
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn section_counts(&self) -> SectionCounts {
+        self.sections
+    }
+
+    /// The snapshot of the PSL this list was loaded from: the `// VERSION:`
+    /// and `// COMMIT:` lines of its header, and the URL it was downloaded
+    /// from by `from_url`, `from_url_cached` or `from_source`.
+    ///
+    /// `None` for a list with neither header lines nor a URL, such as one
+    /// built with [`ListBuilder`]. Kept by [`to_bytes`](Self::to_bytes).
+    ///
+    /// ```rust
+    /// use publicsuffix2::List;
+    ///
+    /// let text = "// VERSION: 2025-09-23_13-07-02_UTC\n\
+    ///             // COMMIT: 6defc0e19dbbe27dc2a0798256077d0b82d9c455\n\
+    ///             com\n";
+    /// let list = List::parse(text)?;
+    /// let info = list.version_info().unwrap();
+    /// assert_eq!(info.date.as_deref(), Some("2025-09-23"));
+    /// assert_eq!(info.commit.as_deref(), Some("6defc0e19dbbe27dc2a0798256077d0b82d9c455"));
+    /// assert_eq!(info.url, None);
+    /// assert_eq!(List::parse("com\n")?.version_info(), None);
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn version_info(&self) -> Option<&ListVersion> {
+        self.version.as_ref()
+    }
+
+    /// Metadata of every annotated rule, in list order.
+    ///
+    /// Rules are annotated with `// @expires YYYY-MM-DD` and `// @owner <name>`
+    /// comment lines directly above them; see [`RuleMeta`]. The rules from
+    /// [`List::rules`] carry the same metadata.
+    pub fn rule_meta(&self) -> &[RuleMeta] {
+        &self.meta
+    }
+
+    /// Metadata of `rule` (as written in the list, `!` included), if annotated.
+    pub fn meta_for(&self, rule: &str) -> Option<&RuleMeta> {
+        self.meta.iter().find(|m| m.rule == rule)
+    }
+
+    /// The comment block above `rule` (as written in the list, `!`
+    /// included), such as the organization and submitter of a private
+    /// entry.
+    ///
+    /// `None` unless the list was loaded with `LoadOpts::keep_metadata`.
+    /// Blocks are not kept by [`to_bytes`](Self::to_bytes) or `serde`.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, LoadOpts};
+    ///
+    /// let text = "// Example Org : https://example.org\n\
+    ///             // Submitted by Jane Doe <psl@example.org>\n\
+    ///             example.net\n";
+    /// let opts = LoadOpts { keep_metadata: true, ..LoadOpts::default() };
+    /// let list = List::parse_with(text, opts).unwrap();
+    /// let block = list.rule_metadata("example.net").unwrap();
+    /// assert_eq!(block.lines[0], "Example Org : https://example.org");
+    /// assert_eq!(block.submitter(), Some("Jane Doe <psl@example.org>"));
+    /// ```
+    pub fn rule_metadata(&self, rule: &str) -> Option<&CommentBlock> {
+        self.comments.get(rule)
+    }
+
+    /// Annotated rules whose `@expires` date is on or before `today`.
+    ///
+    /// Returns `Error::InvalidDate` if `today` is not in `YYYY-MM-DD` form.
+    pub fn expired_rules(&self, today: &str) -> Result<Vec<&RuleMeta>> {
+        if !rules::is_valid_date(today) {
+            return Err(Error::InvalidDate { date: today.into() });
+        }
+        Ok(self.meta.iter().filter(|m| m.is_expired(today)).collect())
+    }
+
     /// Registrable domain (eTLD+1) under PS2 semantics.
     ///
     /// Behavior is controlled by `MatchOpts` (wildcards, strict mode, type
     /// filter, normalization). Returns `None` if:
     /// - input is empty/invalid, or
-    /// - `strict` is true and no rule matches.
+    /// - `strict` is true, or `fallback` is `FallbackPolicy::None`, and no
+    ///   rule matches.
     ///
     /// Without rules (and non-strict), the fallback treats the last label as
     /// the TLD, making the registrable domain the entire host.
     pub fn sld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
-        self.rules.sld(host, opts)
+        match &*self.store {
+            Store::Trie(rs) => rs.sld(host, opts),
+            Store::Flat(f) => f.sld(host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.sld(host, opts),
+        }
+    }
+
+    /// `host` split around its registration boundary, as a [`Domain`].
+    ///
+    /// Returns `None` when [`List::tld`] would.
+    pub fn domain<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Domain<'a>> {
+        match &*self.store {
+            Store::Trie(rs) => engine::domain(rs, host, opts),
+            Store::Flat(f) => engine::domain(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::domain(c, host, opts),
+        }
+    }
+
+    /// Registrable domain of `host` as a [`RegistrableDomain`] value.
+    ///
+    /// The public suffix plus one label, as [`Domain::registrable`] gives
+    /// it, with its suffix attached and a stable
+    /// [`canonical_key`](RegistrableDomain::canonical_key) for storage.
+    /// Unlike [`List::sld`], a host under an unlisted TLD keeps its label
+    /// (`example.local`). Returns `None` when [`List::domain`] would, and
+    /// when `host` is itself a public suffix.
+    pub fn registrable(&self, host: &str, opts: MatchOpts<'_>) -> Option<RegistrableDomain> {
+        RegistrableDomain::from_domain(&self.domain(host, opts)?)
     }
 
     /// Public suffix (PSL match) under PS2 semantics.
@@ -146,7 +1009,275 @@ impl List {
     /// no rule matches. With no rules (and non-strict), the suffix is the last
     /// label of the host.
     pub fn tld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
-        self.rules.tld(host, opts)
+        match &*self.store {
+            Store::Trie(rs) => rs.tld(host, opts),
+            Store::Flat(f) => f.tld(host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.tld(host, opts),
+        }
+    }
+
+    /// Byte offset in `host` at which its public suffix starts.
+    ///
+    /// A low-level form of [`List::tld`] for hot paths: `&host[offset..]` is
+    /// the suffix and `host.len() - offset` its length, so callers can keep
+    /// positions into their own buffer. It never allocates. For that, `host`
+    /// is matched exactly as given: `opts.normalizer` and
+    /// `opts.match_both_forms` are ignored, so lowercase the host and strip
+    /// any trailing dot beforehand. Hosts with control characters yield
+    /// `None` whatever `opts.controls` says.
+    pub fn suffix_len(&self, host: &str, opts: MatchOpts<'_>) -> Option<usize> {
+        match &*self.store {
+            Store::Trie(rs) => engine::suffix_offset(rs, host, opts),
+            Store::Flat(f) => engine::suffix_offset(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::suffix_offset(c, host, opts),
+        }
+    }
+
+    /// Returns true if `host` is itself a public suffix (`co.uk`).
+    ///
+    /// Same answer as comparing [`List::tld`] with the normalized host, but
+    /// without building the result. Without `opts.strict`, a single unlisted
+    /// label counts as a public suffix (any unlisted host, under
+    /// `FallbackPolicy::EntireHost`).
+    pub fn is_public_suffix(&self, host: &str, opts: MatchOpts<'_>) -> bool {
+        match &*self.store {
+            Store::Trie(rs) => engine::is_public_suffix(rs, host, opts),
+            Store::Flat(f) => engine::is_public_suffix(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::is_public_suffix(c, host, opts),
+        }
+    }
+
+    /// Returns true if `host` is a registrable domain: a public suffix plus
+    /// exactly one label (`example.co.uk`, not `www.example.co.uk`).
+    ///
+    /// Same answer as comparing [`List::sld`] with the normalized host, but
+    /// without building the result.
+    pub fn is_registrable_domain(&self, host: &str, opts: MatchOpts<'_>) -> bool {
+        match &*self.store {
+            Store::Trie(rs) => engine::is_registrable_domain(rs, host, opts),
+            Store::Flat(f) => engine::is_registrable_domain(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::is_registrable_domain(c, host, opts),
+        }
+    }
+
+    /// Number of labels a registrable domain under `host`'s public suffix
+    /// has: the suffix's labels plus one.
+    ///
+    /// Compare it with the label count of user input to tell whether the
+    /// input can be registered as is: `foo.co.uk` needs 3 and has 3, `co.uk`
+    /// needs one more label and `www.foo.co.uk` is a subdomain. Returns
+    /// `None` when [`List::tld`] does.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list = List::parse("uk\nco.uk\n*.ck\n")?;
+    /// let opts = MatchOpts::default();
+    /// assert_eq!(list.required_labels("foo.co.uk", opts), Some(3));
+    /// assert_eq!(list.required_labels("co.uk", opts), Some(3));
+    /// assert_eq!(list.required_labels("a.b.ck", opts), Some(3));
+    /// assert_eq!(list.required_labels("example.com", opts), Some(2));
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn required_labels(&self, host: &str, opts: MatchOpts<'_>) -> Option<usize> {
+        let suffix = self.tld(host, opts)?;
+        Some(suffix.split('.').count() + 1)
+    }
+
+    /// Returns true if a rule of the list makes `host` a public suffix,
+    /// using the default match options.
+    ///
+    /// A true answer means `host` cannot be registered itself, only names
+    /// under it. Unlike [`List::is_public_suffix`], an unlisted TLD
+    /// (`example`) is not a suffix here, as only the list's rules count.
+    pub fn is_exactly_suffix(&self, host: &str) -> bool {
+        let opts = MatchOpts {
+            strict: true,
+            ..MatchOpts::default()
+        };
+        self.is_public_suffix(host, opts)
+    }
+
+    /// Deepest suffix of `host` that a rule names exactly.
+    ///
+    /// Unlike [`List::tld`], wildcards are not expanded and there is no
+    /// unlisted-TLD fallback, so this reports the rule-backed ancestor of the
+    /// public suffix: `kobe.jp` for `x.c.kobe.jp`, where `tld` answers
+    /// `c.kobe.jp` through `*.kobe.jp`. Wildcard and exception rules both
+    /// name their parent.
+    /// Returns `None` when no rule matches (e.g. `example.local`).
+    /// `opts.wildcard` and `opts.strict` are ignored.
+    pub fn nearest_listed_suffix<'a>(
+        &self,
+        host: &'a str,
+        opts: MatchOpts<'_>,
+    ) -> Option<Cow<'a, str>> {
+        match &*self.store {
+            Store::Trie(rs) => engine::listed_suffix(rs, host, opts),
+            Store::Flat(f) => engine::listed_suffix(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::listed_suffix(c, host, opts),
+        }
+    }
+
+    /// Where the registration boundary of `host` lies, and why.
+    ///
+    /// Distinguishes names bought from an ICANN registry, names delegated by
+    /// a private suffix operator, suffixes synthesized by wildcard rules and
+    /// unlisted fallbacks; see [`Boundary`]. Returns `None` when [`List::tld`]
+    /// would.
+    pub fn registration_boundary<'a>(
+        &self,
+        host: &'a str,
+        opts: MatchOpts<'_>,
+    ) -> Option<BoundaryInfo<'a>> {
+        match &*self.store {
+            Store::Trie(rs) => engine::boundary(rs, host, opts),
+            Store::Flat(f) => engine::boundary(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::boundary(c, host, opts),
+        }
+    }
+
+    /// The PSL rule that decided the public suffix of `host`.
+    ///
+    /// Reports the rule as written, whether it is a wildcard or exception,
+    /// its section and its depth, for logging why a host was classified the
+    /// way it was. Returns `None` when [`List::tld`] would.
+    pub fn match_info<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<MatchInfo<'a>> {
+        match &*self.store {
+            Store::Trie(rs) => engine::match_info(rs, host, opts),
+            Store::Flat(f) => engine::match_info(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::match_info(c, host, opts),
+        }
+    }
+
+    /// Whether the public suffix of `host` comes from the ICANN or the
+    /// private section, naming the provider's suffix for the latter.
+    ///
+    /// Tells hosted tenants (`alice.github.io`) apart from registered
+    /// domains. Follows [`List::match_info`], so `opts.types` and
+    /// `opts.ignored_private` apply.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{Classification, List, MatchOpts};
+    ///
+    /// let list = List::parse(
+    ///     "// ===BEGIN ICANN DOMAINS===\nio\n// ===END ICANN DOMAINS===\n\
+    ///      // ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\n",
+    /// )?;
+    /// let opts = MatchOpts::default();
+    /// assert_eq!(list.classify("docs.rs.io", opts), Classification::Icann);
+    /// assert_eq!(
+    ///     list.classify("alice.github.io", opts),
+    ///     Classification::PrivateProvider { suffix: "github.io".into() }
+    /// );
+    /// assert_eq!(list.classify("example.local", opts), Classification::Unknown);
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn classify<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Classification<'a> {
+        match self.match_info(host, opts) {
+            Some(MatchInfo {
+                typ: Some(Type::Icann),
+                ..
+            }) => Classification::Icann,
+            Some(MatchInfo {
+                typ: Some(Type::Private),
+                suffix,
+                ..
+            }) => Classification::PrivateProvider { suffix },
+            _ => Classification::Unknown,
+        }
+    }
+
+    /// Every rule that matches `host`, not just the one that decides it.
+    ///
+    /// Lists wildcard and exception rules as well as shorter rules shadowed
+    /// by longer ones, in the order the PSL algorithm prefers them:
+    /// exceptions first, then by decreasing number of labels. Rules excluded
+    /// by `opts` (type filter, ignored private suffixes, `wildcard: false`)
+    /// are left out. Empty when nothing matches, including unlisted TLDs
+    /// and malformed hosts.
+    ///
+    /// Useful for explaining a classification; for the deciding rule alone,
+    /// use [`List::match_info`].
+    pub fn candidates(&self, host: &str, opts: MatchOpts<'_>) -> Vec<RuleMatch> {
+        match &*self.store {
+            Store::Trie(rs) => engine::candidates(rs, host, opts),
+            Store::Flat(f) => engine::candidates(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::candidates(c, host, opts),
+        }
+    }
+
+    /// DMARC organizational domain of `host` (RFC 7489, section 3.2).
+    ///
+    /// The longest matching public suffix plus one label. Unlike
+    /// [`List::sld`], a host with no matching rule uses the implicit `*`
+    /// rule (`a.b.example` → `b.example`; `opts.strict` and `opts.fallback`
+    /// are ignored), and a host that is itself a public suffix is its own
+    /// organizational domain.
+    /// Returns `None` only for empty or malformed hosts.
+    pub fn organizational_domain<'a>(
+        &self,
+        host: &'a str,
+        opts: MatchOpts<'_>,
+    ) -> Option<Cow<'a, str>> {
+        let opts = MatchOpts {
+            strict: false,
+            fallback: FallbackPolicy::LastLabel,
+            ..opts
+        };
+        let info = self.registration_boundary(host, opts)?;
+        Some(info.registrable.unwrap_or(info.suffix))
+    }
+
+    /// Returns true if the list has any rule ending in `label`.
+    ///
+    /// Checks only the last label: `com`, `uk` and `example.co.uk` are all
+    /// known with the built-in list, `example.invalid` is not. Matching is
+    /// case-insensitive and ignores a trailing dot; no IDNA mapping is done,
+    /// so pass either form as written in the list (its A-label form is
+    /// listed too unless `LoadOpts::a_label_duplicates` was disabled).
+    /// Wildcards do not make every label known.
+    pub fn is_known_tld(&self, label: &str) -> bool {
+        let host = label.strip_suffix('.').unwrap_or(label);
+        let last = host.rsplit('.').next().unwrap_or_default();
+        if last.is_empty() || last == "*" {
+            return false;
+        }
+        let last = if last.chars().any(char::is_uppercase) {
+            Cow::Owned(last.to_lowercase())
+        } else {
+            Cow::Borrowed(last)
+        };
+        match &*self.store {
+            Store::Trie(rs) => rs.child(rs.root(), &last).is_some(),
+            Store::Flat(f) => f.child(f.root(), &last).is_some(),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.child(c.root(), &last).is_some(),
+        }
+    }
+
+    /// Every label that ends a rule in the list, sorted.
+    ///
+    /// These are exactly the labels for which [`List::is_known_tld`] returns
+    /// true.
+    pub fn known_tlds(&self) -> impl Iterator<Item = &str> + '_ {
+        let mut tlds: Vec<&str> = match &*self.store {
+            Store::Trie(rs) => rs.root.labeled_kids(&rs.labels).map(|(l, _)| l).collect(),
+            Store::Flat(f) => f.child_labels(f.root()).collect(),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.tlds().collect(),
+        };
+        tlds.retain(|t| *t != "*");
+        tlds.sort_unstable();
+        tlds.into_iter()
     }
 
     /// Split a host into prefix / SLL / SLD / TLD (PS2-compatible).
@@ -161,17 +1292,271 @@ impl List {
     /// - "foo.bar.uk" → TLD="bar.uk", SLD="foo.bar.uk", SLL="foo", Prefix=None
     /// - "foo.city.uk" (exception) → TLD="uk", SLD="city.uk", SLL="city", Prefix=Some("foo")
     pub fn split<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<engine::Parts<'a>> {
-        self.rules.split(host, opts)
+        match &*self.store {
+            Store::Trie(rs) => rs.split(host, opts),
+            Store::Flat(f) => f.split(host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => c.split(host, opts),
+        }
+    }
+
+    /// [`List::sld`] as an owned `String`, for results that outlive `host`
+    /// (sent to another thread, stored in a `'static` map).
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list = List::parse("com\nuk\nco.uk\n")?;
+    /// let sld = {
+    ///     let host = String::from("www.example.co.uk");
+    ///     list.sld_owned(&host, MatchOpts::default())
+    /// };
+    /// std::thread::spawn(move || assert_eq!(sld.as_deref(), Some("example.co.uk")))
+    ///     .join()
+    ///     .unwrap();
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn sld_owned(&self, host: &str, opts: MatchOpts<'_>) -> Option<String> {
+        self.sld(host, opts).map(Cow::into_owned)
+    }
+
+    /// [`List::tld`] as an owned `String`; see [`List::sld_owned`].
+    pub fn tld_owned(&self, host: &str, opts: MatchOpts<'_>) -> Option<String> {
+        self.tld(host, opts).map(Cow::into_owned)
+    }
+
+    /// [`List::split`] with every part owned; see [`List::sld_owned`].
+    pub fn split_owned(&self, host: &str, opts: MatchOpts<'_>) -> Option<engine::Parts<'static>> {
+        self.split(host, opts).map(engine::Parts::into_owned)
+    }
+
+    /// Like [`List::split`], but returns where each part is in `host`
+    /// instead of copying or borrowing it.
+    ///
+    /// For parsers that annotate spans of a larger buffer. Case differences
+    /// and a leading or trailing dot removed by normalization are allowed
+    /// for, so the ranges always index `host` itself. Returns `None` when
+    /// `split` would, and also when normalization rewrites the host instead
+    /// (IDNA conversion, stripped control characters, or lowercasing a
+    /// non-ASCII host), since its parts then have no place in `host`.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// let list = List::parse("uk\nco.uk\n")?;
+    /// let text = "see WWW.Example.CO.UK. for details";
+    /// let host = &text[4..22];
+    /// let idx = list.split_indices(host, MatchOpts::default()).unwrap();
+    /// assert_eq!(&host[idx.tld], "CO.UK");
+    /// assert_eq!(idx.prefix, Some(0..3));
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn split_indices(&self, host: &str, opts: MatchOpts<'_>) -> Option<PartsIdx> {
+        match &*self.store {
+            Store::Trie(rs) => engine::split_indices(rs, host, opts),
+            Store::Flat(f) => engine::split_indices(f, host, opts),
+            #[cfg(feature = "fst")]
+            Store::Compact(c) => engine::split_indices(c, host, opts),
+        }
+    }
+
+    /// Like [`List::split`], but says why there is no answer.
+    ///
+    /// `split` returns `None` for malformed input and for hosts no rule
+    /// matches alike; this reports which, for callers that show the reason
+    /// to a user. The lookup itself runs only once; the reason is worked
+    /// out after it fails.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchError, MatchOpts};
+    ///
+    /// let list = List::parse("uk\nco.uk\n")?;
+    /// let opts = MatchOpts::default();
+    /// assert_eq!(list.try_split("a..co.uk", opts), Err(MatchError::EmptyLabel));
+    /// let strict = MatchOpts { strict: true, ..opts };
+    /// assert_eq!(list.try_split("example.com", strict), Err(MatchError::NoRuleMatched));
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn try_split<'a>(
+        &self,
+        host: &'a str,
+        opts: MatchOpts<'_>,
+    ) -> core::result::Result<engine::Parts<'a>, MatchError> {
+        self.split(host, opts)
+            .ok_or_else(|| engine::match_error(host, opts))
+    }
+
+    /// A cursor for matching labels incrementally, right to left.
+    ///
+    /// See [`cursor::SuffixCursor`].
+    pub fn cursor(&self, opts: MatchOpts<'_>) -> cursor::SuffixCursor<'_> {
+        cursor::SuffixCursor::new(self, opts)
+    }
+
+    /// Touch the trie paths for `sample_hosts` ahead of time.
+    ///
+    /// With the `std` feature, also initializes the global list (see
+    /// [`List::try_global`]), so that
+    /// the first user-facing lookup in a latency-sensitive service pays
+    /// neither the embedded-parse cost nor cold-cache misses. When
+    /// `sample_hosts` is empty, a built-in set of common hosts is used.
+    pub fn warm(&self, sample_hosts: &[&str]) {
+        #[cfg(feature = "std")]
+        let _ = Self::try_global();
+        let hosts = if sample_hosts.is_empty() {
+            WARM_HOSTS
+        } else {
+            sample_hosts
+        };
+        let opts = MatchOpts::default();
+        for host in hosts {
+            core::hint::black_box(self.split(host, opts));
+        }
+    }
+
+    /// Run the embedded `split` specification table against this list.
+    ///
+    /// The table describes expected behavior against the built-in list, so a
+    /// clean report from `List::default()` means the engine still behaves as
+    /// documented. See the [`spec`] module for running it against wrappers.
+    pub fn check_against_spec(&self) -> spec::SpecReport {
+        spec::check_with(|host, opts| self.split(host, opts).map(Parts::into_owned))
     }
 
     /// Returns a reference to a globally shared `List` instance.
     ///
     /// The list is parsed from a built-in copy of the Public Suffix List
-    /// on the first call and cached for subsequent uses.
+    /// on the first call and cached for subsequent uses, unless another list
+    /// was installed first with [`List::set_global`],
+    /// [`List::init_global_from`] or [`List::init_global_with`].
     ///
     /// This is the easiest way to get started if you don't need a custom
     /// list or special loading options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global list is not yet initialized and the built-in
+    /// list fails to parse. Use [`List::try_global`] to handle that case.
+    #[cfg(feature = "std")]
     pub fn global() -> &'static Self {
-        &GLOBAL_LIST
+        Self::try_global().expect("parsing the embedded public suffix list should not fail")
+    }
+
+    /// Returns the globally shared `List` for use on hot, multi-threaded
+    /// paths.
+    ///
+    /// Same list as [`List::global`]. Once initialized, each call is a single
+    /// atomic load: no lock is taken and no reference count is touched, so
+    /// lookups on any number of cores do not contend. Keep the returned
+    /// `&'static List` instead of cloning it; for a list that is replaced at
+    /// runtime, see [`SharedList::load_full_cached`].
+    ///
+    /// # Panics
+    ///
+    /// Same as [`List::global`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn global_ref() -> &'static Self {
+        match GLOBAL_LIST.get() {
+            Some(list) => list,
+            None => Self::global(),
+        }
+    }
+
+    /// Returns the globally shared `List`, initializing it from the built-in
+    /// list on first use.
+    ///
+    /// Unlike [`List::global`], a parse failure is returned instead of
+    /// panicking; a failed initialization is retried on the next call.
+    #[cfg(feature = "std")]
+    pub fn try_global() -> Result<&'static Self> {
+        #[cfg(feature = "bundled-list")]
+        return Ok(GLOBAL_LIST.get_or_init(|| Self::bundled().clone()));
+        #[cfg(not(feature = "bundled-list"))]
+        GLOBAL_LIST.get_or_try_init(|| EMBEDDED_PSL.parse())
+    }
+
+    /// The Public Suffix List embedded at build time by the `bundled-list`
+    /// feature, parsed on first use.
+    ///
+    /// The build script downloads the current official list, or reads the
+    /// path or URL in the `PUBLICSUFFIX2_LIST` environment variable; see
+    /// `build.rs` for the details and the offline fallback. With this
+    /// feature the bundled list also backs [`List::global`] unless another
+    /// list is installed first. Its [`version_info`](Self::version_info)
+    /// gives the snapshot date and, when it was downloaded, the URL.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the embedded list fails to parse, which the build script
+    /// rules out.
+    #[cfg(feature = "bundled-list")]
+    pub fn bundled() -> &'static Self {
+        BUNDLED_LIST.get_or_init(|| {
+            let list: List = EMBEDDED_PSL
+                .parse()
+                .expect("build.rs embeds a valid public suffix list");
+            match env!("PUBLICSUFFIX2_BUNDLED_URL") {
+                "" => list,
+                url => list.fetched_from(url),
+            }
+        })
+    }
+
+    /// Initializes the globally shared `List` with `loader`.
+    ///
+    /// Lets applications choose when the parse cost is paid (e.g. at
+    /// startup) and which list backs [`List::global`] and `List::default()`.
+    /// The loader runs at most once across threads; concurrent callers wait
+    /// for it to finish.
+    ///
+    /// Returns `Error::GlobalAlreadyInitialized` if the global list was
+    /// already set, whether by this function or by a prior lookup through
+    /// [`List::global`]. Errors from `loader` are returned unchanged and
+    /// leave the global list uninitialized.
+    #[cfg(feature = "std")]
+    pub fn init_global_with<F>(loader: F) -> Result<&'static Self>
+    where
+        F: FnOnce() -> Result<List>,
+    {
+        let mut ran = false;
+        let list = GLOBAL_LIST.get_or_try_init(|| {
+            ran = true;
+            loader()
+        })?;
+        if ran {
+            Ok(list)
+        } else {
+            Err(Error::GlobalAlreadyInitialized)
+        }
+    }
+
+    /// Makes `list` the globally shared `List`, for example one fetched at
+    /// startup.
+    ///
+    /// Same rules as [`List::init_global_with`]: the first list set wins and
+    /// later calls return `Error::GlobalAlreadyInitialized`.
+    #[cfg(feature = "std")]
+    pub fn set_global(list: List) -> Result<&'static Self> {
+        Self::init_global_with(|| Ok(list))
+    }
+
+    /// Initializes the globally shared `List` from a file path or, with the
+    /// `fetch` feature, an `http://` or `https://` URL, using
+    /// `LoadOpts::default()`.
+    ///
+    /// Without the `fetch` feature every `source` is read as a path. Load
+    /// errors are returned and leave the global list uninitialized, so a
+    /// caller can fall back to the built-in list with [`List::global`].
+    /// Otherwise the same as [`List::init_global_with`].
+    #[cfg(feature = "std")]
+    pub fn init_global_from(source: &str) -> Result<&'static Self> {
+        Self::init_global_with(|| {
+            #[cfg(feature = "fetch")]
+            if source.starts_with("http://") || source.starts_with("https://") {
+                return Self::from_url(source);
+            }
+            Self::from_file(source)
+        })
     }
 }
@@ -0,0 +1,227 @@
+//! Finding mistakes in a list's rules.
+//!
+//! [`List::lint`] checks a parsed list for rules that are redundant, have
+//! no effect or would not be accepted upstream, so custom lists and fixes
+//! meant for the official list can be reviewed before they ship.
+use crate::errors::{HostError, Warning};
+use crate::options::MatchOpts;
+#[cfg(feature = "idna")]
+use crate::options::Normalizer;
+use crate::rules::{Leaf, RuleRef, Type};
+use crate::validate::validate_host;
+use crate::List;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use hashbrown::HashMap;
+
+/// A problem found in a rule; see [`List::lint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// The rule, as written in a list (`*.ck`, `!www.ck`).
+    pub rule: String,
+    /// What is wrong with it.
+    pub kind: LintKind,
+}
+
+/// What is wrong with a rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LintKind {
+    /// The rule was written more than once.
+    Duplicate,
+    /// An exception rule with no wildcard rule for it to except from, such
+    /// as `!www.ck` without `*.ck`; it only makes its parent a suffix.
+    OrphanException,
+    /// A wildcard rule of the same section already matches the rule, such
+    /// as `a.ck` next to `*.ck`.
+    Shadowed {
+        /// The wildcard rule.
+        by: String,
+    },
+    /// An ICANN rule with a Unicode label. Lookups accept either form, but
+    /// the registry data the ICANN section mirrors uses A-labels
+    /// (`xn--...`).
+    NonPunycode,
+    /// A label breaks host name syntax, per [`List::validate_host`]: a bad
+    /// character or length, or a `*` anywhere but the leftmost label.
+    InvalidLabel(HostError),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: ", self.rule)?;
+        match &self.kind {
+            LintKind::Duplicate => f.write_str("duplicate rule"),
+            LintKind::OrphanException => f.write_str("exception without a wildcard rule"),
+            LintKind::Shadowed { by } => write!(f, "already matched by `{by}`"),
+            LintKind::NonPunycode => f.write_str("Unicode label in the ICANN section"),
+            LintKind::InvalidLabel(e) => write!(f, "invalid label syntax ({e})"),
+        }
+    }
+}
+
+impl List {
+    /// Checks the list's rules for problems, in rule order.
+    ///
+    /// Duplicates are only known from loading, so they are reported when
+    /// the list was loaded with `LoadOpts::collect_warnings`. With
+    /// `LoadOpts::a_label_duplicates`, the A-label copies the loader adds
+    /// are checked too.
+    ///
+    /// ```rust
+    /// use publicsuffix2::lint::LintKind;
+    /// use publicsuffix2::List;
+    ///
+    /// let list = List::parse("ck\n*.ck\na.ck\n!www.ck\n!www.uk\n")?;
+    /// let lints: Vec<_> = list.lint().into_iter().map(|l| (l.rule, l.kind)).collect();
+    /// assert_eq!(
+    ///     lints,
+    ///     [
+    ///         ("!www.uk".into(), LintKind::OrphanException),
+    ///         ("a.ck".into(), LintKind::Shadowed { by: "*.ck".into() }),
+    ///     ]
+    /// );
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn lint(&self) -> Vec<Lint> {
+        let rules: Vec<RuleRef> = self.rules().collect();
+        let wildcards: HashMap<String, Option<Type>> = rules
+            .iter()
+            .filter(|r| r.leaf == Leaf::Positive && r.labels.first().is_some_and(|l| l == "*"))
+            .map(|r| (r.labels[1..].join("."), r.typ))
+            .collect();
+
+        let mut lints = Vec::new();
+        for rule in &rules {
+            let written = rule.to_string();
+            let mut push = |kind| {
+                lints.push(Lint {
+                    rule: written.clone(),
+                    kind,
+                })
+            };
+            let (first, parent) = (&rule.labels[0], rule.labels[1..].join("."));
+            let wildcard = wildcards.get(&parent);
+            match rule.leaf {
+                Leaf::Negative if wildcard.is_none() => push(LintKind::OrphanException),
+                Leaf::Positive if first != "*" && wildcard == Some(&rule.typ) => {
+                    push(LintKind::Shadowed {
+                        by: match parent.is_empty() {
+                            true => "*".into(),
+                            false => format!("*.{parent}"),
+                        },
+                    })
+                }
+                _ => {}
+            }
+            if rule.typ == Some(Type::Icann) && !rule.labels.iter().all(|l| l.is_ascii()) {
+                push(LintKind::NonPunycode);
+            }
+            if let Err(e) = check_syntax(&rule.labels) {
+                push(LintKind::InvalidLabel(e));
+            }
+        }
+
+        for warning in &self.warnings {
+            if let Warning::DuplicateRule { rule } = warning {
+                lints.push(Lint {
+                    rule: rule.clone(),
+                    kind: LintKind::Duplicate,
+                });
+            }
+        }
+        // Stable, so each rule's lints keep the order above.
+        lints.sort_by(|a, b| a.rule.cmp(&b.rule));
+        lints
+    }
+}
+
+/// Checks a rule's labels as a host name, reading a leftmost `*` as a
+/// plain label so error offsets still point into the rule. Without the
+/// `idna` feature, Unicode labels are not checked.
+fn check_syntax(labels: &[String]) -> Result<(), HostError> {
+    let mut name = labels.join(".");
+    if labels[0] == "*" {
+        name.replace_range(..1, "x");
+    }
+    #[cfg(feature = "idna")]
+    {
+        let idna = Normalizer::idna_only();
+        validate_host(&name, MatchOpts::with_normalizer(&idna))
+    }
+    #[cfg(not(feature = "idna"))]
+    match name.is_ascii() {
+        true => validate_host(&name, MatchOpts::raw()),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::LoadOpts;
+
+    fn kinds(text: &str) -> Vec<(String, LintKind)> {
+        let opts = LoadOpts {
+            collect_warnings: true,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with(text, opts).unwrap();
+        list.lint().into_iter().map(|l| (l.rule, l.kind)).collect()
+    }
+
+    #[test]
+    fn clean_rules_have_no_lints() {
+        assert_eq!(kinds("com\nuk\nco.uk\n*.ck\n!www.ck\n"), []);
+    }
+
+    #[test]
+    fn reports_each_problem() {
+        let text = "\
+            // ===BEGIN ICANN DOMAINS===\n\
+            com\ncom\n*.ck\na.ck\n!www.uk\n公司.cn\na_b.com\na.*.org\n\
+            // ===END ICANN DOMAINS===\n\
+            // ===BEGIN PRIVATE DOMAINS===\n\
+            b.ck\n";
+        let got = kinds(text);
+        let want = [
+            ("!www.uk", LintKind::OrphanException),
+            (
+                "a.*.org",
+                LintKind::InvalidLabel(HostError::IllegalChar { index: 2, ch: '*' }),
+            ),
+            ("a.ck", LintKind::Shadowed { by: "*.ck".into() }),
+            (
+                "a_b.com",
+                LintKind::InvalidLabel(HostError::IllegalChar { index: 1, ch: '_' }),
+            ),
+            ("com", LintKind::Duplicate),
+            ("公司.cn", LintKind::NonPunycode),
+        ];
+        assert_eq!(got, want.map(|(r, k)| (String::from(r), k)));
+    }
+
+    #[test]
+    fn wildcard_offsets_count_the_star() {
+        assert_eq!(
+            kinds("*.a_b\n"),
+            [(
+                "*.a_b".into(),
+                LintKind::InvalidLabel(HostError::IllegalChar { index: 3, ch: '_' })
+            )]
+        );
+    }
+
+    #[test]
+    fn display() {
+        let lint = Lint {
+            rule: "a.ck".into(),
+            kind: LintKind::Shadowed { by: "*.ck".into() },
+        };
+        assert_eq!(lint.to_string(), "`a.ck`: already matched by `*.ck`");
+    }
+}
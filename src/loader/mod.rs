@@ -1,10 +1,16 @@
-use crate::rules::{Leaf, RuleSet, Type};
+//! Parsing PSL text into rules.
+use crate::rules::{
+    is_valid_date, CommentBlock, Labels, Leaf, ListVersion, Node, RuleMeta, RuleSet, Type,
+};
 use crate::{
-    errors::{Error, Result, RuleSyntax},
+    builder,
+    errors::{Error, Result, RuleSyntax, Warning},
     options::{CommentPolicy, LoadOpts, SectionPolicy},
+    List,
 };
+use alloc::{borrow::Cow, boxed::Box, format, vec::Vec};
 
-// Loads a `RuleSet` from a string slice containing the Public Suffix List.
+/// Loads a `RuleSet` from a string slice containing the Public Suffix List.
 ///
 /// This function parses the text line by line, handling comments, section markers,
 /// and individual rules. It supports various loading options specified via the
@@ -15,30 +21,145 @@ use crate::{
 /// This function will return an error if:
 /// - The input text is not valid UTF-8.
 /// - The list is empty or contains no valid rules.
-/// - `LoadOpts::strict_rules` is enabled and an invalid rule is found.
+/// - `LoadOpts::strict_rules` is enabled and a rule breaks the PSL syntax
+///   (see [`RuleSyntax`]) or has a label over 63 octets.
 /// - `LoadOpts::sections` is set to `Require` and section markers are missing.
-pub fn load(text: &str, opts: LoadOpts) -> Result<RuleSet> {
+/// - A rule has more labels than `LoadOpts::max_rule_depth`.
+pub(crate) fn load(text: &str, opts: LoadOpts) -> Result<RuleSet> {
     if !text.is_char_boundary(text.len()) {
         return Err(Error::NotUtf8);
     }
+    let mut parser = Parser::new(opts);
+    for raw in text.lines() {
+        parser.line(raw)?;
+    }
+    parser.into_rules()
+}
 
-    let mut rules = RuleSet::default();
-    let mut cur_type: Option<Type> = None;
-    let mut saw_marker = false;
+/// Push-based PSL parser, for text that arrives in pieces.
+///
+/// Bytes are passed to [`feed`](Self::feed) in chunks of any size, for
+/// example as they stream from the network or out of a decompressor; only
+/// the current unfinished line is buffered. [`finish`](Self::finish) checks
+/// the result and builds the `List`. A `Parser` gives the same list as
+/// [`List::parse_with`] on the whole text, and lists fed one after another
+/// are parsed as their concatenation.
+///
+/// After an error, the parser should be dropped.
+///
+/// ```rust
+/// use publicsuffix2::loader::Parser;
+/// use publicsuffix2::{LoadOpts, MatchOpts};
+///
+/// let mut parser = Parser::new(LoadOpts::default());
+/// for chunk in ["com\nc", "o.uk\nu", "k"] {
+///     parser.feed(chunk.as_bytes()).unwrap();
+/// }
+/// let list = parser.finish().unwrap();
+/// assert_eq!(list.tld("a.co.uk", MatchOpts::default()).as_deref(), Some("co.uk"));
+/// ```
+pub struct Parser {
+    opts: LoadOpts,
+    rules: RuleSet,
+    cur_type: Option<Type>,
+    saw_marker: bool,
+    pending: Option<RuleMeta>,
+    /// Index of the comment block rules fall under, and whether the previous
+    /// line added to it.
+    block: Option<usize>,
+    in_block: bool,
+    /// Bytes of the line not yet ended by `\n`.
+    partial: Vec<u8>,
+}
 
-    for raw in text.lines() {
+impl Parser {
+    /// A parser for a new list.
+    pub fn new(opts: LoadOpts) -> Self {
+        Self {
+            opts,
+            rules: RuleSet::default(),
+            cur_type: None,
+            saw_marker: false,
+            pending: None,
+            block: None,
+            in_block: false,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Parses the lines completed by `bytes`.
+    ///
+    /// Returns `Error::NotUtf8` for a line that is not UTF-8, the errors of
+    /// `LoadOpts::strict_rules` for invalid rules, and
+    /// `Error::RuleDepthExceeded` for rules over `LoadOpts::max_rule_depth`.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<()> {
+        let mut rest = bytes;
+        while let Some(end) = rest.iter().position(|&b| b == b'\n') {
+            if self.partial.is_empty() {
+                self.line_bytes(&rest[..end])?;
+            } else {
+                self.partial.extend_from_slice(&rest[..end]);
+                let line = core::mem::take(&mut self.partial);
+                self.line_bytes(&line)?;
+            }
+            rest = &rest[end + 1..];
+        }
+        self.partial.extend_from_slice(rest);
+        Ok(())
+    }
+
+    /// Parses the last line, if it has no `\n`, and builds the list.
+    ///
+    /// Fails as [`List::parse_with`] does on an empty list or, with
+    /// `SectionPolicy::Require`, missing section markers.
+    pub fn finish(mut self) -> Result<List> {
+        let line = core::mem::take(&mut self.partial);
+        self.line_bytes(&line)?;
+        self.into_rules().map(List::from_ruleset)
+    }
+
+    fn line_bytes(&mut self, raw: &[u8]) -> Result<()> {
+        self.line(core::str::from_utf8(raw).map_err(|_| Error::NotUtf8)?)
+    }
+
+    fn line(&mut self, raw: &str) -> Result<()> {
+        let opts = self.opts;
+        let rules = &mut self.rules;
         let line = raw.trim();
-        if line.is_empty() || is_comment(line, opts.comments) {
-            handle_markers(line, &mut cur_type, &mut saw_marker);
-            continue;
+        if line.is_empty() {
+            self.pending = None;
+            self.block = None;
+            self.in_block = false;
+            return Ok(());
+        }
+        if is_comment(line, opts.comments) {
+            let known = handle_markers(line, &mut self.cur_type, &mut self.saw_marker);
+            if opts.keep_metadata {
+                handle_comment_block(line, known, &mut self.block, &mut self.in_block, rules);
+            }
+            if !known && opts.collect_warnings && looks_like_marker(line) {
+                rules
+                    .warnings
+                    .push(Warning::UnknownMarker { line: line.into() });
+            }
+            handle_annotation(line, &mut self.pending, rules, opts);
+            handle_version(line, &mut rules.version);
+            return Ok(());
         }
+        let meta = self.pending.take();
+        self.in_block = false;
 
         let tok = line.split_whitespace().next().unwrap();
         let (neg, raw_rule) = tok
             .strip_prefix('!')
             .map(|r| (true, r))
             .unwrap_or((false, tok));
-        let rule = raw_rule.trim_matches('.');
+        let mut rule = Cow::Borrowed(raw_rule.trim_matches('.'));
+        if opts.collect_warnings && raw_rule.ends_with('.') && !rule.is_empty() {
+            rules
+                .warnings
+                .push(Warning::TrailingDotRule { rule: tok.into() });
+        }
         if rule.is_empty() {
             if opts.strict_rules {
                 return Err(Error::InvalidRule {
@@ -46,44 +167,141 @@ pub fn load(text: &str, opts: LoadOpts) -> Result<RuleSet> {
                     reason: RuleSyntax::Empty,
                 });
             } else {
-                continue;
+                return Ok(());
+            }
+        }
+        let depth = rule.split('.').count();
+        if depth > opts.max_rule_depth {
+            return Err(Error::RuleDepthExceeded { depth });
+        }
+        if opts.strict_rules || opts.collect_warnings {
+            // Lenient loading already reported and stripped a trailing dot.
+            let checked = if opts.strict_rules { raw_rule } else { &*rule };
+            if let Err(e) = check_rule(checked, neg, &line[tok.len()..]) {
+                if opts.strict_rules {
+                    return Err(e);
+                }
+                rules.warnings.push(match e {
+                    Error::InvalidRule { reason, .. } => Warning::InvalidRule {
+                        rule: tok.into(),
+                        reason,
+                    },
+                    _ => Warning::RuleLabelTooLong { rule: tok.into() },
+                });
             }
         }
 
         let typ = match opts.sections {
             SectionPolicy::Auto => {
-                if saw_marker {
-                    cur_type
+                if self.saw_marker {
+                    self.cur_type
                 } else {
                     None
                 }
             }
             SectionPolicy::Ignore => None,
-            SectionPolicy::Require => cur_type,
+            SectionPolicy::Require => self.cur_type,
         };
-        if matches!(opts.sections, SectionPolicy::Require) && typ.is_none() {
-            continue;
+        let counts = &mut *rules.sections;
+        match typ {
+            Some(Type::Icann) => counts.icann += 1,
+            Some(Type::Private) => counts.private += 1,
+            None if matches!(opts.sections, SectionPolicy::Require) => {
+                counts.skipped += 1;
+                return Ok(());
+            }
+            None => counts.unclassified += 1,
         }
 
-        insert(&mut rules, rule, cur_type, neg);
-        // If IDNA is enabled and rule contains non-ASCII, also add an ASCII (A-label) duplicate.
-        #[cfg(feature = "idna")]
-        if rule.bytes().any(|b| b >= 0x80) {
-            if let Ok(ascii) = idna::domain_to_ascii(rule) {
-                if ascii.as_str() != rule {
-                    insert(&mut rules, &ascii, typ, neg);
-                }
+        if opts.lowercase_rules && rule.chars().any(char::is_uppercase) {
+            if opts.collect_warnings {
+                rules
+                    .warnings
+                    .push(Warning::RuleCaseNormalized { rule: tok.into() });
+            }
+            rule = Cow::Owned(rule.to_lowercase());
+        }
+        let rule = rule.as_ref();
+
+        if meta.is_some() || self.block.is_some() {
+            let written = if neg { format!("!{rule}") } else { rule.into() };
+            if let Some(i) = self.block {
+                rules.comments.by_rule.insert(written.clone(), i);
+            }
+            if let Some(mut meta) = meta {
+                meta.rule = written;
+                rules.meta.push(meta);
             }
         }
+        let existed = insert(rules, rule, typ, neg);
+        if existed && opts.collect_warnings {
+            rules
+                .warnings
+                .push(Warning::DuplicateRule { rule: tok.into() });
+        }
+        if opts.a_label_duplicates {
+            insert_a_label(rules, rule, typ, neg);
+        }
+
+        Ok(())
     }
 
-    if matches!(opts.sections, SectionPolicy::Require) && !saw_marker {
-        return Err(Error::MissingSections);
+    /// Checks the parsed rules once every line is in.
+    fn into_rules(self) -> Result<RuleSet> {
+        let (opts, mut rules) = (self.opts, self.rules);
+        if matches!(opts.sections, SectionPolicy::Require) && !self.saw_marker {
+            return Err(Error::MissingSections);
+        }
+        if rules.root.kids.is_empty() {
+            return Err(Error::EmptyList);
+        }
+        if opts.collect_warnings {
+            let mut path = Vec::new();
+            find_shadowed(&rules.root, &rules.labels, &mut path, &mut rules.warnings);
+        }
+        Ok(rules)
+    }
+}
+
+/// Checks `rule` against the PSL rule syntax, as [`ListBuilder`] rules are,
+/// and that only a comment follows it on its line (`rest`).
+///
+/// [`ListBuilder`]: crate::ListBuilder
+fn check_rule(rule: &str, neg: bool, rest: &str) -> Result<()> {
+    let rest = rest.trim_start();
+    if !rest.is_empty() && !rest.starts_with("//") {
+        return Err(Error::InvalidRule {
+            rule: rule.into(),
+            reason: RuleSyntax::ContainsWhitespace,
+        });
     }
-    if rules.root.kids.is_empty() {
-        return Err(Error::EmptyList);
+    builder::validate(rule, neg)
+}
+
+/// Reports positive rules that a wildcard sibling of the same section
+/// already matches, such as `a.ck` next to `*.ck`. `path` holds the labels
+/// leading to `node`, from the right.
+fn find_shadowed<'a>(
+    node: &'a Node,
+    labels: &'a Labels,
+    path: &mut Vec<&'a str>,
+    warnings: &mut Vec<Warning>,
+) {
+    let wildcard = node
+        .kid(labels, "*")
+        .filter(|w| w.leaf == Leaf::Positive)
+        .map(|w| w.typ);
+    for (label, kid) in node.sorted_kids(labels) {
+        path.push(label);
+        if label != "*" && kid.leaf == Leaf::Positive && wildcard == Some(kid.typ) {
+            let rule: Vec<&str> = path.iter().rev().copied().collect();
+            warnings.push(Warning::ShadowedRule {
+                rule: rule.join("."),
+            });
+        }
+        find_shadowed(kid, labels, path, warnings);
+        path.pop();
     }
-    Ok(rules)
 }
 
 fn is_comment(s: &str, policy: CommentPolicy) -> bool {
@@ -93,31 +311,149 @@ fn is_comment(s: &str, policy: CommentPolicy) -> bool {
     }
 }
 
-fn handle_markers(line: &str, cur: &mut Option<Type>, saw: &mut bool) {
+/// Applies a section marker, returning false if `line` is not one.
+fn handle_markers(line: &str, cur: &mut Option<Type>, saw: &mut bool) -> bool {
     if !line.starts_with("//") {
-        return;
+        return false;
     }
+    let mut known = false;
     if line.contains("BEGIN ICANN DOMAINS") {
         *cur = Some(Type::Icann);
         *saw = true;
+        known = true;
     }
     if line.contains("END ICANN DOMAINS") {
         *cur = None;
+        known = true;
     }
     if line.contains("BEGIN PRIVATE DOMAINS") {
         *cur = Some(Type::Private);
         *saw = true;
+        known = true;
     }
     if line.contains("END PRIVATE DOMAINS") {
         *cur = None;
+        known = true;
     }
+    known
+}
+
+/// Returns true for comments shaped like a section marker
+/// (`// ===BEGIN ... ===`).
+fn looks_like_marker(line: &str) -> bool {
+    line.starts_with("//")
+        && line.contains("===")
+        && (line.contains("BEGIN") || line.contains("END"))
 }
 
-fn insert(rules: &mut RuleSet, rule: &str, typ: Option<Type>, neg: bool) {
-    let mut cur = &mut rules.root;
-    for lbl in rule.rsplit('.') {
-        cur = cur.kids.entry(lbl.to_string()).or_default();
+/// Adds a comment line to the block above the next rules, starting a new
+/// block after a rule. Section markers end the block; annotations are
+/// skipped.
+fn handle_comment_block(
+    line: &str,
+    marker: bool,
+    block: &mut Option<usize>,
+    in_block: &mut bool,
+    rules: &mut RuleSet,
+) {
+    if marker {
+        *block = None;
+        *in_block = false;
+        return;
+    }
+    let text = line
+        .strip_prefix("//")
+        .or_else(|| line.strip_prefix(['#', ';']))
+        .unwrap_or(line)
+        .trim();
+    if text.starts_with('@') {
+        return;
+    }
+    let blocks = &mut rules.comments.blocks;
+    if !*in_block {
+        blocks.push(CommentBlock::default());
+        *block = Some(blocks.len() - 1);
+        *in_block = true;
     }
+    blocks.last_mut().unwrap().lines.push(text.into());
+}
+
+/// Records the first `// VERSION:` and `// COMMIT:` header lines.
+fn handle_version(line: &str, version: &mut Option<Box<ListVersion>>) {
+    let Some(body) = line.strip_prefix("//").map(str::trim_start) else {
+        return;
+    };
+    let (key, value) = body.split_once(':').unwrap_or((body, ""));
+    let value = value.trim();
+    if !matches!(key, "VERSION" | "COMMIT") || value.is_empty() {
+        return;
+    }
+    let version = version.get_or_insert_with(Box::default);
+    if key == "COMMIT" {
+        version.commit.get_or_insert_with(|| value.into());
+    } else if version.version.is_none() {
+        version.date = value.get(..10).filter(|d| is_valid_date(d)).map(Into::into);
+        version.version = Some(value.into());
+    }
+}
+
+/// Records a `// @key value` annotation for the next rule. Unknown keys are
+/// treated as ordinary comments.
+fn handle_annotation(
+    line: &str,
+    pending: &mut Option<RuleMeta>,
+    rules: &mut RuleSet,
+    opts: LoadOpts,
+) {
+    let Some(body) = line
+        .strip_prefix("//")
+        .and_then(|c| c.trim_start().strip_prefix('@'))
+    else {
+        return;
+    };
+    let (key, value) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+    let value = value.trim();
+    match key {
+        "expires" if is_valid_date(value) => {
+            pending.get_or_insert_with(RuleMeta::default).expires = Some(value.into());
+        }
+        "owner" if !value.is_empty() => {
+            pending.get_or_insert_with(RuleMeta::default).owner = Some(value.into());
+        }
+        "expires" | "owner" if opts.collect_warnings => {
+            rules
+                .warnings
+                .push(Warning::InvalidAnnotation { line: line.into() });
+        }
+        _ => {}
+    }
+}
+
+/// Inserts `rule` and, when IDNA is enabled, its A-label form.
+pub(crate) fn insert_rule(rules: &mut RuleSet, rule: &str, typ: Option<Type>, neg: bool) {
+    insert(rules, rule, typ, neg);
+    insert_a_label(rules, rule, typ, neg);
+}
+
+/// If IDNA is enabled and `rule` contains non-ASCII, also adds an ASCII
+/// (A-label) duplicate.
+#[cfg_attr(not(feature = "idna"), allow(unused_variables))]
+fn insert_a_label(rules: &mut RuleSet, rule: &str, typ: Option<Type>, neg: bool) {
+    #[cfg(feature = "idna")]
+    if rule.bytes().any(|b| b >= 0x80) {
+        if let Ok(ascii) = idna::domain_to_ascii(rule) {
+            if ascii.as_str() != rule {
+                insert(rules, &ascii, typ, neg);
+            }
+        }
+    }
+}
+
+/// Inserts `rule`, returning true if it was already a rule.
+pub(crate) fn insert(rules: &mut RuleSet, rule: &str, typ: Option<Type>, neg: bool) -> bool {
+    let cur = rules.node_mut_or_insert(rule);
+    let existed = cur.leaf != Leaf::None;
     cur.leaf = if neg { Leaf::Negative } else { Leaf::Positive };
     cur.typ = typ;
+    existed
 }
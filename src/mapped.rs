@@ -0,0 +1,200 @@
+//! Lists compiled for memory mapping.
+//!
+//! [`List::to_mapped_bytes`] writes a list whose rules are searched where
+//! they lie instead of being decoded: after the [`Format::MappedList`]
+//! header comes the [`Engine::Flat`] trie image, then the annotated rules
+//! and snapshot as in [`List::to_bytes`]. [`List::open_mmap`] maps such a
+//! file read-only (with the `mmap` feature), so the rules cost no heap
+//! memory and processes opening the same file share its pages;
+//! [`List::from_mapped_bytes`] loads the same bytes from memory.
+use crate::errors::{Error, Result};
+use crate::flat::{FlatTrie, Image};
+use crate::format::Format;
+#[cfg(doc)]
+use crate::rules::Engine;
+use crate::rules::SectionCounts;
+use crate::{List, Store};
+use alloc::{sync::Arc, vec::Vec};
+
+impl List {
+    /// Serializes the list for [`List::open_mmap`].
+    ///
+    /// Lists of any engine can be written; a list opened from the bytes
+    /// uses [`Engine::Flat`]. Like [`List::to_bytes`], load warnings and
+    /// rule comments are not kept, and equal lists produce equal bytes.
+    ///
+    /// Returns `Error::TooLargeForFlat` if the rules do not fit the
+    /// `Engine::Flat` layout.
+    pub fn to_mapped_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = Format::MappedList.binary_header();
+        match &*self.store {
+            Store::Flat(f) => out.extend_from_slice(f.image()),
+            _ => out.extend_from_slice(FlatTrie::from_ruleset(&self.rule_set())?.image()),
+        }
+        self.write_annotations(&mut out);
+        Ok(out)
+    }
+
+    /// Loads a list written by [`List::to_mapped_bytes`] from memory, e.g.
+    /// embedded with `include_bytes!`.
+    ///
+    /// The rules are copied once and then searched without decoding.
+    /// Returns `Error::UnsupportedFormatVersion` if `bytes` are not a mapped
+    /// list and `Error::InvalidBinary` if they are corrupt.
+    pub fn from_mapped_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_image(Image::Owned(bytes.into()))
+    }
+
+    fn from_image(image: Image) -> Result<Self> {
+        let (_, body) = Format::MappedList.check_binary_header(&image)?;
+        let start = image.len() - body.len();
+        let (trie, end) = FlatTrie::from_image(image, start)?;
+        let (meta, version) =
+            crate::binary::read_annotations(trie.rest()).map_err(|e| match e {
+                Error::InvalidBinary { offset } => Error::InvalidBinary {
+                    offset: end + offset,
+                },
+                e => e,
+            })?;
+        let mut list = Self {
+            store: Arc::new(Store::Flat(trie)),
+            warnings: Vec::new(),
+            meta,
+            comments: Arc::default(),
+            version: version.map(|v| *v),
+            sections: SectionCounts::default(),
+            len: 0,
+        };
+        list.recount();
+        Ok(list)
+    }
+
+    /// Opens a file written from [`List::to_mapped_bytes`] by mapping it
+    /// into memory.
+    ///
+    /// The rules are checked once, then looked up directly in the mapped
+    /// pages; only the rule annotations are copied to the heap. On platforms
+    /// without `mmap` the file is read into memory instead.
+    ///
+    /// Returns `Error::Io` if the file cannot be read,
+    /// `Error::UnsupportedFormatVersion` if it is not a mapped list (a file
+    /// from [`List::to_bytes`] is loaded with [`List::from_bytes`]) and
+    /// `Error::InvalidBinary` if it is corrupt.
+    ///
+    /// This method is only available when the `mmap` feature is enabled.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be changed or truncated, by this process or any
+    /// other, while a list opened from it (or a clone of one) is alive:
+    /// lookups read the mapped pages directly, so a change shows up as
+    /// undefined behavior or a `SIGBUS`. Deploy a new list by writing a new
+    /// file and renaming it over the old one, which leaves the mapped file
+    /// untouched.
+    ///
+    /// ```rust,no_run
+    /// use publicsuffix2::{List, MatchOpts};
+    ///
+    /// // Once, at deploy time:
+    /// let list = List::from_file("public_suffix_list.dat")?;
+    /// std::fs::write("psl.mapped", list.to_mapped_bytes()?)?;
+    ///
+    /// // In each process. SAFETY: deploys replace psl.mapped by renaming,
+    /// // never by writing to it.
+    /// let list = unsafe { List::open_mmap("psl.mapped")? };
+    /// assert_eq!(list.tld("a.co.uk", MatchOpts::default()).as_deref(), Some("co.uk"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub unsafe fn open_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(Error::Io)?;
+        // SAFETY: the caller keeps the file unchanged while the list lives.
+        #[cfg(unix)]
+        let image = Image::Mapped(Arc::new(unsafe { Mapping::new(&file) }.map_err(Error::Io)?));
+        #[cfg(not(unix))]
+        let image = {
+            use std::io::Read;
+            let mut bytes = Vec::new();
+            (&file).read_to_end(&mut bytes).map_err(Error::Io)?;
+            Image::Owned(bytes)
+        };
+        Self::from_image(image)
+    }
+}
+
+/// A read-only memory mapping of a whole file.
+#[cfg(all(feature = "mmap", unix))]
+#[derive(Debug)]
+pub(crate) struct Mapping {
+    ptr: core::ptr::NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: the mapping is only read, never written, through `ptr`, and is
+// unmapped only on drop, so sharing it between threads is like sharing a
+// `&[u8]`. Its bytes stay fixed only as long as the file does, which
+// `List::open_mmap` leaves to its caller.
+#[cfg(all(feature = "mmap", unix))]
+unsafe impl Send for Mapping {}
+#[cfg(all(feature = "mmap", unix))]
+unsafe impl Sync for Mapping {}
+
+#[cfg(all(feature = "mmap", unix))]
+impl Mapping {
+    /// Maps the whole of `file` read-only.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be changed or truncated while the mapping exists.
+    unsafe fn new(file: &std::fs::File) -> std::io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::OutOfMemory))?;
+        if len == 0 {
+            // Zero-length mappings are invalid; an empty slice will do.
+            return Ok(Self {
+                ptr: core::ptr::NonNull::dangling(),
+                len,
+            });
+        }
+        // SAFETY: a fresh shared, read-only mapping of `len` bytes of `file`;
+        // the result is checked before use.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ptr = core::ptr::NonNull::new(ptr.cast()).ok_or_else(std::io::Error::last_os_error)?;
+        Ok(Self { ptr, len })
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+impl core::ops::Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` readable bytes until drop, which
+        // the caller of `new` keeps from changing.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: unmaps exactly the region mapped in `new`.
+            unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Combining lists: merging rules, or consulting an overrides list first.
+//!
+//! Both take a base list, typically the official PSL, and an overrides list
+//! holding internal zones such as `corp.example`. A rule defined in both
+//! with a different kind or section is a conflict, resolved by a
+//! [`MergePolicy`].
+use crate::engine::{MatchInfo, Parts, RuleKind};
+use crate::errors::{Error, Result};
+use crate::options::MatchOpts;
+use crate::rules::{Labels, Leaf, Node, RuleSet};
+use crate::List;
+use alloc::{borrow::Cow, boxed::Box, format, string::String, vec::Vec};
+
+/// How a rule defined differently by two lists is resolved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The overrides list wins.
+    #[default]
+    PreferOverride,
+    /// The base list wins.
+    KeepBase,
+    /// Fail with `Error::MergeConflict`.
+    ErrorOnConflict,
+}
+
+impl List {
+    /// A new list with the rules of `self` and of `overrides`.
+    ///
+    /// Rules defined by both lists with the same kind and section are kept
+    /// once; other overlaps are resolved by `policy`. Annotations and comment
+    /// blocks follow the rule that was kept, and warnings of both lists are
+    /// carried over. The result uses the engine of `self` and keeps its
+    /// [`version_info`](Self::version_info).
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, MatchOpts, MergePolicy};
+    ///
+    /// let base: List = "com\nexample".parse().unwrap();
+    /// let internal: List = "corp.example".parse().unwrap();
+    /// let merged = base.merge(&internal, MergePolicy::ErrorOnConflict).unwrap();
+    /// let m = MatchOpts::default();
+    /// assert_eq!(merged.sld("a.b.corp.example", m).as_deref(), Some("b.corp.example"));
+    /// assert_eq!(merged.sld("www.example.com", m).as_deref(), Some("example.com"));
+    /// ```
+    pub fn merge(&self, overrides: &List, policy: MergePolicy) -> Result<List> {
+        let mut rules = self.rule_set().into_owned();
+        let other = overrides.rule_set();
+        merge_node(
+            &mut rules.root,
+            &mut rules.labels,
+            &other.root,
+            &other.labels,
+            &mut Vec::new(),
+            policy,
+        )?;
+
+        let mut meta = self.meta.clone();
+        for m in &overrides.meta {
+            match meta.iter().position(|own| own.rule == m.rule) {
+                Some(i) if policy == MergePolicy::PreferOverride => meta[i] = m.clone(),
+                Some(_) => {}
+                None => meta.push(m.clone()),
+            }
+        }
+        rules.meta = meta;
+        rules.comments = (*self.comments).clone();
+        rules.version = self.version.clone().map(Box::new);
+        rules
+            .comments
+            .absorb(&overrides.comments, policy == MergePolicy::PreferOverride);
+        rules.warnings = self.warnings.clone();
+        rules.warnings.extend(overrides.warnings.iter().cloned());
+        Ok(List::from_ruleset(rules).with_engine(self.engine()))
+    }
+}
+
+/// Merges the rules below `src` into `dst`, with their labels in
+/// `dst_labels` and `src_labels`; `path` holds the labels leading to both,
+/// from the right.
+fn merge_node<'a>(
+    dst: &mut Node,
+    dst_labels: &mut Labels,
+    src: &'a Node,
+    src_labels: &'a Labels,
+    path: &mut Vec<&'a str>,
+    policy: MergePolicy,
+) -> Result<()> {
+    if src.leaf != Leaf::None {
+        let differs = (dst.leaf, dst.typ) != (src.leaf, src.typ);
+        if dst.leaf == Leaf::None || (differs && policy == MergePolicy::PreferOverride) {
+            (dst.leaf, dst.typ) = (src.leaf, src.typ);
+        } else if differs && policy == MergePolicy::ErrorOnConflict {
+            return Err(Error::MergeConflict {
+                rule: written(path, dst.leaf),
+            });
+        }
+    }
+    for (label, kid) in src.labeled_kids(src_labels) {
+        path.push(label);
+        let dst_kid = dst.kid_or_insert(dst_labels, label);
+        merge_node(dst_kid, dst_labels, kid, src_labels, path, policy)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+/// First rule that `base` and `overrides` define differently.
+fn first_conflict(base: &RuleSet, overrides: &RuleSet) -> Option<String> {
+    fn walk<'a>(
+        base: (&Node, &Labels),
+        over: (&'a Node, &'a Labels),
+        path: &mut Vec<&'a str>,
+    ) -> Option<String> {
+        let ((base, base_labels), (over, over_labels)) = (base, over);
+        if base.leaf != Leaf::None
+            && over.leaf != Leaf::None
+            && (base.leaf, base.typ) != (over.leaf, over.typ)
+        {
+            return Some(written(path, base.leaf));
+        }
+        for (label, kid) in over.labeled_kids(over_labels) {
+            let Some(b) = base.kid(base_labels, label) else {
+                continue;
+            };
+            path.push(label);
+            let found = walk((b, base_labels), (kid, over_labels), path);
+            path.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+    walk(
+        (&base.root, &base.labels),
+        (&overrides.root, &overrides.labels),
+        &mut Vec::new(),
+    )
+}
+
+/// The rule at `path` (labels from the right) as written in a list.
+fn written(path: &[&str], leaf: Leaf) -> String {
+    let labels: Vec<&str> = path.iter().rev().copied().collect();
+    let rule = labels.join(".");
+    if leaf == Leaf::Negative {
+        format!("!{rule}")
+    } else {
+        rule
+    }
+}
+
+/// A base list with an overrides list consulted first.
+///
+/// A host matched by a rule of the overrides list is answered from that
+/// list alone; any other host is answered by the base list. Unlike
+/// [`List::merge`], both lists stay as they are, so the base can be
+/// replaced by a newer PSL without rebuilding the overrides.
+///
+/// ```rust
+/// use publicsuffix2::{LayeredList, List, MatchOpts, MergePolicy};
+///
+/// let base: List = "example\ncom".parse().unwrap();
+/// let overrides: List = "corp.example".parse().unwrap();
+/// let layered = LayeredList::new(overrides, base, MergePolicy::PreferOverride).unwrap();
+/// let m = MatchOpts::default();
+/// assert_eq!(layered.tld("a.corp.example", m).as_deref(), Some("corp.example"));
+/// assert_eq!(layered.tld("a.example", m).as_deref(), Some("example"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LayeredList {
+    overrides: List,
+    base: List,
+}
+
+impl LayeredList {
+    /// Layers `overrides` over `base`.
+    ///
+    /// Under `MergePolicy::ErrorOnConflict`, fails if the lists define a
+    /// rule differently. Otherwise conflicts are allowed; lookups always
+    /// prefer the overrides, so `KeepBase` behaves like `PreferOverride`
+    /// here. Use [`List::merge`] to keep base rules.
+    pub fn new(overrides: List, base: List, policy: MergePolicy) -> Result<Self> {
+        if policy == MergePolicy::ErrorOnConflict {
+            if let Some(rule) = first_conflict(&base.rule_set(), &overrides.rule_set()) {
+                return Err(Error::MergeConflict { rule });
+            }
+        }
+        Ok(Self { overrides, base })
+    }
+
+    /// The list consulted first.
+    pub fn overrides(&self) -> &List {
+        &self.overrides
+    }
+
+    /// The list consulted when the overrides have no rule for a host.
+    pub fn base(&self) -> &List {
+        &self.base
+    }
+
+    /// The list that answers for `host`.
+    fn layer_for(&self, host: &str, opts: MatchOpts<'_>) -> &List {
+        let listed = self.overrides.match_info(host, opts).is_some_and(|i| {
+            matches!(
+                i.kind,
+                RuleKind::Normal | RuleKind::Wildcard | RuleKind::Exception
+            )
+        });
+        if listed {
+            &self.overrides
+        } else {
+            &self.base
+        }
+    }
+
+    /// Splits `host`; see [`List::split`].
+    pub fn split<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Parts<'a>> {
+        self.layer_for(host, opts).split(host, opts)
+    }
+
+    /// Registrable domain of `host`; see [`List::sld`].
+    pub fn sld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+        self.layer_for(host, opts).sld(host, opts)
+    }
+
+    /// Public suffix of `host`; see [`List::tld`].
+    pub fn tld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+        self.layer_for(host, opts).tld(host, opts)
+    }
+
+    /// The rule that decided `host`, from whichever list answered; see
+    /// [`List::match_info`].
+    pub fn match_info<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<MatchInfo<'a>> {
+        self.layer_for(host, opts).match_info(host, opts)
+    }
+}
@@ -0,0 +1,150 @@
+//! Counters for how lookups are resolved.
+//!
+//! Install a [`MetricsSink`] with [`set_sink`] and every suffix search
+//! reports what it did: whether it matched a wildcard or an exception rule,
+//! fell back to the unlisted-TLD answer, or had to copy the host to
+//! normalize it. Until a sink is installed, reporting costs one atomic load
+//! per lookup; without the `metrics` feature it compiles to nothing.
+//!
+//! [`Counters`] is a ready-made sink that just counts:
+//!
+//! ```rust
+//! use publicsuffix2::metrics::{self, Counters, Metric};
+//! use publicsuffix2::{List, MatchOpts};
+//!
+//! static COUNTERS: Counters = Counters::new();
+//! # let _ =
+//! metrics::set_sink(&COUNTERS);
+//!
+//! let list = List::parse("com\n*.ck\n!www.ck\n")?;
+//! list.tld("a.b.ck", MatchOpts::default());
+//! assert!(COUNTERS.get(Metric::Lookup) >= 1);
+//! assert!(COUNTERS.get(Metric::Wildcard) >= 1);
+//! # Ok::<(), publicsuffix2::Error>(())
+//! ```
+//!
+//! The sink is process-wide and called on the lookup path, so it should
+//! only bump counters; forward them to a metrics system from elsewhere.
+use core::sync::atomic::{AtomicU64, Ordering};
+use once_cell::sync::OnceCell;
+
+static SINK: OnceCell<&'static dyn MetricsSink> = OnceCell::new();
+
+/// Something that happened during a lookup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Metric {
+    /// A host was searched for its public suffix.
+    Lookup,
+    /// No rule matched, so the suffix came from `MatchOpts::fallback` (or
+    /// `LiteralPolicy::Whole`).
+    Fallback,
+    /// The suffix was matched through a `*` rule.
+    Wildcard,
+    /// The suffix was matched through an exception (`!`) rule.
+    Exception,
+    /// The host was copied to apply `MatchOpts::controls` or the
+    /// normalizer, rather than borrowed.
+    NormalizationAlloc,
+}
+
+impl Metric {
+    /// Every metric, in declaration order.
+    pub const ALL: [Metric; 5] = [
+        Metric::Lookup,
+        Metric::Fallback,
+        Metric::Wildcard,
+        Metric::Exception,
+        Metric::NormalizationAlloc,
+    ];
+}
+
+/// Receives lookup metrics; see [`set_sink`].
+pub trait MetricsSink: Send + Sync {
+    /// Called once for each occurrence of `metric`.
+    fn increment(&self, metric: Metric);
+}
+
+/// Installs the process-wide sink.
+///
+/// The first sink installed stays for the life of the process; later calls
+/// return the sink they were given back.
+pub fn set_sink(sink: &'static dyn MetricsSink) -> Result<(), &'static dyn MetricsSink> {
+    SINK.set(sink)
+}
+
+/// The installed sink, if any.
+pub fn sink() -> Option<&'static dyn MetricsSink> {
+    SINK.get().copied()
+}
+
+/// Reports `metric` to the installed sink.
+#[inline]
+pub(crate) fn record(metric: Metric) {
+    if let Some(sink) = SINK.get() {
+        sink.increment(metric);
+    }
+}
+
+/// A sink keeping one atomic counter per [`Metric`].
+#[derive(Debug, Default)]
+pub struct Counters {
+    counts: [AtomicU64; Metric::ALL.len()],
+}
+
+impl Counters {
+    /// All counters at zero; usable in a `static`.
+    pub const fn new() -> Self {
+        Self {
+            counts: [const { AtomicU64::new(0) }; Metric::ALL.len()],
+        }
+    }
+
+    /// The count recorded for `metric`.
+    pub fn get(&self, metric: Metric) -> u64 {
+        self.counts[metric as usize].load(Ordering::Relaxed)
+    }
+
+    /// Every metric with its count.
+    pub fn snapshot(&self) -> [(Metric, u64); Metric::ALL.len()] {
+        Metric::ALL.map(|m| (m, self.get(m)))
+    }
+
+    /// Sets every counter back to zero.
+    pub fn reset(&self) {
+        for c in &self.counts {
+            c.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl MetricsSink for Counters {
+    fn increment(&self, metric: Metric) {
+        self.counts[metric as usize].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_count_each_metric() {
+        let c = Counters::new();
+        c.increment(Metric::Wildcard);
+        c.increment(Metric::Wildcard);
+        c.increment(Metric::NormalizationAlloc);
+        assert_eq!(c.get(Metric::Wildcard), 2);
+        assert_eq!(c.get(Metric::Lookup), 0);
+        assert_eq!(c.snapshot()[4], (Metric::NormalizationAlloc, 1));
+        c.reset();
+        assert!(c.snapshot().iter().all(|&(_, n)| n == 0));
+    }
+
+    #[test]
+    fn metrics_index_their_counters() {
+        for (i, m) in Metric::ALL.into_iter().enumerate() {
+            assert_eq!(m as usize, i);
+        }
+    }
+}
@@ -1,4 +1,10 @@
+use crate::errors::{Error, HostError};
+use alloc::borrow::Cow;
+use core::net::{Ipv4Addr, Ipv6Addr};
+
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 /// Parse-time options for loading a Public Suffix List (PSL) into a RuleSet.
 ///
 /// These affect I/O and parsing only; they do not change how lookups behave
@@ -6,17 +12,30 @@
 ///
 /// - `sections`: How to handle PSL section markers (ICANN/PRIVATE) during parsing.
 /// - `comments`: Which kinds of comment lines to accept while parsing.
-/// - `strict_rules`: If true, reject malformed rules with an error instead of skipping them.
+/// - `strict_rules`: If true, reject rules that break the PSL syntax with an error instead of loading them best-effort.
 /// - `collect_warnings`: If true, collect non-fatal parser warnings (e.g., duplicated rules).
+/// - `lowercase_rules`: If true, lowercase rule labels so they match lowercase-normalized hosts.
+/// - `a_label_duplicates`: If true (with the `idna` feature), also store the A-label form of non-ASCII rules.
+/// - `keep_metadata`: If true, keep the comment block above each rule for `List::rule_metadata`.
+/// - `max_rule_depth`: Most labels a rule may have; a deeper rule fails the load with `Error::RuleDepthExceeded`.
 pub struct LoadOpts {
     /// How to handle PSL section markers (ICANN/PRIVATE) during parsing.
     pub sections: SectionPolicy,
     /// Which kinds of comment lines to accept while parsing.
     pub comments: CommentPolicy,
-    /// If true, reject malformed rules with an error instead of skipping them.
+    /// If true, reject rules that break the PSL syntax with an error instead
+    /// of loading them best-effort; otherwise, `collect_warnings` reports them.
     pub strict_rules: bool,
     /// If true, collect non-fatal parser warnings (e.g., duplicated rules).
     pub collect_warnings: bool,
+    /// If true, lowercase rule labels so they match lowercase-normalized hosts.
+    pub lowercase_rules: bool,
+    /// If true (with the `idna` feature), also store the A-label form of non-ASCII rules.
+    pub a_label_duplicates: bool,
+    /// If true, keep the comment block above each rule for `List::rule_metadata`.
+    pub keep_metadata: bool,
+    /// Most labels a rule may have; a deeper rule is an error.
+    pub max_rule_depth: usize,
 }
 impl Default for LoadOpts {
     /// Defaults suitable for most applications:
@@ -24,17 +43,91 @@ impl Default for LoadOpts {
     /// - `comments`: Common
     /// - `strict_rules`: false (best-effort parsing)
     /// - `collect_warnings`: false
+    /// - `lowercase_rules`: true
+    /// - `a_label_duplicates`: true
+    /// - `keep_metadata`: false
+    /// - `max_rule_depth`: 127, the most labels a DNS name can have
     fn default() -> Self {
         Self {
             sections: SectionPolicy::Auto,
             comments: CommentPolicy::Common,
             strict_rules: false,
             collect_warnings: false,
+            lowercase_rules: true,
+            a_label_duplicates: true,
+            keep_metadata: false,
+            max_rule_depth: 127,
+        }
+    }
+}
+
+/// Output options for [`List::to_psl_string`](crate::List::to_psl_string).
+#[derive(Clone, Copy, Debug)]
+pub struct WriteOpts<'a> {
+    /// Comment lines written at the top, each prefixed with `// `.
+    pub header: &'a [&'a str],
+    /// Group typed rules between `===BEGIN/END ICANN DOMAINS===` and
+    /// `===BEGIN/END PRIVATE DOMAINS===` markers. Without markers, every
+    /// rule reads back as unclassified.
+    pub sections: bool,
+    /// Write `// @expires` and `// @owner` annotations before the rules
+    /// that have them.
+    pub annotations: bool,
+}
+
+impl Default for WriteOpts<'_> {
+    /// No header, with sections and annotations.
+    fn default() -> Self {
+        Self {
+            header: &[],
+            sections: true,
+            annotations: true,
+        }
+    }
+}
+
+/// Network options for [`List::from_url_with`](crate::List::from_url_with).
+///
+/// A request that cannot connect or times out before the response, or that
+/// gets `429 Too Many Requests` or a `5xx` status, is retried up to
+/// `retries` times, waiting `backoff` before the first retry and twice as
+/// long before each following one. Other errors fail immediately.
+#[cfg(feature = "fetch")]
+#[derive(Clone, Copy, Debug)]
+pub struct FetchOpts<'a> {
+    /// Limit on each attempt, from connecting until the body is read.
+    /// `None` leaves ureq's defaults (30 seconds to connect, no overall
+    /// limit).
+    pub timeout: Option<core::time::Duration>,
+    /// Number of retries after the first attempt.
+    pub retries: u32,
+    /// Wait before the first retry; doubled for each following one.
+    pub backoff: core::time::Duration,
+    /// `User-Agent` header, instead of ureq's.
+    pub user_agent: Option<&'a str>,
+    /// Proxy to connect through, e.g. `http://proxy.example:3128` or
+    /// `user:password@proxy.example:3128`.
+    pub proxy: Option<&'a str>,
+}
+
+#[cfg(feature = "fetch")]
+impl Default for FetchOpts<'_> {
+    /// No timeout, no retries (with a 1 second backoff if enabled), ureq's
+    /// user agent and no proxy.
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            retries: 0,
+            backoff: core::time::Duration::from_secs(1),
+            user_agent: None,
+            proxy: None,
         }
     }
 }
 
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 /// Policy for handling PSL section markers (ICANN / PRIVATE) during parsing.
 ///
 /// This affects only how lists are loaded; it does not impact match behavior.
@@ -50,6 +143,8 @@ pub enum SectionPolicy {
     Require,
 }
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 /// Which comment syntaxes are accepted when parsing a PSL file.
 ///
 /// - `Common`: Accept both the official `// ...` and commonly-seen `# ...` comments.
@@ -61,7 +156,9 @@ pub enum CommentPolicy {
     OfficialOnly,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 /// Zero-copy normalization options applied to the input host view.
 ///
 /// Internally, only adjustments that can be expressed as a borrowed slice
@@ -71,6 +168,9 @@ pub enum CommentPolicy {
 /// - `lowercase`: Lowercase ASCII A–Z before matching.
 /// - `strip_trailing_dot`: Strip a single trailing dot (root label), if present.
 /// - `idna_ascii`: Convert Unicode labels to IDNA ASCII (A-label) form before matching.
+/// - `idna_transitional`: Use UTS #46 transitional processing for the IDNA conversion.
+/// - `idna_std3_rules`: Apply UseSTD3ASCIIRules during the IDNA conversion.
+/// - `idna_reject_errors`: Reject hosts that fail the IDNA conversion instead of matching them as given.
 pub struct Normalizer {
     /// Lowercase ASCII A–Z before matching.
     pub lowercase: bool,
@@ -78,6 +178,16 @@ pub struct Normalizer {
     pub strip_trailing_dot: bool,
     /// Convert Unicode labels to IDNA ASCII (A-label) form before matching.
     pub idna_ascii: bool,
+    /// Map the UTS #46 deviation characters (`ß`, `ς`, ZWJ, ZWNJ) the way
+    /// IDNA 2003 did (`ß` → `ss`). Browsers use nontransitional processing,
+    /// the default.
+    pub idna_transitional: bool,
+    /// Reject ASCII characters other than letters, digits, hyphens and dots
+    /// in hosts that go through IDNA conversion (UseSTD3ASCIIRules).
+    pub idna_std3_rules: bool,
+    /// Hosts that fail IDNA conversion make lookups return `None`, instead
+    /// of being matched as given.
+    pub idna_reject_errors: bool,
 }
 
 /// Compile-time preset mirroring python-publicsuffix2’s behavior.
@@ -85,6 +195,9 @@ pub const PS2_NORMALIZER: Normalizer = Normalizer {
     lowercase: true,
     strip_trailing_dot: true,
     idna_ascii: cfg!(feature = "idna"),
+    idna_transitional: false,
+    idna_std3_rules: false,
+    idna_reject_errors: false,
 };
 
 /// Explicit “no normalization”.
@@ -92,6 +205,9 @@ pub const RAW_NORMALIZER: Normalizer = Normalizer {
     lowercase: false,
     strip_trailing_dot: false,
     idna_ascii: false,
+    idna_transitional: false,
+    idna_std3_rules: false,
+    idna_reject_errors: false,
 };
 
 impl Normalizer {
@@ -125,6 +241,267 @@ impl Normalizer {
             ..RAW_NORMALIZER
         }
     }
+
+    /// Applies the normalizer to `host` the way lookups do.
+    ///
+    /// Lookups match a host that fails IDNA conversion as given (or reject
+    /// it, with `idna_reject_errors`); this reports the failure instead, as
+    /// [`Error::IdnaError`](crate::Error::IdnaError). A leading dot is
+    /// dropped. Control characters are not handled here; see
+    /// [`ControlPolicy::apply`].
+    pub fn normalize<'a>(&self, host: &'a str) -> Result<Cow<'a, str>, Error> {
+        let out = self.prepare(Cow::Borrowed(host));
+        #[cfg(feature = "idna")]
+        if let Some(ascii) = self.to_ascii(&out) {
+            return ascii.map(Cow::Owned);
+        }
+        Ok(out)
+    }
+
+    /// The steps that cannot fail: dropping a leading dot, the trailing dot
+    /// and lowercasing.
+    pub(crate) fn prepare<'a>(&self, host: Cow<'a, str>) -> Cow<'a, str> {
+        // Drop a single leading dot, then handle trailing dot.
+        let mut out: Cow<'a, str> = match host {
+            Cow::Borrowed(c) => {
+                let base = c.strip_prefix('.').unwrap_or(c);
+                match base.strip_suffix('.') {
+                    Some(stripped) if self.strip_trailing_dot => Cow::Borrowed(stripped),
+                    _ => Cow::Borrowed(base),
+                }
+            }
+            Cow::Owned(mut c) => {
+                if c.starts_with('.') {
+                    c.remove(0);
+                }
+                if self.strip_trailing_dot && c.ends_with('.') {
+                    c.pop();
+                }
+                Cow::Owned(c)
+            }
+        };
+
+        // Lowercase (allocate only if needed, and then into a reused buffer).
+        if self.lowercase && out.chars().any(|c| c.is_ascii_uppercase()) {
+            out = match out {
+                Cow::Owned(mut o) if o.is_ascii() => {
+                    o.make_ascii_lowercase();
+                    Cow::Owned(o)
+                }
+                other => {
+                    let lower = crate::scratch::to_lowercase(&other);
+                    crate::scratch::recycle(other);
+                    Cow::Owned(lower)
+                }
+            };
+        }
+        out
+    }
+
+    /// IDNA ASCII form of `host`, or `None` if conversion is off or `host`
+    /// is already ASCII.
+    #[cfg(feature = "idna")]
+    pub(crate) fn to_ascii(&self, host: &str) -> Option<Result<alloc::string::String, Error>> {
+        use crate::scratch;
+        use idna::uts46::{AsciiDenyList, ErrorPolicy, Hyphens, ProcessingSuccess, Uts46};
+
+        if !self.idna_ascii || host.is_ascii() {
+            return None;
+        }
+        let fail = || Error::IdnaError(alloc::format!("invalid internationalized host `{host}`"));
+        if !crate::engine::idna_candidate(host) {
+            return Some(Err(fail()));
+        }
+        let mapped: Cow<'_, str> = if self.idna_transitional {
+            let mut out = scratch::take();
+            for c in host.chars() {
+                match c {
+                    'ß' => out.push_str("ss"),
+                    'ς' => out.push('σ'),
+                    '\u{200c}' | '\u{200d}' => {}
+                    _ => out.push(c),
+                }
+            }
+            out.into()
+        } else {
+            Cow::Borrowed(host)
+        };
+        let deny = if self.idna_std3_rules {
+            AsciiDenyList::STD3
+        } else {
+            AsciiDenyList::EMPTY
+        };
+        let mut out = scratch::take();
+        let ascii = match Uts46::new().process(
+            mapped.as_bytes(),
+            deny,
+            Hyphens::Allow,
+            ErrorPolicy::FailFast,
+            |_, _, _| false,
+            &mut out,
+            None,
+        ) {
+            Ok(ProcessingSuccess::Passthrough) => {
+                out.clear();
+                out.push_str(&mapped);
+                Ok(out)
+            }
+            Ok(ProcessingSuccess::WroteToSink) => Ok(out),
+            Err(_) => {
+                scratch::recycle(Cow::Owned(out));
+                Err(fail())
+            }
+        };
+        scratch::recycle(mapped);
+        Some(ascii)
+    }
+}
+
+/// How hosts containing control characters (NUL, CR, LF, DEL, C1 controls)
+/// are treated before matching.
+///
+/// Such hosts are never valid DNS names; they usually come from untrusted
+/// input such as log lines or headers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ControlPolicy {
+    /// Reject the host: lookups return `None`.
+    #[default]
+    Reject,
+    /// Remove control characters and match what remains.
+    Strip,
+}
+
+impl ControlPolicy {
+    /// Applies the policy to `host`.
+    ///
+    /// Returns the host unchanged if it has no control characters, the
+    /// cleaned host under `Strip`, or the first offending character under
+    /// `Reject`.
+    pub fn apply(self, host: &str) -> Result<Cow<'_, str>, HostError> {
+        let Some((index, ch)) = host.char_indices().find(|(_, c)| c.is_control()) else {
+            return Ok(Cow::Borrowed(host));
+        };
+        match self {
+            ControlPolicy::Reject => Err(HostError::ControlChar { index, ch }),
+            ControlPolicy::Strip => Ok(Cow::Owned(
+                host.chars().filter(|c| !c.is_control()).collect(),
+            )),
+        }
+    }
+}
+
+/// How IP literals and `localhost` names are treated by lookups.
+///
+/// Neither has a public suffix: `192.168.0.1` would otherwise be split at
+/// its last octet, and `localhost` (RFC 6761) at its last label.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LiteralPolicy {
+    /// Match them like any other host (`192.168.0.1` → suffix `1`).
+    #[default]
+    Match,
+    /// Reject them: lookups return `None`.
+    Reject,
+    /// Treat the whole host as an unlisted public suffix
+    /// (`192.168.0.1` → suffix `192.168.0.1`), so it never shares a
+    /// registrable domain with another host.
+    Whole,
+}
+
+/// What lookups return for a host that no rule matches.
+///
+/// Wildcard and exception rules apply as usual; this only decides the
+/// answer when none of the rules does. `MatchOpts::strict` overrides it
+/// with `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FallbackPolicy {
+    /// The last label is the public suffix (`example.local` → `local`), as
+    /// browsers do for unlisted TLDs.
+    #[default]
+    LastLabel,
+    /// There is no public suffix: lookups return `None`.
+    None,
+    /// The whole host is the public suffix (`example.local` →
+    /// `example.local`), so it never shares a registrable domain with
+    /// another host.
+    EntireHost,
+}
+
+impl LiteralPolicy {
+    /// Returns true if `host` is an IPv4 literal, an IPv6 literal (bare or
+    /// in brackets), or `localhost` or a name under it.
+    pub fn is_literal(host: &str) -> bool {
+        let localhost = host
+            .rsplit('.')
+            .next()
+            .is_some_and(|tld| tld.eq_ignore_ascii_case("localhost"));
+        localhost || is_ip_literal(host)
+    }
+}
+
+/// Returns true if `host` is an IPv4 literal or an IPv6 literal (bare or in
+/// brackets); unlike [`LiteralPolicy::is_literal`], `localhost` is a name.
+pub(crate) fn is_ip_literal(host: &str) -> bool {
+    let v6 = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    host.parse::<Ipv4Addr>().is_ok() || v6.parse::<Ipv6Addr>().is_ok()
+}
+
+/// Which library's answers lookups reproduce where libraries disagree.
+///
+/// Public suffix libraries agree on how rules match but differ on a few
+/// edge cases. A mode other than `Ps2` pins those to the other library's
+/// behavior, so results can be compared bit for bit while migrating:
+///
+/// | Mode             | Leading dot | Trailing dot   | IP literal     | Unlisted TLD registrable |
+/// |------------------|-------------|----------------|----------------|--------------------------|
+/// | `Ps2`            | dropped     | per normalizer | per `literals` | the TLD (`local`)        |
+/// | `Libpsl`         | rejected    | rejected       | rejected       | label + TLD              |
+/// | `GoPublicsuffix` | rejected    | rejected       | matched        | label + TLD              |
+/// | `Rfc`            | rejected    | stripped       | rejected       | label + TLD              |
+///
+/// `Ps2` leaves those cases to the other fields of `MatchOpts`; the other
+/// modes override `literals` and the normalizer's dot handling. Case and
+/// IDNA mapping always follow the normalizer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CompatMode {
+    /// python-publicsuffix2: a leading dot is dropped, and an unlisted TLD
+    /// is its own registrable domain (`example.local` → `local`).
+    #[default]
+    Ps2,
+    /// libpsl's `psl_registrable_domain`.
+    Libpsl,
+    /// Go's `golang.org/x/net/publicsuffix`, which matches IP addresses as
+    /// names (`127.0.0.1` → suffix `1`).
+    GoPublicsuffix,
+    /// Host names as RFC 1034 reads them: a trailing dot marks an absolute
+    /// name and is dropped, and IP addresses are not domain names.
+    Rfc,
+}
+
+impl CompatMode {
+    /// How IP literals and `localhost` names are treated under this mode.
+    pub(crate) fn literals(self, policy: LiteralPolicy) -> LiteralPolicy {
+        match self {
+            CompatMode::Ps2 => policy,
+            CompatMode::GoPublicsuffix => LiteralPolicy::Match,
+            CompatMode::Libpsl | CompatMode::Rfc => LiteralPolicy::Reject,
+        }
+    }
+
+    /// Whether an unlisted TLD is its own registrable domain.
+    pub(crate) fn collapses_unlisted(self) -> bool {
+        self == CompatMode::Ps2
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -135,9 +512,19 @@ impl Normalizer {
 /// The lifetime `'n` ties the borrowed `Normalizer` to this struct.
 ///
 /// - `wildcard`: Enable PSL wildcard rules (e.g., `*.uk`). When false, only exact-label rules are considered and wildcard matches are ignored.
-/// - `strict`: Require a rule-derived suffix. If true and no rule matches (or the ruleset is empty), return `None` instead of falling back to “last label is the TLD”.
+/// - `strict`: Require a rule-derived suffix. If true and no rule matches (or the ruleset is empty), return `None` whatever `fallback` says.
 /// - `types`: Which PSL sections are eligible for matching (ICANN, Private, or Any).
 /// - `normalizer`: Optional borrowed normalizer applied to the input view (zero-copy tweaks like stripping a trailing dot). For lowercasing or IDNA mapping, preprocess in an owned buffer before matching and pass that string here.
+/// - `ignored_private`: Private-section suffixes to treat as if they were not listed (e.g., `github.io`), so hosts under them group by the parent ICANN suffix instead.
+/// - `controls`: Whether hosts containing control characters are rejected or cleaned before matching.
+/// - `match_both_forms`: Also try the other IDNA form (U-label or A-label) of each host label, for lists loaded without `a_label_duplicates`.
+/// - `literals`: Whether IP literals and `localhost` names are matched, rejected or returned whole.
+/// - `fallback`: What the public suffix is when no rule matches: the last label, none, or the whole host.
+/// - `deep_wildcards`: Let a wildcard match any number of labels, so unlisted names below a wildcard suffix are suffixes too (`a.b.c.ck` under `*.ck`).
+/// - `exceptions`: Honor exception rules (e.g., `!www.ck`). When false they are ignored, as if not listed.
+/// - `compat`: Reproduce another library's answers for leading and trailing dots, IP literals and unlisted TLDs; see [`CompatMode`].
+/// - `max_len`: Longest host accepted, in bytes as given. Longer hosts are rejected before any other work, so hostile input costs little.
+/// - `max_labels`: Most labels accepted in a host; hosts with more are rejected like those over `max_len`.
 pub struct MatchOpts<'n> {
     /// Enable PSL wildcard rules (e.g., `*.uk`).
     pub wildcard: bool,
@@ -147,6 +534,26 @@ pub struct MatchOpts<'n> {
     pub types: super::rules::TypeFilter,
     /// Optional borrowed normalizer applied to the input view.
     pub normalizer: Option<&'n Normalizer>,
+    /// Private-section suffixes to treat as if they were not listed.
+    pub ignored_private: &'n [&'n str],
+    /// Whether hosts containing control characters are rejected or cleaned.
+    pub controls: ControlPolicy,
+    /// Also try the other IDNA form of each host label (needs the `idna` feature).
+    pub match_both_forms: bool,
+    /// How IP literals and `localhost` names are treated.
+    pub literals: LiteralPolicy,
+    /// What the public suffix is when no rule matches.
+    pub fallback: FallbackPolicy,
+    /// Let a wildcard match any number of labels.
+    pub deep_wildcards: bool,
+    /// Honor exception rules.
+    pub exceptions: bool,
+    /// Which library's edge-case behavior to reproduce.
+    pub compat: CompatMode,
+    /// Longest host accepted, in bytes as given.
+    pub max_len: usize,
+    /// Most labels accepted in a host.
+    pub max_labels: usize,
 }
 impl Default for MatchOpts<'_> {
     /// Default implementation for `MatchOpts`:
@@ -154,12 +561,33 @@ impl Default for MatchOpts<'_> {
     /// - `strict` = false (allow non-strict fallback when rules are empty)
     /// - `types` = TypeFilter::Any (accept ICANN and Private sections)
     /// - `normalizer` = ``Some(&PS2_NORMALIZER)`` (use python-publicsuffix2-like normalization)
+    /// - `ignored_private` = empty (honor every private rule)
+    /// - `controls` = `ControlPolicy::Reject`
+    /// - `match_both_forms` = false
+    /// - `literals` = `LiteralPolicy::Match`
+    /// - `fallback` = `FallbackPolicy::LastLabel`
+    /// - `deep_wildcards` = false
+    /// - `exceptions` = true
+    /// - `compat` = `CompatMode::Ps2`
+    /// - `max_len` = 1024 (well over the 253-octet DNS limit, which a valid
+    ///   Unicode host can exceed in UTF-8)
+    /// - `max_labels` = 127 (the most labels a DNS name can have)
     fn default() -> Self {
         Self {
             wildcard: true,
             strict: false,
             types: super::rules::TypeFilter::Any,
             normalizer: Some(&PS2_NORMALIZER),
+            ignored_private: &[],
+            controls: ControlPolicy::Reject,
+            match_both_forms: false,
+            literals: LiteralPolicy::Match,
+            fallback: FallbackPolicy::LastLabel,
+            deep_wildcards: false,
+            exceptions: true,
+            compat: CompatMode::Ps2,
+            max_len: 1024,
+            max_labels: 127,
         }
     }
 }
@@ -185,4 +613,49 @@ impl<'n> MatchOpts<'n> {
             ..Self::default()
         }
     }
+
+    /// Returns true if `host` is within `max_len` and `max_labels`.
+    #[inline]
+    pub(crate) fn within_limits(&self, host: &str) -> bool {
+        // A host has at most one label more than it has bytes.
+        host.len() <= self.max_len
+            && (host.len() < self.max_labels
+                || host.bytes().filter(|&b| b == b'.').count() < self.max_labels)
+    }
+
+    /// Treat the given private suffixes as if they were not listed.
+    ///
+    /// Suffixes are compared case-insensitively against the suffix a private
+    /// rule matched in the host, so `github.io` disables that rule and
+    /// `x.github.io` falls back to the `io` suffix. ICANN rules are never
+    /// ignored. Pass [`PLATFORM_SUFFIXES`] for a curated set of hosting
+    /// platforms.
+    pub fn ignoring_private(self, suffixes: &'n [&'n str]) -> Self {
+        Self {
+            ignored_private: suffixes,
+            ..self
+        }
+    }
 }
+
+/// Private suffixes of large hosting platforms that put unrelated tenants
+/// under one shared domain.
+///
+/// Tools that group hosts by registrable domain for reputation or abuse
+/// tracking often want these collapsed into the platform's own domain; see
+/// [`MatchOpts::ignoring_private`].
+pub const PLATFORM_SUFFIXES: &[&str] = &[
+    "appspot.com",
+    "azurewebsites.net",
+    "blogspot.com",
+    "cloudfront.net",
+    "firebaseapp.com",
+    "github.io",
+    "gitlab.io",
+    "herokuapp.com",
+    "netlify.app",
+    "pages.dev",
+    "vercel.app",
+    "web.app",
+    "workers.dev",
+];
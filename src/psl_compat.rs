@@ -0,0 +1,96 @@
+//! [`psl_types::List`] for [`List`], so a `List` can stand in for the lists
+//! of the `psl` and `publicsuffix` crates.
+//!
+//! Code written against those crates takes any `L: psl_types::List` and
+//! calls its `suffix` and `domain` methods on byte strings. Like them, the
+//! lookup here is on the host as given: no lowercasing or IDNA conversion,
+//! which `psl_types` leaves to the caller. Hosts no rule matches get their
+//! last label as suffix, with no type; so do hosts matched only by a rule
+//! loaded outside the ICANN and private sections, as `psl_types::Type` has
+//! nothing else to report.
+//!
+//! The inherent [`List::domain`] takes precedence over the trait method in
+//! method-call syntax; call it as `psl_types::List::domain(&list, name)`.
+use crate::options::MatchOpts;
+use crate::rules::Type;
+use crate::{List, RuleKind};
+use alloc::vec::Vec;
+use psl_types::Info;
+
+impl psl_types::List for List {
+    fn find<'a, T>(&self, labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        let none = Info { len: 0, typ: None };
+        let Ok(mut labels) = labels
+            .map(core::str::from_utf8)
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return none;
+        };
+        labels.reverse();
+        let host = labels.join(".");
+        let raw = MatchOpts {
+            normalizer: None,
+            ..MatchOpts::default()
+        };
+        let Some(info) = self.match_info(&host, raw) else {
+            return none;
+        };
+        let typ = match (info.kind, info.typ) {
+            (RuleKind::Unlisted, _) | (_, None) => None,
+            (_, Some(Type::Icann)) => Some(psl_types::Type::Icann),
+            (_, Some(Type::Private)) => Some(psl_types::Type::Private),
+        };
+        Info {
+            len: info.suffix.len(),
+            typ,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::List;
+    use psl_types::{List as Psl, Type};
+
+    fn list() -> List {
+        List::parse(
+            "// ===BEGIN ICANN DOMAINS===\ncom\nuk\nco.uk\n*.ck\n!www.ck\n\
+             // ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\n\
+             github.io\n// ===END PRIVATE DOMAINS===\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn suffixes_and_domains() {
+        let list = list();
+        let domain = Psl::domain(&list, b"www.example.co.uk").unwrap();
+        assert_eq!(domain, "example.co.uk");
+        assert_eq!(domain.suffix(), "co.uk");
+        assert_eq!(domain.suffix().typ(), Some(Type::Icann));
+
+        let suffix = list.suffix(b"user.github.io").unwrap();
+        assert_eq!(suffix, "github.io");
+        assert_eq!(suffix.typ(), Some(Type::Private));
+        assert_eq!(list.suffix(b"a.b.ck").unwrap(), "b.ck");
+        assert_eq!(list.suffix(b"www.ck").unwrap(), "ck");
+        assert_eq!(Psl::domain(&list, b"com"), None);
+        assert_eq!(list.suffix(b""), None);
+    }
+
+    #[test]
+    fn unlisted_and_fully_qualified_hosts() {
+        let list = list();
+        let suffix = list.suffix(b"example.local").unwrap();
+        assert_eq!(suffix, "local");
+        assert!(!suffix.is_known());
+
+        let domain = Psl::domain(&list, b"www.example.com.").unwrap();
+        assert_eq!(domain.as_bytes(), b"example.com.");
+        assert!(domain.suffix().is_fqdn());
+        assert_eq!(list.suffix(b"\xff.com").map(|s| s.as_bytes()), None);
+    }
+}
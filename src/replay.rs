@@ -0,0 +1,795 @@
+//! Record/replay of lookups for regression checks.
+//!
+//! A [`Recorder`] logs every lookup it performs as a compact, line-oriented
+//! record of `(normalized host, options fingerprint, result)`. The log can
+//! later be fed to [`replay`] together with another `List` (a newer PSL
+//! snapshot, or the same list under a newer crate version) to produce a
+//! [`Report`] of every lookup whose result changed.
+//!
+//! A log starts with the header line of [`Format::ReplayLog`], followed by
+//! one record per line, tab-separated:
+//!
+//! ```text
+//! <fingerprint> \t <host> \t <prefix> \t <sll> \t <sld> \t <tld>
+//! ```
+//!
+//! Optional fields are written as `-` for `None` and `=value` for `Some`.
+//! A lookup that returned `None` is written with all four result fields set
+//! to `-`. Backslash, tab, CR and LF inside values are escaped. Logs
+//! without a header are read as version 1.
+//!
+//! The fingerprint records every option of the lookup; see [`fingerprint`].
+//! Version 1 fingerprints left most options out, so version 1 logs can be
+//! read with [`read_records`] but not replayed.
+use crate::engine::{normalize_view, Parts};
+use crate::errors::{Error, Result};
+use crate::format::{Format, FormatVersion};
+use crate::options::{
+    CompatMode, ControlPolicy, FallbackPolicy, LiteralPolicy, MatchOpts, Normalizer,
+};
+use crate::rules::TypeFilter;
+use crate::List;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// Records lookups performed against a `List` into a writer.
+pub struct Recorder<W: Write> {
+    out: W,
+    count: usize,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a recorder writing records to `out`.
+    pub fn new(out: W) -> Self {
+        Self { out, count: 0 }
+    }
+
+    /// Performs `list.split(host, opts)`, records the lookup and returns its result.
+    pub fn split<'a>(
+        &mut self,
+        list: &List,
+        host: &'a str,
+        opts: MatchOpts<'_>,
+    ) -> Result<Option<Parts<'a>>> {
+        if self.count == 0 {
+            writeln!(self.out, "{}", Format::ReplayLog.text_header()).map_err(Error::Io)?;
+        }
+        let parts = list.split(host, opts);
+        let normalized = normalize_view(host, opts).unwrap_or(Cow::Borrowed(host));
+        let line = encode(&fingerprint(opts), &normalized, parts.as_ref());
+        self.out.write_all(line.as_bytes()).map_err(Error::Io)?;
+        self.count += 1;
+        Ok(parts)
+    }
+
+    /// Number of lookups recorded so far.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Flushes the underlying writer and returns it.
+    pub fn finish(mut self) -> Result<W> {
+        self.out.flush().map_err(Error::Io)?;
+        Ok(self.out)
+    }
+}
+
+/// A single recorded lookup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// Host as seen by the matcher (after normalization).
+    pub host: String,
+    /// Compact fingerprint of the `MatchOpts` used for the lookup.
+    pub fingerprint: String,
+    /// Result observed when the record was written.
+    pub result: Option<Parts<'static>>,
+}
+
+/// A lookup whose result differs between the recording and the replay.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regression {
+    /// 1-based line number of the record in the log.
+    pub line: usize,
+    /// The recorded lookup.
+    pub record: Record,
+    /// Result produced by the list being replayed against.
+    pub actual: Option<Parts<'static>>,
+}
+
+/// Outcome of replaying a record log.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Number of records evaluated.
+    pub total: usize,
+    /// Records whose result changed.
+    pub regressions: Vec<Regression>,
+}
+
+impl Report {
+    /// Returns true if every replayed lookup produced its recorded result.
+    pub fn is_clean(&self) -> bool {
+        self.regressions.is_empty()
+    }
+}
+
+/// Reads every record from a log produced by a [`Recorder`].
+///
+/// Blank lines are skipped. Returns `Error::InvalidRecord` for malformed lines
+/// and `Error::UnsupportedFormatVersion` for logs written in a newer format.
+pub fn read_records<R: BufRead>(input: R) -> Result<Vec<Record>> {
+    let mut out = Vec::new();
+    for_each_record(input, |_, _, record| {
+        out.push(record);
+        Ok(())
+    })?;
+    Ok(out)
+}
+
+/// Re-evaluates a record log against `list` and reports every changed result.
+///
+/// Each lookup runs with the options decoded from its fingerprint. Returns
+/// `Error::InvalidRecord` for a fingerprint that does not describe every
+/// option, and `Error::UnsupportedFormatVersion` for version 1 logs, whose
+/// fingerprints never did.
+pub fn replay<R: BufRead>(list: &List, input: R) -> Result<Report> {
+    let mut report = Report::default();
+    for_each_record(input, |line, version, record| {
+        if version < FormatVersion(2) {
+            return Err(Error::UnsupportedFormatVersion {
+                format: Format::ReplayLog.name(),
+                found: format!("{} {version}", Format::ReplayLog.name()),
+            });
+        }
+        let spec = parse_fingerprint(&record.fingerprint).ok_or(Error::InvalidRecord { line })?;
+        let ignored_private = spec.ignored_private();
+        let opts = spec.opts(&ignored_private);
+        let actual = list.split(&record.host, opts).map(Parts::into_owned);
+        report.total += 1;
+        if actual != record.result {
+            report.regressions.push(Regression {
+                line,
+                record,
+                actual,
+            });
+        }
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+/// Checks the log header, then calls `f` with the 1-based line number, the
+/// log's format version and the decoded record of every non-blank line.
+fn for_each_record<R: BufRead>(
+    input: R,
+    mut f: impl FnMut(usize, FormatVersion, Record) -> Result<()>,
+) -> Result<()> {
+    let mut version = FormatVersion(1);
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(Error::Io)?;
+        if line.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            if let Some(v) = Format::ReplayLog.check_text_header(&line)? {
+                version = v;
+                continue;
+            }
+        }
+        let record = decode(&line).ok_or(Error::InvalidRecord { line: i + 1 })?;
+        f(i + 1, version, record)?;
+    }
+    Ok(())
+}
+
+/// Tag opening every fingerprint written by this version of the format.
+const FINGERPRINT_TAG: &str = "v2";
+
+/// Compact, reversible fingerprint of a `MatchOpts` value.
+///
+/// The [`FINGERPRINT_TAG`] `v2` followed by one `;key=value` field per
+/// option:
+///
+/// - `w`, `s`: `wildcard` and `strict`, as `0` or `1`;
+/// - `t`: `types`, one of `A`, `I`, `P`, `K`, `i`, `p`, `U`;
+/// - `n`: `-` without a normalizer, otherwise its flags as `0`/`1` digits
+///   (lowercase, strip dot, IDNA, transitional, STD3 rules, reject errors);
+/// - `ip`: `ignored_private`, each suffix followed by `,`, with `%`, `,`,
+///   `;`, `=`, `\`, tab, CR and LF percent-encoded;
+/// - `c`: `controls`, `R` (reject) or `S` (strip);
+/// - `b`: `match_both_forms`, as `0` or `1`;
+/// - `l`: `literals`, `M` (match), `R` (reject) or `W` (whole);
+/// - `f`: `fallback`, `L` (last label), `N` (none) or `E` (entire host);
+/// - `d`, `x`: `deep_wildcards` and `exceptions`, as `0` or `1`;
+/// - `m`: `compat`, `P` (ps2), `L` (libpsl), `G` (Go publicsuffix) or `R`
+///   (RFC);
+/// - `len`, `lab`: `max_len` and `max_labels`, in decimal.
+///
+/// [`replay`] only accepts fingerprints with every field, so a lookup is
+/// never replayed under options it was not recorded with.
+pub fn fingerprint(opts: MatchOpts<'_>) -> String {
+    let flag = |b: bool| if b { "1" } else { "0" };
+    let t = match opts.types {
+        TypeFilter::Any => "A",
+        TypeFilter::Icann => "I",
+        TypeFilter::Private => "P",
+        TypeFilter::Known => "K",
+        TypeFilter::IcannOrUnclassified => "i",
+        TypeFilter::PrivateOrUnclassified => "p",
+        TypeFilter::Unclassified => "U",
+    };
+    let n = match opts.normalizer {
+        None => "-".to_string(),
+        Some(n) => [
+            n.lowercase,
+            n.strip_trailing_dot,
+            n.idna_ascii,
+            n.idna_transitional,
+            n.idna_std3_rules,
+            n.idna_reject_errors,
+        ]
+        .into_iter()
+        .map(flag)
+        .collect(),
+    };
+    let mut ip = String::new();
+    for suffix in opts.ignored_private {
+        escape_list_item(&mut ip, suffix);
+        ip.push(',');
+    }
+    let c = match opts.controls {
+        ControlPolicy::Reject => "R",
+        ControlPolicy::Strip => "S",
+    };
+    let l = match opts.literals {
+        LiteralPolicy::Match => "M",
+        LiteralPolicy::Reject => "R",
+        LiteralPolicy::Whole => "W",
+    };
+    let f = match opts.fallback {
+        FallbackPolicy::LastLabel => "L",
+        FallbackPolicy::None => "N",
+        FallbackPolicy::EntireHost => "E",
+    };
+    let m = match opts.compat {
+        CompatMode::Ps2 => "P",
+        CompatMode::Libpsl => "L",
+        CompatMode::GoPublicsuffix => "G",
+        CompatMode::Rfc => "R",
+    };
+    let len = opts.max_len.to_string();
+    let lab = opts.max_labels.to_string();
+    let mut fp = String::from(FINGERPRINT_TAG);
+    for (key, value) in [
+        ("w", flag(opts.wildcard)),
+        ("s", flag(opts.strict)),
+        ("t", t),
+        ("n", &n),
+        ("ip", &ip),
+        ("c", c),
+        ("b", flag(opts.match_both_forms)),
+        ("l", l),
+        ("f", f),
+        ("d", flag(opts.deep_wildcards)),
+        ("x", flag(opts.exceptions)),
+        ("m", m),
+        ("len", &len),
+        ("lab", &lab),
+    ] {
+        fp.push(';');
+        fp.push_str(key);
+        fp.push('=');
+        fp.push_str(value);
+    }
+    fp
+}
+
+/// The options of a fingerprint, owning what `MatchOpts` borrows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ReplayOpts {
+    wildcard: bool,
+    strict: bool,
+    types: TypeFilter,
+    normalizer: Option<Normalizer>,
+    ignored_private: Vec<String>,
+    controls: ControlPolicy,
+    match_both_forms: bool,
+    literals: LiteralPolicy,
+    fallback: FallbackPolicy,
+    deep_wildcards: bool,
+    exceptions: bool,
+    compat: CompatMode,
+    max_len: usize,
+    max_labels: usize,
+}
+
+impl ReplayOpts {
+    /// The options, borrowing `ignored_private` as a slice of
+    /// `self.ignored_private`.
+    fn opts<'a>(&'a self, ignored_private: &'a [&'a str]) -> MatchOpts<'a> {
+        MatchOpts {
+            wildcard: self.wildcard,
+            strict: self.strict,
+            types: self.types,
+            normalizer: self.normalizer.as_ref(),
+            ignored_private,
+            controls: self.controls,
+            match_both_forms: self.match_both_forms,
+            literals: self.literals,
+            fallback: self.fallback,
+            deep_wildcards: self.deep_wildcards,
+            exceptions: self.exceptions,
+            compat: self.compat,
+            max_len: self.max_len,
+            max_labels: self.max_labels,
+        }
+    }
+
+    /// `ignored_private` as the slice [`opts`](Self::opts) takes.
+    fn ignored_private(&self) -> Vec<&str> {
+        self.ignored_private.iter().map(String::as_str).collect()
+    }
+}
+
+/// Decodes a [`fingerprint`]. `None` unless it has the current tag and
+/// every field exactly once, with nothing else.
+fn parse_fingerprint(s: &str) -> Option<ReplayOpts> {
+    let mut fields = s.split(';');
+    if fields.next()? != FINGERPRINT_TAG {
+        return None;
+    }
+    let mut values = HashMap::new();
+    for field in fields {
+        let (key, value) = field.split_once('=')?;
+        if values.insert(key, value).is_some() {
+            return None;
+        }
+    }
+    let mut take = |key: &str| values.remove(key);
+    let flag = |v: &str| flags(v).map(|[b]| b);
+    let types = match take("t")? {
+        "A" => TypeFilter::Any,
+        "I" => TypeFilter::Icann,
+        "P" => TypeFilter::Private,
+        "K" => TypeFilter::Known,
+        "i" => TypeFilter::IcannOrUnclassified,
+        "p" => TypeFilter::PrivateOrUnclassified,
+        "U" => TypeFilter::Unclassified,
+        _ => return None,
+    };
+    let normalizer = match take("n")? {
+        "-" => None,
+        n => {
+            let [lowercase, strip_trailing_dot, idna_ascii, idna_transitional, idna_std3_rules, idna_reject_errors] =
+                flags(n)?;
+            Some(Normalizer {
+                lowercase,
+                strip_trailing_dot,
+                idna_ascii,
+                idna_transitional,
+                idna_std3_rules,
+                idna_reject_errors,
+            })
+        }
+    };
+    let ip = take("ip")?;
+    if !ip.is_empty() && !ip.ends_with(',') {
+        return None;
+    }
+    let ignored_private = ip
+        .split_terminator(',')
+        .map(unescape_list_item)
+        .collect::<Option<_>>()?;
+    let controls = match take("c")? {
+        "R" => ControlPolicy::Reject,
+        "S" => ControlPolicy::Strip,
+        _ => return None,
+    };
+    let literals = match take("l")? {
+        "M" => LiteralPolicy::Match,
+        "R" => LiteralPolicy::Reject,
+        "W" => LiteralPolicy::Whole,
+        _ => return None,
+    };
+    let fallback = match take("f")? {
+        "L" => FallbackPolicy::LastLabel,
+        "N" => FallbackPolicy::None,
+        "E" => FallbackPolicy::EntireHost,
+        _ => return None,
+    };
+    let compat = match take("m")? {
+        "P" => CompatMode::Ps2,
+        "L" => CompatMode::Libpsl,
+        "G" => CompatMode::GoPublicsuffix,
+        "R" => CompatMode::Rfc,
+        _ => return None,
+    };
+    let spec = ReplayOpts {
+        wildcard: flag(take("w")?)?,
+        strict: flag(take("s")?)?,
+        types,
+        normalizer,
+        ignored_private,
+        controls,
+        match_both_forms: flag(take("b")?)?,
+        literals,
+        fallback,
+        deep_wildcards: flag(take("d")?)?,
+        exceptions: flag(take("x")?)?,
+        compat,
+        max_len: count(take("len")?)?,
+        max_labels: count(take("lab")?)?,
+    };
+    values.is_empty().then_some(spec)
+}
+
+/// Appends `item` to a fingerprint list, percent-encoding the characters
+/// that delimit fields and list items or would break the log line.
+fn escape_list_item(out: &mut String, item: &str) {
+    for c in item.chars() {
+        match c {
+            '%' | ',' | ';' | '=' | '\\' | '\t' | '\r' | '\n' => {
+                out.push_str(&format!("%{:02X}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+/// Reverses [`escape_list_item`], rejecting any escape it does not write.
+fn unescape_list_item(item: &str) -> Option<String> {
+    let mut out = String::with_capacity(item.len());
+    let mut rest = item;
+    while let Some(i) = rest.find('%') {
+        out.push_str(&rest[..i]);
+        out.push(match rest.get(i + 1..i + 3)? {
+            "25" => '%',
+            "2C" => ',',
+            "3B" => ';',
+            "3D" => '=',
+            "5C" => '\\',
+            "09" => '\t',
+            "0D" => '\r',
+            "0A" => '\n',
+            _ => return None,
+        });
+        rest = &rest[i + 3..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+/// Decodes a count written in decimal, as [`fingerprint`] writes it.
+fn count(v: &str) -> Option<usize> {
+    let n: usize = v.parse().ok()?;
+    (n.to_string() == v).then_some(n)
+}
+
+/// Decodes `N` flags written as `0`/`1` digits.
+fn flags<const N: usize>(v: &str) -> Option<[bool; N]> {
+    let digits = v.as_bytes();
+    if digits.len() != N {
+        return None;
+    }
+    let mut out = [false; N];
+    for (flag, digit) in out.iter_mut().zip(digits) {
+        *flag = match digit {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+    }
+    Some(out)
+}
+
+fn encode(fp: &str, host: &str, parts: Option<&Parts<'_>>) -> String {
+    let mut line = String::with_capacity(fp.len() + host.len() * 3 + 16);
+    line.push_str(fp);
+    line.push('\t');
+    escape_into(&mut line, host);
+    let fields: [Option<&str>; 4] = match parts {
+        Some(p) => [
+            p.prefix.as_deref(),
+            p.sll.as_deref(),
+            p.sld.as_deref(),
+            Some(&p.tld),
+        ],
+        None => [None; 4],
+    };
+    for f in fields {
+        line.push('\t');
+        match f {
+            Some(v) => {
+                line.push('=');
+                escape_into(&mut line, v);
+            }
+            None => line.push('-'),
+        }
+    }
+    line.push('\n');
+    line
+}
+
+fn decode(line: &str) -> Option<Record> {
+    let mut it = line.split('\t');
+    let fingerprint = it.next()?.to_string();
+    let host = unescape(it.next()?)?;
+    let mut fields = [None, None, None, None];
+    for f in fields.iter_mut() {
+        *f = match it.next()? {
+            "-" => None,
+            v => Some(unescape(v.strip_prefix('=')?)?),
+        };
+    }
+    if it.next().is_some() {
+        return None;
+    }
+    let [prefix, sll, sld, tld] = fields;
+    let result = match tld {
+        Some(tld) => Some(Parts {
+            prefix: prefix.map(Cow::Owned),
+            sll: sll.map(Cow::Owned),
+            sld: sld.map(Cow::Owned),
+            tld: Cow::Owned(tld),
+        }),
+        None if prefix.is_none() && sll.is_none() && sld.is_none() => None,
+        None => return None,
+    };
+    Some(Record {
+        host,
+        fingerprint,
+        result,
+    })
+}
+
+fn escape_into(out: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::PLATFORM_SUFFIXES;
+
+    /// Asserts that `opts` survives a fingerprint round trip.
+    fn roundtrips(opts: MatchOpts<'_>) {
+        let fp = fingerprint(opts);
+        let spec = parse_fingerprint(&fp).unwrap_or_else(|| panic!("{fp}"));
+        assert_eq!(fingerprint(spec.opts(&spec.ignored_private())), fp);
+    }
+
+    #[test]
+    fn fingerprint_roundtrips() {
+        let norm = Normalizer::lowercase_only();
+        let opts = MatchOpts {
+            wildcard: false,
+            strict: true,
+            types: TypeFilter::Private,
+            normalizer: Some(&norm),
+            ..MatchOpts::default()
+        };
+        let fp = fingerprint(opts);
+        assert_eq!(
+            fp,
+            "v2;w=0;s=1;t=P;n=100000;ip=;c=R;b=0;l=M;f=L;d=0;x=1;m=P;len=1024;lab=127"
+        );
+        let spec = parse_fingerprint(&fp).unwrap();
+        assert!(!spec.wildcard && spec.strict);
+        assert_eq!(spec.types, TypeFilter::Private);
+        assert_eq!(spec.normalizer, Some(norm.clone()));
+
+        assert_eq!(
+            fingerprint(MatchOpts::raw()),
+            "v2;w=1;s=0;t=A;n=-;ip=;c=R;b=0;l=M;f=L;d=0;x=1;m=P;len=1024;lab=127"
+        );
+        assert_eq!(
+            parse_fingerprint(
+                "v2;w=1;s=0;t=A;n=-;ip=;c=R;b=0;l=M;f=L;d=0;x=1;m=P;len=1024;lab=127"
+            )
+            .unwrap()
+            .normalizer,
+            None
+        );
+
+        for types in [
+            TypeFilter::Any,
+            TypeFilter::Icann,
+            TypeFilter::Private,
+            TypeFilter::Known,
+            TypeFilter::IcannOrUnclassified,
+            TypeFilter::PrivateOrUnclassified,
+            TypeFilter::Unclassified,
+        ] {
+            let fp = fingerprint(MatchOpts { types, ..opts });
+            assert_eq!(parse_fingerprint(&fp).unwrap().types, types);
+        }
+        roundtrips(MatchOpts::default());
+        roundtrips(opts);
+
+        let ignored = ["github.io", "a,b;c=d%e", "tab\there", ""];
+        let fp = fingerprint(MatchOpts {
+            ignored_private: &ignored,
+            ..opts
+        });
+        assert!(
+            fp.contains(";ip=github.io,a%2Cb%3Bc%3Dd%25e,tab%09here,,;"),
+            "{fp}"
+        );
+        assert_eq!(parse_fingerprint(&fp).unwrap().ignored_private, ignored);
+        roundtrips(MatchOpts::default().ignoring_private(PLATFORM_SUFFIXES));
+
+        let strip = MatchOpts {
+            controls: ControlPolicy::Strip,
+            ..opts
+        };
+        assert_eq!(
+            parse_fingerprint(&fingerprint(strip)).unwrap().controls,
+            ControlPolicy::Strip
+        );
+        roundtrips(strip);
+
+        let both = MatchOpts {
+            match_both_forms: true,
+            ..opts
+        };
+        assert!(
+            parse_fingerprint(&fingerprint(both))
+                .unwrap()
+                .match_both_forms
+        );
+        roundtrips(both);
+
+        for literals in [
+            LiteralPolicy::Match,
+            LiteralPolicy::Reject,
+            LiteralPolicy::Whole,
+        ] {
+            let fp = fingerprint(MatchOpts { literals, ..opts });
+            assert_eq!(parse_fingerprint(&fp).unwrap().literals, literals);
+            roundtrips(MatchOpts { literals, ..opts });
+        }
+
+        let idna = Normalizer {
+            idna_transitional: true,
+            idna_std3_rules: true,
+            idna_reject_errors: true,
+            ..Normalizer::ps2()
+        };
+        let fp = fingerprint(MatchOpts::with_normalizer(&idna));
+        assert_eq!(
+            parse_fingerprint(&fp).unwrap().normalizer,
+            Some(idna.clone())
+        );
+        roundtrips(MatchOpts::with_normalizer(&idna));
+
+        for fallback in [
+            FallbackPolicy::LastLabel,
+            FallbackPolicy::None,
+            FallbackPolicy::EntireHost,
+        ] {
+            let fp = fingerprint(MatchOpts { fallback, ..opts });
+            assert_eq!(parse_fingerprint(&fp).unwrap().fallback, fallback);
+            roundtrips(MatchOpts { fallback, ..opts });
+        }
+
+        let deep = MatchOpts {
+            deep_wildcards: true,
+            exceptions: false,
+            ..opts
+        };
+        let spec = parse_fingerprint(&fingerprint(deep)).unwrap();
+        assert!(spec.deep_wildcards && !spec.exceptions);
+        roundtrips(deep);
+
+        for compat in [
+            CompatMode::Ps2,
+            CompatMode::Libpsl,
+            CompatMode::GoPublicsuffix,
+            CompatMode::Rfc,
+        ] {
+            let fp = fingerprint(MatchOpts { compat, ..opts });
+            assert_eq!(parse_fingerprint(&fp).unwrap().compat, compat);
+            roundtrips(MatchOpts { compat, ..opts });
+        }
+
+        let limits = MatchOpts {
+            max_len: 0,
+            max_labels: usize::MAX,
+            ..opts
+        };
+        let spec = parse_fingerprint(&fingerprint(limits)).unwrap();
+        assert_eq!((spec.max_len, spec.max_labels), (0, usize::MAX));
+        roundtrips(limits);
+    }
+
+    #[test]
+    fn incomplete_fingerprints_are_rejected() {
+        let fp = fingerprint(MatchOpts::default());
+        assert!(parse_fingerprint(&fp).is_some());
+        for bad in [
+            "garbage".to_string(),
+            "w1s0tAn110".to_string(),
+            fp.replacen("v2", "v3", 1),
+            fp.replacen(";w=1", "", 1),
+            fp.replacen(";w=1", ";w=1;w=1", 1),
+            fp.replacen(";w=1", ";w=2", 1),
+            format!("{fp};z=1"),
+            format!("{fp};"),
+            fp.replacen(";ip=", ";ip=a.com", 1),
+            fp.replacen(";ip=", ";ip=a%2.com,", 1),
+            fp.replacen(";ip=", ";ip=a%41.com,", 1),
+            fp.replacen(";ip=", ";ip=a%2c.com,", 1),
+            fp.replacen(";ip=", ";ip=caf%E9.com,", 1),
+            fp.replacen(";c=R", ";c=X", 1),
+            fp.replacen(";b=0", ";b=", 1),
+            fp.replacen(";l=M", ";l=m", 1),
+            fp.replacen("000;ip=", ";ip=", 1),
+            fp.replacen(";f=L", ";f=LL", 1),
+            fp.replacen(";x=1", "", 1),
+            fp.replacen(";m=P", ";m=p", 1),
+            fp.replacen(";len=1024", ";len=+1024", 1),
+            fp.replacen(";len=1024", ";len=01024", 1),
+            fp.replacen(";lab=127", ";lab=-1", 1),
+        ] {
+            assert!(parse_fingerprint(&bad).is_none(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn encode_decode_roundtrip_with_escapes() {
+        let parts = Parts {
+            prefix: Some("a\tb".into()),
+            sll: None,
+            sld: Some("x\\y".into()),
+            tld: "com".into(),
+        };
+        let line = encode("v2;w=1;s=0;t=A;n=-", "a\tb.x\\y.com", Some(&parts));
+        let rec = decode(line.trim_end_matches('\n')).unwrap();
+        assert_eq!(rec.host, "a\tb.x\\y.com");
+        assert_eq!(rec.result, Some(parts));
+
+        let line = encode("v2;w=1;s=0;t=A;n=-", "", None);
+        let rec = decode(line.trim_end_matches('\n')).unwrap();
+        assert_eq!(rec.result, None);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_lines() {
+        assert!(decode("w1s0tAn-\thost").is_none());
+        assert!(decode("w1s0tAn-\thost\t-\t-\t=x\t-").is_none());
+        assert!(decode("w1s0tAn-\thost\tx\t-\t-\t=com").is_none());
+        assert!(decode("w1s0tAn-\th\\q\t-\t-\t-\t-").is_none());
+    }
+}
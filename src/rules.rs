@@ -1,7 +1,13 @@
-use hashbrown::HashMap;
+use crate::errors::Warning;
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt;
+use core::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use hashbrown::{DefaultHashBuilder, HashMap, HashTable};
 
 /// PSL rule section classification.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Type {
     /// Rules curated by ICANN.
     Icann,
@@ -9,19 +15,89 @@ pub enum Type {
     Private,
 }
 
+impl Type {
+    /// Short human-readable name of the section, e.g. `"ICANN section"`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Type::Icann => "ICANN section",
+            Type::Private => "private section",
+        }
+    }
+}
+
 /// Filter applied at match time to restrict which sections are eligible.
+///
+/// A rule with no section is *unclassified*: it comes from a list without
+/// `===BEGIN ICANN DOMAINS===`/`===BEGIN PRIVATE DOMAINS===` markers (as
+/// many custom lists are) or from outside them. Only the filters that say
+/// so accept unclassified rules. The unlisted-TLD fallback has no rule, so
+/// no filter excludes it.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum TypeFilter {
-    /// Allow rules from any section (ICANN and Private).
+    /// Allow every rule: ICANN, private and unclassified.
     Any,
     /// Allow only ICANN rules.
     Icann,
     /// Allow only Private rules.
     Private,
+    /// Allow ICANN and private rules, but not unclassified ones.
+    Known,
+    /// Allow ICANN and unclassified rules, but not private ones.
+    IcannOrUnclassified,
+    /// Allow private and unclassified rules, but not ICANN ones.
+    PrivateOrUnclassified,
+    /// Allow only unclassified rules.
+    Unclassified,
+}
+
+impl TypeFilter {
+    /// Returns true if a rule of section `typ` (`None` when unclassified)
+    /// passes the filter.
+    pub fn accepts(self, typ: Option<Type>) -> bool {
+        match typ {
+            Some(Type::Icann) => matches!(
+                self,
+                TypeFilter::Any
+                    | TypeFilter::Icann
+                    | TypeFilter::Known
+                    | TypeFilter::IcannOrUnclassified
+            ),
+            Some(Type::Private) => matches!(
+                self,
+                TypeFilter::Any
+                    | TypeFilter::Private
+                    | TypeFilter::Known
+                    | TypeFilter::PrivateOrUnclassified
+            ),
+            None => matches!(
+                self,
+                TypeFilter::Any
+                    | TypeFilter::IcannOrUnclassified
+                    | TypeFilter::PrivateOrUnclassified
+                    | TypeFilter::Unclassified
+            ),
+        }
+    }
+}
+
+/// Storage engine backing a compiled `List`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Engine {
+    /// Per-node `HashMap` trie built by the loader. Fastest to build.
+    Trie,
+    /// Compact trie in contiguous buffers with sorted children.
+    /// Several times smaller than `Trie`, at a small lookup cost.
+    Flat,
+    /// Minimal finite-state transducer over the reversed rules, in one
+    /// buffer. About a third of the size of `Flat`, with slower lookups.
+    /// Requires the `fst` feature; `Flat` is used without it.
+    Compact,
 }
 
 /// Marker placed on a trie node indicating how the label path acts as a rule.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Leaf {
     /// This path is not a rule; traversal may continue to children.
     #[default]
@@ -37,9 +113,10 @@ pub enum Leaf {
 
 /// Node in the reverse-label trie used to match PSL rules.
 ///
-/// Children are keyed by label strings as they appear in the list
-/// (including "*" for wildcard entries). The trie is traversed from the
-/// rightmost label of an input host toward the left.
+/// Children are keyed by the [`LabelId`] of their label as it appears in
+/// the list (including "*" for wildcard entries), interned in the
+/// [`RuleSet`]'s [`Labels`]. The trie is traversed from the rightmost label
+/// of an input host toward the left.
 #[derive(Default, Clone, Debug)]
 pub struct Node {
     /// Whether this node represents a rule and of what kind.
@@ -47,15 +124,466 @@ pub struct Node {
     /// Optional section classification for this node’s rule.
     pub typ: Option<Type>,
     /// Child labels reachable from this node.
-    pub kids: HashMap<String, Node>,
+    pub kids: HashMap<LabelId, Node, BuildHasherDefault<IdHasher>>,
 }
 
+impl Node {
+    /// The child reached through `label`.
+    pub(crate) fn kid(&self, labels: &Labels, label: &str) -> Option<&Node> {
+        self.kids.get(&labels.id(label)?)
+    }
+
+    /// The child reached through `label`, added if missing.
+    pub(crate) fn kid_or_insert(&mut self, labels: &mut Labels, label: &str) -> &mut Node {
+        self.kids.entry(labels.intern(label)).or_default()
+    }
+
+    /// The children with their labels, in no particular order.
+    pub(crate) fn labeled_kids<'a>(
+        &'a self,
+        labels: &'a Labels,
+    ) -> impl Iterator<Item = (&'a str, &'a Node)> + 'a {
+        self.kids.iter().map(|(id, kid)| (labels.get(*id), kid))
+    }
+
+    /// The children with their labels, sorted by label.
+    pub(crate) fn sorted_kids<'a>(&'a self, labels: &'a Labels) -> Vec<(&'a str, &'a Node)> {
+        let mut kids: Vec<_> = self.labeled_kids(labels).collect();
+        kids.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        kids
+    }
+}
+
+/// Index of a label in [`Labels`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LabelId(u32);
+
+/// Hashes a [`LabelId`] by spreading its bits with one multiplication.
+///
+/// Ids are small dense integers the list author cannot pick freely, so a
+/// general-purpose hash would only slow down each step of a lookup.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(u64::from(b));
+        }
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(u64::from(n));
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+/// The distinct labels of a trie, each stored once.
+///
+/// A list repeats labels heavily (`com`, `jp`, `*` and city names appear
+/// under many parents), so nodes refer to labels by [`LabelId`] instead of
+/// owning a `String` each. Labels of removed rules stay interned.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Labels {
+    /// The labels, back to back.
+    text: String,
+    /// End of each label in `text`; a label starts where the previous ends.
+    ends: Vec<u32>,
+    /// Every id, found by hashing its label.
+    ids: HashTable<LabelId>,
+    hasher: DefaultHashBuilder,
+}
+
+impl Labels {
+    /// The text of `id`.
+    pub(crate) fn get(&self, id: LabelId) -> &str {
+        let i = id.0 as usize;
+        let start = match i {
+            0 => 0,
+            _ => self.ends.get(i - 1).map_or(0, |&e| e as usize),
+        };
+        let end = self.ends.get(i).map_or(start, |&e| e as usize);
+        self.text.get(start..end).unwrap_or_default()
+    }
+
+    /// The id of `label`, if it was interned.
+    pub(crate) fn id(&self, label: &str) -> Option<LabelId> {
+        let hash = self.hasher.hash_one(label);
+        self.ids.find(hash, |&id| self.get(id) == label).copied()
+    }
+
+    /// The id of `label`, interning it if needed.
+    pub(crate) fn intern(&mut self, label: &str) -> LabelId {
+        if let Some(id) = self.id(label) {
+            return id;
+        }
+        let id = LabelId(self.ends.len() as u32);
+        self.text.push_str(label);
+        self.ends.push(self.text.len() as u32);
+        let hash = self.hasher.hash_one(label);
+        let Self {
+            text,
+            ends,
+            ids,
+            hasher,
+        } = self;
+        // Growing the table rehashes each id's label, found as `get` does.
+        let rehash = |id: &LabelId| {
+            let i = id.0 as usize;
+            let start = if i == 0 { 0 } else { ends[i - 1] as usize };
+            hasher.hash_one(&text[start..ends[i] as usize])
+        };
+        ids.insert_unique(hash, id, rehash);
+        id
+    }
+
+    /// Approximate heap size in bytes.
+    pub(crate) fn approx_bytes(&self) -> usize {
+        self.text.capacity()
+            + self.ends.capacity() * core::mem::size_of::<u32>()
+            + self.ids.capacity() * (core::mem::size_of::<LabelId>() + 1)
+    }
+}
+
+/// Lifecycle metadata attached to a rule by `// @key value` comments.
+///
+/// Annotation comments apply to the next rule line; a blank line discards
+/// them. Recognized keys are `@expires YYYY-MM-DD` and `@owner <name>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleMeta {
+    /// The rule as loaded (lowercased if requested), with `!` for exceptions.
+    pub rule: String,
+    /// Date from `@expires`, in `YYYY-MM-DD` form.
+    pub expires: Option<String>,
+    /// Value of `@owner`.
+    pub owner: Option<String>,
+}
+
+impl RuleMeta {
+    /// Returns true if the rule has an expiry date on or before `today`
+    /// (`YYYY-MM-DD`).
+    pub fn is_expired(&self, today: &str) -> bool {
+        self.expires.as_deref().is_some_and(|d| d <= today)
+    }
+}
+
+/// The comment block above a group of rules in the list text, kept when
+/// loading with `LoadOpts::keep_metadata`.
+///
+/// In the official list, each entry of the private section starts with a
+/// block naming the organization and who submitted it:
+///
+/// ```text
+/// // Example Org : https://example.org
+/// // Submitted by Jane Doe <psl@example.org>
+/// example.net
+/// *.example.net
+/// ```
+///
+/// Every rule up to the next blank line or comment shares the block.
+/// Section markers and `// @key` annotations are not part of it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommentBlock {
+    /// The comment lines, without their `//` (or `#`, `;`) prefix and
+    /// surrounding whitespace.
+    pub lines: Vec<String>,
+}
+
+impl CommentBlock {
+    /// The text after `Submitted by` in the block, e.g.
+    /// `Jane Doe <psl@example.org>`.
+    pub fn submitter(&self) -> Option<&str> {
+        self.lines
+            .iter()
+            .find_map(|l| l.strip_prefix("Submitted by"))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// Comment blocks of a list and the rules under each.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CommentIndex {
+    pub(crate) blocks: Vec<CommentBlock>,
+    /// Rule as written (`!` included) to its index in `blocks`.
+    pub(crate) by_rule: HashMap<String, usize>,
+}
+
+impl CommentIndex {
+    pub(crate) fn get(&self, rule: &str) -> Option<&CommentBlock> {
+        self.by_rule.get(rule).map(|&i| &self.blocks[i])
+    }
+
+    /// Adds the blocks of `other`'s rules; for rules both have, `other`'s
+    /// block is used only if `replace` is set.
+    pub(crate) fn absorb(&mut self, other: &CommentIndex, replace: bool) {
+        let mut moved: HashMap<usize, usize> = HashMap::new();
+        for (rule, &i) in &other.by_rule {
+            if !replace && self.by_rule.contains_key(rule) {
+                continue;
+            }
+            let at = *moved.entry(i).or_insert_with(|| {
+                self.blocks.push(other.blocks[i].clone());
+                self.blocks.len() - 1
+            });
+            self.by_rule.insert(rule.clone(), at);
+        }
+    }
+}
+
+/// Which snapshot of the PSL a list was loaded from.
+///
+/// The official list starts with a header naming its version and the commit
+/// of `publicsuffix/list` it was published from:
+///
+/// ```text
+/// // VERSION: 2025-09-23_13-07-02_UTC
+/// // COMMIT: 6defc0e19dbbe27dc2a0798256077d0b82d9c455
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListVersion {
+    /// The `// VERSION:` value, e.g. `2025-09-23_13-07-02_UTC`.
+    pub version: Option<String>,
+    /// Publication date from `version`, in `YYYY-MM-DD` form.
+    pub date: Option<String>,
+    /// The `// COMMIT:` hash.
+    pub commit: Option<String>,
+    /// URL the list was downloaded from, when loaded from one.
+    pub url: Option<String>,
+}
+
+/// Returns true if `date` is a plausible `YYYY-MM-DD` calendar date.
+pub(crate) fn is_valid_date(date: &str) -> bool {
+    let b = date.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    let num = |r: core::ops::Range<usize>| -> Option<u32> {
+        date.get(r)
+            .filter(|s| s.bytes().all(|c| c.is_ascii_digit()))?
+            .parse()
+            .ok()
+    };
+    match (num(0..4), num(5..7), num(8..10)) {
+        (Some(_), Some(m), Some(d)) => (1..=12).contains(&m) && (1..=31).contains(&d),
+        _ => false,
+    }
+}
+
+/// Callback for `for_each_rule` walks: a rule's labels from the right, its
+/// kind and its section.
+pub(crate) type RuleFn<'f> = dyn FnMut(&[&str], Leaf, Option<Type>) + 'f;
+
 /// Top-level container for the rule trie.
 #[derive(Default, Clone, Debug)]
 pub struct RuleSet {
     /// Root of the reverse-label trie (has no label itself).
     pub(crate) root: Node,
+    /// Labels of the trie's nodes.
+    pub(crate) labels: Labels,
+    /// Non-fatal issues collected while loading (when requested).
+    pub(crate) warnings: Vec<Warning>,
+    /// Annotations of rules that had any, in list order.
+    pub(crate) meta: Vec<RuleMeta>,
+    /// Comment blocks above the rules (with `LoadOpts::keep_metadata`).
+    pub(crate) comments: CommentIndex,
+    /// Snapshot named by the `// VERSION:` and `// COMMIT:` header lines.
+    pub(crate) version: Option<Box<ListVersion>>,
+    /// Rule lines per section, as parsed; boxed like `version` to keep
+    /// `Store` variants close in size.
+    pub(crate) sections: Box<SectionCounts>,
+}
+impl RuleSet {
+    /// Approximate heap and inline size of the trie in bytes.
+    pub(crate) fn approx_bytes(&self) -> usize {
+        fn node_heap(n: &Node) -> usize {
+            n.kids.capacity() * (core::mem::size_of::<(LabelId, Node)>() + 1)
+                + n.kids.values().map(node_heap).sum::<usize>()
+        }
+        core::mem::size_of::<Self>() + node_heap(&self.root) + self.labels.approx_bytes()
+    }
+
+    /// The child of `node` reached through `label`.
+    pub(crate) fn child<'a>(&self, node: &'a Node, label: &str) -> Option<&'a Node> {
+        node.kids.get(&self.labels.id(label)?)
+    }
+
+    /// The node at `rule`'s labels, added (with any missing parents) if
+    /// needed.
+    pub(crate) fn node_mut_or_insert(&mut self, rule: &str) -> &mut Node {
+        let mut cur = &mut self.root;
+        for label in rule.rsplit('.') {
+            cur = cur.kid_or_insert(&mut self.labels, label);
+        }
+        cur
+    }
+
+    /// The node at `rule`'s labels, if there is one.
+    pub(crate) fn node_at(&self, rule: &str) -> Option<&Node> {
+        let mut cur = &self.root;
+        for label in rule.rsplit('.') {
+            cur = self.child(cur, label)?;
+        }
+        Some(cur)
+    }
+
+    /// Calls `f` with every rule: its labels from the right, its kind and
+    /// its section.
+    pub(crate) fn for_each_rule(&self, f: &mut RuleFn<'_>) {
+        fn walk<'a>(
+            node: &'a Node,
+            labels: &'a Labels,
+            path: &mut Vec<&'a str>,
+            f: &mut RuleFn<'_>,
+        ) {
+            if node.leaf != Leaf::None {
+                f(path, node.leaf, node.typ);
+            }
+            for (label, kid) in node.labeled_kids(labels) {
+                path.push(label);
+                walk(kid, labels, path, f);
+                path.pop();
+            }
+        }
+        walk(&self.root, &self.labels, &mut Vec::new(), f);
+    }
+}
+
+/// Rule lines of each section, as counted while parsing a list's text; see
+/// [`List::section_counts`](crate::List::section_counts).
+///
+/// Each rule line counts once, duplicates included; the A-label copies of
+/// `LoadOpts::a_label_duplicates` do not count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SectionCounts {
+    /// Rules loaded into the ICANN section.
+    pub icann: usize,
+    /// Rules loaded into the private section.
+    pub private: usize,
+    /// Rules loaded without a section: those outside the markers, or every
+    /// rule under `SectionPolicy::Ignore`.
+    pub unclassified: usize,
+    /// Rules dropped by `SectionPolicy::Require` for being outside the
+    /// markers.
+    pub skipped: usize,
+}
+
+impl core::ops::AddAssign for SectionCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.icann += other.icann;
+        self.private += other.private;
+        self.unclassified += other.unclassified;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Receives the nodes of a list's rule trie from
+/// [`List::visit`](crate::List::visit).
+///
+/// `path` holds the labels leading to the node from the right (`["uk",
+/// "co"]` for `co.uk`), so the node's own label is the last one. Closures
+/// taking `(path, leaf, typ)` are visitors that only look at
+/// [`enter`](Self::enter).
+pub trait RuleVisitor {
+    /// Called on reaching a node, before its children. `leaf` is
+    /// `Leaf::None`, and `typ` `None`, for a label that only leads to deeper
+    /// rules.
+    fn enter(&mut self, path: &[&str], leaf: Leaf, typ: Option<Type>);
+
+    /// Called once the node's children have all been visited.
+    fn leave(&mut self, path: &[&str]) {
+        let _ = path;
+    }
 }
+
+impl<F: FnMut(&[&str], Leaf, Option<Type>)> RuleVisitor for F {
+    fn enter(&mut self, path: &[&str], leaf: Leaf, typ: Option<Type>) {
+        self(path, leaf, typ)
+    }
+}
+
+/// Counts describing a compiled list, as returned by
+/// [`List::stats`](crate::List::stats).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListStats {
+    /// Number of rules, as [`List::len`](crate::List::len).
+    pub rules: usize,
+    /// Rules whose leftmost label is `*`.
+    pub wildcard_rules: usize,
+    /// Exception (`!`) rules.
+    pub exception_rules: usize,
+    /// Most labels in any rule, counting `*`; 0 for an empty list.
+    pub max_depth: usize,
+    /// Rules in the ICANN section.
+    pub icann_rules: usize,
+    /// Rules in the private section.
+    pub private_rules: usize,
+    /// Approximate memory used, as [`List::approx_bytes`](crate::List::approx_bytes).
+    pub approx_bytes: usize,
+}
+
+/// A rule of a compiled list, as returned by [`List::rules`](crate::List::rules).
+///
+/// Displays as it would be written in a list: `co.uk`, `*.ck`, `!www.ck`.
+/// Any `// @expires` and `// @owner` annotations of the rule are available
+/// from [`expires`](Self::expires) and [`owner`](Self::owner).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RuleRef {
+    /// The rule's labels, left to right (`["*", "ck"]` for `*.ck`).
+    pub labels: Vec<String>,
+    /// `Leaf::Positive` for plain and wildcard rules, `Leaf::Negative` for
+    /// exceptions.
+    pub leaf: Leaf,
+    /// Section of the rule, if any.
+    pub typ: Option<Type>,
+    pub(crate) expires: Option<String>,
+    pub(crate) owner: Option<String>,
+}
+
+impl RuleRef {
+    pub(crate) fn new(rev_labels: &[&str], leaf: Leaf, typ: Option<Type>) -> Self {
+        Self {
+            labels: rev_labels.iter().rev().map(|l| String::from(*l)).collect(),
+            leaf,
+            typ,
+            expires: None,
+            owner: None,
+        }
+    }
+
+    /// Date from the rule's `@expires` annotation, in `YYYY-MM-DD` form.
+    pub fn expires(&self) -> Option<&str> {
+        self.expires.as_deref()
+    }
+
+    /// Value of the rule's `@owner` annotation.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+}
+
+impl fmt::Display for RuleRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.leaf == Leaf::Negative {
+            f.write_str("!")?;
+        }
+        for (i, label) in self.labels.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            f.write_str(label)?;
+        }
+        Ok(())
+    }
+}
+
 // -------------------------------------
 // Unit tests for this private module
 // -------------------------------------
@@ -98,40 +626,58 @@ mod tests {
     }
 
     #[test]
-    fn node_kids_insert_and_get_mut() {
+    fn node_kids_insert_and_get() {
+        let mut labels = Labels::default();
         let mut n = Node::default();
-        n.kids.insert("com".to_string(), Node::default());
-        assert!(n.kids.contains_key("com"));
+        n.kid_or_insert(&mut labels, "com");
+        assert!(n.kid(&labels, "com").is_some());
 
-        let child = n.kids.get_mut("com").unwrap();
+        let child = n.kid_or_insert(&mut labels, "com");
         assert_eq!(child.leaf, Leaf::None);
         child.leaf = Leaf::Positive;
         child.typ = Some(Type::Icann);
 
-        let child_again = n.kids.get("com").unwrap();
+        let child_again = n.kid(&labels, "com").unwrap();
         assert_eq!(child_again.leaf, Leaf::Positive);
         assert_eq!(child_again.typ, Some(Type::Icann));
     }
 
     #[test]
     fn node_clone_is_deep_for_kids_map() {
+        let mut labels = Labels::default();
         let mut n = Node::default();
-        let sub = Node {
-            leaf: Leaf::Negative,
-            ..Default::default()
-        };
-        n.kids.insert("net".into(), sub);
+        n.kid_or_insert(&mut labels, "net").leaf = Leaf::Negative;
 
         let cloned = n.clone();
 
-        n.kids.get_mut("net").unwrap().leaf = Leaf::Positive;
-        n.kids.get_mut("net").unwrap().typ = Some(Type::Private);
+        n.kid_or_insert(&mut labels, "net").leaf = Leaf::Positive;
+        n.kid_or_insert(&mut labels, "net").typ = Some(Type::Private);
 
-        let cloned_child = cloned.kids.get("net").unwrap();
+        let cloned_child = cloned.kid(&labels, "net").unwrap();
         assert_eq!(cloned_child.leaf, Leaf::Negative);
         assert!(cloned_child.typ.is_none());
     }
 
+    #[test]
+    fn labels_are_interned_once() {
+        let mut labels = Labels::default();
+        let words = ["com", "jp", "", "*", "食狮", "com", "jp"];
+        let ids: Vec<LabelId> = words.iter().map(|w| labels.intern(w)).collect();
+        assert_eq!(ids[0], ids[5]);
+        assert_eq!(ids[1], ids[6]);
+        for (word, id) in words.iter().zip(&ids) {
+            assert_eq!(labels.get(*id), *word);
+            assert_eq!(labels.id(word), Some(*id));
+        }
+        assert_eq!(labels.id("org"), None);
+        for i in 0..1000 {
+            let label = alloc::format!("l{i}");
+            let id = labels.intern(&label);
+            assert_eq!(labels.id(&label), Some(id));
+        }
+        assert_eq!(labels.get(ids[4]), "食狮");
+    }
+
     #[test]
     fn node_typ_option_roundtrip_and_clone() {
         let mut n = Node::default();
@@ -143,6 +689,27 @@ mod tests {
         assert_eq!(c.typ, Some(Type::Private));
     }
 
+    #[test]
+    fn date_validation() {
+        assert!(is_valid_date("2020-01-31"));
+        assert!(!is_valid_date("2020-13-01"));
+        assert!(!is_valid_date("2020-1-01"));
+        assert!(!is_valid_date("20x0-01-01"));
+        assert!(!is_valid_date("2020-01-00"));
+    }
+
+    #[test]
+    fn rule_meta_expiry_is_inclusive() {
+        let m = RuleMeta {
+            rule: "a.example".into(),
+            expires: Some("2026-01-01".into()),
+            owner: None,
+        };
+        assert!(!m.is_expired("2025-12-31"));
+        assert!(m.is_expired("2026-01-01"));
+        assert!(!RuleMeta::default().is_expired("9999-12-31"));
+    }
+
     #[test]
     fn ruleset_default_root_is_empty_node() {
         let rs = RuleSet::default();
@@ -0,0 +1,107 @@
+//! Reusable buffers for normalized hosts.
+//!
+//! Lowercasing a non-ASCII host or converting it to its IDNA ASCII form
+//! needs a `String`. Lookups [`take`] one from a small per-thread pool and
+//! [`recycle`] the normalized view once their result no longer borrows
+//! from it, so a thread looking up many internationalized hosts keeps
+//! reusing the same few allocations. Without the `std` feature there is no
+//! pool and every buffer is a fresh `String`.
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// Buffers kept per thread: a lookup holds at most two at once, the
+/// lowercased host and its IDNA form.
+#[cfg(feature = "std")]
+const POOL_SIZE: usize = 2;
+/// Buffers that grew beyond this are dropped rather than kept, so one huge
+/// host does not pin its allocation to the thread.
+#[cfg(feature = "std")]
+const MAX_KEPT_CAPACITY: usize = 4096;
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static POOL: core::cell::RefCell<alloc::vec::Vec<String>> =
+        const { core::cell::RefCell::new(alloc::vec::Vec::new()) };
+}
+
+/// An empty buffer, from this thread's pool if it has one.
+pub(crate) fn take() -> String {
+    #[cfg(feature = "std")]
+    if let Some(buf) = POOL
+        .try_with(|p| p.try_borrow_mut().ok()?.pop())
+        .ok()
+        .flatten()
+    {
+        return buf;
+    }
+    String::new()
+}
+
+/// Returns the buffer behind `view` to this thread's pool, if it owns one.
+pub(crate) fn recycle(view: Cow<'_, str>) {
+    #[cfg(feature = "std")]
+    if let Cow::Owned(mut buf) = view {
+        if (1..=MAX_KEPT_CAPACITY).contains(&buf.capacity()) {
+            buf.clear();
+            let _ = POOL.try_with(|p| {
+                if let Ok(mut pool) = p.try_borrow_mut() {
+                    if pool.len() < POOL_SIZE {
+                        pool.push(buf);
+                    }
+                }
+            });
+        }
+    }
+    #[cfg(not(feature = "std"))]
+    drop(view);
+}
+
+/// Lowercases `s` into a buffer from the pool, like [`str::to_lowercase`].
+pub(crate) fn to_lowercase(s: &str) -> String {
+    // `Σ` is the only letter whose lowercase depends on its neighbours;
+    // leave it to the standard library.
+    if s.contains('Σ') {
+        return s.to_lowercase();
+    }
+    let mut buf = take();
+    buf.extend(s.chars().flat_map(char::to_lowercase));
+    buf
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recycled_buffers_are_reused_empty() {
+        let mut buf = take();
+        buf.push_str("bücher.example");
+        let ptr = buf.as_ptr();
+        recycle(Cow::Owned(buf));
+        let again = take();
+        assert!(again.is_empty());
+        assert_eq!(again.as_ptr(), ptr);
+        recycle(Cow::Owned(again));
+    }
+
+    #[test]
+    fn lookups_return_their_buffers() {
+        let list = crate::List::parse("com\nxn--90ae\n").unwrap();
+        POOL.with(|p| p.borrow_mut().clear());
+        let opts = crate::MatchOpts::default();
+        assert_eq!(list.tld("Bücher.Example.COM", opts).as_deref(), Some("com"));
+        // The lowercased host comes back, and so does its IDNA form when
+        // there is one.
+        let returned = if cfg!(feature = "idna") { POOL_SIZE } else { 1 };
+        assert_eq!(POOL.with(|p| p.borrow().len()), returned);
+        list.sld("пример.бг", opts);
+        assert_eq!(POOL.with(|p| p.borrow().len()), returned);
+    }
+
+    #[test]
+    fn lowercases_like_std() {
+        for s in ["BÜCHER.Example", "ΟΔΟΣ.gr", "İstanbul.tr", "ǅ.com"] {
+            assert_eq!(to_lowercase(s), s.to_lowercase(), "{s}");
+        }
+    }
+}
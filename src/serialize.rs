@@ -0,0 +1,327 @@
+//! `serde` support for [`MatchOpts`] and [`List`].
+//!
+//! The plain option types derive their impls next to their definitions.
+//! `MatchOpts` borrows its normalizer and ignored suffixes, so it goes
+//! through a representation that names them instead:
+//!
+//! - `normalizer` is `null` (no normalization), a preset name (`"ps2"`,
+//!   `"raw"`, `"lowercase_only"`, `"strip_dot_only"`, `"idna_only"`) or an
+//!   object of `Normalizer` flags;
+//! - `ignored_private` is a list of suffixes, or `"platform"` for
+//!   [`PLATFORM_SUFFIXES`]. Only the empty list and the preset can be
+//!   deserialized, since the options cannot own a list; add others with
+//!   [`MatchOpts::ignoring_private`] after loading.
+//!
+//! Missing fields take their `MatchOpts::default()` values.
+//!
+//! A `List` is written as its rules grouped by section, each sorted:
+//! `{"icann": ["com", "*.ck", "!www.ck"], "private": [...], "unclassified": [...]}`.
+//! Empty groups are omitted. The storage engine, warnings and rule
+//! annotations are not included; a deserialized list uses `Engine::Trie`.
+use crate::errors::Error;
+use crate::options::{
+    CompatMode, ControlPolicy, FallbackPolicy, LiteralPolicy, MatchOpts, Normalizer,
+    PLATFORM_SUFFIXES,
+};
+use crate::rules::{RuleSet, Type, TypeFilter};
+use crate::{loader, List};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const fn flags(bits: usize) -> Normalizer {
+    Normalizer {
+        lowercase: bits & 1 != 0,
+        strip_trailing_dot: bits & 2 != 0,
+        idna_ascii: bits & 4 != 0,
+        idna_transitional: bits & 8 != 0,
+        idna_std3_rules: bits & 16 != 0,
+        idna_reject_errors: bits & 32 != 0,
+    }
+}
+
+/// Every possible normalizer, so a deserialized one can be borrowed for
+/// `'static`. Indexed by [`bits`].
+static NORMALIZERS: [Normalizer; 64] = {
+    const NONE: Normalizer = flags(0);
+    let mut all = [NONE; 64];
+    let mut i = 0;
+    while i < all.len() {
+        all[i] = flags(i);
+        i += 1;
+    }
+    all
+};
+
+fn bits(n: &Normalizer) -> usize {
+    usize::from(n.lowercase)
+        | usize::from(n.strip_trailing_dot) << 1
+        | usize::from(n.idna_ascii) << 2
+        | usize::from(n.idna_transitional) << 3
+        | usize::from(n.idna_std3_rules) << 4
+        | usize::from(n.idna_reject_errors) << 5
+}
+
+const PRESETS: [(&str, Normalizer); 5] = [
+    ("ps2", Normalizer::ps2()),
+    ("raw", Normalizer::raw()),
+    ("lowercase_only", Normalizer::lowercase_only()),
+    ("strip_dot_only", Normalizer::strip_dot_only()),
+    ("idna_only", Normalizer::idna_only()),
+];
+
+const PLATFORM: &str = "platform";
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum NormalizerRepr<'a> {
+    Preset(Cow<'a, str>),
+    Flags(Normalizer),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IgnoredRepr<'a> {
+    Preset(Cow<'a, str>),
+    List(Vec<Cow<'a, str>>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct MatchOptsRepr<'a> {
+    wildcard: bool,
+    strict: bool,
+    types: TypeFilter,
+    #[serde(borrow)]
+    normalizer: Option<NormalizerRepr<'a>>,
+    #[serde(borrow)]
+    ignored_private: IgnoredRepr<'a>,
+    controls: ControlPolicy,
+    match_both_forms: bool,
+    literals: LiteralPolicy,
+    fallback: FallbackPolicy,
+    deep_wildcards: bool,
+    exceptions: bool,
+    compat: CompatMode,
+    max_len: usize,
+    max_labels: usize,
+}
+
+impl Default for MatchOptsRepr<'_> {
+    fn default() -> Self {
+        Self::from(&MatchOpts::default())
+    }
+}
+
+impl<'a> From<&MatchOpts<'a>> for MatchOptsRepr<'a> {
+    fn from(o: &MatchOpts<'a>) -> Self {
+        let normalizer = o
+            .normalizer
+            .map(|n| match PRESETS.iter().find(|(_, p)| p == n) {
+                Some((name, _)) => NormalizerRepr::Preset(Cow::Borrowed(name)),
+                None => NormalizerRepr::Flags(n.clone()),
+            });
+        let ignored_private =
+            if !o.ignored_private.is_empty() && o.ignored_private == PLATFORM_SUFFIXES {
+                IgnoredRepr::Preset(Cow::Borrowed(PLATFORM))
+            } else {
+                IgnoredRepr::List(
+                    o.ignored_private
+                        .iter()
+                        .map(|s| Cow::Borrowed(*s))
+                        .collect(),
+                )
+            };
+        Self {
+            wildcard: o.wildcard,
+            strict: o.strict,
+            types: o.types,
+            normalizer,
+            ignored_private,
+            controls: o.controls,
+            match_both_forms: o.match_both_forms,
+            literals: o.literals,
+            fallback: o.fallback,
+            deep_wildcards: o.deep_wildcards,
+            exceptions: o.exceptions,
+            compat: o.compat,
+            max_len: o.max_len,
+            max_labels: o.max_labels,
+        }
+    }
+}
+
+impl Serialize for MatchOpts<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MatchOptsRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MatchOpts<'static> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MatchOptsRepr::deserialize(deserializer)?;
+        let normalizer = match repr.normalizer {
+            None => None,
+            Some(NormalizerRepr::Flags(n)) => Some(&NORMALIZERS[bits(&n)]),
+            Some(NormalizerRepr::Preset(name)) => {
+                let (_, n) = PRESETS.iter().find(|(p, _)| *p == name).ok_or_else(|| {
+                    D::Error::custom(alloc::format!("unknown normalizer preset `{name}`"))
+                })?;
+                Some(&NORMALIZERS[bits(n)])
+            }
+        };
+        let ignored_private: &'static [&'static str] = match repr.ignored_private {
+            IgnoredRepr::Preset(name) if name == PLATFORM => PLATFORM_SUFFIXES,
+            IgnoredRepr::List(list) if list.is_empty() => &[],
+            IgnoredRepr::List(list) if list.iter().eq(PLATFORM_SUFFIXES) => PLATFORM_SUFFIXES,
+            _ => {
+                return Err(D::Error::custom(
+                    "ignored_private must be empty or \"platform\"",
+                ))
+            }
+        };
+        Ok(MatchOpts {
+            wildcard: repr.wildcard,
+            strict: repr.strict,
+            types: repr.types,
+            normalizer,
+            ignored_private,
+            controls: repr.controls,
+            match_both_forms: repr.match_both_forms,
+            literals: repr.literals,
+            fallback: repr.fallback,
+            deep_wildcards: repr.deep_wildcards,
+            exceptions: repr.exceptions,
+            compat: repr.compat,
+            max_len: repr.max_len,
+            max_labels: repr.max_labels,
+        })
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ListRepr {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    icann: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    private: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unclassified: Vec<String>,
+}
+
+impl Serialize for List {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut repr = ListRepr::default();
+        for rule in self.rules() {
+            let (typ, rule) = (rule.typ, rule.to_string());
+            match typ {
+                Some(Type::Icann) => repr.icann.push(rule),
+                Some(Type::Private) => repr.private.push(rule),
+                None => repr.unclassified.push(rule),
+            }
+        }
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for List {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ListRepr::deserialize(deserializer)?;
+        let mut rules = RuleSet::default();
+        for (group, typ) in [
+            (repr.icann, Some(Type::Icann)),
+            (repr.private, Some(Type::Private)),
+            (repr.unclassified, None),
+        ] {
+            for rule in &group {
+                let (neg, name) = match rule.strip_prefix('!') {
+                    Some(name) => (true, name),
+                    None => (false, rule.as_str()),
+                };
+                if name.is_empty() || name.split('.').any(str::is_empty) {
+                    return Err(D::Error::custom(alloc::format!("invalid rule `{rule}`")));
+                }
+                loader::insert(&mut rules, name, typ, neg);
+            }
+        }
+        if rules.root.kids.is_empty() {
+            return Err(D::Error::custom(Error::EmptyList));
+        }
+        Ok(List::from_ruleset(rules))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_normalizer_has_a_static() {
+        for (i, n) in NORMALIZERS.iter().enumerate() {
+            assert_eq!(bits(n), i);
+        }
+        for (_, p) in PRESETS {
+            assert_eq!(NORMALIZERS[bits(&p)], p);
+        }
+    }
+
+    #[test]
+    fn match_opts_round_trip() {
+        let lowercase = Normalizer::lowercase_only();
+        let custom = Normalizer {
+            lowercase: true,
+            idna_ascii: true,
+            ..Normalizer::raw()
+        };
+        for opts in [
+            MatchOpts::default(),
+            MatchOpts::raw(),
+            MatchOpts::with_normalizer(&lowercase),
+            MatchOpts::with_normalizer(&custom),
+            MatchOpts::default().ignoring_private(PLATFORM_SUFFIXES),
+            MatchOpts {
+                strict: true,
+                wildcard: false,
+                types: TypeFilter::Private,
+                controls: ControlPolicy::Strip,
+                match_both_forms: true,
+                literals: LiteralPolicy::Whole,
+                fallback: FallbackPolicy::EntireHost,
+                deep_wildcards: true,
+                exceptions: false,
+                compat: CompatMode::GoPublicsuffix,
+                max_len: 300,
+                max_labels: 10,
+                ..MatchOpts::default()
+            },
+        ] {
+            let json = serde_json::to_string(&opts).unwrap();
+            let back: MatchOpts<'static> = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&back).unwrap(), json);
+            assert_eq!(back.normalizer, opts.normalizer);
+        }
+    }
+
+    #[test]
+    fn match_opts_from_config() {
+        let opts: MatchOpts<'static> =
+            serde_json::from_str(r#"{"strict": true, "normalizer": "lowercase_only"}"#).unwrap();
+        assert!(opts.strict && opts.wildcard);
+        assert_eq!(opts.normalizer, Some(&Normalizer::lowercase_only()));
+        let opts: MatchOpts<'static> =
+            serde_json::from_str(r#"{"normalizer": null, "ignored_private": "platform"}"#).unwrap();
+        assert_eq!(opts.normalizer, None);
+        assert_eq!(opts.ignored_private, PLATFORM_SUFFIXES);
+
+        for bad in [
+            r#"{"normalizer": "nope"}"#,
+            r#"{"ignored_private": ["github.io"]}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<MatchOpts<'static>>(bad).is_err(),
+                "{bad}"
+            );
+        }
+    }
+}
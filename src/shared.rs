@@ -0,0 +1,199 @@
+//! A replaceable list shared between threads.
+//!
+//! [`SharedList`] holds the current `List` in an [`ArcSwap`] that writers
+//! can replace while readers keep serving lookups; neither side takes a
+//! lock. Reading through [`SharedList::load_full`] still bumps the `Arc`
+//! refcount, which turns into cache-line contention when every core does it
+//! on every lookup. [`SharedList::load_full_cached`] avoids that: each
+//! reader keeps a [`SharedListCache`] and only loads again when the list was
+//! replaced since its last load. A lookup then costs one atomic load of a
+//! version counter that is written only by [`SharedList::store`].
+//!
+//! One-off lookups can go through [`SharedList::sld`], [`SharedList::tld`]
+//! and [`SharedList::split`], which borrow the current list for the lookup
+//! without touching its refcount.
+//!
+//! For a list that never changes, [`List::global_ref`] is cheaper still.
+use crate::engine::Parts;
+use crate::options::MatchOpts;
+use crate::List;
+use alloc::borrow::Cow;
+use arc_swap::ArcSwap;
+use core::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A `List` that can be replaced at runtime and read from many threads.
+///
+/// ```rust
+/// use publicsuffix2::{List, MatchOpts, SharedList, SharedListCache};
+///
+/// let shared = SharedList::new("com".parse().unwrap());
+/// let mut cache = SharedListCache::default();
+/// let list = shared.load_full_cached(&mut cache);
+/// assert_eq!(list.tld("a.b.uk", MatchOpts::default()).as_deref(), Some("uk"));
+///
+/// shared.store("uk\nco.uk".parse().unwrap());
+/// let list = shared.load_full_cached(&mut cache);
+/// assert_eq!(list.tld("a.b.co.uk", MatchOpts::default()).as_deref(), Some("co.uk"));
+/// ```
+#[derive(Debug)]
+pub struct SharedList {
+    current: ArcSwap<List>,
+    version: AtomicU64,
+}
+
+/// A reader's cached copy of a [`SharedList`]'s current list.
+///
+/// Keep one per thread (or per worker) and pass it to
+/// [`SharedList::load_full_cached`]. A fresh cache always loads; after that
+/// it must only be used with the same `SharedList`.
+#[derive(Clone, Debug, Default)]
+pub struct SharedListCache {
+    version: u64,
+    list: Option<Arc<List>>,
+}
+
+impl SharedList {
+    /// Shares `list`.
+    pub fn new(list: List) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(list),
+            version: AtomicU64::new(1),
+        }
+    }
+
+    /// Borrows the current list without touching its refcount.
+    ///
+    /// A [`swap`](Self::swap) does not wait for the guard, but the list it
+    /// replaced stays alive until the guard is dropped; keep it for a few
+    /// lookups, not across blocking work.
+    pub fn load(&self) -> impl Deref<Target = List> + '_ {
+        struct Guard(arc_swap::Guard<Arc<List>>);
+        impl Deref for Guard {
+            type Target = List;
+            fn deref(&self) -> &List {
+                &self.0
+            }
+        }
+        Guard(self.current.load())
+    }
+
+    /// Returns the current list.
+    ///
+    /// Clones the `Arc`; prefer
+    /// [`load_full_cached`](Self::load_full_cached) on hot paths.
+    pub fn load_full(&self) -> Arc<List> {
+        self.current.load_full()
+    }
+
+    /// Returns the current list, reusing `cache` when it is up to date.
+    ///
+    /// Only the first call, and the first call after each
+    /// [`store`](Self::store), touch the refcount.
+    pub fn load_full_cached<'c>(&self, cache: &'c mut SharedListCache) -> &'c Arc<List> {
+        let version = self.version.load(Ordering::Acquire);
+        if cache.version != version {
+            // The version is read before the list, so a concurrent store
+            // costs at most one extra refresh and is never missed.
+            cache.list = Some(self.load_full());
+            cache.version = version;
+        }
+        cache.list.get_or_insert_with(|| self.load_full())
+    }
+
+    /// Replaces the current list. Readers see `list` on their next load;
+    /// lookups already running keep the list they started with.
+    pub fn store(&self, list: List) {
+        self.swap(list);
+    }
+
+    /// Replaces the current list like [`store`](Self::store), returning the
+    /// list it replaced.
+    pub fn swap(&self, list: List) -> Arc<List> {
+        let old = self.current.swap(Arc::new(list));
+        self.version.fetch_add(1, Ordering::Release);
+        old
+    }
+
+    /// [`List::sld`] against the current list.
+    pub fn sld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+        self.load().sld(host, opts)
+    }
+
+    /// [`List::tld`] against the current list.
+    pub fn tld<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Cow<'a, str>> {
+        self.load().tld(host, opts)
+    }
+
+    /// [`List::split`] against the current list.
+    pub fn split<'a>(&self, host: &'a str, opts: MatchOpts<'_>) -> Option<Parts<'a>> {
+        self.load().split(host, opts)
+    }
+}
+
+impl From<List> for SharedList {
+    fn from(list: List) -> Self {
+        Self::new(list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchOpts;
+
+    #[test]
+    fn cache_refreshes_only_after_store() {
+        let shared = SharedList::new("com".parse().unwrap());
+        let mut cache = SharedListCache::default();
+        let first = Arc::clone(shared.load_full_cached(&mut cache));
+        assert!(Arc::ptr_eq(&first, shared.load_full_cached(&mut cache)));
+
+        shared.store("uk\nco.uk".parse().unwrap());
+        let second = shared.load_full_cached(&mut cache);
+        assert!(!Arc::ptr_eq(&first, second));
+        assert_eq!(
+            second.tld("a.co.uk", MatchOpts::default()).as_deref(),
+            Some("co.uk")
+        );
+    }
+
+    #[test]
+    fn swap_returns_previous_list() {
+        let shared = SharedList::new("com".parse().unwrap());
+        let opts = MatchOpts::default();
+        assert_eq!(shared.tld("a.co.uk", opts).as_deref(), Some("uk"));
+
+        let old = shared.swap("uk\nco.uk".parse().unwrap());
+        assert_eq!(old.tld("a.co.uk", opts).as_deref(), Some("uk"));
+        assert_eq!(shared.tld("a.co.uk", opts).as_deref(), Some("co.uk"));
+        assert_eq!(shared.sld("a.co.uk", opts).as_deref(), Some("a.co.uk"));
+        let parts = shared.split("www.a.co.uk", opts).unwrap();
+        assert_eq!(parts.prefix.as_deref(), Some("www"));
+        assert_eq!(shared.load().rules().count(), 2);
+
+        let mut cache = SharedListCache::default();
+        shared.load_full_cached(&mut cache);
+        shared.swap("com".parse().unwrap());
+        let list = shared.load_full_cached(&mut cache);
+        assert_eq!(list.tld("a.co.uk", opts).as_deref(), Some("uk"));
+    }
+
+    #[test]
+    fn readers_on_many_threads() {
+        let shared = SharedList::new("com\nuk\nco.uk".parse().unwrap());
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut cache = SharedListCache::default();
+                    for _ in 0..100 {
+                        let list = shared.load_full_cached(&mut cache);
+                        assert!(list.tld("a.example.com", MatchOpts::default()).is_some());
+                    }
+                });
+            }
+            shared.store("com".parse().unwrap());
+        });
+    }
+}
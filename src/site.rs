@@ -0,0 +1,135 @@
+//! HTML "site" computation on top of the registrable domain.
+//!
+//! Follows the URL and HTML standards: the registrable domain of a host is
+//! its public suffix plus one label, and is absent for IP addresses and for
+//! hosts that are themselves a public suffix. A site is the pair
+//! (scheme, registrable domain or host), serialized as `scheme://domain`.
+use crate::domain::RegistrableDomain;
+use crate::engine::normalize_view;
+use crate::options::{is_ip_literal, MatchOpts};
+use crate::List;
+use alloc::{borrow::Cow, format, string::String};
+
+impl List {
+    /// Registrable domain of `host` per the URL standard.
+    ///
+    /// Unlike [`List::sld`], returns `None` when the host is itself a public
+    /// suffix (e.g. `co.uk`) or an IP address, and keeps one label in front
+    /// of unlisted TLDs (`example.local` → `example.local`).
+    pub fn registrable_domain(&self, host: &str, opts: MatchOpts<'_>) -> Option<String> {
+        let host = normalize_view(host, opts)?;
+        if is_ip_literal(&host) {
+            return None;
+        }
+        let raw = MatchOpts {
+            normalizer: None,
+            ..opts
+        };
+        self.registrable(&host, raw)
+            .map(RegistrableDomain::into_string)
+    }
+
+    /// The HTML "site" of an origin, serialized as `scheme://domain`.
+    ///
+    /// `domain` is the registrable domain of `host`, or `host` itself when it
+    /// has none (IP addresses, public suffixes). The scheme is lowercased.
+    /// This is the key used for storage partitioning and `SameSite` cookies.
+    pub fn site_for_origin(&self, scheme: &str, host: &str, opts: MatchOpts<'_>) -> String {
+        let domain = self
+            .registrable_domain(host, opts)
+            .or_else(|| normalize_view(host, opts).map(Cow::into_owned))
+            .unwrap_or_default();
+        format!("{}://{domain}", scheme.to_ascii_lowercase())
+    }
+
+    /// Returns true if two origins are same site (same scheme and same site host).
+    pub fn is_same_site(&self, a: (&str, &str), b: (&str, &str), opts: MatchOpts<'_>) -> bool {
+        self.site_for_origin(a.0, a.1, opts) == self.site_for_origin(b.0, b.1, opts)
+    }
+
+    /// Returns true if two hosts are "schemelessly same site".
+    ///
+    /// Hosts without a registrable domain are compared as-is; otherwise their
+    /// registrable domains must be equal.
+    pub fn is_schemelessly_same_site(&self, a: &str, b: &str, opts: MatchOpts<'_>) -> bool {
+        match (
+            self.registrable_domain(a, opts),
+            self.registrable_domain(b, opts),
+        ) {
+            (Some(x), Some(y)) => x == y,
+            (None, None) => matches!(
+                (normalize_view(a, opts), normalize_view(b, opts)),
+                (Some(x), Some(y)) if x == y
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the origin `(scheme, host)` belongs to `site`, as
+    /// produced by [`List::site_for_origin`].
+    pub fn origin_in_site(
+        &self,
+        site: &str,
+        scheme: &str,
+        host: &str,
+        opts: MatchOpts<'_>,
+    ) -> bool {
+        self.site_for_origin(scheme, host, opts) == site
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list() -> List {
+        "com\nuk\nco.uk\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn registrable_domain_follows_url_standard() {
+        let l = list();
+        let m = MatchOpts::default();
+        assert_eq!(
+            l.registrable_domain("www.Example.co.uk", m).as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(l.registrable_domain("co.uk", m), None);
+        assert_eq!(l.registrable_domain("github.io", m), None);
+        assert_eq!(
+            l.registrable_domain("a.b.example.local", m).as_deref(),
+            Some("example.local")
+        );
+        assert_eq!(l.registrable_domain("127.0.0.1", m), None);
+        assert_eq!(l.registrable_domain("[::1]", m), None);
+        assert_eq!(l.registrable_domain("", m), None);
+    }
+
+    #[test]
+    fn site_for_origin_and_same_site() {
+        let l = list();
+        let m = MatchOpts::default();
+        assert_eq!(
+            l.site_for_origin("HTTPS", "a.b.example.com", m),
+            "https://example.com"
+        );
+        assert_eq!(
+            l.site_for_origin("https", "github.io", m),
+            "https://github.io"
+        );
+        assert_eq!(l.site_for_origin("http", "10.0.0.1", m), "http://10.0.0.1");
+
+        assert!(l.is_same_site(("https", "a.example.com"), ("https", "b.example.com"), m));
+        assert!(!l.is_same_site(("https", "a.example.com"), ("http", "a.example.com"), m));
+        assert!(!l.is_same_site(("https", "a.github.io"), ("https", "b.github.io"), m));
+
+        assert!(l.is_schemelessly_same_site("a.example.com", "example.com", m));
+        assert!(!l.is_schemelessly_same_site("example.com", "co.uk", m));
+        assert!(l.is_schemelessly_same_site("co.uk", "CO.UK", m));
+
+        assert!(l.origin_in_site("https://example.com", "https", "x.example.com", m));
+        assert!(!l.origin_in_site("https://example.com", "https", "x.example.org", m));
+    }
+}
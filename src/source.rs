@@ -0,0 +1,186 @@
+//! Well-known locations the Public Suffix List is published at.
+//!
+//! Hardcoded URLs drift, and mirrors misbehave in different ways (CDNs that
+//! answer with an HTML error page and status 200, caches that lag behind
+//! upstream by hours). A [`Source`] bundles a URL with the checks and polling
+//! cadence appropriate for it, so callers do not have to rediscover them.
+use crate::errors::{Error, Result};
+use crate::http;
+use core::time::Duration;
+
+const HOUR: u64 = 60 * 60;
+
+/// A location the PSL can be downloaded from, with its per-source quirks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Source<'a> {
+    name: &'a str,
+    url: &'a str,
+    requires_markers: bool,
+    min_interval: Duration,
+}
+
+impl<'a> Source<'a> {
+    /// The canonical list at publicsuffix.org.
+    ///
+    /// The maintainers ask consumers not to download it more than once a day.
+    pub const PUBLICSUFFIX_ORG: Source<'static> = Source {
+        name: "publicsuffix.org",
+        url: "https://publicsuffix.org/list/public_suffix_list.dat",
+        requires_markers: true,
+        min_interval: Duration::from_secs(24 * HOUR),
+    };
+
+    /// The `main` branch of `publicsuffix/list` served by GitHub raw.
+    ///
+    /// Updated within minutes of a merge; rate-limited per client IP.
+    pub const GITHUB_RAW: Source<'static> = Source {
+        name: "github-raw",
+        url: "https://raw.githubusercontent.com/publicsuffix/list/main/public_suffix_list.dat",
+        requires_markers: true,
+        min_interval: Duration::from_secs(HOUR),
+    };
+
+    /// The jsDelivr CDN mirror of `publicsuffix/list`.
+    ///
+    /// Branch URLs are cached by the CDN for up to 12 hours, and failures
+    /// can surface as HTML pages rather than HTTP errors.
+    pub const JSDELIVR: Source<'static> = Source {
+        name: "jsdelivr",
+        url: "https://cdn.jsdelivr.net/gh/publicsuffix/list@main/public_suffix_list.dat",
+        requires_markers: true,
+        min_interval: Duration::from_secs(12 * HOUR),
+    };
+
+    /// The canonical source (publicsuffix.org).
+    pub const fn official() -> Source<'static> {
+        Source::PUBLICSUFFIX_ORG
+    }
+
+    /// All built-in sources, in order of preference.
+    pub const fn mirrors() -> &'static [Source<'static>] {
+        &[
+            Source::PUBLICSUFFIX_ORG,
+            Source::GITHUB_RAW,
+            Source::JSDELIVR,
+        ]
+    }
+
+    /// A user-provided URL.
+    ///
+    /// Custom sources are only checked for HTML responses; section markers
+    /// are not required and no polling interval is imposed.
+    pub const fn custom(url: &'a str) -> Self {
+        Source {
+            name: "custom",
+            url,
+            requires_markers: false,
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    /// Short, stable name of the source.
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// URL the list is fetched from.
+    pub const fn url(&self) -> &'a str {
+        self.url
+    }
+
+    /// Shortest interval at which this source should be polled for updates.
+    pub const fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// Downloads the list text and checks that it looks like a PSL.
+    ///
+    /// Returns `Error::UnexpectedContent` if the body is an HTML page or,
+    /// for built-in sources, lacks the ICANN section marker.
+    pub fn fetch(&self) -> Result<String> {
+        let text = http::get(self.url)?;
+        self.validate(&text)?;
+        Ok(text)
+    }
+
+    fn validate(&self, text: &str) -> Result<()> {
+        let head = text.trim_start().get(..512).unwrap_or(text.trim_start());
+        let lower = head.to_ascii_lowercase();
+        let is_html =
+            lower.starts_with('<') || lower.contains("<!doctype html") || lower.contains("<html");
+        let missing_markers = self.requires_markers && !text.contains("BEGIN ICANN DOMAINS");
+        if is_html || missing_markers {
+            return Err(Error::UnexpectedContent {
+                url: self.url.into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Fetches from each source in turn, returning the first valid list text.
+///
+/// The error from the last source is returned if all of them fail.
+pub fn fetch_first(sources: &[Source<'_>]) -> Result<String> {
+    let mut last = Error::EmptyList;
+    for s in sources {
+        match s.fetch() {
+            Ok(text) => return Ok(text),
+            Err(e) => last = e,
+        }
+    }
+    Err(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[test]
+    fn builtin_sources_are_https_and_distinct() {
+        let all = Source::mirrors();
+        assert_eq!(all[0], Source::official());
+        for s in all {
+            assert!(s.url().starts_with("https://"));
+            assert!(s.url().ends_with("public_suffix_list.dat"));
+            assert!(s.min_interval() > Duration::ZERO);
+        }
+        assert_ne!(all[0].url(), all[1].url());
+        assert_ne!(all[1].url(), all[2].url());
+    }
+
+    #[test]
+    fn validate_rejects_html_and_missing_markers() {
+        let official = Source::official();
+        assert!(official
+            .validate("<!DOCTYPE html><html>rate limited</html>")
+            .is_err());
+        assert!(official.validate("com\nnet\n").is_err());
+        assert!(official
+            .validate("// ===BEGIN ICANN DOMAINS===\ncom\n")
+            .is_ok());
+
+        let custom = Source::custom("http://localhost/list");
+        assert!(custom.validate("com\nnet\n").is_ok());
+        assert!(custom.validate("  <html><body>404</body></html>").is_err());
+    }
+
+    #[test]
+    fn fetch_first_falls_through_to_next_source() {
+        let mut server = Server::new();
+        let _html = server
+            .mock("GET", "/a")
+            .with_body("<html>oops</html>")
+            .create();
+        let _ok = server.mock("GET", "/b").with_body("com\n").create();
+        let a = format!("{}/a", server.url());
+        let b = format!("{}/b", server.url());
+
+        let text = fetch_first(&[Source::custom(&a), Source::custom(&b)]).unwrap();
+        assert_eq!(text, "com\n");
+
+        let err = fetch_first(&[Source::custom(&a)]).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedContent { .. }));
+    }
+}
@@ -0,0 +1,191 @@
+//! Executable specification of `split` behavior.
+//!
+//! The table in `spec_table.txt` is embedded in the crate and lists hosts,
+//! match options and the expected prefix / SLL / SLD / TLD against the
+//! built-in list. [`List::check_against_spec`](crate::List::check_against_spec)
+//! runs it against a `List`; [`check_with`] runs it against any function with
+//! the same shape, so wrappers around this crate can be held to the same
+//! behavior.
+use crate::engine::Parts;
+use crate::options::MatchOpts;
+use crate::rules::TypeFilter;
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+
+const TABLE: &str = include_str!("spec_table.txt");
+
+/// One row of the specification table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecCase {
+    /// 1-based line of the row in the embedded table.
+    pub line: usize,
+    /// Host passed to `split`.
+    pub host: &'static str,
+    /// Option tokens, as written in the table (e.g. `raw+strict`).
+    pub opts: &'static str,
+    /// Expected result of `split`.
+    pub expected: Option<Parts<'static>>,
+}
+
+impl SpecCase {
+    /// The `MatchOpts` described by this row's option tokens.
+    pub fn match_opts(&self) -> MatchOpts<'static> {
+        parse_opts(self.opts).expect("embedded spec table has valid options")
+    }
+}
+
+/// A row whose actual result differs from the expected one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecFailure {
+    /// The failing row.
+    pub case: SpecCase,
+    /// What the implementation under test returned.
+    pub actual: Option<Parts<'static>>,
+}
+
+impl fmt::Display for SpecFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: split({:?}, {})",
+            self.case.line, self.case.host, self.case.opts
+        )?;
+        let (want, got) = match (&self.case.expected, &self.actual) {
+            (Some(w), Some(g)) => (w, g),
+            (w, g) => return write!(f, ": expected {w:?}, got {g:?}"),
+        };
+        let fields = [
+            ("prefix", want.prefix.as_deref(), got.prefix.as_deref()),
+            ("sll", want.sll.as_deref(), got.sll.as_deref()),
+            ("sld", want.sld.as_deref(), got.sld.as_deref()),
+            ("tld", Some(&*want.tld), Some(&*got.tld)),
+        ];
+        for (name, w, g) in fields {
+            if w != g {
+                write!(f, "; {name}: expected {w:?}, got {g:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of running the specification table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpecReport {
+    /// Number of rows evaluated.
+    pub total: usize,
+    /// Rows that did not produce the expected result.
+    pub failures: Vec<SpecFailure>,
+}
+
+impl SpecReport {
+    /// Returns true if every row matched.
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Returns every row of the embedded specification table.
+pub fn cases() -> Vec<SpecCase> {
+    TABLE
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .map(|(i, l)| parse_row(i + 1, l).expect("embedded spec table rows are well-formed"))
+        .collect()
+}
+
+/// Runs the table against `split`, an implementation of `List::split`.
+pub fn check_with<F>(mut split: F) -> SpecReport
+where
+    F: FnMut(&str, MatchOpts<'_>) -> Option<Parts<'static>>,
+{
+    let mut report = SpecReport::default();
+    for case in cases() {
+        let actual = split(case.host, case.match_opts());
+        report.total += 1;
+        if actual != case.expected {
+            report.failures.push(SpecFailure { case, actual });
+        }
+    }
+    report
+}
+
+fn parse_row(line: usize, row: &'static str) -> Option<SpecCase> {
+    let cols: Vec<&'static str> = row.split('|').map(str::trim).collect();
+    let [host, opts, prefix, sll, sld, tld] = cols[..] else {
+        return None;
+    };
+    parse_opts(opts)?;
+    let opt = |v: &'static str| (v != "-").then_some(Cow::Borrowed(v));
+    let expected = match opt(tld) {
+        Some(tld) => Some(Parts {
+            prefix: opt(prefix),
+            sll: opt(sll),
+            sld: opt(sld),
+            tld,
+        }),
+        None if [prefix, sll, sld].iter().all(|v| *v == "-") => None,
+        None => return None,
+    };
+    Some(SpecCase {
+        line,
+        host,
+        opts,
+        expected,
+    })
+}
+
+fn parse_opts(s: &str) -> Option<MatchOpts<'static>> {
+    let mut m = MatchOpts::default();
+    for tok in s.split('+') {
+        match tok {
+            "default" => {}
+            "raw" => m.normalizer = None,
+            "strict" => m.strict = true,
+            "nowild" => m.wildcard = false,
+            "icann" => m.types = TypeFilter::Icann,
+            "private" => m.types = TypeFilter::Private,
+            _ => return None,
+        }
+    }
+    Some(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_parses() {
+        let cases = cases();
+        assert!(cases.len() > 20);
+        assert!(cases.iter().any(|c| c.host.is_empty()));
+        assert!(cases.iter().any(|c| c.expected.is_none()));
+    }
+
+    #[test]
+    fn option_tokens_combine() {
+        let m = parse_opts("raw+strict+nowild+icann").unwrap();
+        assert!(m.normalizer.is_none() && m.strict && !m.wildcard);
+        assert_eq!(m.types, TypeFilter::Icann);
+        assert!(parse_opts("bogus").is_none());
+    }
+
+    #[test]
+    fn rows_reject_partial_none() {
+        assert!(parse_row(1, "a.com | default | - | - | a.com | -").is_none());
+        assert!(parse_row(1, "a.com | default | - | -").is_none());
+    }
+
+    #[test]
+    fn failure_display_lists_differing_fields() {
+        let case = parse_row(7, "a.b.com | default | a | b | b.com | com").unwrap();
+        let mut actual = case.expected.clone();
+        actual.as_mut().unwrap().sld = Some("a.b.com".into());
+        let msg = SpecFailure { case, actual }.to_string();
+        assert!(msg.starts_with("line 7: split(\"a.b.com\", default)"));
+        assert!(msg.contains("sld: expected Some(\"b.com\"), got Some(\"a.b.com\")"));
+        assert!(!msg.contains("tld:"));
+    }
+}
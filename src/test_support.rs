@@ -0,0 +1,297 @@
+//! Fixtures and assertions for tests of crates built on this one.
+//!
+//! Enabled by the `test-support` feature; add it to your dev-dependencies
+//! only:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! publicsuffix2 = { version = "*", features = ["test-support"] }
+//! ```
+//!
+//! ```rust
+//! use publicsuffix2::assert_split;
+//! use publicsuffix2::test_support::{fixture_list, icann_only};
+//!
+//! let list = fixture_list();
+//! assert_split!(list, "www.example.co.uk", Some("www"), Some("example"), Some("example.co.uk"), Some("co.uk"));
+//! assert_split!(list, "a.b.blogspot.com", icann_only(), Some("a.b"), Some("blogspot"), Some("blogspot.com"), Some("com"));
+//! ```
+use crate::engine::{normalize_view, Parts};
+use crate::options::{FallbackPolicy, LiteralPolicy, MatchOpts};
+use crate::rules::{Leaf, RuleRef, Type, TypeFilter};
+use crate::List;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+/// A small list covering each kind of rule: plain and multi-label ICANN
+/// suffixes, a wildcard with an exception, an IDN, and private suffixes.
+pub const FIXTURE: &str = "\
+// ===BEGIN ICANN DOMAINS===
+com
+net
+org
+uk
+co.uk
+jp
+kawasaki.jp
+*.kawasaki.jp
+!city.kawasaki.jp
+ck
+*.ck
+!www.ck
+中国
+// ===END ICANN DOMAINS===
+// ===BEGIN PRIVATE DOMAINS===
+blogspot.com
+github.io
+// ===END PRIVATE DOMAINS===
+";
+
+/// [`FIXTURE`] parsed with default `LoadOpts`.
+pub fn fixture_list() -> List {
+    FIXTURE
+        .parse()
+        .expect("the test-support fixture list parses")
+}
+
+/// `MatchOpts::default()`: wildcards on, non-strict, both sections, PS2
+/// normalization.
+pub fn defaults() -> MatchOpts<'static> {
+    MatchOpts::default()
+}
+
+/// Like [`defaults`], but hosts without a matching rule yield `None`.
+pub fn strict() -> MatchOpts<'static> {
+    MatchOpts {
+        strict: true,
+        ..MatchOpts::default()
+    }
+}
+
+/// Like [`defaults`], but only ICANN rules match.
+pub fn icann_only() -> MatchOpts<'static> {
+    MatchOpts {
+        types: TypeFilter::Icann,
+        ..MatchOpts::default()
+    }
+}
+
+/// Like [`defaults`], but only private rules match.
+pub fn private_only() -> MatchOpts<'static> {
+    MatchOpts {
+        types: TypeFilter::Private,
+        ..MatchOpts::default()
+    }
+}
+
+/// Like [`defaults`], but wildcard rules are ignored.
+pub fn no_wildcard() -> MatchOpts<'static> {
+    MatchOpts {
+        wildcard: false,
+        ..MatchOpts::default()
+    }
+}
+
+/// Hosts are matched exactly as given, with no normalization.
+pub fn raw() -> MatchOpts<'static> {
+    MatchOpts::raw()
+}
+
+/// Asserts `list.split(host, opts)`; the implementation of [`assert_split!`].
+///
+/// `want` is `[prefix, sll, sld, tld]`. A lookup returning `None` matches
+/// only four `None`s.
+#[track_caller]
+pub fn check_split(list: &List, host: &str, opts: MatchOpts<'_>, want: [Option<&str>; 4]) {
+    let parts = list.split(host, opts);
+    let got = match &parts {
+        Some(p) => [
+            p.prefix.as_deref(),
+            p.sll.as_deref(),
+            p.sld.as_deref(),
+            Some(&*p.tld),
+        ],
+        None => [None; 4],
+    };
+    assert_eq!(got, want, "split({host:?}) as [prefix, sll, sld, tld]");
+}
+
+/// Asserts the parts of `$list.split($host, opts)`.
+///
+/// Each expected part is an `Option<&str>`. Options default to
+/// `MatchOpts::default()`; pass them as the third argument to override.
+///
+/// ```rust
+/// use publicsuffix2::{assert_split, test_support::{fixture_list, strict}};
+///
+/// let list = fixture_list();
+/// assert_split!(list, "github.io", None, None, Some("github.io"), Some("github.io"));
+/// assert_split!(list, "example.invalid", strict(), None, None, None, None);
+/// ```
+#[macro_export]
+macro_rules! assert_split {
+    ($list:expr, $host:expr, $prefix:expr, $sll:expr, $sld:expr, $tld:expr $(,)?) => {
+        $crate::assert_split!(
+            $list,
+            $host,
+            $crate::MatchOpts::default(),
+            $prefix,
+            $sll,
+            $sld,
+            $tld
+        )
+    };
+    ($list:expr, $host:expr, $opts:expr, $prefix:expr, $sll:expr, $sld:expr, $tld:expr $(,)?) => {
+        $crate::test_support::check_split(&$list, $host, $opts, [$prefix, $sll, $sld, $tld])
+    };
+}
+
+impl List {
+    /// Splits `host` like [`List::split`], by a slow, direct search of the
+    /// rules, to check the storage engines against.
+    ///
+    /// It follows the same matching rules as the engines: hosts are
+    /// normalized the same way, labels are matched right to left, a named
+    /// label is preferred over `*`, and a label that only leads to deeper
+    /// rules still counts as a suffix. But it keeps no trie: each step looks
+    /// through every rule, so it is only fit for small lists.
+    /// `opts.match_both_forms` is not supported.
+    pub fn reference_split(&self, host: &str, opts: MatchOpts<'_>) -> Option<Parts<'static>> {
+        let rules: Vec<RuleRef> = self.rules().collect();
+        let view = normalize_view(host, opts)?;
+        let start = reference_suffix(&rules, &view, opts)?;
+        Some(reference_parts(&rules, &view, start, opts))
+    }
+}
+
+/// What the rules say about the path of labels `path` (rightmost first):
+/// the kind and section of the rule naming it, `(Leaf::None, None)` if it
+/// only leads to deeper rules, or `None` if no rule goes through it.
+fn reference_node(rules: &[RuleRef], path: &[&str]) -> Option<(Leaf, Option<Type>)> {
+    let mut found = None;
+    for rule in rules {
+        let rev: Vec<&str> = rule.labels.iter().rev().map(String::as_str).collect();
+        if rev.len() == path.len() && rev == path {
+            return Some((rule.leaf, rule.typ));
+        }
+        if rev.len() > path.len() && rev[..path.len()] == *path {
+            found = Some((Leaf::None, None));
+        }
+    }
+    found
+}
+
+/// Returns true if some rule is longer than `path` and ends with it.
+fn has_rules_below(rules: &[RuleRef], path: &[&str]) -> bool {
+    rules.iter().any(|rule| {
+        rule.labels.len() > path.len() && rule.labels.iter().rev().zip(path).all(|(l, p)| l == p)
+    })
+}
+
+/// Byte offset of the public suffix in the normalized host `s`.
+fn reference_suffix(rules: &[RuleRef], s: &str, opts: MatchOpts<'_>) -> Option<usize> {
+    if s.is_empty() || s.ends_with('.') || s.contains("..") {
+        return None;
+    }
+    if LiteralPolicy::is_literal(s) {
+        match opts.compat.literals(opts.literals) {
+            LiteralPolicy::Match => {}
+            LiteralPolicy::Reject => return None,
+            LiteralPolicy::Whole => return Some(0),
+        }
+    }
+    // Each label with its offset, right to left.
+    let mut labels = Vec::new();
+    let mut start = 0;
+    for label in s.split('.') {
+        labels.push((start, label));
+        start += label.len() + 1;
+    }
+    labels.reverse();
+
+    let mut path: Vec<&str> = Vec::new();
+    let mut best = None;
+    // Whether the last label was matched by a `*`.
+    let mut under_wildcard = false;
+    for (start, label) in labels {
+        path.push(label);
+        let mut node = reference_node(rules, &path);
+        if !opts.exceptions
+            && node.is_some_and(|(leaf, _)| leaf == Leaf::Negative)
+            && !has_rules_below(rules, &path)
+        {
+            node = None;
+        }
+        if node.is_none() && opts.wildcard {
+            path.pop();
+            path.push("*");
+            node = reference_node(rules, &path);
+            if node.is_none() && under_wildcard && opts.deep_wildcards {
+                // Stay on the `*` the previous label matched.
+                path.pop();
+                node = reference_node(rules, &path);
+            }
+            under_wildcard = true;
+        } else {
+            under_wildcard = false;
+        }
+        let Some((leaf, typ)) = node else {
+            break;
+        };
+        let ignored = typ == Some(Type::Private)
+            && opts
+                .ignored_private
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(&s[start..]));
+        if opts.types.accepts(typ) && !ignored && (leaf != Leaf::Negative || opts.exceptions) {
+            best = Some((start, leaf));
+        }
+    }
+    match best {
+        // An exception makes its parent the suffix.
+        Some((start, Leaf::Negative)) => Some(s[start..].find('.').map_or(0, |i| start + i + 1)),
+        Some((start, _)) => Some(start),
+        None if opts.strict => None,
+        None => match opts.fallback {
+            FallbackPolicy::None => None,
+            FallbackPolicy::LastLabel => Some(s.rfind('.').map_or(0, |i| i + 1)),
+            FallbackPolicy::EntireHost => Some(0),
+        },
+    }
+}
+
+/// The parts of the normalized host `s` whose suffix starts at `start`.
+fn reference_parts(
+    rules: &[RuleRef],
+    s: &str,
+    start: usize,
+    opts: MatchOpts<'_>,
+) -> Parts<'static> {
+    let owned = |p: &str| Cow::Owned(String::from(p));
+    let tld = &s[start..];
+    let listed = rules
+        .iter()
+        .any(|r| r.labels.last().is_some_and(|l| l == tld));
+    let whole = Parts {
+        prefix: None,
+        sll: None,
+        sld: Some(owned(tld)),
+        tld: owned(tld),
+    };
+    // The host is a suffix, or an unlisted TLD stands for itself.
+    if start == 0 || (opts.compat.collapses_unlisted() && !tld.contains('.') && !listed) {
+        return whole;
+    }
+    let labels: Vec<&str> = s[..start - 1].split('.').collect();
+    let (sll, prefix) = labels.split_last().expect("split yields a label");
+    if sll.is_empty() {
+        // A raw leading dot: there is no label left of the suffix.
+        return whole;
+    }
+    let prefix = prefix.join(".");
+    Parts {
+        prefix: (!prefix.is_empty()).then_some(Cow::Owned(prefix)),
+        sll: Some(owned(sll)),
+        sld: Some(owned(&s[start - 1 - sll.len()..])),
+        tld: owned(tld),
+    }
+}
@@ -0,0 +1,177 @@
+//! Host name validation (RFC 1035, RFC 5890).
+use crate::errors::HostError;
+use crate::options::MatchOpts;
+use crate::List;
+#[cfg(feature = "idna")]
+use alloc::borrow::Cow;
+
+/// Longest host, in octets, not counting a trailing dot.
+const MAX_HOST_LEN: usize = 253;
+/// Longest label, in octets.
+const MAX_LABEL_LEN: usize = 63;
+
+impl List {
+    /// Checks that `host` is a valid DNS host name.
+    ///
+    /// A single trailing dot is allowed. Every label must be 1 to 63 octets
+    /// of ASCII letters, digits and hyphens, not starting or ending with a
+    /// hyphen, and the host at most 253 octets. Control characters are
+    /// handled per `opts.controls`.
+    ///
+    /// Non-ASCII hosts are invalid unless `opts.normalizer` enables
+    /// `idna_ascii` (with the `idna` feature). Then the host, and any
+    /// `xn--` label, must pass UTS-46 processing, and the limits apply to the
+    /// A-label form; error offsets then refer to that form.
+    ///
+    /// Lookups do not require valid hosts; this is for callers that must
+    /// reject bad input before storing or comparing it.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{HostError, List, MatchOpts};
+    ///
+    /// let list = List::default();
+    /// assert_eq!(list.validate_host("www.example.com.", MatchOpts::default()), Ok(()));
+    /// assert_eq!(
+    ///     list.validate_host("-bad.example.com", MatchOpts::default()),
+    ///     Err(HostError::HyphenAtEdge { index: 0 })
+    /// );
+    /// ```
+    pub fn validate_host(&self, host: &str, opts: MatchOpts<'_>) -> Result<(), HostError> {
+        validate_host(host, opts)
+    }
+}
+
+pub(crate) fn validate_host(host: &str, opts: MatchOpts<'_>) -> Result<(), HostError> {
+    let host = opts.controls.apply(host)?;
+    #[cfg(feature = "idna")]
+    if opts.normalizer.is_some_and(|n| n.idna_ascii) && needs_idna(&host) {
+        let ascii: Cow<'_, str> = idna::domain_to_ascii(&host)
+            .map_err(|_| HostError::InvalidIdna)?
+            .into();
+        return check_ascii(&ascii);
+    }
+    check_ascii(&host)
+}
+
+/// Returns true if `host` has non-ASCII characters or an `xn--` label.
+#[cfg(feature = "idna")]
+fn needs_idna(host: &str) -> bool {
+    !host.is_ascii()
+        || host
+            .split('.')
+            .any(|l| l.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("xn--")))
+}
+
+/// The RFC 1035 checks proper: lengths, label syntax and characters.
+fn check_ascii(host: &str) -> Result<(), HostError> {
+    let name = host.strip_suffix('.').unwrap_or(host);
+    if name.is_empty() {
+        return Err(HostError::Empty);
+    }
+    if name.len() > MAX_HOST_LEN {
+        return Err(HostError::TooLong { len: name.len() });
+    }
+    let mut index = 0;
+    for label in name.split('.') {
+        if label.is_empty() {
+            return Err(HostError::EmptyLabel { index });
+        }
+        if let Some((i, ch)) = label
+            .char_indices()
+            .find(|&(_, c)| !(c.is_ascii_alphanumeric() || c == '-'))
+        {
+            return Err(HostError::IllegalChar {
+                index: index + i,
+                ch,
+            });
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(HostError::LabelTooLong {
+                index,
+                len: label.len(),
+            });
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(HostError::HyphenAtEdge { index });
+        }
+        index += label.len() + 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::ControlPolicy;
+    use alloc::string::String;
+
+    fn check(host: &str) -> Result<(), HostError> {
+        validate_host(host, MatchOpts::raw())
+    }
+
+    #[test]
+    fn accepts_dns_names() {
+        for host in [
+            "com",
+            "www.example.co.uk",
+            "EXAMPLE.com.",
+            "a-b.c0m",
+            "xn--p1ai",
+        ] {
+            assert_eq!(check(host), Ok(()), "{host}");
+        }
+        let label = "a".repeat(63);
+        let host = [label.as_str(); 3].join(".") + ".abcdefghijklmnopqrstuvwxyz0123456789";
+        assert_eq!(host.len(), 228);
+        assert_eq!(check(&host), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_first_problem() {
+        let long_label = String::from("x") + &"a".repeat(63) + ".com";
+        let long_host = ["abc"; 64].join(".");
+        for (host, err) in [
+            ("", HostError::Empty),
+            (".", HostError::Empty),
+            (".com", HostError::EmptyLabel { index: 0 }),
+            ("a..com", HostError::EmptyLabel { index: 2 }),
+            ("a.com..", HostError::EmptyLabel { index: 6 }),
+            (&long_label, HostError::LabelTooLong { index: 0, len: 64 }),
+            (&long_host, HostError::TooLong { len: 255 }),
+            ("a_b.com", HostError::IllegalChar { index: 1, ch: '_' }),
+            ("a.c m", HostError::IllegalChar { index: 3, ch: ' ' }),
+            ("é.com", HostError::IllegalChar { index: 0, ch: 'é' }),
+            ("a.-b.com", HostError::HyphenAtEdge { index: 2 }),
+            ("a.b-.com", HostError::HyphenAtEdge { index: 2 }),
+            ("a\nb.com", HostError::ControlChar { index: 1, ch: '\n' }),
+        ] {
+            assert_eq!(check(host), Err(err), "{host:?}");
+        }
+    }
+
+    #[test]
+    fn control_policy_applies() {
+        let strip = MatchOpts {
+            controls: ControlPolicy::Strip,
+            ..MatchOpts::raw()
+        };
+        assert_eq!(validate_host("exa\0mple.com", strip), Ok(()));
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna_hosts_need_idna_normalizer() {
+        let idna = crate::options::Normalizer::idna_only();
+        let opts = MatchOpts::with_normalizer(&idna);
+        assert_eq!(validate_host("www.食狮.中国", opts), Ok(()));
+        assert_eq!(validate_host("bücher.de", opts), Ok(()));
+        assert_eq!(
+            validate_host("xn--a.com", opts),
+            Err(HostError::InvalidIdna)
+        );
+        assert!(matches!(
+            check("www.食狮.中国"),
+            Err(HostError::IllegalChar { index: 4, .. })
+        ));
+    }
+}
@@ -0,0 +1,190 @@
+//! JavaScript bindings through `wasm-bindgen`.
+//!
+//! Built with the `wasm` feature, for browser extensions and edge workers.
+//! JavaScript sees a `List` class and a `Parts` result:
+//!
+//! ```js
+//! const list = await List.fromUrl("https://publicsuffix.org/list/public_suffix_list.dat");
+//! list.sld("www.example.co.uk"); // "example.co.uk"
+//! list.tld("www.example.co.uk"); // "co.uk"
+//! list.split("www.example.co.uk").prefix; // "www"
+//! ```
+//!
+//! Lookups use `MatchOpts::default()`. Loading from a URL goes through the
+//! host's global `fetch`, so it works in browsers, service workers and
+//! edge runtimes alike; from Rust, see [`List::from_url_fetch`].
+use crate::errors::{Error, Result};
+use crate::{LoadOpts, MatchOpts};
+use alloc::{borrow::Cow, format, string::String};
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `fetch` of browsers, workers and edge runtimes.
+    #[wasm_bindgen(js_name = fetch, catch)]
+    fn global_fetch(url: &str) -> core::result::Result<Promise, JsValue>;
+}
+
+/// Fetches `url` as text with the global `fetch`.
+pub(crate) async fn get(url: &str) -> Result<String> {
+    let fail = |e: JsValue| Error::Fetch(describe(&e).into());
+    let resp: Response = JsFuture::from(global_fetch(url).map_err(fail)?)
+        .await
+        .map_err(fail)?
+        .dyn_into()
+        .map_err(fail)?;
+    if !resp.ok() {
+        return Err(Error::Fetch(
+            format!("{url}: HTTP status {}", resp.status()).into(),
+        ));
+    }
+    let body = JsFuture::from(resp.text().map_err(fail)?)
+        .await
+        .map_err(fail)?;
+    body.as_string()
+        .ok_or_else(|| Error::Fetch(format!("{url}: response body is not text").into()))
+}
+
+/// A readable message for a thrown JavaScript value.
+fn describe(value: &JsValue) -> String {
+    match value.dyn_ref::<js_sys::Error>() {
+        Some(err) => err.message().into(),
+        None => value.as_string().unwrap_or_else(|| format!("{value:?}")),
+    }
+}
+
+fn owned(s: Option<Cow<'_, str>>) -> Option<String> {
+    s.map(Cow::into_owned)
+}
+
+/// A parsed Public Suffix List, exported to JavaScript as `List`.
+#[wasm_bindgen(js_name = List)]
+#[derive(Clone, Debug)]
+pub struct WasmList {
+    inner: crate::List,
+}
+
+#[wasm_bindgen(js_class = List)]
+impl WasmList {
+    /// Parses PSL text with `LoadOpts::default()`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: &str) -> core::result::Result<WasmList, JsError> {
+        Ok(Self {
+            inner: crate::List::parse_with(text, LoadOpts::default())?,
+        })
+    }
+
+    /// The built-in copy of the list.
+    #[wasm_bindgen(js_name = builtin)]
+    pub fn builtin() -> WasmList {
+        Self {
+            inner: crate::List::default(),
+        }
+    }
+
+    /// Fetches and parses the list at `url`.
+    #[wasm_bindgen(js_name = fromUrl)]
+    pub async fn from_url(url: String) -> core::result::Result<WasmList, JsError> {
+        Ok(Self {
+            inner: crate::List::from_url_fetch(&url).await?,
+        })
+    }
+
+    /// Registrable domain (eTLD+1) of `host`; see [`List::sld`](crate::List::sld).
+    pub fn sld(&self, host: &str) -> Option<String> {
+        owned(self.inner.sld(host, MatchOpts::default()))
+    }
+
+    /// Public suffix of `host`; see [`List::tld`](crate::List::tld).
+    pub fn tld(&self, host: &str) -> Option<String> {
+        owned(self.inner.tld(host, MatchOpts::default()))
+    }
+
+    /// `host` split into its parts; see [`List::split`](crate::List::split).
+    pub fn split(&self, host: &str) -> Option<WasmParts> {
+        let parts = self.inner.split(host, MatchOpts::default())?;
+        Some(WasmParts {
+            prefix: owned(parts.prefix),
+            sll: owned(parts.sll),
+            sld: owned(parts.sld),
+            tld: parts.tld.into_owned(),
+        })
+    }
+}
+
+impl From<crate::List> for WasmList {
+    fn from(inner: crate::List) -> Self {
+        Self { inner }
+    }
+}
+
+/// The parts of a host, exported to JavaScript as `Parts`.
+///
+/// Mirrors [`Parts`](crate::Parts) with owned strings.
+#[wasm_bindgen(js_name = Parts)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmParts {
+    prefix: Option<String>,
+    sll: Option<String>,
+    sld: Option<String>,
+    tld: String,
+}
+
+#[wasm_bindgen(js_class = Parts)]
+impl WasmParts {
+    /// Labels left of the registrable domain, if any.
+    #[wasm_bindgen(getter)]
+    pub fn prefix(&self) -> Option<String> {
+        self.prefix.clone()
+    }
+
+    /// The label immediately left of the public suffix.
+    #[wasm_bindgen(getter)]
+    pub fn sll(&self) -> Option<String> {
+        self.sll.clone()
+    }
+
+    /// The registrable domain (eTLD+1).
+    #[wasm_bindgen(getter)]
+    pub fn sld(&self) -> Option<String> {
+        self.sld.clone()
+    }
+
+    /// The public suffix.
+    #[wasm_bindgen(getter)]
+    pub fn tld(&self) -> String {
+        self.tld.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups_match_list() {
+        let list = WasmList::builtin();
+        assert_eq!(
+            list.sld("www.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(list.tld("www.example.co.uk").as_deref(), Some("co.uk"));
+        let parts = list.split("a.b.example.com").unwrap();
+        assert_eq!(parts.prefix().as_deref(), Some("a.b"));
+        assert_eq!(parts.sll().as_deref(), Some("example"));
+        assert_eq!(parts.sld().as_deref(), Some("example.com"));
+        assert_eq!(parts.tld(), "com");
+        assert_eq!(list.split(""), None);
+    }
+
+    #[test]
+    fn parses_custom_list() {
+        let list = WasmList::new("com\nuk\nco.uk").unwrap();
+        assert_eq!(list.tld("a.co.uk").as_deref(), Some("co.uk"));
+        assert_eq!(list.sld("b.a.co.uk").as_deref(), Some("a.co.uk"));
+    }
+}
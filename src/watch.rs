@@ -0,0 +1,366 @@
+//! Reloading a list when its file on disk changes.
+//!
+//! Changes are picked up from OS file notifications (through `notify`) on
+//! the file's directory, so a list rewritten in place or replaced by a
+//! rename is reloaded right away. Network filesystems do not deliver
+//! notifications, so the file is also polled every
+//! [`WatchOpts::poll_interval`]. Either way the list is only reloaded when
+//! the file's content changed: a notification leads to a content hash
+//! check, a poll first compares modification time and size.
+use crate::errors::{Error, Result};
+use crate::options::LoadOpts;
+use crate::List;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Options for [`List::watch_file_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct WatchOpts {
+    /// How often the file is checked without a notification. `None` relies
+    /// on notifications alone, and then fails to watch where they are not
+    /// available.
+    pub poll_interval: Option<Duration>,
+}
+
+impl Default for WatchOpts {
+    /// Polls every five seconds, as a fallback for notifications.
+    fn default() -> Self {
+        Self {
+            poll_interval: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+/// Watches a list file; see [`List::watch_file`].
+///
+/// Watching stops when the handle is dropped or [`stop`](Self::stop)ped.
+#[derive(Debug)]
+pub struct FileWatcher {
+    wake: Sender<Wake>,
+    notifier: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// What the watching thread is woken up for.
+#[derive(Debug)]
+enum Wake {
+    /// A notification about the watched file.
+    Changed,
+    /// The watcher is stopping.
+    Stop,
+}
+
+impl FileWatcher {
+    /// Stops watching, waiting for a reload in progress to finish.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.notifier.take();
+        let _ = self.wake.send(Wake::Stop);
+        if let Some(thread) = self.thread.take() {
+            // A panicking callback already ended the thread.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// What is compared before reading the file on a poll: modification time
+/// and size.
+type Stamp = (Option<SystemTime>, u64);
+
+fn stamp(path: &Path) -> std::io::Result<Stamp> {
+    let meta = fs::metadata(path)?;
+    Ok((meta.modified().ok(), meta.len()))
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The file as last seen by the watching thread.
+struct Seen {
+    stamp: Stamp,
+    hash: u64,
+}
+
+impl Seen {
+    fn read(path: &Path) -> std::io::Result<(Self, Vec<u8>)> {
+        let stamp = stamp(path)?;
+        let bytes = fs::read(path)?;
+        let hash = content_hash(&bytes);
+        Ok((Self { stamp, hash }, bytes))
+    }
+}
+
+impl List {
+    /// Reloads the list at `path` whenever the file changes, passing each
+    /// new list to `callback` on a background thread.
+    ///
+    /// The file must exist when watching starts; it is not loaded then, only
+    /// after it changes. A change that cannot be read or parsed (such as a
+    /// file caught half-written) is skipped, and the next change is tried
+    /// again. Pair it with a [`SharedList`](crate::SharedList) to serve
+    /// lookups from the latest list.
+    ///
+    /// Uses the default [`WatchOpts`]; see [`watch_file_with`](Self::watch_file_with).
+    ///
+    /// This method is only available when the `watch` feature is enabled.
+    ///
+    /// ```rust,no_run
+    /// use publicsuffix2::{List, LoadOpts, SharedList};
+    /// use std::sync::Arc;
+    ///
+    /// let path = "/etc/psl/public_suffix_list.dat";
+    /// let shared = Arc::new(SharedList::new(List::from_file(path)?));
+    /// let target = Arc::clone(&shared);
+    /// let _watcher = List::watch_file(path, LoadOpts::default(), move |list| target.store(list))?;
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn watch_file<P, F>(path: P, opts: LoadOpts, callback: F) -> Result<FileWatcher>
+    where
+        P: AsRef<Path>,
+        F: FnMut(List) + Send + 'static,
+    {
+        Self::watch_file_with(path, opts, WatchOpts::default(), callback)
+    }
+
+    /// Like [`watch_file`](Self::watch_file), with how the file is watched
+    /// set by `watch`.
+    ///
+    /// Where OS notifications are unavailable, the file is only polled; with
+    /// no `poll_interval` that is an `Error::Io`.
+    ///
+    /// This method is only available when the `watch` feature is enabled.
+    ///
+    /// ```rust,no_run
+    /// use publicsuffix2::{List, LoadOpts, WatchOpts};
+    /// use std::time::Duration;
+    ///
+    /// let watch = WatchOpts {
+    ///     poll_interval: Some(Duration::from_secs(60)),
+    /// };
+    /// let path = "/etc/psl/public_suffix_list.dat";
+    /// let _watcher = List::watch_file_with(path, LoadOpts::default(), watch, |list| {
+    ///     println!("reloaded {} rules", list.len());
+    /// })?;
+    /// # Ok::<(), publicsuffix2::Error>(())
+    /// ```
+    pub fn watch_file_with<P, F>(
+        path: P,
+        opts: LoadOpts,
+        watch: WatchOpts,
+        callback: F,
+    ) -> Result<FileWatcher>
+    where
+        P: AsRef<Path>,
+        F: FnMut(List) + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let (seen, _) = Seen::read(&path).map_err(Error::Io)?;
+        let (wake, woken) = mpsc::channel();
+        let notifier = match (notifier(&path, wake.clone()), watch.poll_interval) {
+            (Ok(notifier), _) => Some(notifier),
+            (Err(_), Some(_)) => None,
+            (Err(e), None) => return Err(Error::Io(std::io::Error::other(e))),
+        };
+        let thread = thread::Builder::new()
+            .name("psl-watch".into())
+            .spawn(move || run(&path, opts, watch.poll_interval, &woken, seen, callback))
+            .map_err(Error::Io)?;
+        Ok(FileWatcher {
+            wake,
+            notifier,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Watches the directory holding `path`, so that replacing the file by a
+/// rename is noticed too, and wakes the watching thread for events on it.
+fn notifier(path: &Path, wake: Sender<Wake>) -> notify::Result<RecommendedWatcher> {
+    let name = path.file_name().map(ToOwned::to_owned);
+    let mut notifier = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // An error may mean missed events; check the file to be safe.
+        let relevant = event.map_or(true, |event| {
+            !event.kind.is_access() && event.paths.iter().any(|p| p.file_name() == name.as_deref())
+        });
+        if relevant {
+            let _ = wake.send(Wake::Changed);
+        }
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    notifier.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(notifier)
+}
+
+fn run(
+    path: &Path,
+    opts: LoadOpts,
+    poll_interval: Option<Duration>,
+    woken: &Receiver<Wake>,
+    mut seen: Seen,
+    mut callback: impl FnMut(List),
+) {
+    loop {
+        let wake = match poll_interval {
+            Some(interval) => match woken.recv_timeout(interval) {
+                Ok(wake) => Some(wake),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            },
+            None => match woken.recv() {
+                Ok(wake) => Some(wake),
+                Err(_) => return,
+            },
+        };
+        let notified = match wake {
+            Some(Wake::Stop) => return,
+            Some(Wake::Changed) => {
+                // One write often comes as several events; check once.
+                for wake in woken.try_iter() {
+                    if let Wake::Stop = wake {
+                        return;
+                    }
+                }
+                true
+            }
+            None => false,
+        };
+        if !notified && stamp(path).is_ok_and(|now| now == seen.stamp) {
+            continue;
+        }
+        let Ok((now, bytes)) = Seen::read(path) else {
+            // Missing for now, e.g. between unlink and rename.
+            continue;
+        };
+        let changed = now.hash != seen.hash;
+        seen = now;
+        if !changed {
+            continue;
+        }
+        let list = core::str::from_utf8(&bytes)
+            .map_err(|_| Error::NotUtf8)
+            .and_then(|text| List::parse_with(text, opts));
+        if let Ok(list) = list {
+            callback(list);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchOpts;
+    use std::sync::mpsc;
+
+    #[test]
+    fn reloads_on_change_and_stops() {
+        let dir = std::env::temp_dir().join(format!("psl-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.dat");
+        fs::write(&path, "com\n").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = List::watch_file(&path, LoadOpts::default(), move |list| {
+            let _ = tx.send(list);
+        })
+        .unwrap();
+        let wait = || rx.recv_timeout(Duration::from_secs(10));
+
+        fs::write(&path, "com\nuk\nco.uk\n").unwrap();
+        let list = wait().unwrap();
+        assert_eq!(
+            list.tld("a.co.uk", MatchOpts::default()).as_deref(),
+            Some("co.uk")
+        );
+
+        // Replaced by a rename, as config management tools do.
+        let tmp = dir.join("list.dat.tmp");
+        fs::write(&tmp, "com\nexample.com\n").unwrap();
+        fs::rename(&tmp, &path).unwrap();
+        let list = wait().unwrap();
+        assert_eq!(
+            list.tld("a.example.com", MatchOpts::default()).as_deref(),
+            Some("example.com")
+        );
+
+        watcher.stop();
+        assert!(wait().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unchanged_content_is_not_reloaded() {
+        let dir = std::env::temp_dir().join(format!("psl-watch-same-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.dat");
+        fs::write(&path, "com\n").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watch = WatchOpts {
+            poll_interval: Some(Duration::from_millis(20)),
+        };
+        let watcher = List::watch_file_with(&path, LoadOpts::default(), watch, move |list| {
+            let _ = tx.send(list);
+        })
+        .unwrap();
+
+        // Rewritten with the same bytes: notified, and its mtime may change,
+        // but there is nothing to reload.
+        fs::write(&path, "com\n").unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        fs::write(&path, "com\nuk\n").unwrap();
+        let list = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(list.len(), 2);
+        watcher.stop();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn notifications_alone() {
+        let dir = std::env::temp_dir().join(format!("psl-watch-notify-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.dat");
+        fs::write(&path, "com\n").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watch = WatchOpts {
+            poll_interval: None,
+        };
+        let watcher = List::watch_file_with(&path, LoadOpts::default(), watch, move |list| {
+            let _ = tx.send(list);
+        })
+        .unwrap();
+        fs::write(&path, "com\nuk\n").unwrap();
+        let list = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(list.len(), 2);
+        watcher.stop();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file() {
+        let result = List::watch_file("/nonexistent/list.dat", LoadOpts::default(), |_| {});
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+}
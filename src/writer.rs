@@ -0,0 +1,101 @@
+//! Writing a `List` back out as PSL text.
+use crate::options::WriteOpts;
+use crate::rules::{RuleMeta, RuleRef, Type};
+use crate::List;
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+impl List {
+    /// The list as PSL text, in a canonical form.
+    ///
+    /// Rules are written one per line, sorted as [`List::rules`] returns
+    /// them, with `*` and `!` syntax. Unclassified rules come first, then
+    /// the ICANN and private sections (empty sections are left out).
+    /// Parsing the text with `LoadOpts::default()` gives back the same
+    /// rules and annotations. Other comments of the original text are not
+    /// kept.
+    ///
+    /// ```rust
+    /// use publicsuffix2::{List, ListBuilder, Type, WriteOpts};
+    ///
+    /// let (list, _) = ListBuilder::new()
+    ///     .add_rule_as("corp.example", Type::Private)
+    ///     .add_rule_as("example", Type::Icann)
+    ///     .finish()
+    ///     .unwrap();
+    /// let text = list.to_psl_string(WriteOpts::default());
+    /// assert_eq!(
+    ///     text,
+    ///     "// ===BEGIN ICANN DOMAINS===\nexample\n// ===END ICANN DOMAINS===\n\n\
+    ///      // ===BEGIN PRIVATE DOMAINS===\ncorp.example\n// ===END PRIVATE DOMAINS===\n"
+    /// );
+    /// ```
+    pub fn to_psl_string(&self, opts: WriteOpts<'_>) -> String {
+        let mut out = String::new();
+        for line in opts.header {
+            out.push_str("// ");
+            out.push_str(line);
+            out.push('\n');
+        }
+        if !opts.header.is_empty() {
+            out.push('\n');
+        }
+
+        let mut groups: [Vec<RuleRef>; 3] = Default::default();
+        for rule in self.rules() {
+            let group = match rule.typ {
+                _ if !opts.sections => 0,
+                None => 0,
+                Some(Type::Icann) => 1,
+                Some(Type::Private) => 2,
+            };
+            groups[group].push(rule);
+        }
+
+        let meta: BTreeMap<&str, &RuleMeta> = if opts.annotations {
+            self.meta.iter().map(|m| (m.rule.as_str(), m)).collect()
+        } else {
+            BTreeMap::new()
+        };
+        let mut first = true;
+        for (group, rules) in groups.iter().enumerate() {
+            if rules.is_empty() {
+                continue;
+            }
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            let section = ["", "ICANN", "PRIVATE"][group];
+            if !section.is_empty() {
+                let _ = writeln!(out, "// ===BEGIN {section} DOMAINS===");
+            }
+            for rule in rules {
+                let text = rule.to_string();
+                if let Some(meta) = meta.get(text.as_str()) {
+                    write_annotations(&mut out, meta);
+                }
+                out.push_str(&text);
+                out.push('\n');
+            }
+            if !section.is_empty() {
+                let _ = writeln!(out, "// ===END {section} DOMAINS===");
+            }
+        }
+        out
+    }
+}
+
+/// Writes the annotation comments of one rule.
+fn write_annotations(out: &mut String, meta: &RuleMeta) {
+    if let Some(date) = &meta.expires {
+        let _ = writeln!(out, "// @expires {date}");
+    }
+    if let Some(owner) = &meta.owner {
+        let _ = writeln!(out, "// @owner {owner}");
+    }
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "cli")]
+
+use mockito::Server;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn psl(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_psl"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(out: &Output) -> &str {
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    std::str::from_utf8(&out.stdout).unwrap()
+}
+
+#[test]
+fn tld_and_sld_for_arguments() {
+    let out = psl(&["tld", "www.example.co.uk", "x.github.io"], "");
+    assert_eq!(stdout(&out), "co.uk\ngithub.io\n");
+    let out = psl(&["--icann", "sld", "x.github.io"], "");
+    assert_eq!(stdout(&out), "github.io\n");
+    let out = psl(&["--strict", "sld", "example.invalid", "a.example.com"], "");
+    assert_eq!(stdout(&out), "\nexample.com\n");
+}
+
+#[test]
+fn split_reads_stdin_in_bulk() {
+    let out = psl(&["split"], "www.example.co.uk\n\n");
+    assert_eq!(
+        stdout(&out),
+        "www.example.co.uk\twww\texample\texample.co.uk\tco.uk\n\t-\t-\t-\t-\n"
+    );
+    let out = psl(&["split", "--json", "a.com", "-"], "b.c.com\n");
+    let lines: Vec<serde_json::Value> = stdout(&out)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["host"], "a.com");
+    assert_eq!(lines[0]["prefix"], serde_json::Value::Null);
+    assert_eq!(lines[1]["prefix"], "b");
+    assert_eq!(lines[1]["sld"], "c.com");
+}
+
+#[test]
+fn custom_list_file() {
+    let path = std::env::temp_dir().join(format!("psl-cli-{}.dat", std::process::id()));
+    std::fs::write(&path, "com\nuk\nco.uk\n").unwrap();
+    let out = psl(&["--list", path.to_str().unwrap(), "tld", "a.b.ck"], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(stdout(&out), "ck\n");
+}
+
+#[test]
+fn fetch_writes_the_list() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/list.dat")
+        .with_status(200)
+        .with_body("com\nuk\nco.uk\n")
+        .create();
+    let path = std::env::temp_dir().join(format!("psl-cli-fetch-{}.dat", std::process::id()));
+    let url = format!("{}/list.dat", server.url());
+    let out = psl(
+        &["fetch", "--url", &url, "--out", path.to_str().unwrap()],
+        "",
+    );
+    stdout(&out);
+    mock.assert();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "com\nuk\nco.uk\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn usage_errors_exit_with_2() {
+    for args in [&["bogus"][..], &["--nope", "tld"], &["--list"]] {
+        let out = psl(args, "");
+        assert_eq!(out.status.code(), Some(2), "{args:?}");
+        assert!(String::from_utf8_lossy(&out.stderr).contains("usage: psl"));
+    }
+    let out = psl(&["--list", "/nonexistent/list.dat", "tld", "a.com"], "");
+    assert_eq!(out.status.code(), Some(1));
+}
@@ -0,0 +1,33 @@
+// The global list is process-wide state, so everything touching it lives in
+// this binary, sequenced within a single test.
+#![cfg(feature = "std")]
+use publicsuffix2::{Error, List, MatchOpts};
+
+#[test]
+fn init_global_with_then_try_global() {
+    // A failing loader leaves the global list uninitialized.
+    let err = List::init_global_with(|| "".parse()).unwrap_err();
+    assert!(matches!(err, Error::EmptyList));
+
+    // The first successful initializer wins.
+    let list = List::init_global_with(|| "com\nexample".parse()).unwrap();
+    assert_eq!(
+        list.tld("foo.example", MatchOpts::default()).as_deref(),
+        Some("example")
+    );
+
+    // Later initializers are rejected and do not run.
+    let err = List::init_global_with(|| panic!("must not run")).unwrap_err();
+    assert!(matches!(err, Error::GlobalAlreadyInitialized));
+
+    // try_global, global and default all observe the injected list.
+    let g = List::try_global().unwrap();
+    assert!(std::ptr::eq(g, List::global()));
+    assert!(std::ptr::eq(g, List::global_ref()));
+    assert_eq!(
+        List::default()
+            .sld("a.b.example", MatchOpts::default())
+            .as_deref(),
+        Some("b.example")
+    );
+}
@@ -0,0 +1,29 @@
+// The global list is process-wide state: this binary checks initialization
+// from a path, separately from `test_global.rs`.
+#![cfg(feature = "std")]
+use publicsuffix2::{Error, List, MatchOpts};
+
+#[test]
+fn init_global_from_path_then_set_global() {
+    let err = List::init_global_from("tests/fixtures/missing.dat").unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+
+    let path = std::env::temp_dir().join(format!("psl-global-{}.dat", std::process::id()));
+    std::fs::write(&path, "com\ninternal").unwrap();
+    let list = List::init_global_from(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        list.tld("a.b.internal", MatchOpts::default()).as_deref(),
+        Some("internal")
+    );
+    assert!(std::ptr::eq(list, List::global()));
+
+    let err = List::set_global("org".parse().unwrap()).unwrap_err();
+    assert!(matches!(err, Error::GlobalAlreadyInitialized));
+    assert_eq!(
+        List::global()
+            .tld("a.b.internal", MatchOpts::default())
+            .as_deref(),
+        Some("internal")
+    );
+}
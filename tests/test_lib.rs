@@ -21,6 +21,7 @@ const NORM_NO_IDNA: Normalizer = Normalizer {
     lowercase: true,
     strip_trailing_dot: true,
     idna_ascii: false,
+    ..Normalizer::raw()
 };
 fn m_no_idna() -> MatchOpts<'static> {
     MatchOpts {
@@ -1696,6 +1697,32 @@ mod from_url {
         assert!(result.is_ok(), "Failed to load from URL: {:?}", result);
         let list = result.unwrap();
         assert_eq!(list.tld("example.com", m()).as_deref(), Some("com"));
+        let info = list.version_info().unwrap();
+        assert_eq!(info.url.as_deref(), Some(url.as_str()));
+        assert_eq!(info.date.as_deref(), Some("2025-09-23"));
+    }
+
+    #[test]
+    fn test_from_url_gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&fs::read(PSL_FILE_PATH).unwrap()).unwrap();
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/list.dat")
+            .match_header("accept-encoding", mockito::Matcher::Regex("gzip".into()))
+            .with_header("content-encoding", "gzip")
+            .with_body(gz.finish().unwrap())
+            .create();
+
+        let list = List::from_url(&format!("{}/list.dat", server.url())).unwrap();
+        mock.assert();
+        assert_eq!(
+            list.sld("a.b.example.co.uk", m()).as_deref(),
+            Some("example.co.uk")
+        );
     }
 
     #[test]
@@ -1712,6 +1739,216 @@ mod from_url {
     }
 }
 
+#[cfg(feature = "fetch")]
+mod fetch_opts {
+    use super::*;
+    use mockito::Server;
+    use publicsuffix2::{Error, FetchOpts, List, LoadOpts};
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    fn quick(retries: u32) -> FetchOpts<'static> {
+        FetchOpts {
+            retries,
+            backoff: Duration::from_millis(10),
+            ..FetchOpts::default()
+        }
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let mut server = Server::new();
+        let failing = server
+            .mock("GET", "/list.dat")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let ok = server.mock("GET", "/list.dat").with_body(PSL).create();
+
+        let url = format!("{}/list.dat", server.url());
+        let list = List::from_url_with(&url, LoadOpts::default(), quick(2)).unwrap();
+        failing.assert();
+        ok.assert();
+        assert_eq!(list.tld("example.com", m()).as_deref(), Some("com"));
+    }
+
+    #[test]
+    fn gives_up_after_retries() {
+        let mut server = Server::new();
+        let failing = server
+            .mock("GET", "/list.dat")
+            .with_status(429)
+            .expect(3)
+            .create();
+        let url = format!("{}/list.dat", server.url());
+        let result = List::from_url_with(&url, LoadOpts::default(), quick(2));
+        failing.assert();
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let mut server = Server::new();
+        let missing = server
+            .mock("GET", "/list.dat")
+            .with_status(404)
+            .expect(1)
+            .create();
+        let url = format!("{}/list.dat", server.url());
+        let result = List::from_url_with(&url, LoadOpts::default(), quick(3));
+        missing.assert();
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+
+    #[test]
+    fn sends_user_agent() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/list.dat")
+            .match_header("user-agent", "psl-test/1.0")
+            .with_body(PSL)
+            .create();
+        let fetch = FetchOpts {
+            user_agent: Some("psl-test/1.0"),
+            ..FetchOpts::default()
+        };
+        let url = format!("{}/list.dat", server.url());
+        assert!(List::from_url_with(&url, LoadOpts::default(), fetch).is_ok());
+        mock.assert();
+    }
+
+    #[test]
+    fn times_out() {
+        // Accepted by the OS but never answered.
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/list.dat", silent.local_addr().unwrap());
+        let fetch = FetchOpts {
+            timeout: Some(Duration::from_millis(200)),
+            ..FetchOpts::default()
+        };
+        let start = Instant::now();
+        let result = List::from_url_with(&url, LoadOpts::default(), fetch);
+        assert!(matches!(result, Err(Error::Fetch(_))));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn invalid_proxy() {
+        let fetch = FetchOpts {
+            proxy: Some("bogus://proxy"),
+            ..FetchOpts::default()
+        };
+        let result = List::from_url_with("http://127.0.0.1:9/", LoadOpts::default(), fetch);
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+}
+
+#[cfg(feature = "fetch")]
+mod from_url_cached {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use publicsuffix2::{Error, List, LoadOpts};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn cache_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("psl-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn refreshes_only_when_changed() {
+        let mut server = Server::new();
+        let url = format!("{}/list.dat", server.url());
+        let cache = cache_path("etag.dat");
+
+        let first = server
+            .mock("GET", "/list.dat")
+            .match_header("if-none-match", Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "\"v1\"")
+            .with_body("com\nuk\nco.uk")
+            .create();
+        let (list, refreshed) = List::from_url_cached(&url, &cache, LoadOpts::default()).unwrap();
+        first.assert();
+        assert!(refreshed);
+        assert_eq!(list.tld("a.co.uk", m()).as_deref(), Some("co.uk"));
+
+        let not_modified = server
+            .mock("GET", "/list.dat")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create();
+        let (list, refreshed) = List::from_url_cached(&url, &cache, LoadOpts::default()).unwrap();
+        not_modified.assert();
+        assert!(!refreshed);
+        assert_eq!(list.tld("a.co.uk", m()).as_deref(), Some("co.uk"));
+        assert_eq!(list.version_info().unwrap().url.as_deref(), Some(&*url));
+        fs::remove_file(&cache).unwrap();
+    }
+
+    #[test]
+    fn bad_download_keeps_cache() {
+        let mut server = Server::new();
+        let url = format!("{}/list.dat", server.url());
+        let cache = cache_path("keep.dat");
+        fs::write(&cache, "com").unwrap();
+
+        let m = server
+            .mock("GET", "/list.dat")
+            .with_status(200)
+            .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_body("")
+            .create();
+        let result = List::from_url_cached(&url, &cache, LoadOpts::default());
+        m.assert();
+        assert!(matches!(result, Err(Error::EmptyList)));
+        assert_eq!(fs::read_to_string(&cache).unwrap(), "com");
+        fs::remove_file(&cache).unwrap();
+    }
+}
+
+#[cfg(feature = "fetch-async")]
+mod from_url_async {
+    use super::*;
+    use mockito::Server;
+    use publicsuffix2::{Error, List, LoadOpts};
+
+    #[tokio::test]
+    async fn test_from_url_async_ok() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/list.dat")
+            .with_status(200)
+            .with_body(PSL)
+            .create_async()
+            .await;
+
+        let url = format!("{}/list.dat", server.url());
+        let list = List::from_url_async(&url).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(list.tld("example.co.uk", m()).as_deref(), Some("co.uk"));
+    }
+
+    #[tokio::test]
+    async fn test_from_url_async_with_http_error() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/list.dat")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let url = format!("{}/list.dat", server.url());
+        let result = List::from_url_async_with(&url, LoadOpts::default()).await;
+
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+}
+
 mod from_str {
     use super::*;
 
@@ -1726,6 +1963,41 @@ mod from_str {
         assert_eq!(list.tld("example.co.uk", m()).as_deref(), Some("co.uk"));
     }
 
+    #[test]
+    fn test_parse_lowercases_rules_and_warns() {
+        use publicsuffix2::{LoadOpts, Warning};
+
+        let opts = LoadOpts {
+            collect_warnings: true,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with("com\nExample.COM", opts).unwrap();
+        assert_eq!(
+            list.tld("foo.example.com", m()).as_deref(),
+            Some("example.com")
+        );
+        assert!(matches!(
+            list.warnings(),
+            [Warning::RuleCaseNormalized { rule }] if rule == "Example.COM"
+        ));
+
+        // Without collection, rules are still lowercased but nothing is recorded.
+        let list = List::parse("com\nExample.COM").unwrap();
+        assert!(list.warnings().is_empty());
+        assert_eq!(
+            list.tld("foo.example.com", m()).as_deref(),
+            Some("example.com")
+        );
+
+        // Opting out keeps the rule verbatim.
+        let verbatim = LoadOpts {
+            lowercase_rules: false,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with("com\nExample.COM", verbatim).unwrap();
+        assert_eq!(list.tld("foo.example.com", m()).as_deref(), Some("com"));
+    }
+
     #[test]
     fn test_parse_err_empty() {
         let psl_data = "";
@@ -1735,20 +2007,2912 @@ mod from_str {
     }
 }
 
-mod default {
+mod budget {
     use super::*;
-    use publicsuffix2::List;
+    use publicsuffix2::{Engine, Error, List, LoadOpts};
 
     #[test]
-    fn test_default_list() {
-        // List::default() should give a working list based on the embedded PSL.
-        let list = List::default();
-        // Check a few common TLDs to ensure it's parsed correctly.
-        assert_eq!(list.tld("example.com", m()).as_deref(), Some("com"));
-        assert_eq!(list.tld("example.co.uk", m()).as_deref(), Some("co.uk"));
+    fn generous_budget_keeps_trie() {
+        let (list, engine) = List::parse_with_budget(PSL, LoadOpts::default(), usize::MAX).unwrap();
+        assert_eq!(engine, Engine::Trie);
+        assert_eq!(list.engine(), Engine::Trie);
+    }
+
+    #[test]
+    fn tight_budget_selects_flat_with_same_results() {
+        let trie = list();
+        let flat_size = trie.clone().with_engine(Engine::Flat).approx_bytes();
+        assert!(flat_size < trie.approx_bytes());
+
+        let (flat, engine) = List::parse_with_budget(PSL, LoadOpts::default(), flat_size).unwrap();
+        assert_eq!(engine, Engine::Flat);
+        assert_eq!(flat.approx_bytes(), flat_size);
+        for host in [
+            "www.example.co.uk",
+            "a.b.ck",
+            "foo.city.kawasaki.jp",
+            "x.github.io",
+            "食狮.中国",
+        ] {
+            assert_eq!(flat.split(host, m()), trie.split(host, m()), "{host}");
+        }
+    }
+
+    #[test]
+    fn impossible_budget_is_an_error() {
+        let err = List::parse_with_budget(PSL, LoadOpts::default(), 16).unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded { budget: 16, .. }));
+    }
+
+    #[test]
+    fn budget_error_reports_the_smallest_engine() {
+        let smallest = list().compile().approx_bytes();
+        let err = List::parse_with_budget(PSL, LoadOpts::default(), smallest - 1).unwrap_err();
+        assert!(
+            matches!(err, Error::BudgetExceeded { needed, .. } if needed == smallest),
+            "{err}"
+        );
+    }
+}
+
+#[cfg(feature = "fst")]
+mod compact {
+    use super::*;
+    use publicsuffix2::{Engine, LoadOpts};
+
+    /// A host under every rule of the built-in list, plus a few odd ones.
+    fn hosts() -> Vec<String> {
+        let mut hosts: Vec<String> = PSL
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with("//"))
+            .map(|rule| format!("a.b.{}", rule.trim_start_matches('!').replace('*', "w")))
+            .collect();
+        hosts.extend(
+            [
+                "",
+                "com",
+                "a..b",
+                "example.local",
+                "WWW.Example.COM.",
+                "c.co",
+            ]
+            .map(String::from),
+        );
+        hosts
+    }
+
+    #[test]
+    fn compiled_list_matches_trie_on_every_rule() {
+        let trie = list();
+        let compact = list().compile();
+        assert_eq!(compact.engine(), Engine::Compact);
+        for host in hosts() {
+            assert_eq!(compact.split(&host, m()), trie.split(&host, m()), "{host}");
+            assert_eq!(
+                compact.match_info(&host, m()),
+                trie.match_info(&host, m()),
+                "{host}"
+            );
+        }
+        assert!(compact.known_tlds().eq(trie.known_tlds()));
+        assert!(compact.is_known_tld("Co.UK"));
+    }
+
+    #[test]
+    fn compiled_list_is_much_smaller() {
+        let trie = list().approx_bytes();
+        let flat = list().with_engine(Engine::Flat).approx_bytes();
+        let compact = list().compile().approx_bytes();
+        assert!(compact * 8 < trie, "{compact} vs {trie}");
+        assert!(compact * 2 < flat, "{compact} vs {flat}");
+    }
+
+    #[test]
+    fn budget_falls_back_to_compact() {
+        let size = list().compile().approx_bytes();
+        let (compact, engine) = List::parse_with_budget(PSL, LoadOpts::default(), size).unwrap();
+        assert_eq!(engine, Engine::Compact);
+        assert_eq!(compact.tld("a.b.ck", m()).as_deref(), Some("b.ck"));
+    }
+
+    #[test]
+    fn binary_round_trip_keeps_engine() {
+        let compact = list().compile();
+        let back = List::from_bytes(&compact.to_bytes()).unwrap();
+        assert_eq!(back.engine(), Engine::Compact);
+        assert_eq!(back.to_bytes(), compact.to_bytes());
         assert_eq!(
-            list.sld("example.co.uk", m()).as_deref(),
-            Some("example.co.uk")
+            back.sld("foo.city.kawasaki.jp", m()).as_deref(),
+            Some("city.kawasaki.jp")
+        );
+    }
+
+    #[test]
+    fn cursor_walks_compiled_list() {
+        let (trie, compact) = (list(), list().compile());
+        for labels in [["uk", "co", "example"], ["jp", "kawasaki", "city"]] {
+            let mut a = trie.cursor(m());
+            let mut b = compact.cursor(m());
+            for label in labels {
+                assert_eq!(b.push_label(label), a.push_label(label), "{label}");
+                assert_eq!(b.suffix_depth(), a.suffix_depth());
+                assert_eq!(b.can_extend(), a.can_extend());
+            }
+        }
+    }
+}
+
+mod ignored_private {
+    use super::*;
+    use publicsuffix2::options::PLATFORM_SUFFIXES;
+    use publicsuffix2::Engine;
+
+    #[test]
+    fn ignored_suffix_falls_back_to_icann_parent() {
+        let opts = m().ignoring_private(&["GitHub.io"]);
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            assert_eq!(list.tld("a.github.io", m()).as_deref(), Some("github.io"));
+            assert_eq!(list.tld("a.github.io", opts).as_deref(), Some("io"));
+            assert_eq!(
+                list.sld("x.a.github.io", opts).as_deref(),
+                Some("github.io")
+            );
+            // Other private suffixes are untouched.
+            assert_eq!(list.tld("a.gitlab.io", opts).as_deref(), Some("gitlab.io"));
+        }
+    }
+
+    #[test]
+    fn icann_rules_are_never_ignored() {
+        let opts = m().ignoring_private(&["co.uk"]);
+        assert_eq!(list().tld("a.co.uk", opts).as_deref(), Some("co.uk"));
+    }
+
+    #[test]
+    fn platform_preset_covers_listed_private_suffixes() {
+        let list = list();
+        let opts = m().ignoring_private(PLATFORM_SUFFIXES);
+        for sfx in PLATFORM_SUFFIXES {
+            let host = format!("tenant.{sfx}");
+            assert_eq!(list.tld(&host, m()).as_deref(), Some(*sfx));
+            assert_ne!(list.tld(&host, opts).as_deref(), Some(*sfx), "{sfx}");
+        }
+    }
+}
+
+mod nearest_listed {
+    use super::*;
+    use publicsuffix2::Engine;
+
+    #[test]
+    fn reports_rule_backed_ancestor() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let near = |h| list.nearest_listed_suffix(h, m()).map(|c| c.into_owned());
+            assert_eq!(list.tld("x.c.kobe.jp", m()).as_deref(), Some("c.kobe.jp"));
+            assert_eq!(near("x.c.kobe.jp").as_deref(), Some("kobe.jp"));
+            assert_eq!(near("www.city.kobe.jp").as_deref(), Some("kobe.jp"));
+            assert_eq!(near("www.example.co.uk").as_deref(), Some("co.uk"));
+            assert_eq!(near("WWW.Example.COM.").as_deref(), Some("com"));
+            // `*.ck` and `!www.ck` both name `ck`.
+            assert_eq!(near("a.b.ck").as_deref(), Some("ck"));
+            assert_eq!(near("www.ck").as_deref(), Some("ck"));
+            assert_eq!(near("example.local"), None);
+            assert_eq!(near(""), None);
+        }
+    }
+}
+
+mod registration_boundary {
+    use super::*;
+    use publicsuffix2::{Boundary, Engine, Type};
+
+    #[test]
+    fn classifies_rule_kinds() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let b = |h| list.registration_boundary(h, m()).unwrap();
+
+            let icann = b("www.Example.co.uk");
+            assert_eq!(icann.boundary, Boundary::Icann);
+            assert_eq!(icann.typ, Some(Type::Icann));
+            assert_eq!(icann.suffix, "co.uk");
+            assert_eq!(icann.registrable.as_deref(), Some("example.co.uk"));
+
+            let private = b("user.github.io");
+            assert_eq!(private.boundary, Boundary::Private);
+            assert_eq!(private.registrable.as_deref(), Some("user.github.io"));
+
+            let wild = b("a.b.ck");
+            assert_eq!(wild.boundary, Boundary::Wildcard);
+            assert_eq!(wild.suffix, "b.ck");
+            // The exception itself is an explicit ICANN rule.
+            assert_eq!(b("www.ck").boundary, Boundary::Icann);
+            assert_eq!(b("www.ck").registrable.as_deref(), Some("www.ck"));
+
+            let unlisted = b("a.example.local");
+            assert_eq!(unlisted.boundary, Boundary::Unlisted);
+            assert_eq!(unlisted.registrable.as_deref(), Some("example.local"));
+
+            assert_eq!(b("co.uk").registrable, None);
+            assert!(list.registration_boundary("", m()).is_none());
+        }
+    }
+
+    #[test]
+    fn summaries_name_the_rule() {
+        let list = list();
+        let summary = |h| list.registration_boundary(h, m()).unwrap().summary();
+        assert_eq!(summary("a.example.co.uk"), "ICANN section, rule co.uk");
+        assert_eq!(summary("a.b.ck"), "ICANN section, wildcard rule *.ck");
+        assert_eq!(
+            summary("a.www.ck"),
+            "ICANN section, wildcard rule *.ck overridden by exception !www.ck"
+        );
+        assert_eq!(summary("user.github.io"), "private section, rule github.io");
+        assert_eq!(
+            summary("example.local"),
+            "not listed, last label local used as suffix"
+        );
+        assert_eq!(
+            summary("kobe.jp"),
+            "no section, kobe.jp implied by deeper rules"
+        );
+        assert_eq!(Type::Private.description(), "private section");
+        assert_eq!(Boundary::Wildcard.description(), "wildcard rule");
+    }
+
+    #[test]
+    fn unsectioned_rules_are_unclassified() {
+        let list: List = "com".parse().unwrap();
+        let info = list.registration_boundary("a.com", m()).unwrap();
+        assert_eq!(info.boundary, Boundary::Unclassified);
+        assert_eq!(info.typ, None);
+    }
+}
+
+mod candidates {
+    use super::*;
+    use publicsuffix2::{Engine, RuleKind, Type, TypeFilter};
+
+    fn rules(list: &List, host: &str, opts: MatchOpts<'_>) -> Vec<(String, RuleKind, String)> {
+        list.candidates(host, opts)
+            .into_iter()
+            .map(|c| (c.rule, c.kind, c.suffix))
+            .collect()
+    }
+
+    #[test]
+    fn lists_every_matching_rule_in_precedence_order() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            assert_eq!(
+                rules(&list, "foo.city.kawasaki.jp", m()),
+                [
+                    (
+                        "!city.kawasaki.jp".into(),
+                        RuleKind::Exception,
+                        "kawasaki.jp".into()
+                    ),
+                    (
+                        "*.kawasaki.jp".into(),
+                        RuleKind::Wildcard,
+                        "city.kawasaki.jp".into()
+                    ),
+                    ("jp".into(), RuleKind::Normal, "jp".into()),
+                ]
+            );
+            assert_eq!(
+                rules(&list, "www.example.co.uk", m()),
+                [
+                    ("co.uk".into(), RuleKind::Normal, "co.uk".into()),
+                    ("uk".into(), RuleKind::Normal, "uk".into()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn first_candidate_decides_the_lookup() {
+        let list = list();
+        for host in [
+            "www.example.com",
+            "a.b.ck",
+            "a.www.ck",
+            "user.github.io",
+            "foo.bar.kawasaki.jp",
+        ] {
+            let first = list.candidates(host, m()).into_iter().next().unwrap();
+            let info = list.match_info(host, m()).unwrap();
+            assert_eq!(Some(first.rule), info.rule, "{host}");
+            assert_eq!(first.suffix, info.suffix, "{host}");
+            assert_eq!(first.typ, info.typ, "{host}");
+        }
+    }
+
+    #[test]
+    fn respects_match_options() {
+        let list = list();
+        let icann = MatchOpts {
+            types: TypeFilter::Icann,
+            ..m()
+        };
+        let found = list.candidates("user.github.io", icann);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].rule.as_str(), found[0].typ),
+            ("io", Some(Type::Icann))
+        );
+
+        let no_wild = MatchOpts {
+            wildcard: false,
+            ..m()
+        };
+        assert_eq!(rules(&list, "a.b.ck", no_wild), []);
+        assert!(list.candidates("example.unlisted", m()).is_empty());
+        assert!(list.candidates("a..com", m()).is_empty());
+    }
+}
+
+mod predicates {
+    use super::*;
+    use publicsuffix2::Engine;
+
+    const HOSTS: [&str; 14] = [
+        "com",
+        "example.com",
+        "www.example.com",
+        "co.uk",
+        "Example.CO.UK.",
+        "a.example.co.uk",
+        "ck",
+        "b.ck",
+        "a.b.ck",
+        "www.ck",
+        "github.io",
+        "user.github.io",
+        "unlisted",
+        "",
+    ];
+
+    #[test]
+    fn classify_hosts() {
+        let list = list();
+        let yes = |h| {
+            (
+                list.is_public_suffix(h, m()),
+                list.is_registrable_domain(h, m()),
+            )
+        };
+        assert_eq!(yes("co.uk"), (true, false));
+        assert_eq!(yes("Example.CO.UK."), (false, true));
+        assert_eq!(yes("a.example.co.uk"), (false, false));
+        assert_eq!(yes("b.ck"), (true, false));
+        assert_eq!(yes("www.ck"), (false, true));
+        assert_eq!(yes("github.io"), (true, false));
+        assert_eq!(yes(""), (false, false));
+        let strict = MatchOpts {
+            strict: true,
+            ..m()
+        };
+        assert!(list.is_public_suffix("unlisted", m()));
+        assert!(!list.is_public_suffix("unlisted", strict));
+    }
+
+    #[test]
+    fn agree_with_split() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            for host in HOSTS {
+                let norm = host.to_lowercase();
+                let norm = norm.trim_end_matches('.');
+                let parts = list.split(host, m());
+                assert_eq!(
+                    list.is_public_suffix(host, m()),
+                    parts.as_ref().is_some_and(|p| p.tld == norm),
+                    "{host}"
+                );
+                assert_eq!(
+                    list.is_registrable_domain(host, m()),
+                    parts.is_some_and(|p| p.tld != norm && p.sld.as_deref() == Some(norm)),
+                    "{host}"
+                );
+            }
+        }
+    }
+    #[test]
+    fn what_would_be_registrable() {
+        let list = list();
+        let needs = |h| list.required_labels(h, m());
+        assert_eq!(needs("foo.co.uk"), Some(3));
+        assert_eq!(needs("co.uk"), Some(3));
+        assert_eq!(needs("www.foo.co.uk"), Some(3));
+        assert_eq!(needs("Foo.COM."), Some(2));
+        assert_eq!(needs("b.ck"), Some(3));
+        assert_eq!(needs("www.ck"), Some(2));
+        assert_eq!(needs("user.github.io"), Some(3));
+        assert_eq!(needs("unlisted"), Some(2));
+        assert_eq!(needs(""), None);
+        let strict = MatchOpts {
+            strict: true,
+            ..m()
+        };
+        assert_eq!(list.required_labels("a.unlisted", strict), None);
+
+        for (host, exact) in [
+            ("co.uk", true),
+            ("CO.UK.", true),
+            ("b.ck", true),
+            ("www.ck", false),
+            ("github.io", true),
+            ("foo.co.uk", false),
+            ("unlisted", false),
+            ("", false),
+        ] {
+            assert_eq!(list.is_exactly_suffix(host), exact, "{host}");
+        }
+    }
+}
+
+mod case_folding {
+    use super::*;
+    use publicsuffix2::Engine;
+    use std::borrow::Cow;
+
+    #[test]
+    fn mixed_case_matches_every_engine() {
+        let long = "A".repeat(70);
+        let hosts = [
+            "WWW.EXAMPLE.CO.UK",
+            "Foo.City.Kawasaki.JP",
+            "User.GitHub.IO",
+            "B.CK",
+            "Example.Local",
+            &format!("{long}.Example.COM"),
+        ];
+        #[allow(unused_mut)]
+        let mut lists = vec![list(), list().with_engine(Engine::Flat)];
+        #[cfg(feature = "fst")]
+        lists.push(list().with_engine(Engine::Compact));
+        for list in lists {
+            for host in hosts {
+                let lower = host.to_ascii_lowercase();
+                assert_eq!(list.split(host, m()), list.split(&lower, m()), "{host}");
+                assert_eq!(list.tld(host, m()), list.tld(&lower, m()), "{host}");
+                assert_eq!(
+                    list.is_registrable_domain(host, m()),
+                    list.is_registrable_domain(&lower, m()),
+                    "{host}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_uppercase_parts_are_copied() {
+        let list = list();
+        let parts = list.split("WWW.example.co.uk", m()).unwrap();
+        assert!(matches!(parts.prefix, Some(Cow::Owned(ref p)) if p == "www"));
+        assert!(matches!(parts.sld, Some(Cow::Borrowed("example.co.uk"))));
+        assert!(matches!(parts.tld, Cow::Borrowed("co.uk")));
+        assert!(matches!(
+            list.tld("WWW.EXAMPLE.co.uk", m()),
+            Some(Cow::Borrowed("co.uk"))
+        ));
+    }
+
+    #[test]
+    fn raw_matching_keeps_case() {
+        let list = list();
+        assert_eq!(
+            list.tld("EXAMPLE.CO.UK", MatchOpts::raw()).as_deref(),
+            Some("UK")
+        );
+        assert_eq!(
+            list.sld("\u{c9}XAMPLE.COM", m_no_idna()).as_deref(),
+            Some("\u{e9}xample.com")
+        );
+    }
+}
+
+mod try_split {
+    use super::*;
+    use publicsuffix2::{ControlPolicy, FallbackPolicy, LiteralPolicy, MatchError};
+
+    #[test]
+    fn reasons() {
+        let list = list();
+        let err = |h, opts| list.try_split(h, opts).unwrap_err();
+        assert_eq!(err("", m()), MatchError::EmptyInput);
+        assert_eq!(err(".", m()), MatchError::EmptyInput);
+        assert_eq!(err("a..example.com", m()), MatchError::EmptyLabel);
+        assert_eq!(
+            err("example.com.", MatchOpts::raw()),
+            MatchError::TrailingDot
+        );
+        assert_eq!(
+            err("exa\nmple.com", m()),
+            MatchError::ControlChar { index: 3, ch: '\n' }
+        );
+        let reject = MatchOpts {
+            literals: LiteralPolicy::Reject,
+            ..m()
+        };
+        assert_eq!(err("192.168.0.1", reject), MatchError::Literal);
+        let strict = MatchOpts {
+            strict: true,
+            ..m()
+        };
+        assert_eq!(err("example.local", strict), MatchError::NoRuleMatched);
+        let no_fallback = MatchOpts {
+            fallback: FallbackPolicy::None,
+            ..m()
+        };
+        assert_eq!(err("example.local", no_fallback), MatchError::NoRuleMatched);
+    }
+
+    #[test]
+    fn agrees_with_split() {
+        let list = list();
+        let strip = MatchOpts {
+            controls: ControlPolicy::Strip,
+            ..m()
+        };
+        for host in [
+            "www.example.co.uk",
+            "example.local",
+            "exa\nmple.com",
+            "com.",
+        ] {
+            for opts in [m(), MatchOpts::raw(), strip] {
+                assert_eq!(list.try_split(host, opts).ok(), list.split(host, opts));
+            }
+        }
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn invalid_idna() {
+        let norm = Normalizer {
+            idna_reject_errors: true,
+            ..Normalizer::ps2()
+        };
+        let opts = MatchOpts::with_normalizer(&norm);
+        assert_eq!(
+            list().try_split("xn--a.\u{FFFF}.com", opts),
+            Err(publicsuffix2::MatchError::InvalidIdna)
+        );
+    }
+}
+
+mod fallback_policy {
+    use super::*;
+    use publicsuffix2::FallbackPolicy;
+
+    fn with(fallback: FallbackPolicy) -> MatchOpts<'static> {
+        MatchOpts { fallback, ..m() }
+    }
+
+    #[test]
+    fn unlisted_hosts() {
+        let list = list();
+        let host = "www.example.local";
+        let tld = |f| list.tld(host, with(f)).map(|t| t.into_owned());
+        assert_eq!(tld(FallbackPolicy::LastLabel).as_deref(), Some("local"));
+        assert_eq!(tld(FallbackPolicy::None), None);
+        assert_eq!(tld(FallbackPolicy::EntireHost).as_deref(), Some(host));
+
+        let parts = list.split(host, with(FallbackPolicy::EntireHost)).unwrap();
+        assert_eq!(parts.sld.as_deref(), Some(host));
+        assert!(!parts.has_subdomain());
+        assert!(list.is_public_suffix(host, with(FallbackPolicy::EntireHost)));
+        assert!(!list.is_public_suffix(host, with(FallbackPolicy::None)));
+        assert!(!list.is_public_suffix("local", with(FallbackPolicy::None)));
+    }
+
+    #[test]
+    fn rules_still_apply() {
+        let list = list();
+        for f in [FallbackPolicy::None, FallbackPolicy::EntireHost] {
+            assert_sld_tld!(
+                list,
+                "a.b.c.kawasaki.jp",
+                with(f),
+                Some("b.c.kawasaki.jp"),
+                Some("c.kawasaki.jp")
+            );
+            assert_sld_tld!(
+                list,
+                "foo.city.kawasaki.jp",
+                with(f),
+                Some("city.kawasaki.jp"),
+                Some("kawasaki.jp")
+            );
+            assert_sld_tld!(
+                list,
+                "www.example.co.uk",
+                with(f),
+                Some("example.co.uk"),
+                Some("co.uk")
+            );
+        }
+    }
+
+    #[test]
+    fn strict_wins() {
+        let list = list();
+        let opts = MatchOpts {
+            strict: true,
+            ..with(FallbackPolicy::EntireHost)
+        };
+        assert_eq!(list.tld("example.local", opts), None);
+        assert_eq!(
+            list.organizational_domain("a.b.example", with(FallbackPolicy::None))
+                .as_deref(),
+            Some("b.example")
+        );
+    }
+}
+
+mod type_filters {
+    use super::*;
+    use publicsuffix2::{Type, TypeFilter};
+
+    const MIXED: &str = "local.test
+// ===BEGIN ICANN DOMAINS===
+com
+uk
+co.uk
+// ===END ICANN DOMAINS===
+// ===BEGIN PRIVATE DOMAINS===
+blogspot.com
+// ===END PRIVATE DOMAINS===
+";
+
+    #[test]
+    fn unclassified_rules_are_selectable() {
+        let list = List::parse(MIXED).unwrap();
+        let cases = [
+            (TypeFilter::Any, ["blogspot.com", "local.test", "co.uk"]),
+            (TypeFilter::Icann, ["com", "test", "co.uk"]),
+            (TypeFilter::Private, ["blogspot.com", "test", "uk"]),
+            (TypeFilter::Known, ["blogspot.com", "test", "co.uk"]),
+            (
+                TypeFilter::IcannOrUnclassified,
+                ["com", "local.test", "co.uk"],
+            ),
+            (
+                TypeFilter::PrivateOrUnclassified,
+                ["blogspot.com", "local.test", "uk"],
+            ),
+            (TypeFilter::Unclassified, ["com", "local.test", "uk"]),
+        ];
+        for (types, want) in cases {
+            let opts = MatchOpts { types, ..m() };
+            let got = ["a.blogspot.com", "a.local.test", "a.co.uk"]
+                .map(|h| list.tld(h, opts).unwrap().into_owned());
+            assert_eq!(got, want, "{types:?}");
+        }
+    }
+
+    #[test]
+    fn accepts() {
+        let all = [Some(Type::Icann), Some(Type::Private), None];
+        let accepted = |f: TypeFilter| all.map(|t| f.accepts(t));
+        assert_eq!(accepted(TypeFilter::Any), [true, true, true]);
+        assert_eq!(accepted(TypeFilter::Known), [true, true, false]);
+        assert_eq!(
+            accepted(TypeFilter::IcannOrUnclassified),
+            [true, false, true]
+        );
+        assert_eq!(accepted(TypeFilter::Unclassified), [false, false, true]);
+    }
+}
+
+mod parts_helpers {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn whole_host_and_labels() {
+        let list = list();
+        let parts = list.split("WWW.shop.Example.co.uk.", m()).unwrap();
+        assert_eq!(parts.fqdn(), "www.shop.example.co.uk");
+        assert_eq!(parts.to_string(), "www.shop.example.co.uk");
+        assert_eq!(
+            parts.labels().collect::<Vec<_>>(),
+            ["www", "shop", "example", "co", "uk"]
+        );
+        assert!(parts.has_subdomain());
+        assert_eq!(parts.suffix_label_count(), 2);
+
+        let parts = list.split("example.com", m()).unwrap();
+        assert!(matches!(parts.fqdn(), Cow::Borrowed("example.com")));
+        assert!(!parts.has_subdomain());
+        assert_eq!(parts.suffix_label_count(), 1);
+        assert_eq!(parts.clone().into_owned(), parts);
+    }
+
+    #[test]
+    fn host_that_is_a_suffix() {
+        let list = list();
+        let parts = list.split("a.b.kawasaki.jp", m()).unwrap();
+        assert_eq!(parts.to_string(), "a.b.kawasaki.jp");
+        assert_eq!(parts.suffix_label_count(), 3);
+        let parts = list.split("co.uk", m()).unwrap();
+        assert_eq!(parts.fqdn(), "co.uk");
+        assert_eq!(parts.labels().count(), 2);
+        assert!(!parts.has_subdomain());
+    }
+
+    #[test]
+    fn subdomain_depth() {
+        let list = list();
+        let parts = list.split("a.b.c.example.co.uk", m()).unwrap();
+        assert_eq!(
+            parts.subdomain_labels().collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        assert_eq!(parts.subdomain_labels().next_back(), Some("c"));
+        assert_eq!(parts.depth_below_registrable(), 3);
+
+        for host in ["example.co.uk", "co.uk", "b.kawasaki.jp"] {
+            let parts = list.split(host, m()).unwrap();
+            assert_eq!(parts.subdomain_labels().count(), 0, "{host}");
+            assert_eq!(parts.depth_below_registrable(), 0, "{host}");
+        }
+        let parts = list.split("www.foo.city.kawasaki.jp", m()).unwrap();
+        assert_eq!(parts.subdomain_labels().collect::<Vec<_>>(), ["www", "foo"]);
+    }
+}
+
+mod split_indices {
+    use super::*;
+    use publicsuffix2::{ControlPolicy, Engine};
+
+    #[test]
+    fn ranges_agree_with_split() {
+        let hosts = [
+            "com",
+            "example.com",
+            "www.example.co.uk",
+            "a.b.c.kawasaki.jp",
+            "foo.city.kawasaki.jp",
+            "user.github.io",
+            "example.local",
+        ];
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            for host in hosts {
+                let parts = list.split(host, m()).unwrap();
+                let idx = list.split_indices(host, m()).unwrap();
+                let at = |r: Option<std::ops::Range<usize>>| r.map(|r| &host[r]);
+                assert_eq!(at(idx.prefix), parts.prefix.as_deref(), "{host}");
+                assert_eq!(at(idx.sll), parts.sll.as_deref(), "{host}");
+                assert_eq!(at(idx.sld), parts.sld.as_deref(), "{host}");
+                assert_eq!(&host[idx.tld], parts.tld, "{host}");
+            }
+        }
+    }
+
+    #[test]
+    fn ranges_index_the_input_as_given() {
+        let list = list();
+        let idx = list.split_indices(".WWW.Example.CO.UK.", m()).unwrap();
+        assert_eq!(idx.prefix, Some(1..4));
+        assert_eq!(idx.sll, Some(5..12));
+        assert_eq!(idx.sld, Some(5..18));
+        assert_eq!(idx.tld, 13..18);
+    }
+
+    #[test]
+    fn rewritten_hosts_have_no_ranges() {
+        let list = list();
+        let strip = MatchOpts {
+            controls: ControlPolicy::Strip,
+            ..m()
+        };
+        assert!(list.split("exa\tmple.com", strip).is_some());
+        assert_eq!(list.split_indices("exa\tmple.com", strip), None);
+        let strict = MatchOpts {
+            strict: true,
+            ..m()
+        };
+        assert_eq!(list.split_indices("example.unlisted", strict), None);
+        assert_eq!(list.split_indices("", m()), None);
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna_hosts_have_no_ranges() {
+        let list = list();
+        assert!(list.split("例子.中国", m()).is_some());
+        assert_eq!(list.split_indices("例子.中国", m()), None);
+        assert!(list.split_indices("例子.中国", m_no_idna()).is_some());
+    }
+}
+
+mod rule_iteration {
+    use super::*;
+    use publicsuffix2::{Engine, Leaf, Type};
+
+    const TEXT: &str = "// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n*.ck\n!www.ck\n\
+                        // ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\n\
+                        github.io\n// ===END PRIVATE DOMAINS===\n";
+
+    #[test]
+    fn walks_rules_in_order() {
+        let list = List::parse(TEXT).unwrap();
+        let mut engines = vec![list.clone(), list.clone().with_engine(Engine::Flat)];
+        if cfg!(feature = "fst") {
+            engines.push(list.clone().compile());
+        }
+        for list in engines {
+            let rules: Vec<String> = list.rules().map(|r| r.to_string()).collect();
+            assert_eq!(rules, ["!www.ck", "*.ck", "co.uk", "github.io", "uk"]);
+            let first = list.rules().next().unwrap();
+            assert_eq!(first.labels, ["www", "ck"]);
+            assert_eq!((first.leaf, first.typ), (Leaf::Negative, Some(Type::Icann)));
+            assert_eq!(
+                (list.len(), list.icann_len(), list.private_len()),
+                (5, 4, 1)
+            );
+            assert!(!list.is_empty());
+        }
+    }
+
+    #[test]
+    fn counts_the_full_list() {
+        let full = list();
+        assert_eq!(full.len(), full.rules().count());
+        assert_eq!(full.len(), full.icann_len() + full.private_len());
+        assert!(full.rules().any(|r| r.to_string() == "!city.kawasaki.jp"));
+        let len = full.len();
+        assert_eq!(full.with_engine(Engine::Flat).len(), len);
+    }
+
+    #[test]
+    fn suffixes_under_a_parent() {
+        let parsed = List::parse(TEXT).unwrap();
+        for list in [
+            parsed.clone(),
+            parsed.clone().with_engine(Engine::Flat),
+            parsed.clone().with_engine(Engine::Compact),
+        ] {
+            let under =
+                |p| -> Vec<String> { list.suffixes_under(p).map(|r| r.to_string()).collect() };
+            assert_eq!(under("ck"), ["!www.ck", "*.ck"]);
+            assert_eq!(under("UK."), ["co.uk"]);
+            assert_eq!(under("co.uk"), Vec::<String>::new());
+            assert_eq!(under("io"), ["github.io"]);
+            assert_eq!(under("nope"), Vec::<String>::new());
+            assert_eq!(under("").len(), list.len());
+        }
+
+        let full = list();
+        let jp: Vec<_> = full.suffixes_under("kawasaki.jp").collect();
+        assert!(jp.iter().any(|r| r.to_string() == "*.kawasaki.jp"));
+        assert!(jp.iter().any(|r| r.to_string() == "!city.kawasaki.jp"));
+        assert!(jp
+            .iter()
+            .all(|r| r.labels.ends_with(&["kawasaki".into(), "jp".into()])));
+    }
+
+    #[test]
+    fn stats_summarize_the_rules() {
+        let parsed = List::parse(TEXT).unwrap();
+        for list in [
+            parsed.clone(),
+            parsed.clone().with_engine(Engine::Flat),
+            parsed.clone().with_engine(Engine::Compact),
+        ] {
+            let stats = list.stats();
+            assert_eq!(
+                (stats.rules, stats.wildcard_rules, stats.exception_rules),
+                (5, 1, 1)
+            );
+            assert_eq!((stats.icann_rules, stats.private_rules), (4, 1));
+            assert_eq!(stats.max_depth, 2);
+            assert_eq!(stats.approx_bytes, list.approx_bytes());
+        }
+
+        let full = list().stats();
+        assert_eq!(full.rules, full.icann_rules + full.private_rules);
+        assert!(full.max_depth >= 4 && full.exception_rules > 0);
+    }
+
+    /// Renders the trie as nested braces, one node per label.
+    #[derive(Default)]
+    struct Nested(String);
+
+    impl publicsuffix2::RuleVisitor for Nested {
+        fn enter(&mut self, path: &[&str], leaf: Leaf, typ: Option<Type>) {
+            let mark = match (leaf, typ) {
+                (Leaf::None, _) => "",
+                (Leaf::Negative, _) => "!",
+                (_, Some(Type::Private)) => "+",
+                _ => "=",
+            };
+            self.0 += &format!("{}{mark}{{", path.last().unwrap());
+        }
+
+        fn leave(&mut self, _: &[&str]) {
+            self.0.push('}');
+        }
+    }
+
+    #[test]
+    fn visit_walks_nodes_depth_first() {
+        let parsed = List::parse(&format!("{TEXT}*.kobe.jp\n")).unwrap();
+        for list in [
+            parsed.clone(),
+            parsed.clone().with_engine(Engine::Flat),
+            parsed.clone().with_engine(Engine::Compact),
+        ] {
+            let mut out = Nested::default();
+            list.visit(&mut out);
+            assert_eq!(out.0, "ck{*={}www!{}}io{github+{}}jp{kobe{*={}}}uk={co={}}");
+        }
+
+        let full = list();
+        let mut leaves = 0;
+        full.visit(&mut |_: &[&str], leaf, _| leaves += usize::from(leaf != Leaf::None));
+        assert_eq!(leaves, full.len());
+    }
+
+    #[test]
+    fn fingerprint_ignores_order_and_engine() {
+        let parsed = List::parse(TEXT).unwrap();
+        let fp = parsed.fingerprint();
+        for list in [
+            parsed.clone().with_engine(Engine::Flat),
+            parsed.clone().with_engine(Engine::Compact),
+        ] {
+            assert_eq!(list.fingerprint(), fp);
+        }
+        let shuffled = "// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\n\
+                        // ===BEGIN ICANN DOMAINS===\n!www.ck\nco.uk\n*.ck\nuk\n\
+                        // ===END ICANN DOMAINS===\n";
+        assert_eq!(List::parse(shuffled).unwrap().fingerprint(), fp);
+
+        // The section counts, not just the rule.
+        let moved = TEXT
+            .replace("github.io\n", "")
+            .replace("uk\nco.uk", "uk\ngithub.io\nco.uk");
+        assert_ne!(List::parse(&moved).unwrap().fingerprint(), fp);
+        // Pinned, so the hash cannot drift between platforms or releases.
+        assert_eq!(
+            List::parse("com\nuk\nco.uk\n").unwrap().fingerprint(),
+            0xa6fa_d2e3_7965_4a24
+        );
+    }
+}
+
+mod match_info {
+    use super::*;
+    use publicsuffix2::{Engine, RuleKind, Type};
+
+    #[test]
+    fn reports_the_deciding_rule() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let info = |h| {
+                let i = list.match_info(h, m()).unwrap();
+                (i.rule, i.kind, i.typ, i.depth, i.suffix.into_owned())
+            };
+            assert_eq!(
+                info("www.example.co.uk"),
+                (
+                    Some("co.uk".into()),
+                    RuleKind::Normal,
+                    Some(Type::Icann),
+                    2,
+                    "co.uk".into()
+                )
+            );
+            assert_eq!(
+                info("a.b.ck"),
+                (
+                    Some("*.ck".into()),
+                    RuleKind::Wildcard,
+                    Some(Type::Icann),
+                    2,
+                    "b.ck".into()
+                )
+            );
+            assert_eq!(
+                info("a.www.ck"),
+                (
+                    Some("!www.ck".into()),
+                    RuleKind::Exception,
+                    Some(Type::Icann),
+                    2,
+                    "ck".into()
+                )
+            );
+            assert_eq!(
+                info("user.github.io"),
+                (
+                    Some("github.io".into()),
+                    RuleKind::Normal,
+                    Some(Type::Private),
+                    2,
+                    "github.io".into()
+                )
+            );
+            assert_eq!(
+                info("a.example.local"),
+                (None, RuleKind::Unlisted, None, 1, "local".into())
+            );
+            assert!(list.match_info("", m()).is_none());
+        }
+        let list: List = "jp\n*.kobe.jp".parse().unwrap();
+        let implied = list.match_info("kobe.jp", m()).unwrap();
+        assert_eq!(
+            (implied.rule, implied.kind, implied.depth),
+            (None, RuleKind::Implied, 2)
+        );
+    }
+}
+
+mod classify {
+    use super::*;
+    use publicsuffix2::{Classification, Engine, TypeFilter};
+
+    #[test]
+    fn names_the_section_and_provider() {
+        for list in [list(), list().with_engine(Engine::Flat), list().compile()] {
+            let private = |s: &'static str| Classification::PrivateProvider { suffix: s.into() };
+            assert_eq!(
+                list.classify("www.example.co.uk", m()),
+                Classification::Icann
+            );
+            assert_eq!(list.classify("foo.www.ck", m()), Classification::Icann);
+            assert_eq!(list.classify("alice.github.io", m()), private("github.io"));
+            assert_eq!(
+                list.classify("bucket.s3.amazonaws.com", m()),
+                private("s3.amazonaws.com")
+            );
+            assert_eq!(list.classify("example.local", m()), Classification::Unknown);
+            assert_eq!(list.classify("a..com", m()), Classification::Unknown);
+        }
+    }
+
+    #[test]
+    fn follows_the_type_filter() {
+        let icann = MatchOpts {
+            types: TypeFilter::Icann,
+            ..m()
+        };
+        assert_eq!(
+            list().classify("alice.github.io", icann),
+            Classification::Icann
+        );
+        let ignored = m().ignoring_private(&["github.io"]);
+        assert_eq!(
+            list().classify("alice.github.io", ignored),
+            Classification::Icann
+        );
+    }
+}
+
+mod organizational_domain {
+    use super::*;
+
+    #[test]
+    fn follows_rfc7489() {
+        let list = list();
+        let org = |h| list.organizational_domain(h, m()).map(|c| c.into_owned());
+        assert_eq!(org("mail.example.com").as_deref(), Some("example.com"));
+        assert_eq!(org("a.b.example.co.uk").as_deref(), Some("example.co.uk"));
+        assert_eq!(
+            org("bounce.user.github.io").as_deref(),
+            Some("user.github.io")
+        );
+        // No matching rule: the implicit `*` rule applies.
+        assert_eq!(org("a.b.example.local").as_deref(), Some("example.local"));
+        // A public suffix is its own organizational domain.
+        assert_eq!(org("co.uk").as_deref(), Some("co.uk"));
+        assert_eq!(org("Example.COM.").as_deref(), Some("example.com"));
+        assert_eq!(org(""), None);
+        assert_eq!(org("a..b"), None);
+
+        let strict = MatchOpts {
+            strict: true,
+            ..m()
+        };
+        assert_eq!(
+            list.organizational_domain("x.example.local", strict)
+                .as_deref(),
+            Some("example.local")
+        );
+    }
+}
+
+mod registrable_apis {
+    use super::*;
+
+    #[test]
+    fn agree_under_unlisted_tlds() {
+        let list = list();
+        for host in [
+            "example.local",
+            "www.example.local",
+            "www.example.co.uk",
+            "user.github.io",
+            "co.uk",
+            "local",
+        ] {
+            let domain = list.domain(host, m());
+            let from_domain = domain.as_ref().and_then(|d| d.registrable());
+            let registrable = list.registrable(host, m());
+            assert_eq!(
+                registrable.as_ref().map(|r| r.as_str()),
+                from_domain,
+                "{host}"
+            );
+            assert_eq!(
+                list.registrable_domain(host, m()).as_deref(),
+                from_domain,
+                "{host}"
+            );
+        }
+        let local = list.registrable("example.local", m()).unwrap();
+        assert_eq!(local.as_str(), "example.local");
+        assert_eq!(local.canonical_key(), b"example.local");
+    }
+}
+
+mod control_chars {
+    use super::*;
+    use publicsuffix2::{ControlPolicy, HostError};
+
+    #[test]
+    fn control_chars_are_rejected_by_default() {
+        let list = list();
+        for host in [
+            "exa\0mple.com",
+            "example.com\r\n",
+            "a\u{7f}.co.uk",
+            "\u{85}x.com",
+        ] {
+            assert_eq!(list.tld(host, m()), None, "{host:?}");
+            assert_eq!(list.split(host, MatchOpts::raw()), None, "{host:?}");
+        }
+        assert_eq!(
+            ControlPolicy::Reject.apply("ab\ncd"),
+            Err(HostError::ControlChar { index: 2, ch: '\n' })
+        );
+    }
+
+    #[test]
+    fn strip_policy_cleans_before_matching() {
+        let list = list();
+        let strip = MatchOpts {
+            controls: ControlPolicy::Strip,
+            ..m()
+        };
+        assert_eq!(
+            list.sld("www.Exa\0mple.co.uk.\r\n", strip).as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(ControlPolicy::Strip.apply("a\tb").unwrap(), "ab");
+        assert!(matches!(
+            ControlPolicy::Strip.apply("plain"),
+            Ok(std::borrow::Cow::Borrowed("plain"))
+        ));
+    }
+}
+
+mod literals {
+    use super::*;
+    use publicsuffix2::{Engine, LiteralPolicy};
+
+    const LITERALS: [&str; 6] = [
+        "192.168.0.1",
+        "[::1]",
+        "2001:db8::1",
+        "localhost",
+        "LOCALHOST.",
+        "app.localhost",
+    ];
+
+    fn with(literals: LiteralPolicy) -> MatchOpts<'static> {
+        MatchOpts { literals, ..m() }
+    }
+
+    #[test]
+    fn detects_ip_literals_and_localhost() {
+        for host in LITERALS {
+            assert!(
+                LiteralPolicy::is_literal(host.trim_end_matches('.')),
+                "{host}"
+            );
+        }
+        for host in [
+            "example.com",
+            "1.2.3",
+            "999.1.1.1",
+            "[example.com]",
+            "localhost.com",
+        ] {
+            assert!(!LiteralPolicy::is_literal(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn matched_like_hosts_by_default() {
+        assert_eq!(list().tld("192.168.0.1", m()).as_deref(), Some("1"));
+        assert_eq!(list().tld("localhost", m()).as_deref(), Some("localhost"));
+    }
+
+    #[test]
+    fn reject_returns_none() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let opts = with(LiteralPolicy::Reject);
+            for host in LITERALS {
+                assert_eq!(list.split(host, opts), None, "{host}");
+                assert_eq!(list.nearest_listed_suffix(host, opts), None, "{host}");
+                assert!(list.candidates(host, opts).is_empty(), "{host}");
+            }
+            assert_eq!(
+                list.sld("www.example.com", opts).as_deref(),
+                Some("example.com")
+            );
+        }
+    }
+
+    #[test]
+    fn whole_is_its_own_suffix() {
+        let list = list();
+        let opts = MatchOpts {
+            strict: true,
+            ..with(LiteralPolicy::Whole)
+        };
+        let parts = list.split("192.168.0.1", opts).unwrap();
+        assert_eq!(parts.tld, "192.168.0.1");
+        assert_eq!((parts.sll, parts.prefix), (None, None));
+        let domain = list.domain("10.0.0.1", opts).unwrap();
+        assert_eq!((domain.suffix(), domain.registrable()), ("10.0.0.1", None));
+        assert_eq!(
+            list.tld("App.Localhost", opts).as_deref(),
+            Some("app.localhost")
+        );
+        assert_eq!(list.sld("[::1]", opts).as_deref(), Some("[::1]"));
+        assert_eq!(
+            list.sld("www.example.com", opts).as_deref(),
+            Some("example.com")
+        );
+    }
+}
+
+#[cfg(feature = "idna")]
+mod idna_forms {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn parts_expose_both_forms() {
+        let list = list();
+        for host in ["www.食狮.中国", "www.xn--85x722f.xn--fiqs8s"] {
+            let parts = list.split(host, m_no_idna()).unwrap();
+            assert_eq!(parts.tld_ascii().as_deref(), Some("xn--fiqs8s"), "{host}");
+            assert_eq!(parts.tld_unicode(), "中国", "{host}");
+            assert_eq!(
+                parts.sld_ascii().as_deref(),
+                Some("xn--85x722f.xn--fiqs8s"),
+                "{host}"
+            );
+            assert_eq!(parts.sld_unicode().as_deref(), Some("食狮.中国"), "{host}");
+            assert_eq!(parts.sll_unicode().as_deref(), Some("食狮"), "{host}");
+            assert_eq!(parts.prefix_ascii().as_deref(), Some("www"), "{host}");
+            assert_eq!(parts.prefix_unicode().as_deref(), Some("www"), "{host}");
+        }
+    }
+
+    #[test]
+    fn borrows_parts_already_in_form() {
+        let parts = list().split("www.example.com", m()).unwrap();
+        assert!(matches!(parts.tld_ascii(), Some(Cow::Borrowed("com"))));
+        assert!(matches!(
+            parts.sld_unicode(),
+            Some(Cow::Borrowed("example.com"))
+        ));
+        let bad = list().split("a.xn--zz", m_no_idna()).unwrap();
+        assert_eq!(bad.tld_unicode(), "xn--zz");
+        assert_eq!(bad.tld_ascii().as_deref(), Some("xn--zz"));
+    }
+}
+
+#[cfg(feature = "idna")]
+mod match_both_forms {
+    use super::*;
+    use publicsuffix2::LoadOpts;
+
+    fn lean(text: &str) -> List {
+        let opts = LoadOpts {
+            a_label_duplicates: false,
+            ..LoadOpts::default()
+        };
+        List::parse_with(text, opts).unwrap()
+    }
+
+    #[test]
+    fn a_label_host_matches_u_label_rules() {
+        let list = lean("中国\n公司.中国");
+        let host = "www.example.xn--55qx5d.xn--fiqs8s";
+        let raw = MatchOpts::raw();
+        assert_eq!(list.tld(host, raw).as_deref(), Some("xn--fiqs8s"));
+        let both = MatchOpts {
+            match_both_forms: true,
+            ..raw
+        };
+        assert_eq!(
+            list.tld(host, both).as_deref(),
+            Some("xn--55qx5d.xn--fiqs8s")
+        );
+        assert_eq!(
+            list.nearest_listed_suffix(host, both).as_deref(),
+            Some("xn--55qx5d.xn--fiqs8s")
+        );
+    }
+
+    #[test]
+    fn u_label_host_matches_a_label_rules() {
+        let list = lean("xn--fiqs8s\nxn--55qx5d.xn--fiqs8s");
+        let both = MatchOpts {
+            match_both_forms: true,
+            ..m_no_idna()
+        };
+        assert_eq!(
+            list.tld("a.公司.中国", m_no_idna()).as_deref(),
+            Some("中国")
+        );
+        assert_eq!(
+            list.sld("a.公司.中国", both).as_deref(),
+            Some("a.公司.中国")
+        );
+    }
+
+    #[test]
+    fn duplicates_are_stored_by_default() {
+        let list: List = "公司.中国".parse().unwrap();
+        assert_eq!(
+            list.tld("a.xn--55qx5d.xn--fiqs8s", MatchOpts::raw())
+                .as_deref(),
+            Some("xn--55qx5d.xn--fiqs8s")
+        );
+    }
+}
+
+mod psl_text {
+    use super::*;
+    use publicsuffix2::{Engine, RuleRef, Type, WriteOpts};
+
+    fn rules(list: &List) -> Vec<RuleRef> {
+        list.rules().collect()
+    }
+
+    #[test]
+    fn full_list_round_trips() {
+        let list = list();
+        let text = list.to_psl_string(WriteOpts::default());
+        let back = List::parse(&text).unwrap();
+        assert_eq!(rules(&back), rules(&list));
+        let flat = list.with_engine(Engine::Flat);
+        assert_eq!(flat.to_psl_string(WriteOpts::default()), text);
+    }
+
+    #[test]
+    fn writes_header_sections_and_annotations() {
+        let text = "// @owner team-x\n// @expires 2026-01-01\nCorp.Example\n\
+                    // ===BEGIN ICANN DOMAINS===\nuk\n*.ck\n!www.ck\n// ===END ICANN DOMAINS===\n";
+        let list = List::parse(text).unwrap();
+        let opts = WriteOpts {
+            header: &["Internal suffixes.", "Generated; do not edit."],
+            ..WriteOpts::default()
+        };
+        assert_eq!(
+            list.to_psl_string(opts),
+            "// Internal suffixes.\n// Generated; do not edit.\n\n\
+             // @expires 2026-01-01\n// @owner team-x\ncorp.example\n\n\
+             // ===BEGIN ICANN DOMAINS===\n!www.ck\n*.ck\nuk\n// ===END ICANN DOMAINS===\n"
+        );
+        let back = List::parse(&list.to_psl_string(opts)).unwrap();
+        assert_eq!(back.rule_meta(), list.rule_meta());
+        assert_eq!(rules(&back), rules(&list));
+    }
+
+    #[test]
+    fn without_sections_rules_are_unclassified() {
+        let list = List::parse(
+            "// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\nio",
+        )
+        .unwrap();
+        let opts = WriteOpts {
+            sections: false,
+            annotations: false,
+            ..WriteOpts::default()
+        };
+        let text = list.to_psl_string(opts);
+        assert_eq!(text, "github.io\nio\n");
+        let back = List::parse(&text).unwrap();
+        assert!(back.rules().all(|r| r.typ.is_none()));
+        assert_eq!(list.rules().next().unwrap().typ, Some(Type::Private));
+    }
+}
+
+mod annotations {
+    use publicsuffix2::{Error, List, LoadOpts, Warning};
+
+    const TEXT: &str = "\
+// @owner team-x
+// @expires 2026-01-01
+// Temporary staging suffix.
+stage.Example
+
+// @expires 2030-06-30
+!keep.stage.example
+
+// @owner orphaned
+
+corp.example
+// @expires soon
+other.example
+";
+
+    #[test]
+    fn annotations_attach_to_next_rule() {
+        let list: List = TEXT.parse().unwrap();
+        let meta = list.rule_meta();
+        assert_eq!(meta.len(), 2);
+        assert_eq!(meta[0].rule, "stage.example");
+        assert_eq!(meta[0].owner.as_deref(), Some("team-x"));
+        assert_eq!(meta[0].expires.as_deref(), Some("2026-01-01"));
+        let exc = list.meta_for("!keep.stage.example").unwrap();
+        assert_eq!(exc.expires.as_deref(), Some("2030-06-30"));
+        assert!(exc.owner.is_none());
+        // A blank line discards pending annotations.
+        assert!(list.meta_for("corp.example").is_none());
+    }
+
+    #[test]
+    fn rules_carry_their_annotations() {
+        let list: List = TEXT.parse().unwrap();
+        let rules: Vec<_> = list.rules().collect();
+        let stage = rules
+            .iter()
+            .find(|r| r.to_string() == "stage.example")
+            .unwrap();
+        assert_eq!(stage.expires(), Some("2026-01-01"));
+        assert_eq!(stage.owner(), Some("team-x"));
+        let exc = list.suffixes_under("stage.example").next().unwrap();
+        assert_eq!(exc.to_string(), "!keep.stage.example");
+        assert_eq!((exc.expires(), exc.owner()), (Some("2030-06-30"), None));
+        let corp = rules
+            .iter()
+            .find(|r| r.to_string() == "corp.example")
+            .unwrap();
+        assert_eq!((corp.expires(), corp.owner()), (None, None));
+    }
+
+    #[test]
+    fn expired_rules_by_date() {
+        let list: List = TEXT.parse().unwrap();
+        assert!(list.expired_rules("2025-12-31").unwrap().is_empty());
+        let expired = list.expired_rules("2026-01-01").unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].rule, "stage.example");
+        assert_eq!(list.expired_rules("2031-01-01").unwrap().len(), 2);
+        assert!(matches!(
+            list.expired_rules("tomorrow"),
+            Err(Error::InvalidDate { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_annotation_is_a_warning() {
+        let opts = LoadOpts {
+            collect_warnings: true,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with(TEXT, opts).unwrap();
+        assert!(list.meta_for("other.example").is_none());
+        assert!(list.warnings().iter().any(
+            |w| matches!(w, Warning::InvalidAnnotation { line } if line == "// @expires soon")
+        ));
+    }
+}
+
+mod comment_blocks {
+    use super::*;
+    use publicsuffix2::{LoadOpts, MergePolicy};
+
+    fn keep() -> LoadOpts {
+        LoadOpts {
+            keep_metadata: true,
+            ..LoadOpts::default()
+        }
+    }
+
+    #[test]
+    fn blocks_attach_to_following_rules() {
+        let text = "\
+// ===BEGIN PRIVATE DOMAINS===
+// (Note: these are in alphabetical order by company name)
+
+// Example Org : https://example.org
+// Submitted by Jane Doe <psl@example.org>
+// @owner team-x
+example.net
+!www.example.net
+// A later note
+Other.example.net
+
+no-comment.net
+// ===END PRIVATE DOMAINS===
+";
+        let list = List::parse_with(text, keep()).unwrap();
+        let block = list.rule_metadata("example.net").unwrap();
+        assert_eq!(
+            block.lines,
+            [
+                "Example Org : https://example.org",
+                "Submitted by Jane Doe <psl@example.org>"
+            ]
+        );
+        assert_eq!(block.submitter(), Some("Jane Doe <psl@example.org>"));
+        assert_eq!(list.rule_metadata("!www.example.net"), Some(block));
+        let later = list.rule_metadata("other.example.net").unwrap();
+        assert_eq!(later.lines, ["A later note"]);
+        assert_eq!(later.submitter(), None);
+        assert!(list.rule_metadata("no-comment.net").is_none());
+        assert!(list.rule_metadata("www.example.net").is_none());
+        assert_eq!(
+            list.meta_for("example.net").unwrap().owner.as_deref(),
+            Some("team-x")
+        );
+
+        let plain = List::parse(text).unwrap();
+        assert!(plain.rule_metadata("example.net").is_none());
+    }
+
+    #[test]
+    fn builtin_list_credits_submitters() {
+        let list = List::parse_with(PSL, keep()).unwrap();
+        assert!(list.rule_metadata("co.uk").is_some());
+        let github = list.rule_metadata("github.io").unwrap();
+        assert!(github.lines.iter().any(|l| l.starts_with("GitHub")));
+        assert!(github.submitter().is_some());
+    }
+
+    #[test]
+    fn blocks_follow_merges_and_removals() {
+        let base = List::parse_with("// Base\na.com\n", keep()).unwrap();
+        let over = List::parse_with("// Override\na.com\nb.com\n", keep()).unwrap();
+        let kept = base.merge(&over, MergePolicy::KeepBase).unwrap();
+        assert_eq!(kept.rule_metadata("a.com").unwrap().lines, ["Base"]);
+        assert_eq!(kept.rule_metadata("b.com").unwrap().lines, ["Override"]);
+        let mut replaced = base.merge(&over, MergePolicy::PreferOverride).unwrap();
+        assert_eq!(replaced.rule_metadata("a.com").unwrap().lines, ["Override"]);
+
+        assert!(replaced.remove_rule("a.com"));
+        assert!(replaced.rule_metadata("a.com").is_none());
+        assert!(base.rule_metadata("a.com").is_some());
+    }
+}
+
+mod version_info {
+    use super::*;
+    use publicsuffix2::{Engine, ListBuilder, MergePolicy};
+
+    #[test]
+    fn header_names_the_snapshot() {
+        let info = List::parse(PSL).unwrap().version_info().cloned().unwrap();
+        assert_eq!(info.version.as_deref(), Some("2025-09-23_13-07-02_UTC"));
+        assert_eq!(info.date.as_deref(), Some("2025-09-23"));
+        assert_eq!(
+            info.commit.as_deref(),
+            Some("6defc0e19dbbe27dc2a0798256077d0b82d9c455")
+        );
+        assert_eq!(info.url, None);
+        let flat = List::parse(PSL).unwrap().with_engine(Engine::Flat);
+        assert_eq!(flat.version_info(), Some(&info));
+    }
+
+    #[test]
+    fn partial_and_missing_headers() {
+        let list = List::parse("// VERSION: nightly\n// VERSION: 2020-01-01\ncom").unwrap();
+        let info = list.version_info().unwrap();
+        assert_eq!(info.version.as_deref(), Some("nightly"));
+        assert_eq!(info.date, None);
+        assert_eq!(info.commit, None);
+
+        assert!(List::parse("// VERSION:\ncom")
+            .unwrap()
+            .version_info()
+            .is_none());
+        assert!(List::parse("com").unwrap().version_info().is_none());
+        let (built, _) = ListBuilder::new().add_rule("com").finish().unwrap();
+        assert!(built.version_info().is_none());
+    }
+
+    #[test]
+    fn merges_keep_the_base_snapshot() {
+        let base = List::parse("// COMMIT: abc\ncom").unwrap();
+        let over = List::parse("// COMMIT: def\nexample.com").unwrap();
+        let merged = base.merge(&over, MergePolicy::default()).unwrap();
+        assert_eq!(merged.version_info(), base.version_info());
+    }
+}
+
+mod rule_diff {
+    use super::*;
+    use publicsuffix2::Type;
+
+    #[test]
+    fn monitors_private_suffixes_of_an_update() {
+        let old = list();
+        let text = PSL.replace("\ngithub.io\n", "\n").replace(
+            "// ===END PRIVATE DOMAINS===",
+            "brand.example\n// ===END PRIVATE DOMAINS===",
+        );
+        let new = List::parse(&text).unwrap();
+        let diff = List::diff(&old, &new);
+        let added: Vec<String> = diff.added.iter().map(ToString::to_string).collect();
+        let removed: Vec<String> = diff.removed.iter().map(ToString::to_string).collect();
+        assert_eq!(added, ["brand.example"]);
+        assert_eq!(removed, ["github.io"]);
+        assert!(diff
+            .added
+            .iter()
+            .chain(&diff.removed)
+            .all(|r| r.typ == Some(Type::Private)));
+        assert!(diff.changed_type.is_empty());
+        assert!(old.diff(&list()).is_empty());
+    }
+}
+
+#[cfg(feature = "bundled-list")]
+mod bundled {
+    use super::*;
+
+    #[test]
+    fn bundled_list_names_its_snapshot() {
+        let list = List::bundled();
+        assert!(std::ptr::eq(list, List::bundled()));
+        let info = list.version_info().unwrap();
+        assert!(info.date.is_some(), "{info:?}");
+        assert_eq!(
+            list.sld("www.example.co.uk", m()).as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(List::global().version_info(), Some(info));
+    }
+}
+
+mod streaming_parser {
+    use super::*;
+    use publicsuffix2::loader::Parser;
+    use publicsuffix2::{Error, LoadOpts};
+
+    fn parse_in_chunks(text: &[u8], size: usize, opts: LoadOpts) -> Result<List, Error> {
+        let mut parser = Parser::new(opts);
+        for chunk in text.chunks(size) {
+            parser.feed(chunk)?;
+        }
+        parser.finish()
+    }
+
+    #[test]
+    fn chunked_input_matches_whole_text() {
+        let opts = LoadOpts {
+            collect_warnings: true,
+            keep_metadata: true,
+            ..LoadOpts::default()
+        };
+        let whole = List::parse_with(PSL, opts).unwrap();
+        for size in [1, 7, 4096, PSL.len()] {
+            let list = parse_in_chunks(PSL.as_bytes(), size, opts).unwrap();
+            assert_eq!(list.to_bytes(), whole.to_bytes(), "{size}");
+            assert_eq!(
+                format!("{:?}", list.warnings()),
+                format!("{:?}", whole.warnings())
+            );
+            assert_eq!(
+                list.rule_metadata("github.io"),
+                whole.rule_metadata("github.io")
+            );
+        }
+    }
+
+    #[test]
+    fn line_endings_and_concatenation() {
+        let text = "// ===BEGIN ICANN DOMAINS===\r\ncom\r\n// ===END ICANN DOMAINS===\r\n";
+        let more = "食狮.中国\nexample";
+        let mut parser = Parser::new(LoadOpts::default());
+        parser.feed(text.as_bytes()).unwrap();
+        // Split inside a multi-byte character.
+        let (a, b) = more.as_bytes().split_at(2);
+        parser.feed(a).unwrap();
+        parser.feed(b).unwrap();
+        let list = parser.finish().unwrap();
+        assert!(list.domain("a.com", m()).unwrap().is_icann());
+        assert_eq!(list.tld("a.b.example", m()).as_deref(), Some("example"));
+        assert_eq!(
+            list.tld("a.食狮.中国", m_no_idna()).as_deref(),
+            Some("食狮.中国")
+        );
+    }
+
+    #[test]
+    fn errors_match_parse() {
+        let mut parser = Parser::new(LoadOpts::default());
+        assert!(matches!(parser.feed(b"com\n\xff\n"), Err(Error::NotUtf8)));
+        // A bad byte in an unfinished line is reported by `finish`.
+        let mut parser = Parser::new(LoadOpts::default());
+        parser.feed(b"com\nx\xff").unwrap();
+        assert!(matches!(parser.finish(), Err(Error::NotUtf8)));
+
+        let strict = LoadOpts {
+            strict_rules: true,
+            ..LoadOpts::default()
+        };
+        assert!(matches!(
+            parse_in_chunks(b"com\n!\n", 3, strict),
+            Err(Error::InvalidRule { .. })
+        ));
+        assert!(matches!(
+            parse_in_chunks(b"// nothing\n", 4, LoadOpts::default()),
+            Err(Error::EmptyList)
+        ));
+        assert!(matches!(
+            Parser::new(LoadOpts::default()).finish(),
+            Err(Error::EmptyList)
+        ));
+    }
+}
+
+mod known_tlds {
+    use super::*;
+    use publicsuffix2::Engine;
+
+    #[test]
+    fn is_known_tld_checks_last_label() {
+        let list = list();
+        for label in ["com", "UK", "example.co.uk", "com."] {
+            assert!(list.is_known_tld(label), "{label}");
+        }
+        for label in ["invalid", "example.invalid", "", ".", "*"] {
+            assert!(!list.is_known_tld(label), "{label}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn is_known_tld_checks_both_idn_forms() {
+        let list = list();
+        for label in ["中国", "xn--fiqs8s"] {
+            assert!(list.is_known_tld(label), "{label}");
+        }
+    }
+
+    #[test]
+    fn known_tlds_lists_top_labels() {
+        let text = "com\nco.uk\n*.ck\n!www.ck\n*.example";
+        for list in [
+            text.parse::<List>().unwrap(),
+            text.parse::<List>().unwrap().with_engine(Engine::Flat),
+        ] {
+            let tlds: Vec<&str> = list.known_tlds().collect();
+            assert_eq!(tlds, ["ck", "com", "example", "uk"]);
+            assert!(tlds.iter().all(|t| list.is_known_tld(t)));
+            assert!(!list.is_known_tld("org"));
+        }
+    }
+}
+
+mod binary {
+    use super::*;
+    use publicsuffix2::Engine;
+
+    #[test]
+    fn full_list_roundtrips() {
+        for list in [list(), list().with_engine(Engine::Flat)] {
+            let back = List::from_bytes(&list.to_bytes()).unwrap();
+            assert_eq!(back.engine(), list.engine());
+            for host in [
+                "www.example.co.uk",
+                "foo.city.kawasaki.jp",
+                "a.b.c.ck",
+                "食狮.中国",
+            ] {
+                assert_eq!(back.split(host, m()), list.split(host, m()), "{host}");
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip_keeps_the_same_rules() {
+        let list = list();
+        let back = List::from_bytes(&list.to_bytes()).unwrap();
+        assert_eq!(back, list);
+        assert!(list.is_subset_of(&back) && back.is_subset_of(&list));
+    }
+}
+
+mod mapped_bytes {
+    use super::*;
+    use publicsuffix2::{Engine, Error};
+
+    #[test]
+    fn roundtrip_from_memory() {
+        let list = list();
+        let back = List::from_mapped_bytes(&list.to_mapped_bytes().unwrap()).unwrap();
+        assert_eq!(back.engine(), Engine::Flat);
+        assert_eq!(back.to_bytes(), list.with_engine(Engine::Flat).to_bytes());
+        assert!(matches!(
+            List::from_mapped_bytes(&back.to_bytes()),
+            Err(Error::UnsupportedFormatVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn labels_too_long_for_the_flat_layout() {
+        let list: List = format!("com\n{}.com", "a".repeat(70_000)).parse().unwrap();
+        assert!(matches!(
+            list.to_mapped_bytes(),
+            Err(Error::TooLargeForFlat)
+        ));
+        // Left on the trie rather than truncated.
+        let list = list.with_engine(Engine::Flat);
+        assert_eq!(list.engine(), Engine::Trie);
+        assert_eq!(list.rules().count(), 2);
+    }
+}
+
+#[cfg(feature = "mmap")]
+mod mapped {
+    use super::*;
+    use publicsuffix2::{Engine, Error};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn write(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("psl-{name}-{}.mapped", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn open(path: impl AsRef<std::path::Path>) -> Result<List, Error> {
+        // SAFETY: each test writes its files once, before mapping them.
+        unsafe { List::open_mmap(path) }
+    }
+
+    #[test]
+    fn mapped_list_matches_parsed_list() {
+        let text =
+            format!("// VERSION: 2025-09-23_13-07-02_UTC\n// @owner team-x\ncorp.example\n{PSL}");
+        let list: List = text.parse().unwrap();
+        let path = write("full", &list.to_mapped_bytes().unwrap());
+        let mapped = open(&path).unwrap();
+
+        assert_eq!(mapped.engine(), Engine::Flat);
+        assert_eq!(
+            mapped.to_bytes(),
+            list.clone().with_engine(Engine::Flat).to_bytes()
+        );
+        assert_eq!(mapped.rule_meta(), list.rule_meta());
+        assert_eq!(mapped.version_info(), list.version_info());
+        assert!(mapped.approx_bytes() < list.approx_bytes() / 10);
+        for host in [
+            "www.example.co.uk",
+            "foo.city.kawasaki.jp",
+            "a.b.c.ck",
+            "www.ck",
+            "食狮.中国",
+            "x.corp.example",
+            "example.local",
+        ] {
+            assert_eq!(mapped.split(host, m()), list.split(host, m()), "{host}");
+        }
+        // Every engine writes the same image.
+        assert_eq!(
+            list.clone().compile().to_mapped_bytes().unwrap(),
+            list.to_mapped_bytes().unwrap()
+        );
+        assert_eq!(
+            mapped.to_mapped_bytes().unwrap(),
+            list.to_mapped_bytes().unwrap()
+        );
+        drop(mapped);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn bad_files_are_errors() {
+        let bytes = List::parse("com\nco.uk")
+            .unwrap()
+            .to_mapped_bytes()
+            .unwrap();
+        let compiled = write("compiled", &List::parse("com").unwrap().to_bytes());
+        assert!(matches!(
+            open(&compiled),
+            Err(Error::UnsupportedFormatVersion { .. })
+        ));
+        let cut = write("cut", &bytes[..bytes.len() - 3]);
+        assert!(matches!(open(&cut), Err(Error::InvalidBinary { .. })));
+        let empty = write("empty", b"");
+        assert!(open(&empty).is_err());
+        assert!(matches!(open("/nonexistent/psl.mapped"), Err(Error::Io(_))));
+        for path in [compiled, cut, empty] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
+
+mod merge {
+    use super::*;
+    use publicsuffix2::{Engine, Error, LayeredList, MergePolicy};
+
+    const BASE: &str = "// ===BEGIN ICANN DOMAINS===\ncom\nexample\n*.ck\n!www.ck\n\
+                        // ===END ICANN DOMAINS===\n";
+
+    #[test]
+    fn merge_adds_override_rules() {
+        let base: List = BASE.parse().unwrap();
+        let internal: List = "corp.example\n*.dev.corp.example".parse().unwrap();
+        let merged = base.merge(&internal, MergePolicy::ErrorOnConflict).unwrap();
+        assert_eq!(merged.len(), base.len() + 2);
+        assert_eq!(
+            merged.sld("www.a.corp.example", m()).as_deref(),
+            Some("a.corp.example")
+        );
+        assert_eq!(
+            merged.tld("x.y.dev.corp.example", m()).as_deref(),
+            Some("y.dev.corp.example")
+        );
+        assert_eq!(merged.tld("www.ck", m()).as_deref(), Some("ck"));
+        assert_eq!(base.tld("a.corp.example", m()).as_deref(), Some("example"));
+    }
+
+    #[test]
+    fn conflicts_follow_the_policy() {
+        let base: List = BASE.parse().unwrap();
+        // Same rule, other section; and an exception turned into a rule.
+        let over: List = "// ===BEGIN PRIVATE DOMAINS===\ncom\n".parse().unwrap();
+        assert!(matches!(
+            base.merge(&over, MergePolicy::ErrorOnConflict).unwrap_err(),
+            Error::MergeConflict { rule } if rule == "com"
+        ));
+        let exc: List = "www.ck".parse().unwrap();
+        assert!(matches!(
+            base.merge(&exc, MergePolicy::ErrorOnConflict).unwrap_err(),
+            Error::MergeConflict { rule } if rule == "!www.ck"
+        ));
+
+        let kept = base.merge(&over, MergePolicy::KeepBase).unwrap();
+        assert!(!kept.domain("a.com", m()).unwrap().is_private());
+        let replaced = base.merge(&over, MergePolicy::PreferOverride).unwrap();
+        assert!(replaced.domain("a.com", m()).unwrap().is_private());
+        assert_eq!(replaced.len(), base.len());
+
+        // Identical rules are not conflicts.
+        let same: List = "// ===BEGIN ICANN DOMAINS===\nexample\n".parse().unwrap();
+        assert!(base.merge(&same, MergePolicy::ErrorOnConflict).is_ok());
+    }
+
+    #[test]
+    fn merge_keeps_engine_of_base() {
+        let base = BASE.parse::<List>().unwrap().with_engine(Engine::Flat);
+        let internal = "corp.example".parse::<List>().unwrap();
+        let merged = base.merge(&internal, MergePolicy::default()).unwrap();
+        assert_eq!(merged.engine(), Engine::Flat);
+        assert_eq!(
+            merged.tld("a.corp.example", m()).as_deref(),
+            Some("corp.example")
+        );
+    }
+
+    #[test]
+    fn layered_lookup_prefers_overrides() {
+        let overrides: List = "corp.example\nck".parse().unwrap();
+        let layered = LayeredList::new(
+            overrides,
+            BASE.parse().unwrap(),
+            MergePolicy::PreferOverride,
+        )
+        .unwrap();
+        assert_eq!(
+            layered.sld("www.a.corp.example", m()).as_deref(),
+            Some("a.corp.example")
+        );
+        // The overrides answer for every host under one of their rules.
+        assert_eq!(layered.tld("a.b.ck", m()).as_deref(), Some("ck"));
+        // Other hosts go to the base list, including its wildcards.
+        assert_eq!(layered.tld("a.b.c.ck", m()).as_deref(), Some("ck"));
+        assert_eq!(layered.tld("www.example.com", m()).as_deref(), Some("com"));
+        assert_eq!(
+            layered.split("www.example.com", m()),
+            layered.base().split("www.example.com", m())
+        );
+        assert_eq!(
+            layered
+                .match_info("a.corp.example", m())
+                .unwrap()
+                .rule
+                .as_deref(),
+            Some("corp.example")
+        );
+    }
+
+    #[test]
+    fn layered_conflicts_can_be_rejected() {
+        let overrides: List = "// ===BEGIN PRIVATE DOMAINS===\ncom\n".parse().unwrap();
+        let err = LayeredList::new(
+            overrides.clone(),
+            BASE.parse().unwrap(),
+            MergePolicy::ErrorOnConflict,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::MergeConflict { rule } if rule == "com"));
+        assert!(LayeredList::new(overrides, BASE.parse().unwrap(), MergePolicy::KeepBase).is_ok());
+    }
+}
+
+mod mutation {
+    use super::*;
+    use publicsuffix2::{Engine, Type};
+
+    #[test]
+    fn tenant_rules_on_a_shared_list() {
+        let shared = list();
+        let mut tenant = shared.clone();
+        assert!(!tenant
+            .insert_rule("tenant.example.com", Some(Type::Private))
+            .unwrap());
+        assert_eq!(tenant.len(), shared.len() + 1);
+        let d = tenant.domain("www.a.tenant.example.com", m()).unwrap();
+        assert_eq!(d.registrable(), Some("a.tenant.example.com"));
+        assert!(d.is_private());
+        assert_eq!(
+            shared.sld("www.a.tenant.example.com", m()).as_deref(),
+            Some("example.com")
+        );
+
+        assert!(tenant.remove_rule("tenant.example.com"));
+        assert_eq!(tenant.len(), shared.len());
+        assert_eq!(
+            tenant.sld("www.a.tenant.example.com", m()).as_deref(),
+            Some("example.com")
+        );
+    }
+
+    #[test]
+    fn insert_replaces_and_remove_matches_kind() {
+        let mut list: List = "com\n*.ck\n!www.ck".parse().unwrap();
+        assert!(list.insert_rule("!www.ck", Some(Type::Icann)).unwrap());
+        assert!(!list.remove_rule("www.ck"));
+        assert!(list.remove_rule("!WWW.ck"));
+        assert_eq!(list.tld("www.ck", m()).as_deref(), Some("www.ck"));
+        assert!(list.remove_rule("*.ck"));
+        assert_eq!(list.tld("www.ck", m()).as_deref(), Some("ck"));
+        assert!(!list.remove_rule("org"));
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn unicode_rules_keep_both_forms() {
+        let mut list = list().with_engine(Engine::Flat);
+        list.insert_rule("例え.jp", None).unwrap();
+        assert_eq!(list.engine(), Engine::Trie);
+        assert_eq!(
+            list.tld("a.例え.jp", m_no_idna()).as_deref(),
+            Some("例え.jp")
+        );
+        assert!(list.remove_rule("例え.jp"));
+        assert_eq!(list.tld("a.例え.jp", m_no_idna()).as_deref(), Some("jp"));
+        assert_eq!(list.len(), super::list().len());
+    }
+}
+
+mod warnings {
+    use publicsuffix2::{List, LoadOpts, Warning};
+
+    const TEXT: &str = "\
+// ===BEGIN ICANN DOMAINS===
+com
+uk.
+*.ck
+a.ck
+!www.ck
+com
+// ===BEGIN EXPERIMENTAL DOMAINS===
+// ===END ICANN DOMAINS===
+";
+
+    #[test]
+    fn parse_with_warnings_reports_each_kind() {
+        let (list, warnings) = List::parse_with_warnings(TEXT, LoadOpts::default()).unwrap();
+        let got: Vec<String> = warnings
+            .iter()
+            .map(|w| match w {
+                Warning::TrailingDotRule { rule } => format!("trailing {rule}"),
+                Warning::DuplicateRule { rule } => format!("duplicate {rule}"),
+                Warning::UnknownMarker { line } => format!("marker {line}"),
+                Warning::ShadowedRule { rule } => format!("shadowed {rule}"),
+                other => format!("{other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            got,
+            [
+                "trailing uk.",
+                "duplicate com",
+                "marker // ===BEGIN EXPERIMENTAL DOMAINS===",
+                "shadowed a.ck",
+            ]
+        );
+        assert_eq!(list.warnings().len(), warnings.len());
+    }
+
+    #[test]
+    fn clean_list_has_no_warnings() {
+        let (_, warnings) = List::parse_with_warnings(super::PSL, LoadOpts::default()).unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let list = List::parse(TEXT).unwrap();
+        assert!(list.warnings().is_empty());
+    }
+}
+
+mod default {
+    use super::*;
+    use publicsuffix2::List;
+
+    #[test]
+    fn test_default_list() {
+        // List::default() should give a working list based on the embedded PSL.
+        let list = List::default();
+        // Check a few common TLDs to ensure it's parsed correctly.
+        assert_eq!(list.tld("example.com", m()).as_deref(), Some("com"));
+        assert_eq!(list.tld("example.co.uk", m()).as_deref(), Some("co.uk"));
+        assert_eq!(
+            list.sld("example.co.uk", m()).as_deref(),
+            Some("example.co.uk")
+        );
+    }
+
+    #[test]
+    fn test_warm_does_not_change_results() {
+        let list = List::default();
+        list.warm(&[]);
+        list.warm(&["www.example.co.uk", "", "..", "食狮.中国"]);
+        assert_eq!(list.tld("www.example.co.uk", m()).as_deref(), Some("co.uk"));
+    }
+
+    #[test]
+    fn test_default_list_matches_spec_table() {
+        let report = List::default().check_against_spec();
+        assert!(report.total > 0);
+        let failures: Vec<String> = report.failures.iter().map(|f| f.to_string()).collect();
+        assert!(report.is_clean(), "spec failures:\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_spec_table_detects_divergent_list() {
+        let list: List = "com".parse().unwrap();
+        let report = list.check_against_spec();
+        assert!(!report.is_clean());
+        assert!(report
+            .failures
+            .iter()
+            .any(|f| f.case.host == "www.example.co.uk"));
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::*;
+    use publicsuffix2::{CommentPolicy, Engine, LoadOpts, Parts, SectionPolicy, Type, TypeFilter};
+    use serde_json::json;
+
+    #[test]
+    fn parts_and_sections_as_json() {
+        let parts = list().split("www.example.co.uk", m()).unwrap();
+        let value = serde_json::to_value(&parts).unwrap();
+        assert_eq!(
+            value,
+            json!({"prefix": "www", "sll": "example", "sld": "example.co.uk", "tld": "co.uk"})
+        );
+        let back: Parts<'static> = serde_json::from_value(value).unwrap();
+        assert_eq!(back, parts);
+
+        assert_eq!(serde_json::to_value(Type::Icann).unwrap(), json!("icann"));
+        assert_eq!(
+            serde_json::from_value::<TypeFilter>(json!("private")).unwrap(),
+            TypeFilter::Private
+        );
+    }
+
+    #[test]
+    fn load_opts_from_partial_config() {
+        let opts: LoadOpts = serde_json::from_value(json!({
+            "sections": "require",
+            "comments": "official_only",
+            "strict_rules": true
+        }))
+        .unwrap();
+        assert!(matches!(opts.sections, SectionPolicy::Require));
+        assert!(matches!(opts.comments, CommentPolicy::OfficialOnly));
+        assert!(opts.strict_rules && opts.lowercase_rules && !opts.collect_warnings);
+    }
+
+    #[test]
+    fn list_round_trips_through_its_rules() {
+        let list = List::parse(
+            "// ===BEGIN ICANN DOMAINS===\ncom\n*.ck\n!www.ck\n// ===END ICANN DOMAINS===\n\
+             // ===BEGIN PRIVATE DOMAINS===\ngithub.io\n",
+        )
+        .unwrap();
+        let value = serde_json::to_value(&list).unwrap();
+        assert_eq!(
+            value,
+            json!({"icann": ["!www.ck", "*.ck", "com"], "private": ["github.io"]})
+        );
+        let flat = serde_json::to_value(list.clone().with_engine(Engine::Flat)).unwrap();
+        assert_eq!(flat, value);
+
+        let back: List = serde_json::from_value(value).unwrap();
+        assert_eq!(back.tld("a.b.ck", m()).as_deref(), Some("b.ck"));
+        assert_eq!(back.tld("www.ck", m()).as_deref(), Some("ck"));
+        assert!(back.domain("x.github.io", m()).unwrap().is_private());
+
+        assert!(serde_json::from_value::<List>(json!({})).is_err());
+        assert!(serde_json::from_value::<List>(json!({"icann": ["a..b"]})).is_err());
+    }
+
+    #[test]
+    fn builtin_list_round_trips() {
+        let list = list();
+        let text = serde_json::to_string(&list).unwrap();
+        let back: List = serde_json::from_str(&text).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), text);
+        for host in [
+            "www.example.co.uk",
+            "foo.city.kawasaki.jp",
+            "a.b.ck",
+            "食狮.中国",
+        ] {
+            assert_eq!(back.split(host, m()), list.split(host, m()), "{host}");
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod from_reader {
+    use super::*;
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use publicsuffix2::{Error, LoadOpts};
+    use std::io::{self, Read, Write};
+
+    /// Yields at most three bytes per read, interrupting every other call.
+    struct Trickle<'a>(&'a [u8], bool);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1 = !self.1;
+            if self.1 {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let n = buf.len().min(3).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn decompressing_reader() {
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(PSL.as_bytes()).unwrap();
+        let compressed = gz.finish().unwrap();
+        let read = List::from_reader(GzDecoder::new(&compressed[..]), LoadOpts::default()).unwrap();
+        assert_eq!(read.to_bytes(), list().to_bytes());
+    }
+
+    #[test]
+    fn short_and_interrupted_reads() {
+        let read = List::from_reader(Trickle(PSL.as_bytes(), false), LoadOpts::default()).unwrap();
+        assert_eq!(read.to_bytes(), list().to_bytes());
+    }
+
+    #[test]
+    fn read_errors() {
+        let garbage: &[u8] = b"not gzip at all";
+        let result = List::from_reader(GzDecoder::new(garbage), LoadOpts::default());
+        assert!(matches!(result, Err(Error::Io(_))));
+        let result = List::from_reader(&b"com\n\xff\n"[..], LoadOpts::default());
+        assert!(matches!(result, Err(Error::NotUtf8)));
+    }
+}
+
+mod conformance {
+    use super::*;
+    use publicsuffix2::Engine;
+
+    const TESTS: &str = include_str!("fixtures/tests.txt");
+
+    #[test]
+    fn ascii_upstream_vectors_pass_on_every_engine() {
+        // IDN hosts, as U-labels or A-labels, only match the list's rules
+        // through IDNA; see below.
+        let ascii: String = TESTS
+            .lines()
+            .filter(|line| line.is_ascii() && !line.contains("xn--"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        for engine in [Engine::Trie, Engine::Flat, Engine::Compact] {
+            let list = list().with_engine(engine);
+            let report = list.run_conformance(&ascii);
+            assert!(report.is_clean(), "{engine:?}: {:?}", report.failures);
+            assert_eq!(report.total, 60);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn upstream_vectors_pass_on_every_engine() {
+        for engine in [Engine::Trie, Engine::Flat, Engine::Compact] {
+            let list = list().with_engine(engine);
+            let report = list.run_conformance(TESTS);
+            assert!(report.is_clean(), "{engine:?}: {:?}", report.failures);
+            assert_eq!(report.total, 78);
+        }
+    }
+
+    #[test]
+    fn mismatches_and_bad_lines_are_reported() {
+        let list = List::parse("com\nuk.com\n").unwrap();
+        let report = list.run_conformance(
+            "checkPublicSuffix('a.uk.com', 'uk.com');\n\
+             checkPublicSuffix('a.b.com', 'b.com');\n\
+             checkPublicSuffix('a.b.com';\n",
+        );
+        assert_eq!(report.total, 2);
+        assert_eq!(report.malformed, [3]);
+        assert_eq!(report.failures.len(), 1);
+        let failure = &report.failures[0];
+        assert_eq!(failure.line, 1);
+        assert_eq!(failure.expected.as_deref(), Some("uk.com"));
+        assert_eq!(failure.actual.as_deref(), Some("a.uk.com"));
+    }
+}
+
+#[cfg(feature = "metrics")]
+mod metrics {
+    use super::*;
+    use publicsuffix2::metrics::{self, Metric, MetricsSink};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    thread_local! {
+        static COUNTS: RefCell<HashMap<Metric, u64>> = RefCell::default();
+    }
+
+    /// Counts per thread, so tests running in parallel do not interfere.
+    struct PerThread;
+
+    impl MetricsSink for PerThread {
+        fn increment(&self, metric: Metric) {
+            COUNTS.with(|c| *c.borrow_mut().entry(metric).or_default() += 1);
+        }
+    }
+
+    /// Runs `f` and returns the metrics it recorded on this thread.
+    fn recorded(f: impl FnOnce()) -> HashMap<Metric, u64> {
+        static SINK: PerThread = PerThread;
+        let _ = metrics::set_sink(&SINK);
+        COUNTS.with(|c| c.borrow_mut().clear());
+        f();
+        COUNTS.with(|c| c.take())
+    }
+
+    #[test]
+    fn lookups_report_how_they_matched() {
+        let list = List::parse("com\n*.ck\n!www.ck\n").unwrap();
+        let tld = |host| drop(list.tld(host, m()));
+
+        let got = recorded(|| tld("a.b.ck"));
+        assert_eq!(
+            got,
+            HashMap::from([(Metric::Lookup, 1), (Metric::Wildcard, 1)])
+        );
+        let got = recorded(|| tld("www.ck"));
+        assert_eq!(
+            got,
+            HashMap::from([(Metric::Lookup, 1), (Metric::Exception, 1)])
+        );
+        let got = recorded(|| tld("example.invalid"));
+        assert_eq!(
+            got,
+            HashMap::from([(Metric::Lookup, 1), (Metric::Fallback, 1)])
+        );
+        let got = recorded(|| tld("www.example.com"));
+        assert_eq!(got, HashMap::from([(Metric::Lookup, 1)]));
+    }
+
+    #[test]
+    fn copied_hosts_are_reported() {
+        let list = List::parse("com\n").unwrap();
+        // Uppercase ASCII is folded label by label without a copy.
+        let got = recorded(|| drop(list.tld("WWW.EXAMPLE.COM", m())));
+        assert_eq!(got.get(&Metric::NormalizationAlloc), None);
+        let got = recorded(|| drop(list.tld("example.com.", m())));
+        assert_eq!(got.get(&Metric::NormalizationAlloc), None);
+        #[cfg(feature = "idna")]
+        {
+            let got = recorded(|| drop(list.tld("食狮.com", m())));
+            assert_eq!(got.get(&Metric::NormalizationAlloc), Some(&1));
+        }
+    }
+}
+
+mod lint {
+    use super::*;
+    use publicsuffix2::lint::LintKind;
+    use publicsuffix2::LoadOpts;
+
+    #[test]
+    fn official_list_only_has_unicode_icann_rules() {
+        let opts = LoadOpts {
+            collect_warnings: true,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with(PSL, opts).unwrap();
+        let lints = list.lint();
+        assert!(lints.iter().any(|l| l.rule == "公司.cn"));
+        assert!(
+            lints.iter().all(|l| l.kind == LintKind::NonPunycode),
+            "{:?}",
+            lints.iter().find(|l| l.kind != LintKind::NonPunycode)
+        );
+    }
+}
+
+mod wildcard_modes {
+    use super::*;
+    use publicsuffix2::{Engine, RuleKind};
+
+    fn opts(deep_wildcards: bool, exceptions: bool) -> MatchOpts<'static> {
+        MatchOpts {
+            deep_wildcards,
+            exceptions,
+            ..m()
+        }
+    }
+
+    #[test]
+    fn deep_wildcards_match_every_label_below() {
+        for list in [list(), list().with_engine(Engine::Flat), list().compile()] {
+            let tld = |h, o| list.tld(h, o).map(|t| t.into_owned());
+            let deep = opts(true, true);
+            assert_eq!(
+                tld("a.b.c.kawasaki.jp", m()).as_deref(),
+                Some("c.kawasaki.jp")
+            );
+            assert_eq!(
+                tld("a.b.c.kawasaki.jp", deep).as_deref(),
+                Some("a.b.c.kawasaki.jp")
+            );
+            // Named rules and exceptions still come first.
+            assert_eq!(tld("www.example.co.uk", deep).as_deref(), Some("co.uk"));
+            assert_eq!(
+                tld("foo.city.kawasaki.jp", deep).as_deref(),
+                Some("kawasaki.jp")
+            );
+            assert_eq!(tld("a.www.ck", deep).as_deref(), Some("ck"));
+
+            let info = list.match_info("x.y.kawasaki.jp", deep).unwrap();
+            assert_eq!(info.suffix, "x.y.kawasaki.jp");
+            assert_eq!(info.rule.as_deref(), Some("*.kawasaki.jp"));
+            assert_eq!(info.kind, RuleKind::Wildcard);
+        }
+    }
+
+    #[test]
+    fn exceptions_can_be_ignored() {
+        for list in [list(), list().with_engine(Engine::Flat), list().compile()] {
+            let no_exc = opts(false, false);
+            let tld = |h, o| list.tld(h, o).map(|t| t.into_owned());
+            assert_eq!(
+                tld("foo.city.kawasaki.jp", m()).as_deref(),
+                Some("kawasaki.jp")
+            );
+            assert_eq!(
+                tld("foo.city.kawasaki.jp", no_exc).as_deref(),
+                Some("city.kawasaki.jp")
+            );
+            assert_eq!(tld("www.ck", no_exc).as_deref(), Some("www.ck"));
+            assert_eq!(tld("a.www.ck", no_exc).as_deref(), Some("www.ck"));
+            assert!(list.is_public_suffix("www.ck", no_exc));
+            assert!(list
+                .candidates("www.ck", no_exc)
+                .iter()
+                .all(|c| c.kind != RuleKind::Exception));
+        }
+    }
+}
+
+mod compat_modes {
+    use super::*;
+    use publicsuffix2::{CompatMode, Engine, LiteralPolicy, MatchError};
+
+    fn opts(compat: CompatMode) -> MatchOpts<'static> {
+        MatchOpts { compat, ..m() }
+    }
+
+    #[test]
+    fn modes_differ_only_on_edge_cases() {
+        use CompatMode::*;
+        let cases: &[(&str, [Option<&str>; 4])] = &[
+            // host, sld for [Ps2, Libpsl, GoPublicsuffix, Rfc]
+            ("www.example.com", [Some("example.com"); 4]),
+            (".example.com", [Some("example.com"), None, None, None]),
+            (
+                "example.com.",
+                [Some("example.com"), None, None, Some("example.com")],
+            ),
+            ("127.0.0.1", [Some("1"), None, Some("0.1"), None]),
+            (
+                "a.b.example.local",
+                [
+                    Some("local"),
+                    Some("example.local"),
+                    Some("example.local"),
+                    Some("example.local"),
+                ],
+            ),
+        ];
+        for list in [list(), list().with_engine(Engine::Flat), list().compile()] {
+            for (host, want) in cases {
+                for (mode, want) in [Ps2, Libpsl, GoPublicsuffix, Rfc].into_iter().zip(want) {
+                    let got = list.sld(host, opts(mode)).map(|s| s.into_owned());
+                    assert_eq!(got.as_deref(), *want, "{host} {mode:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ps2_defers_to_the_other_options() {
+        let whole = MatchOpts {
+            literals: LiteralPolicy::Whole,
+            ..m()
+        };
+        assert_eq!(list().tld("127.0.0.1", whole).as_deref(), Some("127.0.0.1"));
+        // Other modes override `literals`.
+        let go = MatchOpts {
+            compat: CompatMode::GoPublicsuffix,
+            ..whole
+        };
+        assert_eq!(list().tld("127.0.0.1", go).as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn errors_name_the_rejected_dot() {
+        let list = list();
+        let err = |h, mode| list.try_split(h, opts(mode)).unwrap_err();
+        assert_eq!(
+            err(".example.com", CompatMode::Libpsl),
+            MatchError::EmptyLabel
+        );
+        assert_eq!(
+            err("example.com.", CompatMode::GoPublicsuffix),
+            MatchError::TrailingDot
+        );
+        assert_eq!(err("10.0.0.1", CompatMode::Rfc), MatchError::Literal);
+    }
+}
+
+mod section_policy {
+    use super::*;
+    use publicsuffix2::{LoadOpts, SectionCounts, SectionPolicy, Type};
+
+    const TEXT: &str = "com\n// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n// ===END ICANN DOMAINS===\n\
+                        // ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\n\
+                        example\n";
+
+    fn load(sections: SectionPolicy) -> List {
+        List::parse_with(
+            TEXT,
+            LoadOpts {
+                sections,
+                ..LoadOpts::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn types(list: &List) -> Vec<(String, Option<Type>)> {
+        list.rules().map(|r| (r.to_string(), r.typ)).collect()
+    }
+
+    #[test]
+    fn ignore_leaves_every_rule_unclassified() {
+        let list = load(SectionPolicy::Ignore);
+        assert!(list.rules().all(|r| r.typ.is_none()));
+        assert_eq!((list.icann_len(), list.private_len()), (0, 0));
+        assert_eq!(
+            list.section_counts(),
+            SectionCounts {
+                unclassified: 5,
+                ..SectionCounts::default()
+            }
+        );
+    }
+
+    #[test]
+    fn auto_tags_rules_inside_markers() {
+        let list = load(SectionPolicy::Auto);
+        assert_eq!(
+            types(&list),
+            [
+                ("co.uk".into(), Some(Type::Icann)),
+                ("com".into(), None),
+                ("example".into(), None),
+                ("github.io".into(), Some(Type::Private)),
+                ("uk".into(), Some(Type::Icann)),
+            ]
+        );
+        assert_eq!(
+            list.section_counts(),
+            SectionCounts {
+                icann: 2,
+                private: 1,
+                unclassified: 2,
+                skipped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn require_drops_rules_outside_markers() {
+        let list = load(SectionPolicy::Require);
+        assert_eq!(list.len(), 3);
+        assert!(list.rules().all(|r| r.typ.is_some()));
+        let counts = list.section_counts();
+        assert_eq!((counts.icann, counts.private), (2, 1));
+        assert_eq!((counts.unclassified, counts.skipped), (0, 2));
+    }
+
+    #[test]
+    fn full_list_counts_match_its_rules() {
+        let opts = LoadOpts {
+            a_label_duplicates: false,
+            ..LoadOpts::default()
+        };
+        let list = List::parse_with(PSL, opts).unwrap();
+        let counts = list.section_counts();
+        assert_eq!(counts.skipped + counts.unclassified, 0);
+        assert_eq!(
+            (counts.icann, counts.private),
+            (list.icann_len(), list.private_len())
+        );
+        // The A-label copies are rules, but not lines.
+        let copies = super::list().section_counts();
+        assert_eq!(copies, counts);
+    }
+}
+
+mod strict_rules {
+    use super::*;
+    use publicsuffix2::errors::RuleSyntax;
+    use publicsuffix2::{Error, LoadOpts, Warning};
+
+    fn strict(text: &str) -> Result<List, Error> {
+        List::parse_with(
+            text,
+            LoadOpts {
+                strict_rules: true,
+                ..LoadOpts::default()
+            },
+        )
+    }
+
+    fn lenient(text: &str) -> List {
+        List::parse_with(
+            text,
+            LoadOpts {
+                collect_warnings: true,
+                ..LoadOpts::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_each_syntax_error() {
+        let long = format!("{}.com", "a".repeat(64));
+        let cases: &[(&str, Option<RuleSyntax>)] = &[
+            ("a..com", Some(RuleSyntax::HasEmptyLabel)),
+            (".a.com", Some(RuleSyntax::StartsOrEndsWithDot)),
+            ("a.com.", Some(RuleSyntax::StartsOrEndsWithDot)),
+            ("a.com junk", Some(RuleSyntax::ContainsWhitespace)),
+            ("a$b.com", Some(RuleSyntax::ContainsIllegalChar)),
+            ("a.*.com", Some(RuleSyntax::ContainsIllegalChar)),
+            ("*a.com", Some(RuleSyntax::ContainsIllegalChar)),
+            ("!*.com", Some(RuleSyntax::ContainsIllegalChar)),
+            ("a.!b.com", Some(RuleSyntax::ContainsIllegalChar)),
+            (&long, None),
+        ];
+        for (rule, reason) in cases {
+            let text = format!("com\n{rule}\n");
+            match (strict(&text), reason) {
+                (Err(Error::InvalidRule { reason: got, .. }), Some(want)) => {
+                    assert_eq!(
+                        core::mem::discriminant(&got),
+                        core::mem::discriminant(want),
+                        "{rule}"
+                    );
+                }
+                (Err(Error::LabelTooLong { .. }), None) => {}
+                (other, _) => panic!("{rule}: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn accepts_valid_rules() {
+        let list = strict("com\n*.ck\n!www.ck\na_b.com // note\n食狮.中国\nCO.UK\n").unwrap();
+        assert_eq!(list.tld("x.a_b.com", m()).as_deref(), Some("a_b.com"));
+        assert!(strict(PSL).is_ok());
+    }
+
+    #[test]
+    fn lenient_loading_warns_and_keeps_the_rule() {
+        let list = lenient("com\na$b.com\nx.com trailing\n");
+        let warnings: Vec<_> = list
+            .warnings()
+            .iter()
+            .filter_map(|w| match w {
+                Warning::InvalidRule { rule, reason } => Some((rule.as_str(), *reason)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].0, "a$b.com");
+        assert!(matches!(warnings[0].1, RuleSyntax::ContainsIllegalChar));
+        assert_eq!(warnings[1].0, "x.com");
+        assert!(matches!(warnings[1].1, RuleSyntax::ContainsWhitespace));
+        assert_eq!(
+            list.tld("y.a$b.com", m_no_idna()).as_deref(),
+            Some("a$b.com")
+        );
+
+        let long = lenient(&format!("com\n{}.com\n", "a".repeat(64)));
+        assert!(matches!(
+            long.warnings(),
+            [Warning::RuleLabelTooLong { .. }]
+        ));
+        // A trailing dot keeps its own warning.
+        assert!(matches!(
+            lenient("com.\n").warnings(),
+            [Warning::TrailingDotRule { .. }]
+        ));
+        assert!(lenient(PSL).warnings().iter().all(|w| !matches!(
+            w,
+            Warning::InvalidRule { .. } | Warning::RuleLabelTooLong { .. }
+        )));
+    }
+}
+
+mod limits {
+    use super::*;
+    use publicsuffix2::{Error, LoadOpts, MatchError};
+
+    #[test]
+    fn hosts_over_the_limits_are_rejected() {
+        let list = list();
+        let deep = format!("{}example.com", "a.".repeat(200));
+        assert_eq!(list.tld(&deep, m()), None);
+        assert_eq!(list.try_split(&deep, m()).unwrap_err(), MatchError::TooLong);
+        let roomy = MatchOpts {
+            max_labels: 300,
+            ..m()
+        };
+        assert_eq!(list.sld(&deep, roomy).as_deref(), Some("example.com"));
+
+        let long = format!("{}.example.com", "a".repeat(2000));
+        assert_eq!(
+            list.try_split(&long, roomy).unwrap_err(),
+            MatchError::TooLong
+        );
+        let tight = MatchOpts { max_len: 10, ..m() };
+        assert_eq!(list.tld("example.com", tight), None);
+        assert_eq!(list.tld("a.co.uk", tight).as_deref(), Some("co.uk"));
+
+        // 127 labels is the most a DNS name has, so it still passes.
+        let max = format!("{}com", "a.".repeat(126));
+        assert_eq!(list.tld(&max, m()).as_deref(), Some("com"));
+        assert!(list.tld(&format!("a.{max}"), m()).is_none());
+    }
+
+    #[test]
+    fn rules_over_the_depth_limit_fail_the_load() {
+        let opts = LoadOpts {
+            max_rule_depth: 3,
+            ..LoadOpts::default()
+        };
+        assert!(List::parse_with("com\na.b.com\n", opts).is_ok());
+        assert!(matches!(
+            List::parse_with("com\na.b.c.com\n", opts),
+            Err(Error::RuleDepthExceeded { depth: 4 })
+        ));
+        let absurd = format!("{}com\n", "a.".repeat(500));
+        assert!(matches!(
+            List::parse(&absurd),
+            Err(Error::RuleDepthExceeded { depth: 501 })
+        ));
+    }
+}
+
+mod owned_results {
+    use super::*;
+    use publicsuffix2::Parts;
+
+    #[test]
+    fn owned_variants_match_the_borrowed_ones() {
+        let list = list();
+        for host in [
+            "www.example.co.uk",
+            "WWW.Example.COM.",
+            "a.b.ck",
+            "",
+            "a..com",
+        ] {
+            let owned: (Option<String>, Option<String>, Option<Parts<'static>>) = {
+                let host = String::from(host);
+                (
+                    list.sld_owned(&host, m()),
+                    list.tld_owned(&host, m()),
+                    list.split_owned(&host, m()),
+                )
+            };
+            assert_eq!(owned.0.as_deref(), list.sld(host, m()).as_deref(), "{host}");
+            assert_eq!(owned.1.as_deref(), list.tld(host, m()).as_deref(), "{host}");
+            assert_eq!(owned.2, list.split(host, m()), "{host}");
+        }
+    }
+}
+
+mod debug_output {
+    use super::*;
+
+    #[test]
+    fn debug_summarizes_and_dump_shows_everything() {
+        let list = list();
+        let summary = format!("{list:?}");
+        assert!(summary.len() < 512, "{summary}");
+        assert!(
+            summary.contains(&format!("rules: {}", list.len())),
+            "{summary}"
+        );
+        assert!(!summary.contains("fingerprint"), "{summary}");
+
+        let sections = list.section_counts();
+        assert!(
+            summary.contains(&format!(
+                "icann: {}, private: {}, unclassified: 0",
+                sections.icann, sections.private
+            )),
+            "{summary}"
+        );
+
+        let small = List::parse("// COMMIT: 6defc0e\ncom\n!www.ck\n").unwrap();
+        assert!(format!("{small:?}").contains(
+            r#"rules: 2, icann: 0, private: 0, unclassified: 2, version: None, commit: Some("6defc0e"), warnings: 0"#
+        ));
+        let dump = format!("{:?}", small.dump());
+        assert!(dump.len() > format!("{small:?}").len());
+        assert!(dump.contains("store: "), "{dump}");
+        assert!(
+            dump.contains(&format!("fingerprint: {:#018x}", small.fingerprint())),
+            "{dump}"
         );
     }
 }
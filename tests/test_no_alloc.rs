@@ -0,0 +1,83 @@
+// Counts heap allocations process-wide, so everything that must not
+// allocate is checked within a single test in this binary.
+use publicsuffix2::{Engine, List, MatchOpts, TypeFilter};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCS.load(Ordering::Relaxed);
+    f();
+    ALLOCS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn suffix_len_never_allocates() {
+    let text = include_str!("fixtures/public_suffix_list.dat");
+    let lists = [
+        List::parse(text).unwrap(),
+        List::parse(text).unwrap().with_engine(Engine::Flat),
+    ];
+    let opts = [
+        MatchOpts::default(),
+        MatchOpts {
+            strict: true,
+            types: TypeFilter::Icann,
+            match_both_forms: true,
+            ..MatchOpts::default()
+        }
+        .ignoring_private(publicsuffix2::options::PLATFORM_SUFFIXES),
+    ];
+    let hosts = [
+        "www.example.co.uk",
+        "foo.city.kawasaki.jp",
+        "a.b.c.ck",
+        "user.github.io",
+        "食狮.中国",
+        "WWW.EXAMPLE.COM",
+        "example.com.",
+        "a\nb.com",
+        "",
+    ];
+    for list in &lists {
+        for opts in opts {
+            for host in hosts {
+                let mut got = None;
+                let n = allocations(|| got = list.suffix_len(host, opts));
+                assert_eq!(n, 0, "{host:?} allocated");
+                if let Some(off) = got {
+                    let want = list.tld(
+                        host,
+                        MatchOpts {
+                            normalizer: None,
+                            ..opts
+                        },
+                    );
+                    assert_eq!(Some(&host[off..]), want.as_deref(), "{host:?}");
+                }
+            }
+        }
+    }
+    let list = &lists[0];
+    let m = MatchOpts::default();
+    assert_eq!(list.suffix_len("www.example.co.uk", m), Some(12));
+    assert_eq!(list.suffix_len("a.b.c.ck", m), Some(4));
+    assert_eq!(list.suffix_len("a\nb.com", m), None);
+    assert_eq!(list.suffix_len("example.com.", m), None);
+}
@@ -0,0 +1,123 @@
+// Exhaustive small-alphabet sweep over the lookup path.
+//
+// Every string up to `MAX_LEN` characters over an alphabet of label
+// characters, dots, rule syntax and non-ASCII is fed through `tld`, `sld` and
+// `split` under every combination of match options. The lookup path must
+// never panic on such input, whatever it returns.
+use publicsuffix2::{
+    CompatMode, ControlPolicy, FallbackPolicy, List, LiteralPolicy, MatchOpts, Normalizer,
+    TypeFilter,
+};
+
+const ALPHABET: &[char] = &['a', 'C', '.', '*', '!', '-', 'é', '中'];
+const MAX_LEN: usize = 5;
+
+// Rules exercising exact, wildcard and exception matches, at several depths.
+const RULES: &str = "a\nC\n*.a\n!a.a\n*.*.a\né\n中.é\n-\n*\n!.a";
+
+fn all_opts() -> Vec<MatchOpts<'static>> {
+    const RAW: Normalizer = Normalizer::raw();
+    const PS2: Normalizer = Normalizer::ps2();
+    const NORMS: [Option<&Normalizer>; 3] = [None, Some(&RAW), Some(&PS2)];
+
+    let mut out = Vec::new();
+    for wildcard in [true, false] {
+        for strict in [true, false] {
+            for types in [TypeFilter::Any, TypeFilter::Icann, TypeFilter::Private] {
+                for normalizer in NORMS {
+                    out.push(MatchOpts {
+                        wildcard,
+                        strict,
+                        types,
+                        normalizer,
+                        ignored_private: &[],
+                        controls: ControlPolicy::Reject,
+                        match_both_forms: false,
+                        literals: LiteralPolicy::Match,
+                        fallback: FallbackPolicy::LastLabel,
+                        deep_wildcards: false,
+                        exceptions: true,
+                        compat: CompatMode::Ps2,
+                        max_len: 1024,
+                        max_labels: 127,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+fn for_each_input(mut f: impl FnMut(&str)) {
+    let mut buf = String::new();
+    fn rec(buf: &mut String, depth: usize, f: &mut dyn FnMut(&str)) {
+        f(buf);
+        if depth == MAX_LEN {
+            return;
+        }
+        for &c in ALPHABET {
+            buf.push(c);
+            rec(buf, depth + 1, f);
+            buf.pop();
+        }
+    }
+    rec(&mut buf, 0, &mut f);
+}
+
+fn sweep(list: &List) {
+    let opts = all_opts();
+    for_each_input(|host| {
+        for &o in &opts {
+            let _ = list.tld(host, o);
+            let _ = list.sld(host, o);
+            let _ = list.split(host, o);
+        }
+    });
+}
+
+#[test]
+fn lookup_never_panics_on_synthetic_rules() {
+    let list = List::parse(RULES).expect("parse rules");
+    sweep(&list);
+}
+
+#[test]
+fn lookup_never_panics_on_edge_hosts_with_default_list() {
+    // The real list has deep wildcard/exception chains (e.g. `*.kawasaki.jp`).
+    let list = List::default();
+    for host in [
+        ".com",
+        ".co.uk",
+        "..",
+        ".",
+        "!",
+        "*.ck",
+        ".city.kawasaki.jp",
+        "city.kawasaki.jp",
+        "x.city.kawasaki.jp.",
+        ".www.ck",
+        "\0",
+        "\0.com",
+        ".\u{7f}.com",
+        "a\r\n.co.uk.",
+        "\u{85}..\u{9f}",
+        "xn--",
+        "a.xn--.com",
+        "xn--a.é",
+        "é.xn--fiqs8s",
+    ] {
+        for o in all_opts() {
+            for controls in [ControlPolicy::Reject, ControlPolicy::Strip] {
+                for match_both_forms in [false, true] {
+                    let o = MatchOpts {
+                        controls,
+                        match_both_forms,
+                        ..o
+                    };
+                    let _ = list.split(host, o);
+                    let _ = list.tld(host, o);
+                }
+            }
+        }
+    }
+}
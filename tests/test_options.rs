@@ -1,4 +1,6 @@
-use publicsuffix2::options::{CommentPolicy, LoadOpts, MatchOpts, Normalizer, SectionPolicy};
+use publicsuffix2::options::{
+    CommentPolicy, ControlPolicy, LoadOpts, MatchOpts, Normalizer, SectionPolicy,
+};
 
 #[test]
 fn loadopts_default_values() {
@@ -7,6 +9,9 @@ fn loadopts_default_values() {
     assert!(matches!(opts.comments, CommentPolicy::Common));
     assert!(!opts.strict_rules);
     assert!(!opts.collect_warnings);
+    assert!(opts.lowercase_rules);
+    assert!(opts.a_label_duplicates);
+    assert!(!opts.keep_metadata);
 }
 
 #[test]
@@ -53,6 +58,9 @@ fn matchopts_default_values() {
     let m = MatchOpts::default();
     assert!(m.wildcard);
     assert!(!m.strict);
+    assert!(m.ignored_private.is_empty());
+    assert_eq!(m.controls, ControlPolicy::Reject);
+    assert!(!m.match_both_forms);
     assert!(m.normalizer.is_some());
     let n = m.normalizer.unwrap();
     assert!(n.lowercase);
@@ -66,6 +74,7 @@ fn matchopts_is_copy_and_holds_normalizer_ref() {
         lowercase: true,
         strip_trailing_dot: true,
         idna_ascii: true,
+        ..Normalizer::default()
     };
     let m1 = MatchOpts {
         normalizer: Some(&norm),
@@ -154,3 +163,71 @@ fn matchopts_presets() {
     assert!(m_with.normalizer.is_some());
     assert!(core::ptr::eq(m_with.normalizer.unwrap(), &norm));
 }
+
+#[cfg(feature = "idna")]
+mod idna_processing {
+    use super::*;
+    use publicsuffix2::{Error, List};
+
+    fn with(f: impl FnOnce(&mut Normalizer)) -> Normalizer {
+        let mut n = Normalizer::ps2();
+        f(&mut n);
+        n
+    }
+
+    #[test]
+    fn normalize_matches_lookups() {
+        let n = Normalizer::ps2();
+        assert_eq!(
+            n.normalize(".WWW.Bücher.DE.").unwrap(),
+            "www.xn--bcher-kva.de"
+        );
+        assert!(matches!(
+            n.normalize("www.example.com").unwrap(),
+            std::borrow::Cow::Borrowed("www.example.com")
+        ));
+    }
+
+    #[test]
+    fn transitional_maps_deviation_characters() {
+        assert_eq!(
+            Normalizer::ps2().normalize("faß.de").unwrap(),
+            "xn--fa-hia.de"
+        );
+        let n = with(|n| n.idna_transitional = true);
+        assert_eq!(n.normalize("faß.de").unwrap(), "fass.de");
+        assert_eq!(
+            n.normalize("a\u{200d}ü.de").unwrap(),
+            Normalizer::ps2().normalize("aü.de").unwrap()
+        );
+    }
+
+    #[test]
+    fn std3_rules_reject_non_ldh_ascii() {
+        assert!(Normalizer::ps2().normalize("a_b.bücher.de").is_ok());
+        let n = with(|n| n.idna_std3_rules = true);
+        assert!(matches!(
+            n.normalize("a_b.bücher.de"),
+            Err(Error::IdnaError(_))
+        ));
+        assert!(n.normalize("a-b.bücher.de").is_ok());
+    }
+
+    #[test]
+    fn errors_are_surfaced_or_rejected() {
+        let list: List = "de\ncom".parse().unwrap();
+        let bad = "ü.xn--a.de";
+        assert!(matches!(
+            Normalizer::ps2().normalize(bad),
+            Err(Error::IdnaError(_))
+        ));
+        assert_eq!(list.tld(bad, MatchOpts::default()).as_deref(), Some("de"));
+        let reject = with(|n| n.idna_reject_errors = true);
+        assert_eq!(list.tld(bad, MatchOpts::with_normalizer(&reject)), None);
+        assert_eq!(
+            list.tld("ü.example.de", MatchOpts::with_normalizer(&reject))
+                .as_deref(),
+            Some("de")
+        );
+    }
+}
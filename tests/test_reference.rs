@@ -0,0 +1,198 @@
+// Cross-checks every storage engine against `List::reference_split`, a naive
+// matcher that searches the rule list directly, over generated lists and
+// hosts.
+//
+// Generation is seeded, so runs are reproducible. Set `PSL_REFERENCE_SEED`
+// to explore other inputs and `PSL_REFERENCE_CASES` to run longer; a
+// failure prints the seed-independent list, host and options to replay.
+#![cfg(feature = "test-support")]
+use publicsuffix2::{CompatMode, Engine, FallbackPolicy, List, MatchOpts, TypeFilter};
+
+/// Labels rules are made of. A few labels keep collisions (and so shared
+/// trie nodes, wildcards meeting exceptions) frequent.
+const RULE_LABELS: &[&str] = &["a", "b", "c", "*"];
+/// Labels hosts are made of: rule labels, an unlisted one, a case variant
+/// and the empty label.
+const HOST_LABELS: &[&str] = &["a", "b", "c", "x", "A", "*", ""];
+
+/// xorshift64*, enough to spread test inputs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+}
+
+fn env(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn gen_rule(rng: &mut Rng) -> String {
+    let depth = 1 + rng.below(3);
+    let labels: Vec<&str> = (0..depth).map(|_| *rng.pick(RULE_LABELS)).collect();
+    let rule = labels.join(".");
+    if rng.chance(20) {
+        format!("!{rule}")
+    } else {
+        rule
+    }
+}
+
+/// A list with unclassified, ICANN and private rules.
+fn gen_list(rng: &mut Rng) -> String {
+    let section =
+        |rng: &mut Rng| -> Vec<String> { (0..rng.below(4)).map(|_| gen_rule(rng)).collect() };
+    let (none, icann, private) = (section(rng), section(rng), section(rng));
+    let mut text = none.join("\n");
+    text.push_str("\n// ===BEGIN ICANN DOMAINS===\n");
+    text.push_str(&icann.join("\n"));
+    text.push_str("\n// ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\n");
+    text.push_str(&private.join("\n"));
+    text.push_str("\n// ===END PRIVATE DOMAINS===\n");
+    text
+}
+
+fn gen_host(rng: &mut Rng) -> String {
+    let depth = 1 + rng.below(4);
+    let mut host = (0..depth)
+        .map(|_| *rng.pick(HOST_LABELS))
+        .collect::<Vec<_>>()
+        .join(".");
+    if rng.chance(10) {
+        host.push('.');
+    }
+    host
+}
+
+fn gen_opts(rng: &mut Rng) -> MatchOpts<'static> {
+    let base = if rng.chance(50) {
+        MatchOpts::default()
+    } else {
+        MatchOpts::raw()
+    };
+    MatchOpts {
+        wildcard: rng.chance(80),
+        strict: rng.chance(20),
+        types: *rng.pick(&[
+            TypeFilter::Any,
+            TypeFilter::Icann,
+            TypeFilter::Private,
+            TypeFilter::Known,
+            TypeFilter::Unclassified,
+        ]),
+        fallback: *rng.pick(&[
+            FallbackPolicy::LastLabel,
+            FallbackPolicy::None,
+            FallbackPolicy::EntireHost,
+        ]),
+        deep_wildcards: rng.chance(20),
+        exceptions: rng.chance(80),
+        compat: *rng.pick(&[
+            CompatMode::Ps2,
+            CompatMode::Libpsl,
+            CompatMode::GoPublicsuffix,
+            CompatMode::Rfc,
+        ]),
+        ..base
+    }
+}
+
+fn describe(opts: &MatchOpts<'_>) -> String {
+    format!(
+        "wildcard={} strict={} types={:?} fallback={:?} deep_wildcards={} exceptions={} compat={:?} raw={}",
+        opts.wildcard,
+        opts.strict,
+        opts.types,
+        opts.fallback,
+        opts.deep_wildcards,
+        opts.exceptions,
+        opts.compat,
+        opts.normalizer.is_none()
+    )
+}
+
+fn engines(list: &List) -> Vec<List> {
+    #[allow(unused_mut)]
+    let mut out = vec![list.clone(), list.clone().with_engine(Engine::Flat)];
+    #[cfg(feature = "fst")]
+    out.push(list.clone().with_engine(Engine::Compact));
+    out
+}
+
+/// Checks `host` on `lists`, the same list in each engine.
+#[track_caller]
+fn check(lists: &[List], text: &str, host: &str, opts: MatchOpts<'_>) {
+    let want = lists[0].reference_split(host, opts);
+    for engine in lists {
+        let got = engine.split(host, opts).map(|p| p.into_owned());
+        assert_eq!(
+            got,
+            want,
+            "{:?} engine disagrees with the reference\nhost: {host:?}\nopts: {}\nlist:\n{text}",
+            engine.engine(),
+            describe(&opts)
+        );
+    }
+}
+
+#[test]
+fn engines_agree_with_reference() {
+    let mut rng = Rng(env("PSL_REFERENCE_SEED", 0x5eed_1e55_c0ff_ee00) | 1);
+    for _ in 0..env("PSL_REFERENCE_CASES", 400) {
+        let text = gen_list(&mut rng);
+        let Ok(list) = List::parse(&text) else {
+            continue;
+        };
+        let lists = engines(&list);
+        for _ in 0..40 {
+            let host = gen_host(&mut rng);
+            let opts = gen_opts(&mut rng);
+            check(&lists, &text, &host, opts);
+        }
+    }
+}
+
+#[test]
+fn corner_cases_agree_with_reference() {
+    let text = "a\n*.a\n!b.a\n*.*.a\n!c.*.a\nb.b.a\n*.c\n!*.c\n";
+    let lists = engines(&List::parse(text).unwrap());
+    let hosts = [
+        "a", "b.a", "x.b.a", "c.x.a", "x.c.x.a", "b.b.a", "x.b.b.a", "a..a", ".a", "a.", "..", ".",
+        "", "*.a", "B.A", "x.c",
+    ];
+    for host in hosts {
+        for opts in [
+            MatchOpts::default(),
+            MatchOpts::raw(),
+            MatchOpts {
+                wildcard: false,
+                ..MatchOpts::default()
+            },
+            MatchOpts {
+                fallback: FallbackPolicy::EntireHost,
+                ..MatchOpts::default()
+            },
+        ] {
+            check(&lists, text, host, opts);
+        }
+    }
+}
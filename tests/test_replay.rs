@@ -0,0 +1,169 @@
+#![cfg(feature = "std")]
+use publicsuffix2::replay::{read_records, replay, Recorder};
+use publicsuffix2::{ControlPolicy, Error, FallbackPolicy, List, LiteralPolicy, MatchOpts};
+
+#[test]
+fn replay_against_same_list_is_clean() {
+    let list: List = "com\nuk\nco.uk".parse().unwrap();
+    let mut rec = Recorder::new(Vec::new());
+    let p = rec
+        .split(&list, "WWW.Example.co.uk", MatchOpts::default())
+        .unwrap();
+    assert_eq!(p.unwrap().sld.as_deref(), Some("example.co.uk"));
+    rec.split(&list, "a.b.com", MatchOpts::raw()).unwrap();
+    rec.split(&list, "bad..host", MatchOpts::default()).unwrap();
+    assert_eq!(rec.len(), 3);
+    let log = rec.finish().unwrap();
+
+    let records = read_records(&log[..]).unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].host, "www.example.co.uk");
+    assert!(records[2].result.is_none());
+
+    let report = replay(&list, &log[..]).unwrap();
+    assert_eq!(report.total, 3);
+    assert!(report.is_clean());
+}
+
+#[test]
+fn replay_against_changed_list_reports_regressions() {
+    let old: List = "com\nuk".parse().unwrap();
+    let new: List = "com\nuk\nco.uk".parse().unwrap();
+    let mut rec = Recorder::new(Vec::new());
+    rec.split(&old, "www.example.co.uk", MatchOpts::default())
+        .unwrap();
+    rec.split(&old, "www.example.com", MatchOpts::default())
+        .unwrap();
+    let log = rec.finish().unwrap();
+
+    let report = replay(&new, &log[..]).unwrap();
+    assert_eq!(report.total, 2);
+    assert_eq!(report.regressions.len(), 1);
+    let r = &report.regressions[0];
+    assert_eq!(r.line, 2); // line 1 is the format header
+    assert_eq!(
+        r.record.result.as_ref().unwrap().sld.as_deref(),
+        Some("co.uk")
+    );
+    assert_eq!(
+        r.actual.as_ref().unwrap().sld.as_deref(),
+        Some("example.co.uk")
+    );
+}
+
+#[test]
+fn replay_rejects_malformed_log() {
+    let list = List::default();
+    let err = replay(&list, &b"\nnot a record\n"[..]).unwrap_err();
+    assert!(matches!(err, Error::InvalidRecord { line: 2 }));
+}
+
+#[test]
+fn v1_logs_are_readable_but_not_replayed() {
+    let list: List = "com".parse().unwrap();
+    let log = "w1s0tAn110\twww.example.com\t=www\t=example\t=example.com\t=com\n";
+    let with_header = format!("#publicsuffix2 replay v1\n{log}");
+    for log in [log, &with_header] {
+        assert_eq!(read_records(log.as_bytes()).unwrap().len(), 1);
+        // v1 fingerprints leave out most options, so replaying them could
+        // report changes that are not there.
+        assert!(matches!(
+            replay(&list, log.as_bytes()).unwrap_err(),
+            Error::UnsupportedFormatVersion {
+                format: "replay",
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn replay_rejects_incomplete_fingerprints() {
+    let list: List = "com".parse().unwrap();
+    let log = "#publicsuffix2 replay v2\nv2;w=1;s=0\ta.com\t=a\t-\t=a.com\t=com\n";
+    assert!(matches!(
+        replay(&list, log.as_bytes()).unwrap_err(),
+        Error::InvalidRecord { line: 2 }
+    ));
+}
+
+#[test]
+fn log_from_newer_format_version_is_rejected() {
+    let list: List = "com".parse().unwrap();
+    let mut rec = Recorder::new(Vec::new());
+    rec.split(&list, "a.com", MatchOpts::default()).unwrap();
+    let log = String::from_utf8(rec.finish().unwrap()).unwrap();
+    assert!(log.starts_with("#publicsuffix2 replay v2\n"));
+
+    let future = log.replacen("replay v2", "replay v3", 1);
+    for err in [
+        read_records(future.as_bytes()).unwrap_err(),
+        replay(&list, future.as_bytes()).unwrap_err(),
+    ] {
+        assert!(matches!(
+            err,
+            Error::UnsupportedFormatVersion {
+                format: "replay",
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn replay_uses_the_recorded_options() {
+    let list: List = "com\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n"
+        .parse()
+        .unwrap();
+    let lookups = [
+        (
+            "user.github.io",
+            MatchOpts::default().ignoring_private(&["github.io"]),
+        ),
+        (
+            "user.git\u{7}hub.io",
+            MatchOpts {
+                controls: ControlPolicy::Strip,
+                ..MatchOpts::default()
+            },
+        ),
+        (
+            "127.0.0.1",
+            MatchOpts {
+                literals: LiteralPolicy::Whole,
+                ..MatchOpts::default()
+            },
+        ),
+        (
+            "www.example.local",
+            MatchOpts {
+                fallback: FallbackPolicy::EntireHost,
+                ..MatchOpts::default()
+            },
+        ),
+        (
+            "a.b.c.com",
+            MatchOpts {
+                max_labels: 3,
+                ..MatchOpts::default()
+            },
+        ),
+    ];
+    let mut rec = Recorder::new(Vec::new());
+    for (host, opts) in lookups {
+        rec.split(&list, host, opts).unwrap();
+    }
+    let log = rec.finish().unwrap();
+    assert_eq!(
+        read_records(&log[..]).unwrap()[0]
+            .result
+            .as_ref()
+            .unwrap()
+            .sld
+            .as_deref(),
+        Some("github.io")
+    );
+    let report = replay(&list, &log[..]).unwrap();
+    assert_eq!(report.total, lookups.len());
+    assert!(report.is_clean(), "{:?}", report.regressions);
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "test-support")]
+use publicsuffix2::assert_split;
+use publicsuffix2::test_support::*;
+
+#[test]
+fn fixture_covers_rule_kinds() {
+    let list = fixture_list();
+    assert_split!(
+        list,
+        "www.example.co.uk",
+        Some("www"),
+        Some("example"),
+        Some("example.co.uk"),
+        Some("co.uk")
+    );
+    assert_split!(
+        list,
+        "a.b.c.kawasaki.jp",
+        Some("a"),
+        Some("b"),
+        Some("b.c.kawasaki.jp"),
+        Some("c.kawasaki.jp")
+    );
+    assert_split!(
+        list,
+        "x.city.kawasaki.jp",
+        Some("x"),
+        Some("city"),
+        Some("city.kawasaki.jp"),
+        Some("kawasaki.jp")
+    );
+    assert_split!(
+        list,
+        "alice.github.io",
+        None,
+        Some("alice"),
+        Some("alice.github.io"),
+        Some("github.io")
+    );
+}
+
+#[test]
+fn presets_change_results() {
+    let list = fixture_list();
+    assert_split!(
+        list,
+        "a.b.blogspot.com",
+        icann_only(),
+        Some("a.b"),
+        Some("blogspot"),
+        Some("blogspot.com"),
+        Some("com")
+    );
+    assert_split!(
+        list,
+        "a.b.blogspot.com",
+        private_only(),
+        Some("a"),
+        Some("b"),
+        Some("b.blogspot.com"),
+        Some("blogspot.com")
+    );
+    assert_split!(
+        list,
+        "a.b.ck",
+        no_wildcard(),
+        Some("a"),
+        Some("b"),
+        Some("b.ck"),
+        Some("ck")
+    );
+    assert_split!(list, "example.invalid", strict(), None, None, None, None);
+    assert_split!(
+        list,
+        "example.invalid",
+        defaults(),
+        None,
+        None,
+        Some("invalid"),
+        Some("invalid")
+    );
+}
+
+#[test]
+#[should_panic(expected = "split(\"www.example.com\")")]
+fn mismatch_names_the_host() {
+    assert_split!(
+        fixture_list(),
+        "www.example.com",
+        None,
+        None,
+        Some("www.example.com"),
+        Some("com")
+    );
+}